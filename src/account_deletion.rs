@@ -0,0 +1,444 @@
+//! 회원 탈퇴.
+//!
+//! `DELETE /me`는 바로 지우지 않고 유예 기간(`ACCOUNT_DELETION_GRACE_DAYS`, 기본 14일) 뒤로 예약한다.
+//! 그 사이에는 로그인해서 취소할 수 있고, 기한이 지나면 워커가 계정과 딸린 행을 한 트랜잭션으로 지운다.
+//! 작성한 글은 `ACCOUNT_DELETION_POST_POLICY`에 따라 다른 계정으로 넘기거나(`reassign`, 기본) 함께 지운다(`delete`).
+
+use std::{env, sync::Arc, time::Duration};
+
+use chrono::Utc;
+use poem::{http::StatusCode, Error};
+use sqlx::{query, query_as, query_scalar, FromRow, Sqlite, SqlitePool, Transaction};
+use tokio::time::interval;
+
+use crate::attachments::{attachments_uploaded_by, remove_attachment};
+use crate::blog_redeploy::{is_blog_redeploy_target, BlogContentEvent, BlogVisibility};
+use crate::data_export::remove_archive;
+use crate::models::AppState;
+use crate::utils::parse_tags;
+
+const DEFAULT_GRACE_DAYS: i64 = 14;
+
+fn internal_error(message: String) -> Error {
+    Error::from_string(message, StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn timestamp(at: chrono::DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostDeletionPolicy {
+    /// 글과 업로드 파일의 작성자를 다른 계정으로 바꾼다.
+    Reassign,
+    /// 글을 지우고, 글에 붙어 있던 이미지는 연결만 끊는다(파일은 image GC가 정리한다).
+    Delete,
+}
+
+impl PostDeletionPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Reassign => "reassign",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AccountDeletionSettings {
+    pub grace_days: i64,
+    pub post_policy: PostDeletionPolicy,
+    /// `reassign`일 때 글을 넘겨받을 계정. 없으면 남아 있는 가장 오래된 관리자에게 넘긴다.
+    pub post_owner: Option<String>,
+}
+
+impl AccountDeletionSettings {
+    pub fn from_env() -> Self {
+        let read = |name: &str| {
+            env::var(name)
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        Self {
+            grace_days: read("ACCOUNT_DELETION_GRACE_DAYS")
+                .and_then(|value| value.parse::<i64>().ok())
+                .filter(|value| *value >= 0)
+                .unwrap_or(DEFAULT_GRACE_DAYS),
+            post_policy: match read("ACCOUNT_DELETION_POST_POLICY").as_deref() {
+                Some("delete") => PostDeletionPolicy::Delete,
+                _ => PostDeletionPolicy::Reassign,
+            },
+            post_owner: read("ACCOUNT_DELETION_POST_OWNER"),
+        }
+    }
+}
+
+/// `user_id`가 관리자일 때, 탈퇴 예약도 비활성화도 없는 다른 관리자가 남아 있는지. 관리자가 아니면 늘 `true`.
+pub async fn has_other_active_admin(db: &SqlitePool, user_id: &str) -> Result<bool, Error> {
+    let role: Option<String> = query_scalar("SELECT user_role FROM users WHERE user_id = ?")
+        .bind(user_id)
+        .fetch_optional(db)
+        .await
+        .map_err(|err| internal_error(format!("사용자 조회 실패: {}", err)))?;
+    if role.as_deref() != Some("admin") {
+        return Ok(true);
+    }
+
+    let other_admins: i64 = query_scalar(
+        r#"
+        SELECT COUNT(*) FROM users
+        WHERE user_role = 'admin' AND user_id != ? AND deletion_scheduled_at IS NULL
+          AND disabled_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(db)
+    .await
+    .map_err(|err| internal_error(format!("관리자 수 조회 실패: {}", err)))?;
+
+    Ok(other_admins > 0)
+}
+
+/// 마지막 관리자면 `409`.
+pub async fn ensure_not_last_admin(db: &SqlitePool, user_id: &str) -> Result<(), Error> {
+    if !has_other_active_admin(db, user_id).await? {
+        return Err(Error::from_string(
+            "마지막 관리자 계정은 삭제할 수 없습니다. 다른 관리자를 먼저 지정해 주세요.",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, FromRow)]
+pub struct ScheduledDeletion {
+    pub deletion_requested_at: String,
+    pub deletion_scheduled_at: String,
+}
+
+/// 탈퇴를 예약한다. 이미 예약돼 있으면 처음 예약을 그대로 돌려준다.
+pub async fn schedule_account_deletion(
+    db: &SqlitePool,
+    user_id: &str,
+    grace_days: i64,
+) -> Result<ScheduledDeletion, Error> {
+    let now = Utc::now();
+    query(
+        r#"
+        UPDATE users
+        SET deletion_requested_at = ?, deletion_scheduled_at = ?
+        WHERE user_id = ? AND deletion_scheduled_at IS NULL
+        "#,
+    )
+    .bind(timestamp(now))
+    .bind(timestamp(now + chrono::Duration::days(grace_days)))
+    .bind(user_id)
+    .execute(db)
+    .await
+    .map_err(|err| internal_error(format!("탈퇴 예약 실패: {}", err)))?;
+
+    query_as::<_, ScheduledDeletion>(
+        r#"
+        SELECT deletion_requested_at, deletion_scheduled_at
+        FROM users
+        WHERE user_id = ? AND deletion_scheduled_at IS NOT NULL
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("탈퇴 예약 조회 실패: {}", err)))?
+    .ok_or_else(|| Error::from_string("사용자를 찾을 수 없습니다.", StatusCode::NOT_FOUND))
+}
+
+/// 예약을 취소한다. 예약이 없었으면 `false`.
+pub async fn cancel_account_deletion(db: &SqlitePool, user_id: &str) -> Result<bool, Error> {
+    let result = query(
+        r#"
+        UPDATE users
+        SET deletion_requested_at = NULL, deletion_scheduled_at = NULL
+        WHERE user_id = ? AND deletion_scheduled_at IS NOT NULL
+        "#,
+    )
+    .bind(user_id)
+    .execute(db)
+    .await
+    .map_err(|err| internal_error(format!("탈퇴 취소 실패: {}", err)))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+async fn resolve_post_owner(
+    db: &SqlitePool,
+    user_id: &str,
+    settings: &AccountDeletionSettings,
+) -> Result<Option<String>, Error> {
+    if let Some(owner) = &settings.post_owner {
+        let exists: Option<String> = query_scalar(
+            "SELECT user_id FROM users WHERE user_id = ? AND user_id != ? AND deletion_scheduled_at IS NULL",
+        )
+        .bind(owner)
+        .bind(user_id)
+        .fetch_optional(db)
+        .await
+        .map_err(|err| internal_error(format!("글 인수 계정 조회 실패: {}", err)))?;
+        return Ok(exists);
+    }
+
+    query_scalar(
+        r#"
+        SELECT user_id FROM users
+        WHERE user_role = 'admin' AND user_id != ? AND deletion_scheduled_at IS NULL
+          AND disabled_at IS NULL
+        ORDER BY rowid
+        LIMIT 1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("글 인수 계정 조회 실패: {}", err)))
+}
+
+#[derive(Debug, FromRow)]
+struct OwnedPost {
+    post_id: String,
+    status: String,
+    tags: String,
+}
+
+async fn exec(tx: &mut Transaction<'_, Sqlite>, sql: &str, user_id: &str) -> Result<(), Error> {
+    query(sql)
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|err| internal_error(format!("탈퇴 계정 정리 실패: {}", err)))?;
+    Ok(())
+}
+
+/// 계정 하나를 지운다. 글 처리 후 딸린 행을 모두 지우고 마지막으로 `users` 행을 지운다.
+/// 지금도 마지막 관리자이거나, `reassign`인데 넘길 글이나 첨부 파일이 있는데 받을 계정이 없으면 지우지 않고 `409`를 돌려준다.
+/// `delete`일 때 첨부 파일은 커밋 뒤 파일과 행을 함께 지운다.
+pub async fn purge_account(
+    state: &AppState,
+    user_id: &str,
+    settings: &AccountDeletionSettings,
+) -> Result<(), Error> {
+    let db = &state.db;
+    ensure_not_last_admin(db, user_id).await?;
+
+    let post_owner = match settings.post_policy {
+        PostDeletionPolicy::Reassign => resolve_post_owner(db, user_id, settings).await?,
+        PostDeletionPolicy::Delete => None,
+    };
+
+    let owned_posts = match query_as::<_, OwnedPost>(
+        r#"
+        SELECT p.post_id, p.status,
+        IFNULL((SELECT GROUP_CONCAT(t.category || '::' || t.name, ',')
+                FROM post_tags pt JOIN tags t ON t.tag_id = pt.tag_id
+                WHERE pt.post_id = p.post_id), '') AS tags
+        FROM posts p
+        WHERE p.writer_id = ?
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+    {
+        Ok(posts) => posts,
+        // post_tags/tags는 init_db가 만들지 않으므로, 테이블이 없으면 태그 없이 읽는다.
+        Err(_) => query_as::<_, OwnedPost>(
+            "SELECT post_id, status, '' AS tags FROM posts WHERE writer_id = ?",
+        )
+        .bind(user_id)
+        .fetch_all(db)
+        .await
+        .map_err(|err| internal_error(format!("작성 글 조회 실패: {}", err)))?,
+    };
+
+    let owned_attachments = attachments_uploaded_by(db, user_id).await?;
+    if settings.post_policy == PostDeletionPolicy::Reassign
+        && post_owner.is_none()
+        && !(owned_posts.is_empty() && owned_attachments.is_empty())
+    {
+        return Err(Error::from_string(
+            "글을 넘겨받을 계정이 없어 탈퇴를 진행할 수 없습니다.",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    let export_ids: Vec<String> =
+        query_scalar("SELECT export_id FROM data_exports WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_all(db)
+            .await
+            .map_err(|err| internal_error(format!("내보내기 조회 실패: {}", err)))?;
+
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|err| internal_error(format!("트랜잭션 시작 실패: {}", err)))?;
+
+    match (settings.post_policy, &post_owner) {
+        (PostDeletionPolicy::Reassign, Some(owner)) => {
+            for sql in [
+                "UPDATE posts SET writer_id = ? WHERE writer_id = ?",
+                "UPDATE images SET uploaded_by = ? WHERE uploaded_by = ?",
+                "UPDATE attachments SET uploaded_by = ? WHERE uploaded_by = ?",
+            ] {
+                query(sql)
+                    .bind(owner)
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|err| internal_error(format!("글 인계 실패: {}", err)))?;
+            }
+        }
+        (PostDeletionPolicy::Reassign, None) => {
+            exec(
+                &mut tx,
+                "UPDATE images SET uploaded_by = NULL WHERE uploaded_by = ?",
+                user_id,
+            )
+            .await?;
+        }
+        (PostDeletionPolicy::Delete, _) => {
+            for post in &owned_posts {
+                for sql in [
+                    "UPDATE images SET post_id = NULL WHERE post_id = ?",
+                    "UPDATE attachments SET post_id = NULL WHERE post_id = ?",
+                    "DELETE FROM posts WHERE post_id = ?",
+                ] {
+                    exec(&mut tx, sql, &post.post_id).await?;
+                }
+            }
+            exec(
+                &mut tx,
+                "UPDATE images SET uploaded_by = NULL WHERE uploaded_by = ?",
+                user_id,
+            )
+            .await?;
+        }
+    }
+
+    for sql in [
+        "DELETE FROM spending_records WHERE owner_user_id = ?",
+        "DELETE FROM budget_periods WHERE owner_user_id = ?",
+        r#"DELETE FROM match_messages WHERE match_id IN (
+            SELECT match_id FROM user_matches WHERE requester_user_id = ?1 OR target_user_id = ?1
+        )"#,
+        "DELETE FROM user_matches WHERE requester_user_id = ?1 OR target_user_id = ?1",
+        "DELETE FROM user_rss_subscriptions WHERE user_id = ?",
+        r#"DELETE FROM push_delivery_logs WHERE push_subscription_id IN (
+            SELECT push_subscription_id FROM web_push_subscriptions WHERE user_id = ?
+        )"#,
+        "DELETE FROM web_push_subscriptions WHERE user_id = ?",
+        "DELETE FROM api_keys WHERE user_id = ?",
+        r#"DELETE FROM refresh_tokens WHERE family_id IN (
+            SELECT family_id FROM refresh_token_families WHERE user_id = ?
+        )"#,
+        "DELETE FROM refresh_token_families WHERE user_id = ?",
+        "DELETE FROM password_reset_tokens WHERE user_id = ?",
+        "DELETE FROM user_totp WHERE user_id = ?",
+        "DELETE FROM totp_recovery_codes WHERE user_id = ?",
+        "DELETE FROM login_challenges WHERE user_id = ?",
+        "DELETE FROM passkey_credentials WHERE user_id = ?",
+        "DELETE FROM webauthn_challenges WHERE user_id = ?",
+        "DELETE FROM login_throttles WHERE scope IN ('account', 'reset_account') AND throttle_key = lower(?)",
+        "DELETE FROM login_audit_events WHERE user_id = lower(?)",
+        "DELETE FROM email_outbox WHERE recipient = ? AND status = 'pending'",
+        "DELETE FROM data_exports WHERE user_id = ?",
+        "DELETE FROM users WHERE user_id = ?",
+    ] {
+        exec(&mut tx, sql, user_id).await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|err| internal_error(format!("트랜잭션 커밋 실패: {}", err)))?;
+
+    for export_id in &export_ids {
+        remove_archive(export_id).await;
+    }
+
+    if settings.post_policy == PostDeletionPolicy::Reassign {
+        return Ok(());
+    }
+
+    for attachment in &owned_attachments {
+        if let Err(err) = remove_attachment(db, state.storage.as_ref(), attachment).await {
+            eprintln!(
+                "탈퇴 계정 첨부 파일 삭제 실패 ({}): {}",
+                attachment.attachment_id, err
+            );
+        }
+    }
+
+    for post in &owned_posts {
+        if is_blog_redeploy_target(
+            &post.status,
+            parse_tags(&post.tags).iter().map(|tag| tag.tag.as_str()),
+        ) {
+            state
+                .blog_redeploy
+                .dispatch_content_change(
+                    BlogContentEvent::Delete,
+                    &post.post_id,
+                    BlogVisibility::Hidden,
+                )
+                .await;
+        }
+    }
+
+    Ok(())
+}
+
+/// 유예 기간이 지난 계정을 지우고 지운 계정 ID를 돌려준다. 한 계정이 실패해도 나머지는 계속 진행한다.
+pub async fn purge_due_accounts(
+    state: &AppState,
+    settings: &AccountDeletionSettings,
+) -> Result<Vec<String>, Error> {
+    let due: Vec<String> = query_scalar(
+        r#"
+        SELECT user_id FROM users
+        WHERE deletion_scheduled_at IS NOT NULL AND deletion_scheduled_at <= ?
+        ORDER BY deletion_scheduled_at
+        "#,
+    )
+    .bind(timestamp(Utc::now()))
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| internal_error(format!("탈퇴 예정 계정 조회 실패: {}", err)))?;
+
+    let mut purged = Vec::new();
+    for user_id in due {
+        match purge_account(state, &user_id, settings).await {
+            Ok(()) => purged.push(user_id),
+            Err(err) => eprintln!("계정 삭제 실패 ({}): {}", user_id, err),
+        }
+    }
+
+    Ok(purged)
+}
+
+/// 한 시간마다 유예 기간이 지난 계정을 지운다.
+pub fn start_account_deletion_worker(state: Arc<AppState>) {
+    let settings = AccountDeletionSettings::from_env();
+
+    tokio::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(60 * 60));
+
+        loop {
+            ticker.tick().await;
+            match purge_due_accounts(&state, &settings).await {
+                Ok(purged) if purged.is_empty() => {}
+                Ok(purged) => println!("scheduled accounts deleted: {}", purged.join(", ")),
+                Err(err) => eprintln!("account deletion failed: {}", err),
+            }
+        }
+    });
+}
@@ -0,0 +1,601 @@
+use std::env;
+
+use poem::{http::StatusCode, Error};
+use sqlx::{query, query_as, query_scalar, QueryBuilder, Sqlite, SqlitePool};
+use tyange_cms_api::auth::authorization::{ensure_post_owner, AuthenticatedUser};
+use uuid::Uuid;
+
+use crate::{
+    models::{AttachmentDb, AttachmentListQuery, AttachmentListResponse, AttachmentResponse},
+    storage::UploadStorage,
+};
+
+/// 첨부 파일 저장 키 접두사. 이미지 경로(`/images/...`)로는 첨부 파일을 내려주지 않는다.
+pub const ATTACHMENT_KEY_PREFIX: &str = "attachment-";
+
+const ATTACHMENT_COLUMNS: &str = r#"
+    attachment_id, post_id, file_name, origin_name, mime_type, kind, byte_size,
+    uploaded_by, uploaded_at, download_count, last_downloaded_at
+"#;
+
+const MB: u64 = 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttachmentKind {
+    Document,
+    Archive,
+    Audio,
+}
+
+impl AttachmentKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Document => "document",
+            Self::Archive => "archive",
+            Self::Audio => "audio",
+        }
+    }
+
+    fn max_bytes_env(self) -> &'static str {
+        match self {
+            Self::Document => "ATTACHMENT_MAX_BYTES_DOCUMENT",
+            Self::Archive => "ATTACHMENT_MAX_BYTES_ARCHIVE",
+            Self::Audio => "ATTACHMENT_MAX_BYTES_AUDIO",
+        }
+    }
+
+    fn default_max_bytes(self) -> u64 {
+        match self {
+            Self::Document => 30 * MB,
+            Self::Archive => 100 * MB,
+            Self::Audio => 50 * MB,
+        }
+    }
+
+    /// 종류별 최대 크기. `ATTACHMENT_MAX_BYTES_<KIND>`로 바꿀 수 있다.
+    pub fn max_bytes(self) -> u64 {
+        env::var(self.max_bytes_env())
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .filter(|value| *value > 0)
+            .unwrap_or_else(|| self.default_max_bytes())
+    }
+}
+
+/// 허용하는 첨부 형식. 확장자로 형식을 고르고, 파일 앞부분이 그 형식과 맞는지 확인한다.
+pub struct AllowedAttachmentType {
+    pub extension: &'static str,
+    pub mime_type: &'static str,
+    pub kind: AttachmentKind,
+    matches: fn(&[u8]) -> bool,
+}
+
+const ALLOWED_ATTACHMENT_TYPES: &[AllowedAttachmentType] = &[
+    AllowedAttachmentType {
+        extension: "pdf",
+        mime_type: "application/pdf",
+        kind: AttachmentKind::Document,
+        matches: is_pdf,
+    },
+    AllowedAttachmentType {
+        extension: "pptx",
+        mime_type: "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        kind: AttachmentKind::Document,
+        matches: is_zip,
+    },
+    AllowedAttachmentType {
+        extension: "docx",
+        mime_type: "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        kind: AttachmentKind::Document,
+        matches: is_zip,
+    },
+    AllowedAttachmentType {
+        extension: "zip",
+        mime_type: "application/zip",
+        kind: AttachmentKind::Archive,
+        matches: is_zip,
+    },
+    AllowedAttachmentType {
+        extension: "gz",
+        mime_type: "application/gzip",
+        kind: AttachmentKind::Archive,
+        matches: is_gzip,
+    },
+    AllowedAttachmentType {
+        extension: "7z",
+        mime_type: "application/x-7z-compressed",
+        kind: AttachmentKind::Archive,
+        matches: is_7z,
+    },
+    AllowedAttachmentType {
+        extension: "mp3",
+        mime_type: "audio/mpeg",
+        kind: AttachmentKind::Audio,
+        matches: is_mp3,
+    },
+    AllowedAttachmentType {
+        extension: "m4a",
+        mime_type: "audio/mp4",
+        kind: AttachmentKind::Audio,
+        matches: is_m4a,
+    },
+    AllowedAttachmentType {
+        extension: "ogg",
+        mime_type: "audio/ogg",
+        kind: AttachmentKind::Audio,
+        matches: is_ogg,
+    },
+    AllowedAttachmentType {
+        extension: "wav",
+        mime_type: "audio/wav",
+        kind: AttachmentKind::Audio,
+        matches: is_wav,
+    },
+    AllowedAttachmentType {
+        extension: "flac",
+        mime_type: "audio/flac",
+        kind: AttachmentKind::Audio,
+        matches: is_flac,
+    },
+];
+
+fn is_pdf(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"%PDF-")
+}
+
+fn is_zip(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"PK\x03\x04") || bytes.starts_with(b"PK\x05\x06")
+}
+
+fn is_gzip(bytes: &[u8]) -> bool {
+    bytes.starts_with(&[0x1F, 0x8B])
+}
+
+fn is_7z(bytes: &[u8]) -> bool {
+    bytes.starts_with(&[0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C])
+}
+
+fn is_mp3(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"ID3") || (bytes.len() >= 2 && bytes[0] == 0xFF && bytes[1] & 0xE0 == 0xE0)
+}
+
+fn is_m4a(bytes: &[u8]) -> bool {
+    bytes.len() >= 12 && &bytes[4..8] == b"ftyp" && matches!(&bytes[8..12], b"M4A " | b"mp42")
+}
+
+fn is_ogg(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"OggS")
+}
+
+fn is_wav(bytes: &[u8]) -> bool {
+    bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WAVE"
+}
+
+fn is_flac(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"fLaC")
+}
+
+/// 업로드를 받을 확장자 목록. `ATTACHMENT_ALLOWED_EXTENSIONS`(쉼표 구분)로 기본 목록을 좁힐 수 있다.
+pub fn allowed_attachment_types() -> Vec<&'static AllowedAttachmentType> {
+    let configured = env::var("ATTACHMENT_ALLOWED_EXTENSIONS")
+        .ok()
+        .map(|value| {
+            value
+                .split(',')
+                .map(|extension| {
+                    extension
+                        .trim()
+                        .trim_start_matches('.')
+                        .to_ascii_lowercase()
+                })
+                .filter(|extension| !extension.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|extensions| !extensions.is_empty());
+
+    ALLOWED_ATTACHMENT_TYPES
+        .iter()
+        .filter(|allowed| match &configured {
+            Some(extensions) => extensions
+                .iter()
+                .any(|extension| extension == allowed.extension),
+            None => true,
+        })
+        .collect()
+}
+
+/// 라우트의 요청 크기 제한으로 쓴다. 종류별 제한 중 가장 큰 값이다.
+pub fn attachment_size_limit() -> usize {
+    [
+        AttachmentKind::Document,
+        AttachmentKind::Archive,
+        AttachmentKind::Audio,
+    ]
+    .into_iter()
+    .map(AttachmentKind::max_bytes)
+    .max()
+    .unwrap_or_default() as usize
+}
+
+#[derive(Debug)]
+pub enum AttachmentUploadError {
+    Empty,
+    Unsupported {
+        extension: String,
+    },
+    ContentMismatch {
+        extension: &'static str,
+    },
+    TooLarge {
+        kind: AttachmentKind,
+        max_bytes: u64,
+    },
+}
+
+impl From<AttachmentUploadError> for Error {
+    fn from(err: AttachmentUploadError) -> Self {
+        match err {
+            AttachmentUploadError::Empty => Error::from_string(
+                "비어 있는 파일은 업로드할 수 없습니다.",
+                StatusCode::BAD_REQUEST,
+            ),
+            AttachmentUploadError::Unsupported { extension } => {
+                let allowed = allowed_attachment_types()
+                    .iter()
+                    .map(|allowed| allowed.extension)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Error::from_string(
+                    format!(
+                        "허용하지 않는 첨부 파일 형식입니다({}). 허용 형식: {}",
+                        extension, allowed
+                    ),
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                )
+            }
+            AttachmentUploadError::ContentMismatch { extension } => Error::from_string(
+                format!("파일 내용이 .{} 형식이 아닙니다.", extension),
+                StatusCode::BAD_REQUEST,
+            ),
+            AttachmentUploadError::TooLarge { kind, max_bytes } => Error::from_string(
+                format!(
+                    "{} 첨부 파일은 {}바이트까지 올릴 수 있습니다.",
+                    kind.as_str(),
+                    max_bytes
+                ),
+                StatusCode::PAYLOAD_TOO_LARGE,
+            ),
+        }
+    }
+}
+
+/// 파일 이름의 확장자로 허용 형식을 찾고, 내용과 크기가 그 형식의 규칙에 맞는지 확인한다.
+pub fn validate_attachment(
+    origin_name: &str,
+    bytes: &[u8],
+) -> Result<&'static AllowedAttachmentType, AttachmentUploadError> {
+    if bytes.is_empty() {
+        return Err(AttachmentUploadError::Empty);
+    }
+
+    let extension = origin_name
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+    let allowed = allowed_attachment_types()
+        .into_iter()
+        .find(|allowed| allowed.extension == extension)
+        .ok_or(AttachmentUploadError::Unsupported { extension })?;
+
+    if !(allowed.matches)(bytes) {
+        return Err(AttachmentUploadError::ContentMismatch {
+            extension: allowed.extension,
+        });
+    }
+
+    let max_bytes = allowed.kind.max_bytes();
+    if bytes.len() as u64 > max_bytes {
+        return Err(AttachmentUploadError::TooLarge {
+            kind: allowed.kind,
+            max_bytes,
+        });
+    }
+
+    Ok(allowed)
+}
+
+fn db_error(action: &str, err: sqlx::Error) -> Error {
+    Error::from_string(
+        format!("첨부 파일 {} 실패: {}", action, err),
+        StatusCode::INTERNAL_SERVER_ERROR,
+    )
+}
+
+pub fn attachment_response(attachment: AttachmentDb) -> AttachmentResponse {
+    AttachmentResponse {
+        download_path: format!("/attachments/{}/download", attachment.attachment_id),
+        attachment_id: attachment.attachment_id,
+        post_id: attachment.post_id,
+        origin_name: attachment.origin_name,
+        mime_type: attachment.mime_type,
+        kind: attachment.kind,
+        byte_size: attachment.byte_size as u64,
+        uploaded_by: attachment.uploaded_by,
+        uploaded_at: attachment.uploaded_at,
+        download_count: attachment.download_count as u64,
+        last_downloaded_at: attachment.last_downloaded_at,
+    }
+}
+
+/// 검증을 통과한 파일을 저장소에 올리고 `attachments` 행을 만든다.
+/// `post_id`를 주면 호출하는 쪽에서 글 작성자인지 먼저 확인해야 한다.
+pub async fn store_attachment(
+    db: &SqlitePool,
+    storage: &dyn UploadStorage,
+    user_id: &str,
+    post_id: Option<&str>,
+    origin_name: &str,
+    file_bytes: Vec<u8>,
+) -> Result<AttachmentDb, Error> {
+    let allowed = validate_attachment(origin_name, &file_bytes)?;
+
+    let attachment_id = Uuid::new_v4().to_string();
+    let file_name = format!(
+        "{}{}.{}",
+        ATTACHMENT_KEY_PREFIX, attachment_id, allowed.extension
+    );
+    let byte_size = file_bytes.len() as i64;
+    storage
+        .put(&file_name, file_bytes, allowed.mime_type)
+        .await?;
+
+    let inserted = query(
+        r#"
+        INSERT INTO attachments (
+            attachment_id, post_id, file_name, origin_name, file_path, mime_type, kind,
+            byte_size, uploaded_by
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&attachment_id)
+    .bind(post_id)
+    .bind(&file_name)
+    .bind(origin_name)
+    .bind(storage.location(&file_name))
+    .bind(allowed.mime_type)
+    .bind(allowed.kind.as_str())
+    .bind(byte_size)
+    .bind(user_id)
+    .execute(db)
+    .await;
+
+    if let Err(err) = inserted {
+        // 행을 만들지 못했으면 방금 올린 파일도 남기지 않는다.
+        if let Err(delete_err) = storage.delete(&file_name).await {
+            eprintln!(
+                "Error removing attachment file {}: {}",
+                file_name, delete_err
+            );
+        }
+        return Err(db_error("저장", err));
+    }
+
+    fetch_attachment(db, &attachment_id).await
+}
+
+pub async fn fetch_attachment(db: &SqlitePool, attachment_id: &str) -> Result<AttachmentDb, Error> {
+    query_as::<Sqlite, AttachmentDb>(&format!(
+        "SELECT {} FROM attachments WHERE attachment_id = ?",
+        ATTACHMENT_COLUMNS
+    ))
+    .bind(attachment_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| db_error("조회", err))?
+    .ok_or_else(|| {
+        Error::from_string(
+            "해당 id에 해당하는 첨부 파일이 없습니다.",
+            StatusCode::NOT_FOUND,
+        )
+    })
+}
+
+/// 최근 업로드 순으로 첨부 파일을 돌려준다. `post_id`, `kind`, `uploaded_by`로 좁힐 수 있다.
+pub async fn list_attachments(
+    db: &SqlitePool,
+    params: AttachmentListQuery,
+) -> Result<AttachmentListResponse, Error> {
+    let limit = params.limit.unwrap_or(50).clamp(1, 100) as i64;
+    let offset = params.offset.unwrap_or(0) as i64;
+    let filters = [
+        ("post_id", params.post_id),
+        ("kind", params.kind),
+        ("uploaded_by", params.uploaded_by),
+    ];
+
+    let mut count_builder =
+        QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM attachments WHERE 1 = 1");
+    let mut list_builder = QueryBuilder::<Sqlite>::new(format!(
+        "SELECT {} FROM attachments WHERE 1 = 1",
+        ATTACHMENT_COLUMNS
+    ));
+
+    for (column, value) in filters {
+        let Some(value) = value.map(|value| value.trim().to_string()) else {
+            continue;
+        };
+        if value.is_empty() {
+            continue;
+        }
+        for builder in [&mut count_builder, &mut list_builder] {
+            builder.push(format!(" AND {} = ", column));
+            builder.push_bind(value.clone());
+        }
+    }
+
+    let total_count: i64 = count_builder
+        .build_query_scalar()
+        .fetch_one(db)
+        .await
+        .map_err(|err| db_error("개수 조회", err))?;
+
+    list_builder.push(" ORDER BY uploaded_at DESC, attachment_id DESC LIMIT ");
+    list_builder.push_bind(limit);
+    list_builder.push(" OFFSET ");
+    list_builder.push_bind(offset);
+
+    let attachments = list_builder
+        .build_query_as::<AttachmentDb>()
+        .fetch_all(db)
+        .await
+        .map_err(|err| db_error("목록 조회", err))?;
+
+    Ok(AttachmentListResponse {
+        attachments: attachments.into_iter().map(attachment_response).collect(),
+        total_count,
+    })
+}
+
+/// 글에 붙은 첨부 파일은 글 작성자(`ensure_post_owner`)가, 글과 연결되지 않은 첨부 파일은
+/// 올린 본인이 다룰 수 있다. 관리자는 모두 다룰 수 있다.
+pub async fn ensure_attachment_owner(
+    user: &AuthenticatedUser,
+    attachment: &AttachmentDb,
+    db: &SqlitePool,
+) -> Result<(), Error> {
+    if let Some(post_id) = attachment.post_id.as_deref() {
+        return ensure_post_owner(user, post_id, db).await;
+    }
+
+    if user.role == "admin" || attachment.uploaded_by == user.user_id {
+        Ok(())
+    } else {
+        Err(Error::from_string(
+            "본인이 업로드한 첨부 파일만 수정 또는 삭제할 수 있습니다.",
+            StatusCode::FORBIDDEN,
+        ))
+    }
+}
+
+pub async fn record_attachment_download(db: &SqlitePool, attachment_id: &str) -> Result<(), Error> {
+    query(
+        r#"
+        UPDATE attachments
+        SET download_count = download_count + 1, last_downloaded_at = CURRENT_TIMESTAMP
+        WHERE attachment_id = ?
+        "#,
+    )
+    .bind(attachment_id)
+    .execute(db)
+    .await
+    .map_err(|err| db_error("다운로드 기록", err))?;
+
+    Ok(())
+}
+
+/// 한 사용자가 올린 첨부 파일 전체. 탈퇴 처리에서 쓴다.
+pub async fn attachments_uploaded_by(
+    db: &SqlitePool,
+    user_id: &str,
+) -> Result<Vec<AttachmentDb>, Error> {
+    query_as::<Sqlite, AttachmentDb>(&format!(
+        "SELECT {} FROM attachments WHERE uploaded_by = ?",
+        ATTACHMENT_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+    .map_err(|err| db_error("조회", err))
+}
+
+/// 파일과 행을 함께 지운다. 파일이 이미 없으면 행만 지운다.
+pub async fn remove_attachment(
+    db: &SqlitePool,
+    storage: &dyn UploadStorage,
+    attachment: &AttachmentDb,
+) -> Result<(), Error> {
+    query("DELETE FROM attachments WHERE attachment_id = ?")
+        .bind(&attachment.attachment_id)
+        .execute(db)
+        .await
+        .map_err(|err| db_error("삭제", err))?;
+
+    storage.delete(&attachment.file_name).await
+}
+
+pub async fn attachment_file_names(db: &SqlitePool) -> Result<Vec<String>, Error> {
+    query_scalar("SELECT file_name FROM attachments")
+        .fetch_all(db)
+        .await
+        .map_err(|err| db_error("파일 목록 조회", err))
+}
+
+/// `Content-Disposition` 값을 만든다. 오래된 클라이언트용 ASCII 이름(`filename`)과
+/// 원래 이름(`filename*`, RFC 5987)을 함께 보낸다.
+pub fn content_disposition(origin_name: &str, inline: bool) -> String {
+    let disposition = if inline { "inline" } else { "attachment" };
+    let fallback: String = origin_name
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_graphic() && !matches!(ch, '"' | '\\' | ';' | '%') || ch == ' ' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    let mut encoded = String::new();
+    for byte in origin_name.bytes() {
+        if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    format!(
+        "{}; filename=\"{}\"; filename*=UTF-8''{}",
+        disposition, fallback, encoded
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{content_disposition, validate_attachment, AttachmentKind, AttachmentUploadError};
+
+    #[test]
+    fn validate_attachment_checks_extension_content_and_size() {
+        let pdf = validate_attachment("Slides.PDF", b"%PDF-1.7\n...").unwrap();
+        assert_eq!(pdf.mime_type, "application/pdf");
+        assert_eq!(pdf.kind, AttachmentKind::Document);
+
+        let audio = validate_attachment("clip.mp3", b"ID3\x04\x00rest").unwrap();
+        assert_eq!(audio.kind, AttachmentKind::Audio);
+
+        assert!(matches!(
+            validate_attachment("run.exe", b"MZ\x90\x00"),
+            Err(AttachmentUploadError::Unsupported { .. })
+        ));
+        assert!(matches!(
+            validate_attachment("sample.zip", b"<html></html>"),
+            Err(AttachmentUploadError::ContentMismatch { extension: "zip" })
+        ));
+        assert!(matches!(
+            validate_attachment("empty.pdf", b""),
+            Err(AttachmentUploadError::Empty)
+        ));
+    }
+
+    #[test]
+    fn content_disposition_keeps_unicode_name_and_ascii_fallback() {
+        assert_eq!(
+            content_disposition("발표 자료.pdf", false),
+            "attachment; filename=\"__ __.pdf\"; filename*=UTF-8''%EB%B0%9C%ED%91%9C%20%EC%9E%90%EB%A3%8C.pdf"
+        );
+        assert_eq!(
+            content_disposition("a\"b.mp3", true),
+            "inline; filename=\"a_b.mp3\"; filename*=UTF-8''a%22b.mp3"
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use chrono::Utc;
+use sqlx::{query, query_scalar, Pool, Sqlite};
+
+/// 관리자가 비활성화한 계정인지. 계정 행이 없으면 `false`다(토큰 검증은 호출하는 쪽이 이미 했다).
+pub async fn is_account_disabled(db: &Pool<Sqlite>, user_id: &str) -> Result<bool, sqlx::Error> {
+    let disabled: Option<bool> =
+        query_scalar("SELECT disabled_at IS NOT NULL FROM users WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(db)
+            .await?;
+
+    Ok(disabled.unwrap_or(false))
+}
+
+/// 지금 DB에 저장된 역할. 계정 행이 없으면 `None`이다.
+pub async fn account_role(db: &Pool<Sqlite>, user_id: &str) -> Result<Option<String>, sqlx::Error> {
+    query_scalar("SELECT user_role FROM users WHERE user_id = ?")
+        .bind(user_id)
+        .fetch_optional(db)
+        .await
+}
+
+/// 로그인에 성공한 시각을 남긴다. 관리자 사용자 목록의 `last_login_at`이다.
+pub async fn record_login(db: &Pool<Sqlite>, user_id: &str) -> Result<(), sqlx::Error> {
+    query("UPDATE users SET last_login_at = ? WHERE user_id = ?")
+        .bind(Utc::now().format("%Y-%m-%d %H:%M:%S").to_string())
+        .bind(user_id)
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
@@ -6,6 +6,8 @@ use sqlx::{query_scalar, Error as SqlxError, Pool, Sqlite};
 pub struct AuthenticatedUser {
     pub user_id: String,
     pub role: String,
+    /// JWT로 로그인했을 때의 세션(refresh token family) ID. API key나 세션 ID가 없는 예전 토큰이면 `None`.
+    pub session_id: Option<String>,
 }
 
 pub fn current_user(req: &Request) -> Result<&AuthenticatedUser, Error> {
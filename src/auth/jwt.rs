@@ -3,6 +3,8 @@ use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData,
 use poem::{http::StatusCode, Error};
 use serde::{Deserialize, Serialize};
 
+pub const REFRESH_TOKEN_TTL_MINUTES: i64 = 7 * 24 * 60;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
@@ -10,6 +12,13 @@ pub struct Claims {
     pub exp: usize,
     pub iat: usize,
     pub token_type: String,
+    /// refresh 토큰 하나를 가리키는 ID. 회전할 때마다 새로 만든다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    /// 같은 로그인에서 회전해 나온 refresh 토큰 묶음(family)의 ID. 로그인 세션 ID이기도 해서
+    /// access 토큰에도 넣고, `Auth`는 이 세션이 폐기됐으면 토큰을 받지 않는다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_id: Option<String>,
 }
 
 impl Claims {
@@ -27,6 +36,8 @@ impl Claims {
             exp: expiration,
             iat,
             token_type: token_type.to_owned(),
+            jti: None,
+            family_id: None,
         }
     }
 
@@ -85,7 +96,22 @@ impl Claims {
     }
 
     pub fn create_refresh_token(user_id: &str, role: &str, secret: &[u8]) -> Result<String, Error> {
-        let claims = Self::new(user_id, role, "refresh", 7 * 24 * 60);
+        let claims = Self::new(user_id, role, "refresh", REFRESH_TOKEN_TTL_MINUTES);
         claims.to_token(secret)
     }
+
+    /// 서버에 기록하는 refresh 토큰. `/refresh`는 `jti`와 `family_id`가 있는 토큰만 받는다.
+    /// 오류는 응답으로 바꾸지 않고 그대로 돌려주므로 호출하는 쪽에서 상태 코드를 정한다.
+    pub fn create_family_refresh_token(
+        user_id: &str,
+        role: &str,
+        family_id: &str,
+        token_id: &str,
+        secret: &[u8],
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        let mut claims = Self::new(user_id, role, "refresh", REFRESH_TOKEN_TTL_MINUTES);
+        claims.jti = Some(token_id.to_owned());
+        claims.family_id = Some(family_id.to_owned());
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(secret))
+    }
 }
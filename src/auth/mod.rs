@@ -1,4 +1,9 @@
+pub mod account;
 pub mod api_key;
 pub mod authorization;
 pub mod google;
 pub mod jwt;
+pub mod password;
+pub mod refresh_token;
+pub mod totp;
+pub mod webauthn;
@@ -0,0 +1,120 @@
+use std::{fmt, sync::OnceLock};
+
+use bcrypt::{hash, verify, DEFAULT_COST};
+use uuid::Uuid;
+
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+/// bcrypt는 72바이트 뒤를 버리므로 그보다 긴 비밀번호는 받지 않는다.
+pub const MAX_PASSWORD_BYTES: usize = 72;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordPolicyError {
+    TooShort,
+    TooLong,
+    MissingLetterOrDigit,
+    ContainsUserId,
+}
+
+impl fmt::Display for PasswordPolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort => write!(
+                f,
+                "비밀번호는 최소 {}자 이상이어야 합니다.",
+                MIN_PASSWORD_LENGTH
+            ),
+            Self::TooLong => write!(
+                f,
+                "비밀번호는 {}바이트를 넘을 수 없습니다.",
+                MAX_PASSWORD_BYTES
+            ),
+            Self::MissingLetterOrDigit => {
+                write!(f, "비밀번호에는 문자와 숫자가 모두 있어야 합니다.")
+            }
+            Self::ContainsUserId => write!(f, "비밀번호에 아이디(이메일)를 넣을 수 없습니다."),
+        }
+    }
+}
+
+/// 비밀번호 변경과 재설정이 함께 쓰는 정책.
+/// 길이는 글자 수로 재고, 아이디 비교는 이메일의 `@` 앞부분까지 대소문자 없이 본다.
+pub fn check_password_policy(password: &str, user_id: &str) -> Result<(), PasswordPolicyError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(PasswordPolicyError::TooShort);
+    }
+    if password.len() > MAX_PASSWORD_BYTES {
+        return Err(PasswordPolicyError::TooLong);
+    }
+    if !password.chars().any(char::is_alphabetic) || !password.chars().any(|c| c.is_ascii_digit()) {
+        return Err(PasswordPolicyError::MissingLetterOrDigit);
+    }
+
+    let lowered = password.to_lowercase();
+    let local_part = user_id.split('@').next().unwrap_or_default().to_lowercase();
+    if local_part.chars().count() >= 3 && lowered.contains(&local_part) {
+        return Err(PasswordPolicyError::ContainsUserId);
+    }
+
+    Ok(())
+}
+
+/// 저장된 값이 bcrypt 해시면 검증하고, 예전 평문 값이면 그대로 비교한다.
+pub fn matches_password(candidate_password: &str, stored_password: Option<&str>) -> bool {
+    let Some(stored_password) = stored_password else {
+        return false;
+    };
+
+    if is_bcrypt_hash(stored_password) {
+        verify(candidate_password, stored_password).unwrap_or(false)
+    } else {
+        candidate_password == stored_password
+    }
+}
+
+/// 계정이 없거나 비밀번호가 없는(Google 전용) 계정이어도 bcrypt 검증 한 번만큼 시간을 쓴다.
+/// 응답 시간으로 가입 여부를 알아낼 수 없게 로그인에서 쓴다.
+pub fn matches_password_uniform(candidate_password: &str, stored_password: Option<&str>) -> bool {
+    if stored_password.is_some_and(is_bcrypt_hash) {
+        return matches_password(candidate_password, stored_password);
+    }
+
+    static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+    let dummy_hash = DUMMY_HASH.get_or_init(|| {
+        hash(Uuid::new_v4().simple().to_string(), DEFAULT_COST).expect("bcrypt hashing failed")
+    });
+    let _ = verify(candidate_password, dummy_hash);
+    matches_password(candidate_password, stored_password)
+}
+
+pub fn is_bcrypt_hash(value: &str) -> bool {
+    value.starts_with("$2a$") || value.starts_with("$2b$") || value.starts_with("$2y$")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_rejects_weak_passwords_with_the_first_broken_rule() {
+        assert_eq!(
+            check_password_policy("abc123", "me@example.com"),
+            Err(PasswordPolicyError::TooShort)
+        );
+        assert_eq!(
+            check_password_policy(&"a1".repeat(40), "me@example.com"),
+            Err(PasswordPolicyError::TooLong)
+        );
+        assert_eq!(
+            check_password_policy("onlyletters", "me@example.com"),
+            Err(PasswordPolicyError::MissingLetterOrDigit)
+        );
+        assert_eq!(
+            check_password_policy("Tyange2026!", "tyange@example.com"),
+            Err(PasswordPolicyError::ContainsUserId)
+        );
+        assert_eq!(
+            check_password_policy("비밀번호는길게1", "me@example.com"),
+            Ok(())
+        );
+    }
+}
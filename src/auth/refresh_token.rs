@@ -0,0 +1,265 @@
+use chrono::{Duration, Utc};
+use sqlx::{query, query_as, query_scalar, FromRow, Pool, Sqlite};
+use uuid::Uuid;
+
+use crate::auth::jwt::REFRESH_TOKEN_TTL_MINUTES;
+
+/// refresh 토큰을 한 번 쓰려 했을 때의 결과.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshTokenUse {
+    /// 처음 쓰는 토큰이라 사용 처리했다. 같은 family로 새 토큰을 발급하면 된다.
+    Fresh,
+    /// 이미 회전된 토큰을 다시 썼다. 탈취로 보고 family 전체를 폐기했다.
+    Reused,
+    /// family가 로그아웃 등으로 이미 폐기됐다.
+    Revoked,
+    /// 서버에 기록이 없는 토큰.
+    Unknown,
+}
+
+#[derive(Debug, FromRow)]
+struct RefreshTokenState {
+    used_at: Option<String>,
+    revoked_at: Option<String>,
+}
+
+fn now_timestamp() -> String {
+    Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// 세션의 `last_seen_at`은 요청마다 쓰지 않고 이 간격(분)보다 오래됐을 때만 고친다.
+const SESSION_TOUCH_INTERVAL_MINUTES: i64 = 5;
+
+/// 로그인 한 번에 family 하나를 만든다. family가 곧 로그인 세션이고, `family_id`가 세션 ID다.
+/// 어느 기기인지 보여 주려고 로그인한 요청의 User-Agent와 IP를 함께 남긴다.
+pub async fn create_refresh_family(
+    db: &Pool<Sqlite>,
+    user_id: &str,
+    user_agent: Option<&str>,
+    ip_address: Option<&str>,
+) -> Result<String, sqlx::Error> {
+    let family_id = Uuid::new_v4().to_string();
+    query(
+        r#"
+        INSERT INTO refresh_token_families (family_id, user_id, user_agent, ip_address, last_seen_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&family_id)
+    .bind(user_id)
+    .bind(user_agent)
+    .bind(ip_address)
+    .bind(now_timestamp())
+    .execute(db)
+    .await?;
+
+    Ok(family_id)
+}
+
+/// access 토큰의 세션이 아직 살아 있는지. 다른 사용자의 세션이거나 기록이 없으면 `false`.
+pub async fn is_session_active(
+    db: &Pool<Sqlite>,
+    user_id: &str,
+    family_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let revoked_at: Option<Option<String>> = query_scalar(
+        "SELECT revoked_at FROM refresh_token_families WHERE family_id = ? AND user_id = ?",
+    )
+    .bind(family_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(matches!(revoked_at, Some(None)))
+}
+
+/// family(세션)를 가진 계정. 기록이 없으면 `None`.
+pub async fn family_user_id(
+    db: &Pool<Sqlite>,
+    family_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    query_scalar("SELECT user_id FROM refresh_token_families WHERE family_id = ?")
+        .bind(family_id)
+        .fetch_optional(db)
+        .await
+}
+
+/// 세션을 마지막으로 쓴 시각을 남긴다. 최근에 이미 남겼으면 쓰지 않는다.
+pub async fn touch_session(db: &Pool<Sqlite>, family_id: &str) -> Result<(), sqlx::Error> {
+    let stale_before = (Utc::now() - Duration::minutes(SESSION_TOUCH_INTERVAL_MINUTES))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    query(
+        r#"
+        UPDATE refresh_token_families
+        SET last_seen_at = ?
+        WHERE family_id = ? AND (last_seen_at IS NULL OR last_seen_at < ?)
+        "#,
+    )
+    .bind(now_timestamp())
+    .bind(family_id)
+    .bind(stale_before)
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// family에 새 토큰 ID를 기록하고 돌려준다. 토큰 서명은 호출하는 쪽에서 이 ID를 `jti`로 넣어 만든다.
+pub async fn record_refresh_token(
+    db: &Pool<Sqlite>,
+    family_id: &str,
+) -> Result<String, sqlx::Error> {
+    let token_id = Uuid::new_v4().to_string();
+    let expires_at = (Utc::now() + Duration::minutes(REFRESH_TOKEN_TTL_MINUTES))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    query(
+        r#"
+        INSERT INTO refresh_tokens (token_id, family_id, expires_at)
+        VALUES (?, ?, ?)
+        "#,
+    )
+    .bind(&token_id)
+    .bind(family_id)
+    .bind(expires_at)
+    .execute(db)
+    .await?;
+
+    Ok(token_id)
+}
+
+/// 토큰을 사용 처리한다. 이미 쓴 토큰이면 family 전체를 `reuse`로 폐기한다.
+pub async fn consume_refresh_token(
+    db: &Pool<Sqlite>,
+    family_id: &str,
+    token_id: &str,
+) -> Result<RefreshTokenUse, sqlx::Error> {
+    let state = query_as::<_, RefreshTokenState>(
+        r#"
+        SELECT t.used_at, f.revoked_at
+        FROM refresh_tokens t
+        JOIN refresh_token_families f ON f.family_id = t.family_id
+        WHERE t.token_id = ? AND t.family_id = ?
+        "#,
+    )
+    .bind(token_id)
+    .bind(family_id)
+    .fetch_optional(db)
+    .await?;
+
+    let Some(state) = state else {
+        return Ok(RefreshTokenUse::Unknown);
+    };
+    if state.revoked_at.is_some() {
+        return Ok(RefreshTokenUse::Revoked);
+    }
+
+    // 조건부 UPDATE로 표시해서, 같은 토큰으로 동시에 들어온 요청 중 하나만 통과시킨다.
+    let marked = if state.used_at.is_none() {
+        query(
+            r#"
+            UPDATE refresh_tokens
+            SET used_at = ?
+            WHERE token_id = ? AND used_at IS NULL
+            "#,
+        )
+        .bind(now_timestamp())
+        .bind(token_id)
+        .execute(db)
+        .await?
+        .rows_affected()
+            == 1
+    } else {
+        false
+    };
+
+    if marked {
+        return Ok(RefreshTokenUse::Fresh);
+    }
+
+    revoke_refresh_family(db, family_id, "reuse").await?;
+    Ok(RefreshTokenUse::Reused)
+}
+
+/// family를 폐기한다. 이미 폐기된 family는 처음 사유를 유지하고 `false`를 돌려준다.
+pub async fn revoke_refresh_family(
+    db: &Pool<Sqlite>,
+    family_id: &str,
+    reason: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = query(
+        r#"
+        UPDATE refresh_token_families
+        SET revoked_at = ?, revoked_reason = ?
+        WHERE family_id = ? AND revoked_at IS NULL
+        "#,
+    )
+    .bind(now_timestamp())
+    .bind(reason)
+    .bind(family_id)
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 사용자의 살아 있는 family를 모두 폐기하고 폐기한 개수를 돌려준다. 비밀번호를 재설정하거나 계정을 막을 때 쓴다.
+pub async fn revoke_user_refresh_families(
+    db: &Pool<Sqlite>,
+    user_id: &str,
+    reason: &str,
+) -> Result<u64, sqlx::Error> {
+    let result = query(
+        r#"
+        UPDATE refresh_token_families
+        SET revoked_at = ?, revoked_reason = ?
+        WHERE user_id = ? AND revoked_at IS NULL
+        "#,
+    )
+    .bind(now_timestamp())
+    .bind(reason)
+    .bind(user_id)
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// `keep_family_id`만 남기고 사용자의 나머지 family를 폐기한다. 비밀번호를 바꾼 기기는 로그인을 유지한다.
+pub async fn revoke_other_refresh_families(
+    db: &Pool<Sqlite>,
+    user_id: &str,
+    keep_family_id: &str,
+    reason: &str,
+) -> Result<u64, sqlx::Error> {
+    let result = query(
+        r#"
+        UPDATE refresh_token_families
+        SET revoked_at = ?, revoked_reason = ?
+        WHERE user_id = ? AND family_id != ? AND revoked_at IS NULL
+        "#,
+    )
+    .bind(now_timestamp())
+    .bind(reason)
+    .bind(user_id)
+    .bind(keep_family_id)
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// 아직 쓰지 않은 refresh 토큰 기록을 지운다. 지운 토큰은 서버 기록이 없는 토큰이 되어 family를 건드리지 않고
+/// 거절되므로, 같은 세션에 새 토큰을 발급하기 전에 예전 토큰만 무효로 할 때 쓴다.
+pub async fn discard_unused_refresh_tokens(
+    db: &Pool<Sqlite>,
+    family_id: &str,
+) -> Result<(), sqlx::Error> {
+    query("DELETE FROM refresh_tokens WHERE family_id = ? AND used_at IS NULL")
+        .bind(family_id)
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,204 @@
+//! RFC 6238 TOTP(HMAC-SHA1, 30초, 6자리). 인증 앱 대부분이 이 기본값만 제대로 지원한다.
+
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use url::form_urlencoded::byte_serialize;
+
+pub const TOTP_STEP_SECONDS: i64 = 30;
+pub const TOTP_DIGITS: u32 = 6;
+pub const RECOVERY_CODE_COUNT: usize = 10;
+/// 복구 코드 하나의 무작위 바이트 수(80비트, hex 20자리).
+const RECOVERY_CODE_BYTES: usize = 10;
+/// 휴대폰 시계가 조금 틀려도 받도록 앞뒤 한 구간(±30초)까지 본다.
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+/// RFC 4226이 권하는 160비트 키.
+const SECRET_BYTES: usize = 20;
+
+/// 새 비밀키를 base32(패딩 없음)로 만든다. 인증 앱에 그대로 입력할 수 있는 형태다.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; SECRET_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    BASE32_NOPAD.encode(&bytes)
+}
+
+/// 공백, 소문자, `=` 패딩이 섞여 있어도 받는다.
+pub fn decode_secret(secret: &str) -> Option<Vec<u8>> {
+    let normalized: String = secret
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '=')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    BASE32_NOPAD.decode(normalized.as_bytes()).ok()
+}
+
+fn hotp(key: &[u8], counter: u64, digits: u32) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC은 어떤 길이의 키도 받는다");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    binary % 10u32.pow(digits)
+}
+
+pub fn time_step(unix_time: i64) -> i64 {
+    unix_time.div_euclid(TOTP_STEP_SECONDS)
+}
+
+/// `unix_time`이 속한 구간의 코드.
+pub fn code_at(key: &[u8], unix_time: i64) -> String {
+    format!(
+        "{:0width$}",
+        hotp(key, time_step(unix_time) as u64, TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    )
+}
+
+/// 코드가 맞으면 맞은 구간 번호를 돌려준다. `last_used_step` 이하의 구간은 보지 않으므로
+/// 한 번 쓴 코드를 같은 30초 안에 다시 쓸 수 없다.
+pub fn verify_code(
+    key: &[u8],
+    code: &str,
+    unix_time: i64,
+    last_used_step: Option<i64>,
+) -> Option<i64> {
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    if code.len() != TOTP_DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let expected: u32 = code.parse().ok()?;
+
+    let current = time_step(unix_time);
+    (current - ALLOWED_DRIFT_STEPS..=current + ALLOWED_DRIFT_STEPS)
+        .filter(|step| *step >= 0 && last_used_step.is_none_or(|last| *step > last))
+        .find(|step| hotp(key, *step as u64, TOTP_DIGITS) == expected)
+}
+
+fn encode_component(value: &str) -> String {
+    byte_serialize(value.as_bytes())
+        .collect::<String>()
+        .replace('+', "%20")
+}
+
+/// 인증 앱 QR 코드에 넣는 `otpauth://totp/...` URI.
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        encode_component(issuer),
+        encode_component(account),
+        secret,
+        encode_component(issuer),
+        TOTP_DIGITS,
+        TOTP_STEP_SECONDS
+    )
+}
+
+/// `xxxxx-xxxxx-xxxxx-xxxxx` 꼴(80비트)의 복구 코드. 원문은 만들 때 한 번만 보여 준다.
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; RECOVERY_CODE_BYTES];
+            OsRng.fill_bytes(&mut bytes);
+            let hex = hex::encode(bytes);
+            format!(
+                "{}-{}-{}-{}",
+                &hex[0..5],
+                &hex[5..10],
+                &hex[10..15],
+                &hex[15..20]
+            )
+        })
+        .collect()
+}
+
+/// 저장과 비교에 쓰는 복구 코드 SHA-256. 대소문자, `-`, 공백은 무시한다.
+pub fn recovery_code_hash(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let mut hasher = Sha256::new();
+    hasher.update(normalized.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RFC_KEY: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn codes_match_the_rfc_test_vectors() {
+        // RFC 4226 부록 D (6자리)
+        assert_eq!(hotp(RFC_KEY, 0, 6), 755224);
+        assert_eq!(hotp(RFC_KEY, 9, 6), 520489);
+        // RFC 6238 부록 B (SHA1, 8자리)
+        for (time, expected) in [
+            (59, 94287082),
+            (1111111109, 7081804),
+            (1234567890, 89005924),
+            (2000000000, 69279037),
+        ] {
+            assert_eq!(hotp(RFC_KEY, time_step(time) as u64, 8), expected);
+        }
+        assert_eq!(code_at(RFC_KEY, 1111111109), "081804");
+    }
+
+    #[test]
+    fn verification_allows_one_step_of_drift_and_rejects_reuse() {
+        let secret = generate_secret();
+        assert_eq!(secret.len(), 32);
+        let key = decode_secret(&secret.to_lowercase()).unwrap();
+        let now = 1_700_000_000;
+        let step = time_step(now);
+
+        let previous = code_at(&key, now - TOTP_STEP_SECONDS);
+        assert_eq!(verify_code(&key, &previous, now, None), Some(step - 1));
+        assert_eq!(
+            verify_code(&key, &code_at(&key, now), now, None),
+            Some(step)
+        );
+        assert_eq!(
+            verify_code(&key, &code_at(&key, now - 2 * TOTP_STEP_SECONDS), now, None),
+            None
+        );
+        assert_eq!(
+            verify_code(&key, &code_at(&key, now), now, Some(step)),
+            None
+        );
+        assert_eq!(verify_code(&key, "12345", now, None), None);
+    }
+
+    #[test]
+    fn recovery_codes_hash_without_formatting() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        let code = &codes[0];
+        let groups: Vec<&str> = code.split('-').collect();
+        assert_eq!(groups.len(), 4);
+        assert!(groups
+            .iter()
+            .all(|group| group.len() == 5 && group.chars().all(|c| c.is_ascii_hexdigit())));
+        assert_eq!(
+            recovery_code_hash(code),
+            recovery_code_hash(&code.replace('-', " ").to_uppercase())
+        );
+        assert_ne!(recovery_code_hash(code), recovery_code_hash(&codes[1]));
+
+        let uri = otpauth_uri("tyange CMS", "me@example.com", "ABC");
+        assert_eq!(
+            uri,
+            "otpauth://totp/tyange%20CMS:me%40example.com?secret=ABC&issuer=tyange%20CMS&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}
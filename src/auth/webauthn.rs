@@ -0,0 +1,263 @@
+//! WebAuthn 등록/인증 응답 검증.
+//!
+//! 패스키 로그인에 필요한 만큼만 구현한다. 공개키는 ES256(P-256)만 받고, attestation은 요청하지 않으므로
+//! (`attestation: "none"`) `attStmt`는 보지 않는다. 사용자 확인(UV)이 없는 응답은 받지 않는다.
+
+use std::fmt;
+
+use ciborium::value::Value;
+use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// COSE 알고리즘 번호. ES256(ECDSA P-256 + SHA-256).
+pub const COSE_ALG_ES256: i64 = -7;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebauthnError {
+    Encoding,
+    ClientData,
+    ChallengeMismatch,
+    OriginMismatch,
+    RpIdMismatch,
+    UserNotVerified,
+    MissingCredential,
+    UnsupportedKey,
+    InvalidSignature,
+    CounterRegression,
+}
+
+impl fmt::Display for WebauthnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::Encoding => "패스키 응답 형식이 올바르지 않습니다.",
+            Self::ClientData => "패스키 clientDataJSON이 올바르지 않습니다.",
+            Self::ChallengeMismatch => "패스키 challenge가 맞지 않습니다.",
+            Self::OriginMismatch => "허용하지 않은 origin에서 만든 패스키 응답입니다.",
+            Self::RpIdMismatch => "다른 사이트(RP ID)용 패스키 응답입니다.",
+            Self::UserNotVerified => "기기에서 사용자 확인(생체 인증이나 PIN)이 필요합니다.",
+            Self::MissingCredential => "패스키 응답에 공개키가 없습니다.",
+            Self::UnsupportedKey => "ES256(P-256) 패스키만 등록할 수 있습니다.",
+            Self::InvalidSignature => "패스키 서명이 맞지 않습니다.",
+            Self::CounterRegression => {
+                "패스키 서명 횟수가 줄었습니다. 복제된 인증기일 수 있습니다."
+            }
+        };
+        f.write_str(message)
+    }
+}
+
+/// 이 서버의 relying party. `id`는 도메인(예: `tyange.com`), `origins`는 브라우저가 보내는 origin 목록이다.
+#[derive(Debug, Clone)]
+pub struct RelyingParty {
+    pub id: String,
+    pub name: String,
+    pub origins: Vec<String>,
+}
+
+/// 등록이 끝난 credential. `public_key`는 SEC1 비압축 형식(`0x04 || x || y`)이다.
+#[derive(Debug, Clone)]
+pub struct RegisteredCredential {
+    pub credential_id: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub sign_count: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+    origin: String,
+    #[serde(default, rename = "crossOrigin")]
+    cross_origin: bool,
+}
+
+struct AuthenticatorData<'a> {
+    rp_id_hash: &'a [u8],
+    flags: u8,
+    sign_count: u32,
+    attested: &'a [u8],
+}
+
+pub fn encode_base64url(bytes: &[u8]) -> String {
+    base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
+
+/// 브라우저에 따라 패딩이 붙어 오기도 해서 `=`는 떼고 읽는다.
+pub fn decode_base64url(value: &str) -> Result<Vec<u8>, WebauthnError> {
+    base64::decode_config(value.trim_end_matches('='), base64::URL_SAFE_NO_PAD)
+        .map_err(|_| WebauthnError::Encoding)
+}
+
+fn sha256(bytes: &[u8]) -> Vec<u8> {
+    Sha256::digest(bytes).to_vec()
+}
+
+/// clientDataJSON에 들어 있는 challenge(base64url). 서버는 이 값으로 저장해 둔 challenge를 찾는다.
+pub fn client_data_challenge(client_data_json: &[u8]) -> Result<String, WebauthnError> {
+    let client_data: ClientData =
+        serde_json::from_slice(client_data_json).map_err(|_| WebauthnError::ClientData)?;
+    Ok(client_data.challenge)
+}
+
+fn check_client_data(
+    rp: &RelyingParty,
+    client_data_json: &[u8],
+    expected_type: &str,
+    expected_challenge: &str,
+) -> Result<(), WebauthnError> {
+    let client_data: ClientData =
+        serde_json::from_slice(client_data_json).map_err(|_| WebauthnError::ClientData)?;
+    if client_data.kind != expected_type {
+        return Err(WebauthnError::ClientData);
+    }
+    if client_data.challenge != expected_challenge {
+        return Err(WebauthnError::ChallengeMismatch);
+    }
+    if client_data.cross_origin || !rp.origins.contains(&client_data.origin) {
+        return Err(WebauthnError::OriginMismatch);
+    }
+    Ok(())
+}
+
+fn parse_authenticator_data(bytes: &[u8]) -> Result<AuthenticatorData<'_>, WebauthnError> {
+    if bytes.len() < 37 {
+        return Err(WebauthnError::Encoding);
+    }
+    Ok(AuthenticatorData {
+        rp_id_hash: &bytes[..32],
+        flags: bytes[32],
+        sign_count: u32::from_be_bytes([bytes[33], bytes[34], bytes[35], bytes[36]]),
+        attested: &bytes[37..],
+    })
+}
+
+fn check_authenticator_data(
+    rp: &RelyingParty,
+    data: &AuthenticatorData<'_>,
+) -> Result<(), WebauthnError> {
+    if data.rp_id_hash != sha256(rp.id.as_bytes()).as_slice() {
+        return Err(WebauthnError::RpIdMismatch);
+    }
+    if data.flags & FLAG_USER_PRESENT == 0 || data.flags & FLAG_USER_VERIFIED == 0 {
+        return Err(WebauthnError::UserNotVerified);
+    }
+    Ok(())
+}
+
+fn map_get<'a>(map: &'a [(Value, Value)], key: &Value) -> Option<&'a Value> {
+    map.iter()
+        .find(|(candidate, _)| candidate == key)
+        .map(|(_, value)| value)
+}
+
+fn integer_key(key: i64) -> Value {
+    Value::Integer(key.into())
+}
+
+/// COSE_Key(EC2, ES256, P-256)를 SEC1 비압축 공개키로 바꾼다.
+fn cose_to_sec1(cose_key: &Value) -> Result<Vec<u8>, WebauthnError> {
+    let map = cose_key.as_map().ok_or(WebauthnError::Encoding)?;
+    let int = |key: i64| {
+        map_get(map, &integer_key(key))
+            .and_then(Value::as_integer)
+            .map(i128::from)
+    };
+    let bytes = |key: i64| map_get(map, &integer_key(key)).and_then(Value::as_bytes);
+
+    // kty 2 = EC2, crv 1 = P-256
+    if int(1) != Some(2) || int(3) != Some(COSE_ALG_ES256 as i128) || int(-1) != Some(1) {
+        return Err(WebauthnError::UnsupportedKey);
+    }
+    let (Some(x), Some(y)) = (bytes(-2), bytes(-3)) else {
+        return Err(WebauthnError::UnsupportedKey);
+    };
+    if x.len() != 32 || y.len() != 32 {
+        return Err(WebauthnError::UnsupportedKey);
+    }
+
+    let mut public_key = Vec::with_capacity(65);
+    public_key.push(0x04);
+    public_key.extend_from_slice(x);
+    public_key.extend_from_slice(y);
+    VerifyingKey::from_sec1_bytes(&public_key).map_err(|_| WebauthnError::UnsupportedKey)?;
+    Ok(public_key)
+}
+
+/// `navigator.credentials.create()` 응답을 확인하고 저장할 credential을 돌려준다.
+pub fn verify_registration(
+    rp: &RelyingParty,
+    expected_challenge: &str,
+    client_data_json: &[u8],
+    attestation_object: &[u8],
+) -> Result<RegisteredCredential, WebauthnError> {
+    check_client_data(rp, client_data_json, "webauthn.create", expected_challenge)?;
+
+    let attestation: Value =
+        ciborium::de::from_reader(attestation_object).map_err(|_| WebauthnError::Encoding)?;
+    let auth_data_bytes = attestation
+        .as_map()
+        .and_then(|map| map_get(map, &Value::Text("authData".to_string())))
+        .and_then(Value::as_bytes)
+        .ok_or(WebauthnError::Encoding)?;
+    let auth_data = parse_authenticator_data(auth_data_bytes)?;
+    check_authenticator_data(rp, &auth_data)?;
+
+    // attestedCredentialData: aaguid(16) || credentialIdLength(2) || credentialId || credentialPublicKey
+    if auth_data.flags & FLAG_ATTESTED_CREDENTIAL == 0 || auth_data.attested.len() < 18 {
+        return Err(WebauthnError::MissingCredential);
+    }
+    let attested = auth_data.attested;
+    let id_length = u16::from_be_bytes([attested[16], attested[17]]) as usize;
+    let rest = &attested[18..];
+    if rest.len() < id_length {
+        return Err(WebauthnError::Encoding);
+    }
+    let (credential_id, key_bytes) = rest.split_at(id_length);
+    let cose_key: Value =
+        ciborium::de::from_reader(key_bytes).map_err(|_| WebauthnError::Encoding)?;
+
+    Ok(RegisteredCredential {
+        credential_id: credential_id.to_vec(),
+        public_key: cose_to_sec1(&cose_key)?,
+        sign_count: auth_data.sign_count,
+    })
+}
+
+/// `navigator.credentials.get()` 응답을 확인하고 새 서명 횟수를 돌려준다.
+/// 횟수를 세지 않는 인증기(항상 0)도 있어서 둘 다 0이면 비교하지 않는다.
+pub fn verify_assertion(
+    rp: &RelyingParty,
+    expected_challenge: &str,
+    client_data_json: &[u8],
+    authenticator_data: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+    stored_sign_count: u32,
+) -> Result<u32, WebauthnError> {
+    check_client_data(rp, client_data_json, "webauthn.get", expected_challenge)?;
+    let auth_data = parse_authenticator_data(authenticator_data)?;
+    check_authenticator_data(rp, &auth_data)?;
+
+    let verifying_key =
+        VerifyingKey::from_sec1_bytes(public_key).map_err(|_| WebauthnError::UnsupportedKey)?;
+    let signature = Signature::from_der(signature).map_err(|_| WebauthnError::InvalidSignature)?;
+    let mut signed = authenticator_data.to_vec();
+    signed.extend_from_slice(&sha256(client_data_json));
+    verifying_key
+        .verify(&signed, &signature)
+        .map_err(|_| WebauthnError::InvalidSignature)?;
+
+    if (auth_data.sign_count != 0 || stored_sign_count != 0)
+        && auth_data.sign_count <= stored_sign_count
+    {
+        return Err(WebauthnError::CounterRegression);
+    }
+
+    Ok(auth_data.sign_count)
+}
@@ -9,6 +9,7 @@ use serde::Serialize;
 const DEFAULT_API_BASE: &str = "https://api.github.com";
 const DEFAULT_REPO: &str = "tyange/tyange-blog";
 const DEFAULT_EVENT_TYPE: &str = "cms-content-changed";
+const DEFAULT_PORTFOLIO_EVENT_TYPE: &str = "cms-portfolio-published";
 const SOURCE_NAME: &str = "tyange-cms-api";
 const GITHUB_API_VERSION: &str = "2022-11-28";
 
@@ -69,13 +70,15 @@ struct BlogRedeployConfig {
     api_base: String,
     repo: String,
     event_type: String,
+    portfolio_repo: String,
+    portfolio_event_type: String,
     token: String,
 }
 
 #[derive(Debug, Serialize)]
-struct RepositoryDispatchRequest<'a> {
+struct RepositoryDispatchRequest<'a, P> {
     event_type: &'a str,
-    client_payload: RepositoryDispatchPayload<'a>,
+    client_payload: P,
 }
 
 #[derive(Debug, Serialize)]
@@ -86,6 +89,16 @@ struct RepositoryDispatchPayload<'a> {
     visibility: &'a str,
 }
 
+#[derive(Debug, Serialize)]
+struct PortfolioDispatchPayload<'a> {
+    source: &'a str,
+    content_event: &'a str,
+    content_type: &'a str,
+    slug: &'a str,
+    lang: &'a str,
+    version_number: i64,
+}
+
 #[derive(Debug)]
 struct DispatchFailureLog {
     content_event: BlogContentEvent,
@@ -141,58 +154,23 @@ impl BlogRedeployService {
                     },
                 };
 
-                let url = format!(
-                    "{}/repos/{}/dispatches",
-                    config.api_base.trim_end_matches('/'),
-                    config.repo
-                );
-
-                match self
-                    .client
-                    .post(url)
-                    .header(ACCEPT, "application/vnd.github+json")
-                    .header(AUTHORIZATION, format!("Bearer {}", config.token))
-                    .header("X-GitHub-Api-Version", GITHUB_API_VERSION)
-                    .header(USER_AGENT, SOURCE_NAME)
-                    .json(&payload)
-                    .send()
-                    .await
-                {
-                    Ok(response) if response.status() == StatusCode::NO_CONTENT => {
+                match self.send_dispatch(config, &config.repo, &payload).await {
+                    Ok(status) => {
                         println!(
                             "blog redeploy dispatch accepted: content_event={}, post_id={}, visibility={}, github_status={}",
                             content_event.as_str(),
                             post_id,
                             visibility.as_str(),
-                            response.status().as_u16()
+                            status.as_u16()
                         );
                     }
-                    Ok(response) => {
-                        let status = response.status();
-                        let body = response.text().await.unwrap_or_default();
-                        self.log_failure(DispatchFailureLog {
-                            content_event,
-                            post_id: post_id.to_string(),
-                            visibility,
-                            status: Some(status),
-                            message: if body.is_empty() {
-                                "GitHub repository_dispatch 호출이 204를 반환하지 않았습니다."
-                                    .to_string()
-                            } else {
-                                format!(
-                                    "GitHub repository_dispatch 호출이 204를 반환하지 않았습니다. body={}",
-                                    body
-                                )
-                            },
-                        });
-                    }
-                    Err(error) => {
+                    Err((status, message)) => {
                         self.log_failure(DispatchFailureLog {
                             content_event,
                             post_id: post_id.to_string(),
                             visibility,
-                            status: None,
-                            message: error.to_string(),
+                            status,
+                            message,
                         });
                     }
                 }
@@ -206,6 +184,113 @@ impl BlogRedeployService {
         }
     }
 
+    /// 포트폴리오를 발행했을 때 사이트 빌드를 요청한다. 토큰과 API 주소는 블로그 설정을 같이 쓴다.
+    pub async fn dispatch_portfolio_publish(&self, slug: &str, lang: &str, version_number: i64) {
+        match &self.mode {
+            BlogRedeployMode::Disabled { reason } => {
+                eprintln!(
+                    "portfolio redeploy dispatch failed: slug={}, lang={}, version_number={}, github_status=none, error={}",
+                    slug, lang, version_number, reason
+                );
+            }
+            BlogRedeployMode::GitHub { config } => {
+                let payload = RepositoryDispatchRequest {
+                    event_type: &config.portfolio_event_type,
+                    client_payload: PortfolioDispatchPayload {
+                        source: SOURCE_NAME,
+                        content_event: BlogContentEvent::Publish.as_str(),
+                        content_type: "portfolio",
+                        slug,
+                        lang,
+                        version_number,
+                    },
+                };
+
+                match self
+                    .send_dispatch(config, &config.portfolio_repo, &payload)
+                    .await
+                {
+                    Ok(status) => {
+                        println!(
+                            "portfolio redeploy dispatch accepted: slug={}, lang={}, version_number={}, github_status={}",
+                            slug,
+                            lang,
+                            version_number,
+                            status.as_u16()
+                        );
+                    }
+                    Err((status, message)) => {
+                        eprintln!(
+                            "portfolio redeploy dispatch failed: slug={}, lang={}, version_number={}, github_status={}, error={}",
+                            slug,
+                            lang,
+                            version_number,
+                            status
+                                .map(|status| status.as_u16().to_string())
+                                .unwrap_or_else(|| "none".to_string()),
+                            message
+                        );
+                    }
+                }
+            }
+            #[cfg(test)]
+            BlogRedeployMode::Mock { handle } => {
+                handle
+                    .portfolio_calls
+                    .lock()
+                    .await
+                    .push(MockPortfolioDispatchCall {
+                        slug: slug.to_string(),
+                        lang: lang.to_string(),
+                        version_number,
+                    });
+            }
+        }
+    }
+
+    async fn send_dispatch<P: Serialize>(
+        &self,
+        config: &BlogRedeployConfig,
+        repo: &str,
+        payload: &RepositoryDispatchRequest<'_, P>,
+    ) -> Result<StatusCode, (Option<StatusCode>, String)> {
+        let url = format!(
+            "{}/repos/{}/dispatches",
+            config.api_base.trim_end_matches('/'),
+            repo
+        );
+
+        match self
+            .client
+            .post(url)
+            .header(ACCEPT, "application/vnd.github+json")
+            .header(AUTHORIZATION, format!("Bearer {}", config.token))
+            .header("X-GitHub-Api-Version", GITHUB_API_VERSION)
+            .header(USER_AGENT, SOURCE_NAME)
+            .json(payload)
+            .send()
+            .await
+        {
+            Ok(response) if response.status() == StatusCode::NO_CONTENT => Ok(response.status()),
+            Ok(response) => {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                Err((
+                    Some(status),
+                    if body.is_empty() {
+                        "GitHub repository_dispatch 호출이 204를 반환하지 않았습니다.".to_string()
+                    } else {
+                        format!(
+                            "GitHub repository_dispatch 호출이 204를 반환하지 않았습니다. body={}",
+                            body
+                        )
+                    },
+                ))
+            }
+            Err(error) => Err((None, error.to_string())),
+        }
+    }
+
     fn log_failure(&self, failure: DispatchFailureLog) {
         eprintln!(
             "blog redeploy dispatch failed: content_event={}, post_id={}, visibility={}, github_status={}, error={}",
@@ -254,6 +339,19 @@ impl BlogRedeployConfig {
             .filter(|value| !value.is_empty())
             .unwrap_or_else(|| DEFAULT_EVENT_TYPE.to_string());
 
+        // 포트폴리오 사이트가 따로 있으면 저장소와 이벤트 이름만 바꿔 쓴다.
+        let portfolio_repo = env::var("TYANGE_PORTFOLIO_REDEPLOY_REPO")
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| repo.clone());
+
+        let portfolio_event_type = env::var("TYANGE_PORTFOLIO_REDEPLOY_EVENT_TYPE")
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| DEFAULT_PORTFOLIO_EVENT_TYPE.to_string());
+
         let api_base = env::var("TYANGE_BLOG_REDEPLOY_API_BASE")
             .ok()
             .map(|value| value.trim().to_string())
@@ -264,6 +362,8 @@ impl BlogRedeployConfig {
             api_base,
             repo,
             event_type,
+            portfolio_repo,
+            portfolio_event_type,
             token,
         })
     }
@@ -292,6 +392,14 @@ pub struct MockDispatchCall {
     pub visibility: BlogVisibility,
 }
 
+#[cfg(test)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockPortfolioDispatchCall {
+    pub slug: String,
+    pub lang: String,
+    pub version_number: i64,
+}
+
 #[cfg(test)]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MockDispatchFailure {
@@ -313,6 +421,7 @@ struct MockFailurePlan {
 #[derive(Default)]
 pub struct MockBlogRedeployHandle {
     calls: tokio::sync::Mutex<Vec<MockDispatchCall>>,
+    portfolio_calls: tokio::sync::Mutex<Vec<MockPortfolioDispatchCall>>,
     failures: tokio::sync::Mutex<Vec<MockDispatchFailure>>,
     next_failure: tokio::sync::Mutex<Option<MockFailurePlan>>,
 }
@@ -360,6 +469,10 @@ impl MockBlogRedeployHandle {
         std::mem::take(&mut *self.calls.lock().await)
     }
 
+    pub async fn take_portfolio_calls(&self) -> Vec<MockPortfolioDispatchCall> {
+        std::mem::take(&mut *self.portfolio_calls.lock().await)
+    }
+
     pub async fn take_failures(&self) -> Vec<MockDispatchFailure> {
         std::mem::take(&mut *self.failures.lock().await)
     }
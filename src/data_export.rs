@@ -0,0 +1,625 @@
+//! 개인 데이터 내보내기.
+//!
+//! `POST /me/exports`는 작업 행만 만들고 바로 응답한다. zip 파일은 백그라운드에서 만들어 `DATA_EXPORT_PATH`에 두고,
+//! 서명한 다운로드 링크로 내려준다. 링크는 `DATA_EXPORT_TTL_HOURS`(기본 24시간)가 지나면 쓸 수 없고 파일도 지운다.
+//! 다른 핸들러와 같이 모든 조회는 `owner_user_id`/`user_id`로만 거른다.
+
+use std::{collections::HashSet, env, io::Write, path::PathBuf, sync::Arc, time::Duration};
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use poem::{http::StatusCode, Error};
+use serde_json::{json, Map, Value};
+use sha2::Sha256;
+use sqlx::{
+    query, query_as, query_scalar, sqlite::SqliteRow, Column, Executor, Row, SqlitePool, TypeInfo,
+    ValueRef,
+};
+use tokio::time::interval;
+use uuid::Uuid;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::models::{AppState, DataExportResponse, DataExportRow};
+use crate::routes::login::{jwt_secret, MissingJwtSecret};
+
+const DEFAULT_TTL_HOURS: i64 = 24;
+const DEFAULT_INTERVAL_SECS: u64 = 60;
+
+const EXPORT_COLUMNS: &str =
+    "export_id, user_id, status, byte_size, error, created_at, completed_at, expires_at";
+
+/// 압축 파일을 두는 로컬 디렉토리.
+pub fn data_export_path() -> PathBuf {
+    PathBuf::from(env::var("DATA_EXPORT_PATH").unwrap_or_else(|_| ".uploads/exports".to_string()))
+}
+
+pub fn archive_path(export_id: &str) -> PathBuf {
+    data_export_path().join(format!("{}.zip", export_id))
+}
+
+fn ttl_hours() -> i64 {
+    env::var("DATA_EXPORT_TTL_HOURS")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_TTL_HOURS)
+}
+
+fn internal_error(message: String) -> Error {
+    Error::from_string(message, StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn timestamp(at: chrono::DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// 내보낼 표 하나. `csv`면 같은 행을 CSV로도 넣는다.
+struct ExportTable {
+    name: &'static str,
+    sql: &'static str,
+    csv: bool,
+}
+
+/// `?1`에는 내보내는 사용자 ID가 들어간다. 비밀번호, Google `sub`, API key 해시처럼 로그인에 쓰는 값은 빼고 내보낸다.
+const EXPORT_TABLES: &[ExportTable] = &[
+    ExportTable {
+        name: "posts",
+        sql: r#"
+            SELECT post_id, title, description, published_at, tags, content, status, created_at
+            FROM posts
+            WHERE writer_id = ?1
+            ORDER BY created_at, post_id
+        "#,
+        csv: false,
+    },
+    ExportTable {
+        name: "images",
+        sql: r#"
+            SELECT image_id, post_id, file_name, origin_name, mime_type, image_type,
+                   width, height, byte_size, alt_text, caption, uploaded_at
+            FROM images
+            WHERE uploaded_by = ?1
+               OR post_id IN (SELECT post_id FROM posts WHERE writer_id = ?1)
+            ORDER BY uploaded_at, image_id
+        "#,
+        csv: false,
+    },
+    ExportTable {
+        name: "budget_periods",
+        sql: r#"
+            SELECT budget_id, total_budget, from_date, to_date, alert_threshold, created_at, updated_at
+            FROM budget_periods
+            WHERE owner_user_id = ?1
+            ORDER BY from_date, budget_id
+        "#,
+        csv: true,
+    },
+    ExportTable {
+        name: "spending_records",
+        sql: r#"
+            SELECT record_id, amount, merchant, transacted_at, source_type, created_at
+            FROM spending_records
+            WHERE owner_user_id = ?1
+            ORDER BY transacted_at, record_id
+        "#,
+        csv: true,
+    },
+    ExportTable {
+        name: "rss_subscriptions",
+        sql: r#"
+            SELECT s.subscription_id, s.source_id, r.feed_url, r.title, r.site_url, s.created_at
+            FROM user_rss_subscriptions s
+            JOIN rss_sources r ON r.source_id = s.source_id
+            WHERE s.user_id = ?1
+            ORDER BY s.created_at, s.subscription_id
+        "#,
+        csv: false,
+    },
+    ExportTable {
+        name: "matches",
+        sql: r#"
+            SELECT match_id, requester_user_id, target_user_id, status, created_at, responded_at, closed_at
+            FROM user_matches
+            WHERE requester_user_id = ?1 OR target_user_id = ?1
+            ORDER BY created_at, match_id
+        "#,
+        csv: false,
+    },
+    ExportTable {
+        name: "match_messages",
+        sql: r#"
+            SELECT m.message_id, m.match_id, m.sender_user_id, m.receiver_user_id, m.content, m.created_at
+            FROM match_messages m
+            JOIN user_matches um ON um.match_id = m.match_id
+            WHERE um.requester_user_id = ?1 OR um.target_user_id = ?1
+            ORDER BY m.created_at, m.message_id
+        "#,
+        csv: false,
+    },
+    ExportTable {
+        name: "api_keys",
+        sql: r#"
+            SELECT api_key_id, name, user_role, created_at, last_used_at, revoked_at
+            FROM api_keys
+            WHERE user_id = ?1
+            ORDER BY created_at, api_key_id
+        "#,
+        csv: false,
+    },
+];
+
+const PROFILE_SQL: &str = r#"
+    SELECT user_id, user_role, auth_provider, google_sub IS NOT NULL AS google_linked,
+           display_name, avatar_url, bio, deletion_requested_at, deletion_scheduled_at
+    FROM users
+    WHERE user_id = ?1
+"#;
+
+/// SQLite 값의 실제 저장 형식을 보고 JSON 값으로 옮긴다. BLOB은 내보내지 않는다.
+fn column_value(row: &SqliteRow, index: usize) -> Value {
+    let Ok(raw) = row.try_get_raw(index) else {
+        return Value::Null;
+    };
+    if raw.is_null() {
+        return Value::Null;
+    }
+
+    match raw.type_info().name() {
+        "INTEGER" | "BOOLEAN" => row.try_get::<i64, _>(index).map(Value::from),
+        "REAL" => row.try_get::<f64, _>(index).map(Value::from),
+        _ => row.try_get::<String, _>(index).map(Value::from),
+    }
+    .unwrap_or(Value::Null)
+}
+
+struct TableRows {
+    columns: Vec<String>,
+    rows: Vec<Map<String, Value>>,
+}
+
+async fn fetch_rows(db: &SqlitePool, sql: &str, user_id: &str) -> Result<TableRows, sqlx::Error> {
+    // 행이 없어도 CSV 머리글은 남도록 열 이름은 문장에서 읽는다.
+    let columns = db
+        .describe(sql)
+        .await?
+        .columns()
+        .iter()
+        .map(|column| column.name().to_string())
+        .collect();
+    let rows = query(sql).bind(user_id).fetch_all(db).await?;
+
+    Ok(TableRows {
+        rows: rows
+            .iter()
+            .map(|row| {
+                row.columns()
+                    .iter()
+                    .map(|column| {
+                        (
+                            column.name().to_string(),
+                            column_value(row, column.ordinal()),
+                        )
+                    })
+                    .collect()
+            })
+            .collect(),
+        columns,
+    })
+}
+
+fn csv_field(value: &Value) -> String {
+    let text = match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+/// 엑셀에서 한글이 깨지지 않도록 BOM을 붙인다.
+fn to_csv(table: &TableRows) -> String {
+    let mut csv = String::from("\u{feff}");
+    csv.push_str(&table.columns.join(","));
+    csv.push_str("\r\n");
+    for row in &table.rows {
+        let fields: Vec<String> = table
+            .columns
+            .iter()
+            .map(|column| csv_field(row.get(column).unwrap_or(&Value::Null)))
+            .collect();
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+struct ArchiveEntry {
+    name: String,
+    bytes: Vec<u8>,
+    /// 이미지처럼 이미 압축된 파일은 그대로 넣는다.
+    compress: bool,
+}
+
+fn json_entry(name: String, value: &Value) -> ArchiveEntry {
+    ArchiveEntry {
+        name,
+        bytes: serde_json::to_vec_pretty(value).unwrap_or_default(),
+        compress: true,
+    }
+}
+
+fn write_archive(entries: Vec<ArchiveEntry>) -> Result<Vec<u8>, String> {
+    let mut writer = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for entry in entries {
+        let method = if entry.compress {
+            CompressionMethod::Deflated
+        } else {
+            CompressionMethod::Stored
+        };
+        writer
+            .start_file(
+                entry.name.as_str(),
+                SimpleFileOptions::default().compression_method(method),
+            )
+            .map_err(|err| format!("{} 추가 실패: {}", entry.name, err))?;
+        writer
+            .write_all(&entry.bytes)
+            .map_err(|err| format!("{} 쓰기 실패: {}", entry.name, err))?;
+    }
+
+    writer
+        .finish()
+        .map(|cursor| cursor.into_inner())
+        .map_err(|err| format!("zip 마무리 실패: {}", err))
+}
+
+/// 사용자의 데이터를 모아 zip 바이트로 만든다.
+pub async fn build_export_archive(state: &AppState, user_id: &str) -> Result<Vec<u8>, String> {
+    let db = &state.db;
+    let mut entries = Vec::new();
+
+    let profile = fetch_rows(db, PROFILE_SQL, user_id)
+        .await
+        .map_err(|err| format!("profile 조회 실패: {}", err))?;
+    let profile = profile
+        .rows
+        .into_iter()
+        .next()
+        .ok_or_else(|| "사용자를 찾을 수 없습니다.".to_string())?;
+    entries.push(json_entry(
+        "profile.json".to_string(),
+        &Value::Object(profile),
+    ));
+
+    let mut image_files = Vec::new();
+    for table in EXPORT_TABLES {
+        let rows = fetch_rows(db, table.sql, user_id)
+            .await
+            .map_err(|err| format!("{} 조회 실패: {}", table.name, err))?;
+        if table.name == "images" {
+            image_files = rows
+                .rows
+                .iter()
+                .filter_map(|row| row.get("file_name").and_then(Value::as_str))
+                .map(str::to_string)
+                .collect();
+        }
+        if table.csv {
+            entries.push(ArchiveEntry {
+                name: format!("{}.csv", table.name),
+                bytes: to_csv(&rows).into_bytes(),
+                compress: true,
+            });
+        }
+        entries.push(json_entry(
+            format!("{}.json", table.name),
+            &Value::Array(rows.rows.into_iter().map(Value::Object).collect()),
+        ));
+    }
+
+    // 같은 내용을 여러 번 올리면 행마다 같은 파일을 가리키므로 파일은 한 번만 넣는다.
+    let mut seen_images = HashSet::new();
+    let mut missing_images = Vec::new();
+    for file_name in image_files {
+        if !seen_images.insert(file_name.clone()) {
+            continue;
+        }
+        match state.storage.get(&file_name).await {
+            Ok(Some(bytes)) => entries.push(ArchiveEntry {
+                name: format!("images/{}", file_name),
+                bytes,
+                compress: false,
+            }),
+            Ok(None) => missing_images.push(file_name),
+            Err(err) => {
+                eprintln!("내보내기 이미지 읽기 실패 ({}): {}", file_name, err);
+                missing_images.push(file_name);
+            }
+        }
+    }
+
+    let files: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+    let manifest = json!({
+        "user_id": user_id,
+        "exported_at": timestamp(Utc::now()),
+        "files": files,
+        "missing_images": missing_images,
+    });
+    entries.insert(0, json_entry("manifest.json".to_string(), &manifest));
+
+    tokio::task::spawn_blocking(move || write_archive(entries))
+        .await
+        .map_err(|err| format!("zip 작성 작업 실패: {}", err))?
+}
+
+/// 진행 중인 내보내기가 있으면 그것을, 없으면 새 작업을 돌려준다. 새로 만들었으면 `true`.
+pub async fn request_data_export(
+    db: &SqlitePool,
+    user_id: &str,
+) -> Result<(DataExportRow, bool), Error> {
+    let in_progress = query_as::<_, DataExportRow>(&format!(
+        "SELECT {} FROM data_exports WHERE user_id = ? AND status IN ('pending', 'running') ORDER BY created_at DESC LIMIT 1",
+        EXPORT_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("내보내기 조회 실패: {}", err)))?;
+    if let Some(export) = in_progress {
+        return Ok((export, false));
+    }
+
+    let export_id = Uuid::new_v4().to_string();
+    query("INSERT INTO data_exports (export_id, user_id, created_at) VALUES (?, ?, ?)")
+        .bind(&export_id)
+        .bind(user_id)
+        .bind(timestamp(Utc::now()))
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("내보내기 작업 등록 실패: {}", err)))?;
+
+    let export = find_user_export(db, user_id, &export_id)
+        .await?
+        .ok_or_else(|| internal_error("내보내기 작업을 찾을 수 없습니다.".to_string()))?;
+    Ok((export, true))
+}
+
+pub async fn find_user_export(
+    db: &SqlitePool,
+    user_id: &str,
+    export_id: &str,
+) -> Result<Option<DataExportRow>, Error> {
+    query_as::<_, DataExportRow>(&format!(
+        "SELECT {} FROM data_exports WHERE export_id = ? AND user_id = ?",
+        EXPORT_COLUMNS
+    ))
+    .bind(export_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("내보내기 조회 실패: {}", err)))
+}
+
+pub async fn find_export(db: &SqlitePool, export_id: &str) -> Result<Option<DataExportRow>, Error> {
+    query_as::<_, DataExportRow>(&format!(
+        "SELECT {} FROM data_exports WHERE export_id = ?",
+        EXPORT_COLUMNS
+    ))
+    .bind(export_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("내보내기 조회 실패: {}", err)))
+}
+
+/// 작업 하나를 실행한다. 다른 곳에서 이미 가져간 작업이면 `false`.
+pub async fn run_data_export(state: &AppState, export_id: &str) -> Result<bool, Error> {
+    let db = &state.db;
+    let user_id: Option<String> = query_scalar(
+        "UPDATE data_exports SET status = 'running' WHERE export_id = ? AND status = 'pending' RETURNING user_id",
+    )
+    .bind(export_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("내보내기 작업 시작 실패: {}", err)))?;
+    let Some(user_id) = user_id else {
+        return Ok(false);
+    };
+
+    let written = match build_export_archive(state, &user_id).await {
+        Ok(bytes) => {
+            let path = archive_path(export_id);
+            let byte_size = bytes.len() as i64;
+            match tokio::fs::create_dir_all(data_export_path()).await {
+                Ok(()) => tokio::fs::write(&path, bytes)
+                    .await
+                    .map(|_| byte_size)
+                    .map_err(|err| format!("zip 저장 실패: {}", err)),
+                Err(err) => Err(format!("내보내기 디렉토리 생성 실패: {}", err)),
+            }
+        }
+        Err(err) => Err(err),
+    };
+
+    let now = Utc::now();
+    let result = match &written {
+        Ok(byte_size) => query(
+            r#"
+            UPDATE data_exports
+            SET status = 'ready', byte_size = ?, error = NULL, completed_at = ?, expires_at = ?
+            WHERE export_id = ?
+            "#,
+        )
+        .bind(byte_size)
+        .bind(timestamp(now))
+        .bind(timestamp(now + chrono::Duration::hours(ttl_hours())))
+        .bind(export_id),
+        Err(err) => {
+            eprintln!("데이터 내보내기 실패 ({}): {}", export_id, err);
+            query(
+                "UPDATE data_exports SET status = 'failed', error = ?, completed_at = ? WHERE export_id = ?",
+            )
+            .bind(err)
+            .bind(timestamp(now))
+            .bind(export_id)
+        }
+    };
+    result
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("내보내기 결과 저장 실패: {}", err)))?;
+
+    Ok(true)
+}
+
+/// 요청을 기다리게 하지 않도록 작업을 바로 백그라운드에서 돌린다.
+pub fn spawn_data_export(state: Arc<AppState>, export_id: String) {
+    tokio::spawn(async move {
+        if let Err(err) = run_data_export(&state, &export_id).await {
+            eprintln!("데이터 내보내기 실행 실패 ({}): {}", export_id, err);
+        }
+    });
+}
+
+/// 기한이 지난 압축 파일을 지우고 `expired`로 바꾼다. 바꾼 개수를 돌려준다.
+pub async fn expire_data_exports(db: &SqlitePool) -> Result<usize, Error> {
+    let expired: Vec<String> = query_scalar(
+        "SELECT export_id FROM data_exports WHERE status = 'ready' AND expires_at <= ?",
+    )
+    .bind(timestamp(Utc::now()))
+    .fetch_all(db)
+    .await
+    .map_err(|err| internal_error(format!("만료된 내보내기 조회 실패: {}", err)))?;
+
+    for export_id in &expired {
+        remove_archive(export_id).await;
+        query("UPDATE data_exports SET status = 'expired' WHERE export_id = ?")
+            .bind(export_id)
+            .execute(db)
+            .await
+            .map_err(|err| internal_error(format!("내보내기 만료 처리 실패: {}", err)))?;
+    }
+
+    Ok(expired.len())
+}
+
+/// 압축 파일을 지운다. 이미 없으면 무시한다.
+pub async fn remove_archive(export_id: &str) {
+    let path = archive_path(export_id);
+    if let Err(err) = tokio::fs::remove_file(&path).await {
+        if err.kind() != std::io::ErrorKind::NotFound {
+            eprintln!("내보내기 파일 삭제 실패 ({}): {}", path.display(), err);
+        }
+    }
+}
+
+fn signature_mac(secret: &str, export: &DataExportRow) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(export.export_id.as_bytes());
+    mac.update(b"\n");
+    mac.update(export.user_id.as_bytes());
+    mac.update(b"\n");
+    mac.update(export.expires_at.as_deref().unwrap_or_default().as_bytes());
+    mac
+}
+
+/// 다운로드 링크의 서명. 만료 시각을 함께 서명하므로 다시 만든 내보내기에는 쓸 수 없다.
+pub fn download_signature(export: &DataExportRow) -> Result<String, MissingJwtSecret> {
+    let secret = jwt_secret("JWT_ACCESS_SECRET")?;
+    Ok(hex::encode(
+        signature_mac(&secret, export).finalize().into_bytes(),
+    ))
+}
+
+pub fn verify_download_signature(
+    export: &DataExportRow,
+    signature: &str,
+) -> Result<bool, MissingJwtSecret> {
+    let secret = jwt_secret("JWT_ACCESS_SECRET")?;
+    let Ok(signature) = hex::decode(signature) else {
+        return Ok(false);
+    };
+    Ok(signature_mac(&secret, export)
+        .verify_slice(&signature)
+        .is_ok())
+}
+
+pub fn is_expired(export: &DataExportRow) -> bool {
+    export
+        .expires_at
+        .as_deref()
+        .is_none_or(|expires_at| expires_at <= timestamp(Utc::now()).as_str())
+}
+
+pub fn data_export_response(export: DataExportRow) -> Result<DataExportResponse, MissingJwtSecret> {
+    let download_url = if export.status == "ready" && !is_expired(&export) {
+        Some(format!(
+            "/exports/{}/download?signature={}",
+            export.export_id,
+            download_signature(&export)?
+        ))
+    } else {
+        None
+    };
+
+    Ok(DataExportResponse {
+        export_id: export.export_id,
+        status: export.status,
+        byte_size: export.byte_size,
+        error: export.error,
+        created_at: export.created_at,
+        completed_at: export.completed_at,
+        expires_at: export.expires_at,
+        download_url,
+    })
+}
+
+/// 서버가 다시 떠서 남은 작업을 이어서 돌리고, `DATA_EXPORT_INTERVAL_SECS`(기본 60초)마다 만료된 파일을 지운다.
+pub fn start_data_export_worker(state: Arc<AppState>) {
+    let interval_secs = env::var("DATA_EXPORT_INTERVAL_SECS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_INTERVAL_SECS);
+
+    tokio::spawn(async move {
+        // 이전 프로세스가 돌리다 멈춘 작업은 처음부터 다시 한다.
+        if let Err(err) =
+            query("UPDATE data_exports SET status = 'pending' WHERE status = 'running'")
+                .execute(&state.db)
+                .await
+        {
+            eprintln!("data export recovery failed: {}", err);
+        }
+
+        let mut ticker = interval(Duration::from_secs(interval_secs));
+        loop {
+            ticker.tick().await;
+
+            let pending: Vec<String> = query_scalar(
+                "SELECT export_id FROM data_exports WHERE status = 'pending' ORDER BY created_at",
+            )
+            .fetch_all(&state.db)
+            .await
+            .unwrap_or_else(|err| {
+                eprintln!("data export lookup failed: {}", err);
+                Vec::new()
+            });
+            for export_id in pending {
+                if let Err(err) = run_data_export(&state, &export_id).await {
+                    eprintln!("data export failed ({}): {}", export_id, err);
+                }
+            }
+
+            match expire_data_exports(&state.db).await {
+                Ok(0) => {}
+                Ok(count) => println!("data exports expired: {}", count),
+                Err(err) => eprintln!("data export expiry failed: {}", err),
+            }
+        }
+    });
+}
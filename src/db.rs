@@ -1,6 +1,41 @@
 use poem::{Result, error::InternalServerError};
 use sqlx::{SqlitePool, query};
 
+const PORTFOLIO_TABLE: &str = r#"
+    CREATE TABLE IF NOT EXISTS portfolio (
+        portfolio_id INTEGER PRIMARY KEY AUTOINCREMENT,
+        slug TEXT NOT NULL,
+        lang TEXT NOT NULL DEFAULT 'ko',
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        UNIQUE(slug, lang)
+    )
+"#;
+
+const PORTFOLIO_DRAFT_TABLE: &str = r#"
+    CREATE TABLE IF NOT EXISTS portfolio_draft (
+        slug TEXT NOT NULL,
+        lang TEXT NOT NULL DEFAULT 'ko',
+        content TEXT NOT NULL,
+        updated_by TEXT,
+        created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        PRIMARY KEY (slug, lang)
+    )
+"#;
+
+const PORTFOLIO_VERSION_TABLE: &str = r#"
+    CREATE TABLE IF NOT EXISTS portfolio_version (
+        version_id INTEGER PRIMARY KEY AUTOINCREMENT,
+        slug TEXT NOT NULL,
+        lang TEXT NOT NULL DEFAULT 'ko',
+        version_number INTEGER NOT NULL,
+        content TEXT NOT NULL,
+        source TEXT NOT NULL,
+        created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        UNIQUE(slug, lang, version_number)
+    )
+"#;
+
 pub async fn init_db(pool: &SqlitePool) -> Result<()> {
     // posts
     query(
@@ -42,6 +77,125 @@ pub async fn init_db(pool: &SqlitePool) -> Result<()> {
     .await
     .map_err(InternalServerError)?;
 
+    ensure_column(pool, "images", "width", "INTEGER").await?;
+    ensure_column(pool, "images", "height", "INTEGER").await?;
+    ensure_column(pool, "images", "byte_size", "INTEGER").await?;
+    ensure_column(pool, "images", "dominant_color", "TEXT").await?;
+    ensure_column(pool, "images", "blurhash", "TEXT").await?;
+    ensure_column(pool, "images", "uploaded_by", "TEXT").await?;
+    ensure_column(pool, "images", "alt_text", "TEXT").await?;
+    ensure_column(pool, "images", "caption", "TEXT").await?;
+    ensure_column(pool, "images", "content_hash", "TEXT").await?;
+
+    query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_images_post_id
+        ON images(post_id)
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // image_blobs
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS image_blobs (
+            content_hash TEXT PRIMARY KEY,
+            file_name TEXT NOT NULL UNIQUE,
+            byte_size INTEGER NOT NULL,
+            ref_count INTEGER NOT NULL DEFAULT 1,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // image_variants
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS image_variants (
+            variant_id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id TEXT NOT NULL,
+            file_name TEXT NOT NULL UNIQUE,
+            width INTEGER NOT NULL,
+            height INTEGER NOT NULL,
+            format TEXT NOT NULL,
+            mime_type TEXT NOT NULL,
+            byte_size INTEGER NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (image_id) REFERENCES images(image_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_image_variants_image_id
+        ON image_variants(image_id, format, width)
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // upload_sessions
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS upload_sessions (
+            session_id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            origin_name TEXT NOT NULL,
+            content_type TEXT NOT NULL,
+            total_size INTEGER NOT NULL,
+            received_size INTEGER NOT NULL DEFAULT 0,
+            post_id TEXT,
+            image_type TEXT,
+            status TEXT NOT NULL DEFAULT 'open',
+            image_id TEXT,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // attachments
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS attachments (
+            attachment_id TEXT PRIMARY KEY,
+            post_id TEXT,
+            file_name TEXT NOT NULL UNIQUE,
+            origin_name TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            mime_type TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            byte_size INTEGER NOT NULL,
+            uploaded_by TEXT NOT NULL,
+            uploaded_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            download_count INTEGER NOT NULL DEFAULT 0,
+            last_downloaded_at TEXT,
+            FOREIGN KEY (post_id) REFERENCES posts(post_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    query("CREATE INDEX IF NOT EXISTS idx_attachments_post_id ON attachments(post_id)")
+        .execute(pool)
+        .await
+        .map_err(InternalServerError)?;
+
     // users
     query(
         r#"
@@ -72,19 +226,30 @@ pub async fn init_db(pool: &SqlitePool) -> Result<()> {
     .await
     .map_err(InternalServerError)?;
 
-    // portfolio (master)
-    query(
-        r#"
-        CREATE TABLE IF NOT EXISTS portfolio (
-            portfolio_id INTEGER PRIMARY KEY AUTOINCREMENT,
-            slug TEXT NOT NULL UNIQUE,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-        )
-        "#,
+    // 탈퇴 예약. 두 값이 있으면 `deletion_scheduled_at`이 지난 뒤 워커가 계정을 지운다.
+    ensure_column(pool, "users", "deletion_requested_at", "TEXT").await?;
+    ensure_column(pool, "users", "deletion_scheduled_at", "TEXT").await?;
+    // 관리자 사용자 관리. 이 열을 추가하기 전에 만든 계정은 `created_at`이 비어 있다.
+    ensure_column(pool, "users", "created_at", "TEXT").await?;
+    ensure_column(pool, "users", "last_login_at", "TEXT").await?;
+    ensure_column(pool, "users", "disabled_at", "TEXT").await?;
+    ensure_column(pool, "users", "disabled_reason", "TEXT").await?;
+    // 패스키의 user.id. 이메일 대신 임의 값을 인증기에 넘긴다.
+    ensure_column(pool, "users", "webauthn_user_handle", "TEXT").await?;
+
+    // portfolio (master). slug마다 언어(`lang`)별로 한 행씩 둔다.
+    rebuild_table_for_column(
+        pool,
+        "portfolio",
+        "lang",
+        PORTFOLIO_TABLE,
+        "portfolio_id, slug, created_at",
     )
-    .execute(pool)
-    .await
-    .map_err(InternalServerError)?;
+    .await?;
+    query(PORTFOLIO_TABLE)
+        .execute(pool)
+        .await
+        .map_err(InternalServerError)?;
 
     // portfolio_section
     query(
@@ -104,6 +269,53 @@ pub async fn init_db(pool: &SqlitePool) -> Result<()> {
     .await
     .map_err(InternalServerError)?;
 
+    // portfolio_draft (발행 전까지 공개 문서와 따로 두는 편집본)
+    rebuild_table_for_column(
+        pool,
+        "portfolio_draft",
+        "lang",
+        PORTFOLIO_DRAFT_TABLE,
+        "slug, content, updated_by, created_at, updated_at",
+    )
+    .await?;
+    query(PORTFOLIO_DRAFT_TABLE)
+        .execute(pool)
+        .await
+        .map_err(InternalServerError)?;
+
+    // portfolio_version (저장할 때마다 남기는 문서 스냅샷)
+    rebuild_table_for_column(
+        pool,
+        "portfolio_version",
+        "lang",
+        PORTFOLIO_VERSION_TABLE,
+        "version_id, slug, version_number, content, source, created_at",
+    )
+    .await?;
+    query(PORTFOLIO_VERSION_TABLE)
+        .execute(pool)
+        .await
+        .map_err(InternalServerError)?;
+
+    // portfolio_render_cache
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS portfolio_render_cache (
+            slug TEXT NOT NULL,
+            lang TEXT NOT NULL,
+            format TEXT NOT NULL,
+            version_number INTEGER NOT NULL,
+            renderer TEXT NOT NULL,
+            content BLOB NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (slug, lang, format)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
     // budget_periods
     query(
         r#"
@@ -213,6 +425,313 @@ pub async fn init_db(pool: &SqlitePool) -> Result<()> {
     .await
     .map_err(InternalServerError)?;
 
+    // refresh_token_families: 로그인 한 번에 하나. 회전한 토큰을 다시 쓰면 family 전체를 폐기한다.
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS refresh_token_families (
+            family_id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            revoked_at DATETIME,
+            revoked_reason TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // 세션(기기) 목록에 보여 줄 로그인 기기 정보와 마지막 사용 시각.
+    ensure_column(pool, "refresh_token_families", "user_agent", "TEXT").await?;
+    ensure_column(pool, "refresh_token_families", "ip_address", "TEXT").await?;
+    ensure_column(pool, "refresh_token_families", "last_seen_at", "TEXT").await?;
+
+    query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_refresh_token_families_user_id
+        ON refresh_token_families(user_id)
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // refresh_tokens
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS refresh_tokens (
+            token_id TEXT PRIMARY KEY,
+            family_id TEXT NOT NULL,
+            issued_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            expires_at DATETIME NOT NULL,
+            used_at DATETIME,
+            FOREIGN KEY (family_id) REFERENCES refresh_token_families(family_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_refresh_tokens_family_id
+        ON refresh_tokens(family_id)
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // password_reset_tokens: 메일로 보낸 토큰의 SHA-256만 저장한다.
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS password_reset_tokens (
+            token_hash TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            expires_at DATETIME NOT NULL,
+            used_at DATETIME
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_password_reset_tokens_user_id
+        ON password_reset_tokens(user_id)
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // user_totp: TOTP 비밀키. `confirmed_at`이 비어 있으면 등록 중이라 로그인에는 쓰지 않는다.
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS user_totp (
+            user_id TEXT PRIMARY KEY,
+            secret TEXT NOT NULL,
+            confirmed_at DATETIME,
+            last_used_step INTEGER,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // totp_recovery_codes: 복구 코드의 SHA-256만 저장한다.
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS totp_recovery_codes (
+            code_hash TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            used_at DATETIME
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_totp_recovery_codes_user_id
+        ON totp_recovery_codes(user_id)
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // login_challenges: 비밀번호는 맞았고 2단계 인증을 기다리는 로그인. 토큰의 SHA-256만 저장한다.
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS login_challenges (
+            token_hash TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            expires_at DATETIME NOT NULL,
+            used_at DATETIME
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // passkey_credentials: WebAuthn credential. `credential_id`는 base64url, `public_key`는 SEC1 비압축 P-256 키다.
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS passkey_credentials (
+            passkey_id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id TEXT NOT NULL,
+            credential_id TEXT NOT NULL UNIQUE,
+            public_key BLOB NOT NULL,
+            sign_count INTEGER NOT NULL DEFAULT 0,
+            name TEXT NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            last_used_at DATETIME,
+            revoked_at DATETIME
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_passkey_credentials_user_id
+        ON passkey_credentials(user_id)
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // webauthn_challenges: 등록/로그인 ceremony마다 한 번만 쓰는 challenge. 로그인용은 `user_id`가 없다.
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS webauthn_challenges (
+            challenge TEXT PRIMARY KEY,
+            purpose TEXT NOT NULL,
+            user_id TEXT,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            expires_at DATETIME NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // login_throttles: 로그인 실패 카운터. `scope`는 `account`(입력한 아이디, 소문자) 또는 `ip`.
+    // 비밀번호 재설정 요청 횟수도 `reset_account`/`reset_ip`로 같이 센다.
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS login_throttles (
+            scope TEXT NOT NULL,
+            throttle_key TEXT NOT NULL,
+            failure_count INTEGER NOT NULL DEFAULT 0,
+            last_failed_at DATETIME NOT NULL,
+            locked_until DATETIME,
+            PRIMARY KEY (scope, throttle_key)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // login_audit_events: 실패한 로그인, 잠긴 동안의 시도, 관리자의 잠금 해제 기록.
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS login_audit_events (
+            event_id INTEGER PRIMARY KEY AUTOINCREMENT,
+            event TEXT NOT NULL,
+            user_id TEXT,
+            ip_address TEXT,
+            actor_id TEXT,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_login_audit_events_user_id
+        ON login_audit_events(user_id, event_id)
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // app_settings: 관리자가 API로 바꾸는 서버 설정(key/value).
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS app_settings (
+            setting_key TEXT PRIMARY KEY,
+            setting_value TEXT NOT NULL,
+            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // email_outbox: 요청 처리와 SMTP 전송을 떼어 놓는다. 워커가 `pending`을 보낸다.
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS email_outbox (
+            email_id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL,
+            recipient TEXT NOT NULL,
+            subject TEXT NOT NULL,
+            body TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            sent_at DATETIME
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_email_outbox_status
+        ON email_outbox(status, email_id)
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    // data_exports: 개인 데이터 내보내기 작업. 파일은 `DATA_EXPORT_PATH`에 `{export_id}.zip`으로 둔다.
+    query(
+        r#"
+        CREATE TABLE IF NOT EXISTS data_exports (
+            export_id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            byte_size INTEGER,
+            error TEXT,
+            created_at TEXT NOT NULL,
+            completed_at TEXT,
+            expires_at TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
+    query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_data_exports_user_id
+        ON data_exports(user_id, created_at)
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(InternalServerError)?;
+
     // user_matches
     query(
         r#"
@@ -444,4 +963,83 @@ pub async fn init_db(pool: &SqlitePool) -> Result<()> {
     Ok(())
 }
 
+/// 이미 만들어진 테이블에 나중에 추가된 컬럼이 없으면 `ALTER TABLE ... ADD COLUMN`으로 붙인다.
+async fn ensure_column(
+    pool: &SqlitePool,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let exists: Option<i64> =
+        sqlx::query_scalar("SELECT 1 FROM pragma_table_info(?) WHERE name = ?")
+            .bind(table)
+            .bind(column)
+            .fetch_optional(pool)
+            .await
+            .map_err(InternalServerError)?;
 
+    if exists.is_none() {
+        query(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table, column, definition
+        ))
+        .execute(pool)
+        .await
+        .map_err(InternalServerError)?;
+    }
+
+    Ok(())
+}
+
+/// 고유 제약이 바뀌어 `ADD COLUMN`만으로는 옮길 수 없는 테이블을 새 정의(`create_sql`)로 다시 만든다.
+/// 테이블이 없거나 이미 `column`이 있으면 아무것도 하지 않는다. 기존 행은 `columns`만 옮기고 새 컬럼은 기본값을 쓴다.
+async fn rebuild_table_for_column(
+    pool: &SqlitePool,
+    table: &str,
+    column: &str,
+    create_sql: &str,
+    columns: &str,
+) -> Result<()> {
+    let table_exists: Option<i64> =
+        sqlx::query_scalar("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(table)
+            .fetch_optional(pool)
+            .await
+            .map_err(InternalServerError)?;
+    let column_exists: Option<i64> =
+        sqlx::query_scalar("SELECT 1 FROM pragma_table_info(?) WHERE name = ?")
+            .bind(table)
+            .bind(column)
+            .fetch_optional(pool)
+            .await
+            .map_err(InternalServerError)?;
+
+    if table_exists.is_none() || column_exists.is_some() {
+        return Ok(());
+    }
+
+    let old_table = format!("{}_before_{}", table, column);
+    let mut tx = pool.begin().await.map_err(InternalServerError)?;
+    query(&format!("ALTER TABLE {} RENAME TO {}", table, old_table))
+        .execute(&mut *tx)
+        .await
+        .map_err(InternalServerError)?;
+    query(create_sql)
+        .execute(&mut *tx)
+        .await
+        .map_err(InternalServerError)?;
+    query(&format!(
+        "INSERT INTO {} ({}) SELECT {} FROM {}",
+        table, columns, columns, old_table
+    ))
+    .execute(&mut *tx)
+    .await
+    .map_err(InternalServerError)?;
+    query(&format!("DROP TABLE {}", old_table))
+        .execute(&mut *tx)
+        .await
+        .map_err(InternalServerError)?;
+    tx.commit().await.map_err(InternalServerError)?;
+
+    Ok(())
+}
@@ -0,0 +1,450 @@
+use std::{collections::HashMap, env};
+
+use poem::{http::StatusCode, Error};
+use sha2::{Digest, Sha256};
+use sqlx::{query, query_as, query_scalar, FromRow, SqlitePool};
+
+use crate::{
+    image_gc::{remove_upload_file, IMAGE_PUBLIC_PREFIX},
+    models::ImageHashBackfillResponse,
+    storage::{SharedStorage, UploadStorage},
+};
+
+#[derive(Debug, FromRow)]
+struct UnhashedImageRow {
+    image_id: String,
+    file_name: String,
+}
+
+pub fn content_hash(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    hex::encode(hasher.finalize())
+}
+
+/// 같은 내용의 파일이 이미 있으면 참조 수를 올리고 그 파일 이름을 돌려준다.
+/// 처음 보는 내용이면 `candidate_file_name`으로 새로 등록하고 `true`를 함께 돌려준다.
+pub async fn register_blob(
+    db: &SqlitePool,
+    content_hash: &str,
+    candidate_file_name: &str,
+    byte_size: u64,
+) -> Result<(String, bool), Error> {
+    let file_name: String = query_scalar(
+        r#"
+        INSERT INTO image_blobs (content_hash, file_name, byte_size, ref_count)
+        VALUES (?, ?, ?, 1)
+        ON CONFLICT(content_hash) DO UPDATE SET ref_count = ref_count + 1
+        RETURNING file_name
+        "#,
+    )
+    .bind(content_hash)
+    .bind(candidate_file_name)
+    .bind(byte_size as i64)
+    .fetch_one(db)
+    .await
+    .map_err(|err| {
+        Error::from_string(
+            format!("이미지 해시 기록 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?;
+
+    let is_new = file_name == candidate_file_name;
+    Ok((file_name, is_new))
+}
+
+/// 논리 이미지 하나가 사라질 때 참조 수를 내린다. 마지막 참조였으면 blob 행을 지우고 `true`를 돌려준다.
+/// `dry_run`이면 아무것도 바꾸지 않고 마지막 참조인지 여부만 알려준다.
+pub async fn release_blob(db: &SqlitePool, content_hash: &str, dry_run: bool) -> Result<bool, Error> {
+    let ref_count: Option<i64> =
+        query_scalar("SELECT ref_count FROM image_blobs WHERE content_hash = ?")
+            .bind(content_hash)
+            .fetch_optional(db)
+            .await
+            .map_err(|err| {
+                Error::from_string(
+                    format!("이미지 참조 수 조회 실패: {}", err),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )
+            })?;
+
+    let is_last = ref_count.unwrap_or(0) <= 1;
+    if dry_run {
+        return Ok(is_last);
+    }
+
+    let sql = if is_last {
+        "DELETE FROM image_blobs WHERE content_hash = ?"
+    } else {
+        "UPDATE image_blobs SET ref_count = ref_count - 1 WHERE content_hash = ?"
+    };
+    query(sql)
+        .bind(content_hash)
+        .execute(db)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("이미지 참조 수 갱신 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+
+    Ok(is_last)
+}
+
+/// 이 이미지를 지우면 실제 파일도 사라지는지 확인한다. 해시가 없는 예전 행은 항상 마지막 참조로 본다.
+pub async fn is_last_blob_reference(db: &SqlitePool, image_id: &str) -> Result<bool, Error> {
+    let ref_count: Option<i64> = query_scalar(
+        r#"
+        SELECT b.ref_count
+        FROM images i
+        JOIN image_blobs b ON b.content_hash = i.content_hash
+        WHERE i.image_id = ?
+        "#,
+    )
+    .bind(image_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| {
+        Error::from_string(
+            format!("이미지 참조 수 조회 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?;
+
+    Ok(ref_count.unwrap_or(0) <= 1)
+}
+
+/// `register_blob`이 돌려줄 파일 이름을 기록 없이 미리 구한다. `backfill_content_hashes`의 dry_run에서 쓴다.
+async fn planned_blob_file_name(
+    db: &SqlitePool,
+    planned_blobs: &mut HashMap<String, String>,
+    content_hash: &str,
+    candidate_file_name: &str,
+) -> Result<String, Error> {
+    if let Some(file_name) = planned_blobs.get(content_hash) {
+        return Ok(file_name.clone());
+    }
+
+    let existing: Option<String> =
+        query_scalar("SELECT file_name FROM image_blobs WHERE content_hash = ?")
+            .bind(content_hash)
+            .fetch_optional(db)
+            .await
+            .map_err(|err| {
+                Error::from_string(
+                    format!("이미지 해시 조회 실패: {}", err),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )
+            })?;
+    let file_name = existing.unwrap_or_else(|| candidate_file_name.to_string());
+    planned_blobs.insert(content_hash.to_string(), file_name.clone());
+    Ok(file_name)
+}
+
+/// 해시가 없는 기존 이미지를 읽어 blob으로 등록한다.
+/// 이미 같은 내용의 파일이 있으면 행을 그 파일로 돌리고, 본문 링크를 바꾼 뒤 중복 파일과 variant를 지운다.
+/// `dry_run`이면 같은 보고서만 돌려주고 DB와 파일은 건드리지 않는다.
+pub async fn backfill_content_hashes(
+    db: &SqlitePool,
+    storage: &dyn UploadStorage,
+    dry_run: bool,
+) -> Result<ImageHashBackfillResponse, Error> {
+    let rows = query_as::<_, UnhashedImageRow>(
+        r#"
+        SELECT image_id, file_name
+        FROM images
+        WHERE content_hash IS NULL
+        ORDER BY uploaded_at ASC, image_id ASC
+        "#,
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|err| {
+        Error::from_string(
+            format!("해시 없는 이미지 조회 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?;
+
+    let mut response = ImageHashBackfillResponse {
+        dry_run,
+        hashed_count: 0,
+        merged_image_ids: Vec::new(),
+        missing_image_ids: Vec::new(),
+        removed_files: Vec::new(),
+        freed_bytes: 0,
+    };
+
+    // dry_run에서는 blob을 등록하지 않으므로, 이번 실행에서 먼저 본 내용을 여기에 기억한다.
+    let mut planned_blobs = HashMap::new();
+    for row in rows {
+        let Some(bytes) = storage.get(&row.file_name).await? else {
+            response.missing_image_ids.push(row.image_id);
+            continue;
+        };
+
+        let hash = content_hash(&bytes);
+        let canonical_file_name = if dry_run {
+            planned_blob_file_name(db, &mut planned_blobs, &hash, &row.file_name).await?
+        } else {
+            register_blob(db, &hash, &row.file_name, bytes.len() as u64)
+                .await?
+                .0
+        };
+
+        if canonical_file_name != row.file_name {
+            let mut duplicate_files = vec![row.file_name.clone()];
+            duplicate_files.extend(
+                query_scalar::<_, String>(
+                    "SELECT file_name FROM image_variants WHERE image_id = ?",
+                )
+                .bind(&row.image_id)
+                .fetch_all(db)
+                .await
+                .map_err(|err| {
+                    Error::from_string(
+                        format!("이미지 variant 조회 실패: {}", err),
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    )
+                })?,
+            );
+
+            if !dry_run {
+                for duplicate_file in &duplicate_files {
+                    rewrite_image_links(db, duplicate_file, &canonical_file_name).await?;
+                }
+
+                query("DELETE FROM image_variants WHERE image_id = ?")
+                    .bind(&row.image_id)
+                    .execute(db)
+                    .await
+                    .map_err(|err| {
+                        Error::from_string(
+                            format!("이미지 variant 행 삭제 실패: {}", err),
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                        )
+                    })?;
+                query("UPDATE images SET file_name = ?, file_path = ? WHERE image_id = ?")
+                    .bind(&canonical_file_name)
+                    .bind(storage.location(&canonical_file_name))
+                    .bind(&row.image_id)
+                    .execute(db)
+                    .await
+                    .map_err(|err| {
+                        Error::from_string(
+                            format!("이미지 파일 이름 갱신 실패: {}", err),
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                        )
+                    })?;
+            }
+
+            for duplicate_file in duplicate_files {
+                if let Some(byte_size) =
+                    remove_upload_file(storage, &duplicate_file, dry_run).await?
+                {
+                    response.freed_bytes += byte_size;
+                    response.removed_files.push(duplicate_file);
+                }
+            }
+            response.merged_image_ids.push(row.image_id.clone());
+        }
+
+        if !dry_run {
+            query("UPDATE images SET content_hash = ? WHERE image_id = ?")
+                .bind(&hash)
+                .bind(&row.image_id)
+                .execute(db)
+                .await
+                .map_err(|err| {
+                    Error::from_string(
+                        format!("이미지 해시 저장 실패: {}", err),
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    )
+                })?;
+        }
+        response.hashed_count += 1;
+    }
+
+    Ok(response)
+}
+
+/// `IMAGE_HASH_BACKFILL_ON_START`가 설정된 경우에만 서버가 뜰 때 한 번 백그라운드로 돌린다.
+/// `apply`일 때만 실제로 합치고, 그 밖의 값이면 dry_run 보고서만 로그로 남긴다.
+pub fn start_content_hash_backfill(db: SqlitePool, storage: SharedStorage) {
+    let Some(mode) = env::var("IMAGE_HASH_BACKFILL_ON_START")
+        .ok()
+        .map(|value| value.trim().to_ascii_lowercase())
+        .filter(|value| !value.is_empty())
+    else {
+        return;
+    };
+    let dry_run = mode != "apply";
+
+    tokio::spawn(async move {
+        match backfill_content_hashes(&db, storage.as_ref(), dry_run).await {
+            Ok(result) => println!(
+                "image hash backfill finished: dry_run={}, hashed={}, merged={}, missing={}, freed_bytes={}",
+                result.dry_run,
+                result.hashed_count,
+                result.merged_image_ids.len(),
+                result.missing_image_ids.len(),
+                result.freed_bytes
+            ),
+            Err(err) => eprintln!("image hash backfill failed: {}", err),
+        }
+    });
+}
+
+/// 글 본문, portfolio 섹션/초안/스냅샷, 프로필 사진에 걸린 `/images/<from>` 링크를 `/images/<to>`로 바꾼다.
+async fn rewrite_image_links(db: &SqlitePool, from: &str, to: &str) -> Result<(), Error> {
+    let from = format!("{}{}", IMAGE_PUBLIC_PREFIX, from);
+    let to = format!("{}{}", IMAGE_PUBLIC_PREFIX, to);
+    let statements = [
+        "UPDATE posts SET content = REPLACE(content, ?, ?) WHERE instr(content, ?) > 0",
+        "UPDATE portfolio_section SET content = REPLACE(content, ?, ?) WHERE instr(content, ?) > 0",
+        "UPDATE portfolio_draft SET content = REPLACE(content, ?, ?) WHERE instr(content, ?) > 0",
+        "UPDATE portfolio_version SET content = REPLACE(content, ?, ?) WHERE instr(content, ?) > 0",
+        "UPDATE users SET avatar_url = REPLACE(avatar_url, ?, ?) WHERE instr(avatar_url, ?) > 0",
+    ];
+
+    for sql in statements {
+        query(sql)
+            .bind(&from)
+            .bind(&to)
+            .bind(&from)
+            .execute(db)
+            .await
+            .map_err(|err| {
+                Error::from_string(
+                    format!("이미지 링크 갱신 실패: {}", err),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )
+            })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{query, query_scalar, SqlitePool};
+
+    use super::{backfill_content_hashes, content_hash, register_blob, release_blob};
+    use crate::db::init_db;
+    use crate::storage::{MemoryStorage, UploadStorage};
+
+    async fn setup() -> (SqlitePool, MemoryStorage) {
+        let db = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("failed to connect sqlite");
+        init_db(&db).await.expect("failed to init db");
+
+        (db, MemoryStorage::default())
+    }
+
+    #[tokio::test]
+    async fn register_and_release_blob_counts_references() {
+        let (db, _) = setup().await;
+        let hash = content_hash(b"same bytes");
+
+        let first = register_blob(&db, &hash, "a.png", 10).await.unwrap();
+        let second = register_blob(&db, &hash, "b.png", 10).await.unwrap();
+        assert_eq!(first, ("a.png".to_string(), true));
+        assert_eq!(second, ("a.png".to_string(), false));
+
+        assert!(!release_blob(&db, &hash, false).await.unwrap());
+        assert!(release_blob(&db, &hash, true).await.unwrap());
+        assert!(release_blob(&db, &hash, false).await.unwrap());
+
+        let remaining: i64 = query_scalar("SELECT COUNT(*) FROM image_blobs")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn backfill_merges_duplicate_files_and_rewrites_links() {
+        let (db, storage) = setup().await;
+
+        for (image_id, file_name, bytes, uploaded_at) in [
+            ("first", "first.png", b"duplicate".as_slice(), "2024-01-01 00:00:00"),
+            ("second", "second.png", b"duplicate".as_slice(), "2024-01-02 00:00:00"),
+            ("other", "other.png", b"unique".as_slice(), "2024-01-03 00:00:00"),
+        ] {
+            storage
+                .put(file_name, bytes.to_vec(), "image/png")
+                .await
+                .unwrap();
+            query(
+                r#"
+                INSERT INTO images (image_id, file_name, origin_name, file_path, mime_type, image_type, uploaded_at)
+                VALUES (?, ?, ?, '', 'image/png', 'in_post', ?)
+                "#,
+            )
+            .bind(image_id)
+            .bind(file_name)
+            .bind(file_name)
+            .bind(uploaded_at)
+            .execute(&db)
+            .await
+            .unwrap();
+        }
+        query(
+            "INSERT INTO posts (post_id, title, content, writer_id, status) VALUES ('post-1', 't', '![a](/images/second.png)', 'writer', 'published')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let report = backfill_content_hashes(&db, &storage, true).await.unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.hashed_count, 3);
+        assert_eq!(report.merged_image_ids, vec!["second".to_string()]);
+        assert_eq!(report.removed_files, vec!["second.png".to_string()]);
+        assert_eq!(report.freed_bytes, b"duplicate".len() as u64);
+        // dry_run은 파일, 링크, 해시를 그대로 둔다.
+        assert!(storage.head("second.png").await.unwrap().is_some());
+        let untouched: String = query_scalar("SELECT content FROM posts WHERE post_id = 'post-1'")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(untouched, "![a](/images/second.png)");
+        let blobs: i64 = query_scalar("SELECT COUNT(*) FROM image_blobs")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(blobs, 0);
+
+        let result = backfill_content_hashes(&db, &storage, false).await.unwrap();
+
+        assert_eq!(result.hashed_count, 3);
+        assert_eq!(result.merged_image_ids, vec!["second".to_string()]);
+        assert_eq!(result.removed_files, vec!["second.png".to_string()]);
+        assert!(storage.head("second.png").await.unwrap().is_none());
+
+        let file_name: String =
+            query_scalar("SELECT file_name FROM images WHERE image_id = 'second'")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(file_name, "first.png");
+
+        let content: String = query_scalar("SELECT content FROM posts WHERE post_id = 'post-1'")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(content, "![a](/images/first.png)");
+
+        let ref_count: i64 = query_scalar("SELECT ref_count FROM image_blobs WHERE file_name = 'first.png'")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(ref_count, 2);
+
+        let again = backfill_content_hashes(&db, &storage, false).await.unwrap();
+        assert_eq!(again.hashed_count, 0);
+    }
+}
@@ -0,0 +1,723 @@
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    time::{Duration, SystemTime},
+};
+
+use poem::{http::StatusCode, Error};
+use sqlx::{query, query_as, query_scalar, FromRow, SqlitePool};
+use tokio::time::interval;
+
+use crate::attachments::attachment_file_names;
+use crate::image_dedup::release_blob;
+use crate::storage::{is_valid_key, SharedStorage, UploadStorage};
+use crate::models::{
+    ImageGcResponse, ImageOrphanReportResponse, ImageReference, MissingImageFileResponse,
+    OrphanedImageResponse, UntrackedImageFileResponse,
+};
+
+pub const IMAGE_PUBLIC_PREFIX: &str = "/images/";
+const DEFAULT_MIN_AGE_HOURS: i64 = 24;
+
+#[derive(Debug, FromRow)]
+struct StoredImageRow {
+    image_id: String,
+    post_id: Option<String>,
+    file_name: String,
+    origin_name: String,
+    image_type: String,
+    uploaded_at: Option<String>,
+    is_old_enough: bool,
+}
+
+#[derive(Debug, FromRow)]
+struct StoredVariantRow {
+    image_id: String,
+    file_name: String,
+}
+
+#[derive(Debug, FromRow)]
+struct ContentSourceRow {
+    source_id: String,
+    content: Option<String>,
+}
+
+/// 업로드 직후 아직 글에 붙이지 않은 이미지를 지우지 않도록, 이 시간보다 오래된 것만 고아로 본다.
+pub fn gc_min_age_hours() -> i64 {
+    env::var("IMAGE_GC_MIN_AGE_HOURS")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .filter(|value| *value >= 0)
+        .unwrap_or(DEFAULT_MIN_AGE_HOURS)
+}
+
+/// 본문에서 `/images/<file>` 링크를 찾아 파일 이름만 뽑는다.
+/// 절대 URL(`https://.../images/a.png`)과 Markdown/HTML 링크 모두 같은 규칙으로 잡힌다.
+/// 문장 끝의 마침표처럼 뒤에 붙은 `.`/`-`/`_`는 파일 이름이 아니므로 떼어 낸다.
+pub fn extract_image_file_names(content: &str) -> Vec<String> {
+    let mut file_names = Vec::new();
+    let mut rest = content;
+
+    while let Some(index) = rest.find(IMAGE_PUBLIC_PREFIX) {
+        rest = &rest[index + IMAGE_PUBLIC_PREFIX.len()..];
+        let file_name: String = rest
+            .chars()
+            .take_while(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-' | '_'))
+            .collect();
+        let file_name = file_name.trim_end_matches(['.', '-', '_']).to_string();
+
+        if !file_name.is_empty() && !file_names.contains(&file_name) {
+            file_names.push(file_name);
+        }
+    }
+
+    file_names
+}
+
+/// posts 본문, portfolio 섹션, 프로필 사진 URL에서 참조 중인 이미지 파일을 모은다.
+/// 발행 전 초안과 되돌릴 수 있는 portfolio 스냅샷도 참조로 본다.
+pub async fn collect_image_references(
+    db: &SqlitePool,
+) -> Result<HashMap<String, Vec<ImageReference>>, Error> {
+    let sources: [(&str, &str); 5] = [
+        ("post", "SELECT post_id AS source_id, content FROM posts"),
+        (
+            "portfolio",
+            r#"
+            SELECT p.slug || ':' || s.section_key AS source_id, s.content
+            FROM portfolio_section s
+            JOIN portfolio p ON p.portfolio_id = s.portfolio_id
+            "#,
+        ),
+        (
+            "portfolio_draft",
+            "SELECT slug || ':' || lang AS source_id, content FROM portfolio_draft",
+        ),
+        (
+            "portfolio_version",
+            "SELECT slug || ':' || lang || ':' || version_number AS source_id, content FROM portfolio_version",
+        ),
+        (
+            "user_avatar",
+            "SELECT user_id AS source_id, avatar_url AS content FROM users WHERE avatar_url IS NOT NULL",
+        ),
+    ];
+
+    let mut references: HashMap<String, Vec<ImageReference>> = HashMap::new();
+
+    for (source_type, sql) in sources {
+        let rows = query_as::<_, ContentSourceRow>(sql)
+            .fetch_all(db)
+            .await
+            .map_err(|err| {
+                Error::from_string(
+                    format!("이미지 참조 조회 실패 ({}): {}", source_type, err),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )
+            })?;
+
+        for row in rows {
+            let Some(content) = row.content.as_deref() else {
+                continue;
+            };
+
+            for file_name in extract_image_file_names(content) {
+                references.entry(file_name).or_default().push(ImageReference {
+                    source_type: source_type.to_string(),
+                    source_id: row.source_id.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(references)
+}
+
+pub async fn build_orphan_report(
+    db: &SqlitePool,
+    storage: &dyn UploadStorage,
+    min_age_hours: i64,
+) -> Result<ImageOrphanReportResponse, Error> {
+    let references = collect_image_references(db).await?;
+
+    let images = query_as::<_, StoredImageRow>(
+        r#"
+        SELECT
+            image_id,
+            post_id,
+            file_name,
+            origin_name,
+            image_type,
+            uploaded_at,
+            COALESCE(uploaded_at <= datetime('now', ?), 1) AS is_old_enough
+        FROM images
+        ORDER BY uploaded_at ASC, image_id ASC
+        "#,
+    )
+    .bind(format!("-{} hours", min_age_hours))
+    .fetch_all(db)
+    .await
+    .map_err(|err| {
+        Error::from_string(
+            format!("이미지 목록 조회 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?;
+
+    let variants = list_variant_files(db).await?;
+
+    let mut orphaned_images = Vec::new();
+    let mut missing_files = Vec::new();
+    // 첨부 파일도 같은 저장소를 쓰므로 추적 중인 파일로 본다.
+    let mut tracked_file_names: HashSet<String> = variants
+        .iter()
+        .map(|variant| variant.file_name.clone())
+        .chain(attachment_file_names(db).await?)
+        .collect();
+
+    for image in images {
+        tracked_file_names.insert(image.file_name.clone());
+        let file_exists = storage.head(&image.file_name).await?.is_some();
+        // variant 파일을 직접 링크한 경우도 원본 이미지를 참조한 것으로 본다.
+        let image_references = references.get(&image.file_name).or_else(|| {
+            variants
+                .iter()
+                .filter(|variant| variant.image_id == image.image_id)
+                .find_map(|variant| references.get(&variant.file_name))
+        });
+
+        if !file_exists {
+            missing_files.push(MissingImageFileResponse {
+                image_id: image.image_id.clone(),
+                file_name: image.file_name.clone(),
+                references: image_references.cloned().unwrap_or_default(),
+            });
+        }
+
+        if image_references.is_none() && image.is_old_enough {
+            orphaned_images.push(OrphanedImageResponse {
+                image_id: image.image_id,
+                post_id: image.post_id,
+                file_name: image.file_name,
+                origin_name: image.origin_name,
+                image_type: image.image_type,
+                uploaded_at: image.uploaded_at,
+                file_exists,
+            });
+        }
+    }
+
+    let untracked_files =
+        list_untracked_files(storage, &tracked_file_names, min_age_hours).await?;
+
+    Ok(ImageOrphanReportResponse {
+        min_age_hours,
+        orphaned_images,
+        missing_files,
+        untracked_files,
+    })
+}
+
+/// 고아 이미지(행+파일), 파일이 사라진 행, 행이 없는 파일을 정리한다.
+/// `dry_run`이면 같은 보고서만 돌려주고 아무것도 지우지 않는다.
+pub async fn sweep_images(
+    db: &SqlitePool,
+    storage: &dyn UploadStorage,
+    min_age_hours: i64,
+    dry_run: bool,
+) -> Result<ImageGcResponse, Error> {
+    let report = build_orphan_report(db, storage, min_age_hours).await?;
+
+    let mut removed_image_ids = Vec::new();
+    let mut removed_files = Vec::new();
+    let mut freed_bytes = 0;
+
+    let orphan_rows = report
+        .orphaned_images
+        .iter()
+        .map(|image| (image.image_id.as_str(), Some(image.file_name.as_str())));
+    let missing_rows = report
+        .missing_files
+        .iter()
+        .map(|image| (image.image_id.as_str(), None));
+
+    for (image_id, file_name) in orphan_rows.chain(missing_rows) {
+        if removed_image_ids.iter().any(|id| id == image_id) {
+            continue;
+        }
+
+        let (files, byte_size) = remove_image(db, storage, image_id, file_name, dry_run).await?;
+        freed_bytes += byte_size;
+        removed_files.extend(files);
+        removed_image_ids.push(image_id.to_string());
+    }
+
+    for file in &report.untracked_files {
+        if let Some(byte_size) = remove_upload_file(storage, &file.file_name, dry_run).await? {
+            freed_bytes += byte_size;
+            removed_files.push(file.file_name.clone());
+        }
+    }
+
+    Ok(ImageGcResponse {
+        dry_run,
+        removed_image_ids,
+        removed_files,
+        freed_bytes,
+        report,
+    })
+}
+
+/// `IMAGE_GC_INTERVAL_HOURS`가 설정된 경우에만 주기적으로 GC를 돌린다.
+pub fn start_image_gc_worker(db: SqlitePool, storage: SharedStorage) {
+    let Some(interval_hours) = env::var("IMAGE_GC_INTERVAL_HOURS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .filter(|value| *value > 0)
+    else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(interval_hours * 60 * 60));
+
+        loop {
+            ticker.tick().await;
+            match sweep_images(&db, storage.as_ref(), gc_min_age_hours(), false).await {
+                Ok(result) => println!(
+                    "image gc finished: removed_rows={}, removed_files={}, freed_bytes={}",
+                    result.removed_image_ids.len(),
+                    result.removed_files.len(),
+                    result.freed_bytes
+                ),
+                Err(err) => eprintln!("image gc failed: {}", err),
+            }
+        }
+    });
+}
+
+/// 이미지 파일 이름이나 그 variant 파일 이름을 참조하는 곳을 모두 찾는다.
+pub async fn find_image_references(
+    db: &SqlitePool,
+    image_id: &str,
+    file_name: &str,
+) -> Result<Vec<ImageReference>, Error> {
+    let mut references = collect_image_references(db).await?;
+    let variant_files = list_image_variant_files(db, image_id).await?;
+
+    let mut found = references.remove(file_name).unwrap_or_default();
+    for variant_file in variant_files {
+        for reference in references.remove(&variant_file).unwrap_or_default() {
+            if !found.iter().any(|existing| {
+                existing.source_type == reference.source_type
+                    && existing.source_id == reference.source_id
+            }) {
+                found.push(reference);
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+/// 원본 파일, variant 파일과 두 테이블의 행을 함께 지운다. `file_name`이 없으면 행만 지운다.
+/// 같은 내용을 공유하는 다른 이미지가 남아 있으면 파일은 두고 이 행만 지운다.
+/// 지운(또는 `dry_run`이면 지울) 파일 이름과 크기 합계를 돌려준다.
+pub async fn remove_image(
+    db: &SqlitePool,
+    storage: &dyn UploadStorage,
+    image_id: &str,
+    file_name: Option<&str>,
+    dry_run: bool,
+) -> Result<(Vec<String>, u64), Error> {
+    let mut removed_files = Vec::new();
+    let mut freed_bytes = 0;
+
+    let content_hash: Option<String> =
+        query_scalar("SELECT content_hash FROM images WHERE image_id = ?")
+            .bind(image_id)
+            .fetch_optional(db)
+            .await
+            .map_err(|err| {
+                Error::from_string(
+                    format!("이미지 해시 조회 실패: {}", err),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )
+            })?
+            .flatten();
+
+    if let Some(content_hash) = content_hash {
+        if !release_blob(db, &content_hash, dry_run).await? {
+            if !dry_run {
+                detach_shared_image(db, image_id).await?;
+            }
+            return Ok((removed_files, freed_bytes));
+        }
+    }
+
+    if let Some(file_name) = file_name {
+        if let Some(byte_size) = remove_upload_file(storage, file_name, dry_run).await? {
+            freed_bytes += byte_size;
+            removed_files.push(file_name.to_string());
+        }
+    }
+
+    for variant_file in list_image_variant_files(db, image_id).await? {
+        if let Some(byte_size) = remove_upload_file(storage, &variant_file, dry_run).await? {
+            freed_bytes += byte_size;
+            removed_files.push(variant_file);
+        }
+    }
+
+    if !dry_run {
+        query("DELETE FROM image_variants WHERE image_id = ?")
+            .bind(image_id)
+            .execute(db)
+            .await
+            .map_err(|err| {
+                Error::from_string(
+                    format!("이미지 variant 행 삭제 실패: {}", err),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )
+            })?;
+        query("DELETE FROM images WHERE image_id = ?")
+            .bind(image_id)
+            .execute(db)
+            .await
+            .map_err(|err| {
+                Error::from_string(
+                    format!("이미지 행 삭제 실패: {}", err),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )
+            })?;
+    }
+
+    Ok((removed_files, freed_bytes))
+}
+
+/// 파일을 공유하는 다른 이미지에 variant 행을 넘긴 뒤 이 이미지 행만 지운다.
+async fn detach_shared_image(db: &SqlitePool, image_id: &str) -> Result<(), Error> {
+    query(
+        r#"
+        UPDATE image_variants
+        SET image_id = (
+            SELECT other.image_id
+            FROM images other
+            JOIN images this ON this.file_name = other.file_name
+            WHERE this.image_id = ? AND other.image_id != ?
+            LIMIT 1
+        )
+        WHERE image_id = ?
+        "#,
+    )
+    .bind(image_id)
+    .bind(image_id)
+    .bind(image_id)
+    .execute(db)
+    .await
+    .map_err(|err| {
+        Error::from_string(
+            format!("이미지 variant 소유자 변경 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?;
+
+    query("DELETE FROM images WHERE image_id = ?")
+        .bind(image_id)
+        .execute(db)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("이미지 행 삭제 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+
+    Ok(())
+}
+
+async fn list_image_variant_files(db: &SqlitePool, image_id: &str) -> Result<Vec<String>, Error> {
+    query_scalar("SELECT file_name FROM image_variants WHERE image_id = ?")
+        .bind(image_id)
+        .fetch_all(db)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("이미지 variant 조회 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })
+}
+
+async fn list_variant_files(db: &SqlitePool) -> Result<Vec<StoredVariantRow>, Error> {
+    query_as::<_, StoredVariantRow>("SELECT image_id, file_name FROM image_variants")
+        .fetch_all(db)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("이미지 variant 조회 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })
+}
+
+async fn list_untracked_files(
+    storage: &dyn UploadStorage,
+    tracked_file_names: &HashSet<String>,
+    min_age_hours: i64,
+) -> Result<Vec<UntrackedImageFileResponse>, Error> {
+    let min_age = Duration::from_secs(min_age_hours.max(0) as u64 * 60 * 60);
+    let mut untracked_files = Vec::new();
+
+    for object in storage.list().await? {
+        if tracked_file_names.contains(&object.key) {
+            continue;
+        }
+
+        let age = object
+            .modified_at
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .unwrap_or_default();
+        if age < min_age {
+            continue;
+        }
+
+        untracked_files.push(UntrackedImageFileResponse {
+            file_name: object.key,
+            byte_size: object.byte_size,
+        });
+    }
+
+    untracked_files.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    Ok(untracked_files)
+}
+
+pub async fn remove_upload_file(
+    storage: &dyn UploadStorage,
+    file_name: &str,
+    dry_run: bool,
+) -> Result<Option<u64>, Error> {
+    if !is_valid_key(file_name) {
+        return Ok(None);
+    }
+
+    let Some(object) = storage.head(file_name).await? else {
+        return Ok(None);
+    };
+
+    if !dry_run {
+        storage.delete(file_name).await?;
+    }
+
+    Ok(Some(object.byte_size))
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{query, query_scalar, SqlitePool};
+
+    use crate::db::init_db;
+    use crate::storage::{MemoryStorage, UploadStorage};
+
+    use super::{
+        build_orphan_report, extract_image_file_names, find_image_references, sweep_images,
+    };
+
+    async fn create_test_db() -> SqlitePool {
+        let db = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("failed to connect sqlite");
+        init_db(&db).await.expect("failed to init db");
+        db
+    }
+
+    async fn put_file(storage: &MemoryStorage, file_name: &str, bytes: &[u8]) {
+        storage
+            .put(file_name, bytes.to_vec(), "image/png")
+            .await
+            .expect("failed to store file");
+    }
+
+    async fn file_exists(storage: &MemoryStorage, file_name: &str) -> bool {
+        storage.head(file_name).await.unwrap().is_some()
+    }
+
+    async fn insert_image(db: &SqlitePool, image_id: &str, file_name: &str) {
+        query(
+            r#"
+            INSERT INTO images (image_id, post_id, file_name, origin_name, file_path, mime_type, image_type, uploaded_at)
+            VALUES (?, NULL, ?, ?, ?, 'image/png', 'in_post', datetime('now', '-2 days'))
+            "#,
+        )
+        .bind(image_id)
+        .bind(file_name)
+        .bind(file_name)
+        .bind(file_name)
+        .execute(db)
+        .await
+        .expect("failed to insert image");
+    }
+
+    #[test]
+    fn extract_image_file_names_finds_markdown_html_and_absolute_links() {
+        let content = r#"
+![a](/images/a.png)
+<img src="https://api.tyange.com/images/b-1_2.webp" />
+![again](/images/a.png) /images/ /images/c.jpg?w=320
+See /images/d.png. Or /images/e.gif...
+"#;
+
+        assert_eq!(
+            extract_image_file_names(content),
+            vec!["a.png", "b-1_2.webp", "c.jpg", "d.png", "e.gif"]
+        );
+    }
+
+    #[tokio::test]
+    async fn report_classifies_orphaned_missing_and_untracked_files() {
+        let db = create_test_db().await;
+        let storage = MemoryStorage::default();
+
+        query(
+            "INSERT INTO posts (post_id, title, content, writer_id, status) VALUES ('post-1', 't', '![x](/images/used.png)', 'writer', 'published')",
+        )
+        .execute(&db)
+        .await
+        .expect("failed to insert post");
+
+        insert_image(&db, "used", "used.png").await;
+        insert_image(&db, "orphan", "orphan.png").await;
+        insert_image(&db, "missing", "missing.png").await;
+        put_file(&storage, "used.png", b"used").await;
+        put_file(&storage, "orphan.png", b"orphan").await;
+        put_file(&storage, "stray.png", b"stray").await;
+
+        let report = build_orphan_report(&db, &storage, 0)
+            .await
+            .expect("failed to build report");
+
+        let orphan_ids: Vec<_> = report
+            .orphaned_images
+            .iter()
+            .map(|image| image.image_id.as_str())
+            .collect();
+        assert_eq!(orphan_ids, vec!["missing", "orphan"]);
+        assert_eq!(report.missing_files.len(), 1);
+        assert_eq!(report.missing_files[0].image_id, "missing");
+        assert_eq!(report.untracked_files.len(), 1);
+        assert_eq!(report.untracked_files[0].file_name, "stray.png");
+    }
+
+    #[tokio::test]
+    async fn dry_run_keeps_everything_and_real_run_removes_orphans() {
+        let db = create_test_db().await;
+        let storage = MemoryStorage::default();
+
+        query(
+            "INSERT INTO portfolio (slug) VALUES ('dev')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        query(
+            "INSERT INTO portfolio_section (portfolio_id, section_key, content) VALUES (1, 'intro', '{\"icon_url\":\"/images/icon.svg\"}')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        insert_image(&db, "icon", "icon.svg").await;
+        insert_image(&db, "orphan", "orphan.png").await;
+        put_file(&storage, "icon.svg", b"<svg/>").await;
+        put_file(&storage, "orphan.png", b"orphan").await;
+        put_file(&storage, "stray.png", b"stray").await;
+
+        let dry_run = sweep_images(&db, &storage, 0, true)
+            .await
+            .expect("dry run failed");
+        assert!(dry_run.dry_run);
+        assert_eq!(dry_run.removed_image_ids, vec!["orphan"]);
+        assert_eq!(dry_run.freed_bytes, 11);
+        assert!(file_exists(&storage, "orphan.png").await);
+        assert!(file_exists(&storage, "stray.png").await);
+
+        let result = sweep_images(&db, &storage, 0, false)
+            .await
+            .expect("gc failed");
+        assert_eq!(result.removed_image_ids, vec!["orphan"]);
+        assert!(!file_exists(&storage, "orphan.png").await);
+        assert!(!file_exists(&storage, "stray.png").await);
+        assert!(file_exists(&storage, "icon.svg").await);
+
+        let remaining: Vec<String> = query_scalar("SELECT image_id FROM images")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(remaining, vec!["icon"]);
+    }
+
+    #[tokio::test]
+    async fn images_used_only_by_drafts_or_versions_are_kept() {
+        let db = create_test_db().await;
+        let storage = MemoryStorage::default();
+
+        query(
+            "INSERT INTO portfolio_draft (slug, lang, content) VALUES ('dev', 'ko', '{\"intro\":{\"icon_url\":\"/images/draft.png\"}}')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        query(
+            "INSERT INTO portfolio_version (slug, lang, version_number, content, source) VALUES ('dev', 'ko', 1, '{\"intro\":{\"icon_url\":\"/images/old.png\"}}', 'publish')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        insert_image(&db, "draft", "draft.png").await;
+        insert_image(&db, "old", "old.png").await;
+        insert_image(&db, "orphan", "orphan.png").await;
+        put_file(&storage, "draft.png", b"draft").await;
+        put_file(&storage, "old.png", b"old").await;
+        put_file(&storage, "orphan.png", b"orphan").await;
+
+        let result = sweep_images(&db, &storage, 0, false)
+            .await
+            .expect("gc failed");
+        assert_eq!(result.removed_image_ids, vec!["orphan"]);
+        assert!(file_exists(&storage, "draft.png").await);
+        assert!(file_exists(&storage, "old.png").await);
+
+        let references = find_image_references(&db, "draft", "draft.png")
+            .await
+            .expect("failed to find references");
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].source_type, "portfolio_draft");
+        assert_eq!(references[0].source_id, "dev:ko");
+    }
+
+    #[tokio::test]
+    async fn recent_uploads_are_not_reported_before_min_age() {
+        let db = create_test_db().await;
+        let storage = MemoryStorage::default();
+
+        query(
+            r#"
+            INSERT INTO images (image_id, post_id, file_name, origin_name, file_path, mime_type, image_type)
+            VALUES ('fresh', NULL, 'fresh.png', 'fresh.png', 'fresh.png', 'image/png', 'in_post')
+            "#,
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        put_file(&storage, "fresh.png", b"fresh").await;
+        put_file(&storage, "stray.png", b"stray").await;
+
+        let report = build_orphan_report(&db, &storage, 24)
+            .await
+            .expect("failed to build report");
+
+        assert!(report.orphaned_images.is_empty());
+        assert!(report.untracked_files.is_empty());
+    }
+}
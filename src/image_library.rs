@@ -0,0 +1,148 @@
+use poem::{http::StatusCode, Error};
+use sqlx::{query_as, query_scalar, QueryBuilder, Sqlite, SqlitePool};
+use tyange_cms_api::auth::authorization::AuthenticatedUser;
+
+use crate::{
+    image_gc::IMAGE_PUBLIC_PREFIX,
+    models::{ImageDetailDb, ImageItemResponse, ImageListQuery, ImageListResponse},
+};
+
+const IMAGE_COLUMNS: &str = r#"
+    image_id, post_id, file_name, origin_name, mime_type, image_type, uploaded_by, uploaded_at,
+    alt_text, caption, width, height, byte_size, dominant_color, blurhash
+"#;
+
+pub fn image_item_response(image: ImageDetailDb) -> ImageItemResponse {
+    ImageItemResponse {
+        image_id: image.image_id,
+        post_id: image.post_id,
+        image_path: format!("{}{}", IMAGE_PUBLIC_PREFIX, image.file_name),
+        origin_name: image.origin_name,
+        mime_type: image.mime_type,
+        image_type: image.image_type,
+        uploaded_by: image.uploaded_by,
+        uploaded_at: image.uploaded_at,
+        alt_text: image.alt_text,
+        caption: image.caption,
+        width: image.width.map(|width| width as u32),
+        height: image.height.map(|height| height as u32),
+        byte_size: image.byte_size.map(|byte_size| byte_size as u64),
+        dominant_color: image.dominant_color,
+        blurhash: image.blurhash,
+    }
+}
+
+pub async fn fetch_image(db: &SqlitePool, image_id: &str) -> Result<ImageDetailDb, Error> {
+    query_as::<Sqlite, ImageDetailDb>(&format!(
+        "SELECT {} FROM images WHERE image_id = ?",
+        IMAGE_COLUMNS
+    ))
+    .bind(image_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| {
+        Error::from_string(
+            format!("Error fetching image: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?
+    .ok_or_else(|| Error::from_string("해당 id에 해당하는 이미지가 없네요.", StatusCode::NOT_FOUND))
+}
+
+/// 최근 업로드 순으로 이미지를 돌려준다. `post_id`, `image_type`, `uploaded_by`로 좁힐 수 있다.
+pub async fn list_images(
+    db: &SqlitePool,
+    params: ImageListQuery,
+) -> Result<ImageListResponse, Error> {
+    let limit = params.limit.unwrap_or(50).clamp(1, 100) as i64;
+    let offset = params.offset.unwrap_or(0) as i64;
+    let filters = [
+        ("post_id", params.post_id),
+        ("image_type", params.image_type),
+        ("uploaded_by", params.uploaded_by),
+    ];
+
+    let mut count_builder = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM images WHERE 1 = 1");
+    let mut list_builder =
+        QueryBuilder::<Sqlite>::new(format!("SELECT {} FROM images WHERE 1 = 1", IMAGE_COLUMNS));
+
+    for (column, value) in filters {
+        let Some(value) = value.map(|value| value.trim().to_string()) else {
+            continue;
+        };
+        if value.is_empty() {
+            continue;
+        }
+        for builder in [&mut count_builder, &mut list_builder] {
+            builder.push(format!(" AND {} = ", column));
+            builder.push_bind(value.clone());
+        }
+    }
+
+    let total_count: i64 = count_builder
+        .build_query_scalar()
+        .fetch_one(db)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("이미지 개수 조회 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+
+    list_builder.push(" ORDER BY uploaded_at DESC, image_id DESC LIMIT ");
+    list_builder.push_bind(limit);
+    list_builder.push(" OFFSET ");
+    list_builder.push_bind(offset);
+
+    let images = list_builder
+        .build_query_as::<ImageDetailDb>()
+        .fetch_all(db)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("이미지 목록 조회 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+
+    Ok(ImageListResponse {
+        images: images.into_iter().map(image_item_response).collect(),
+        total_count,
+    })
+}
+
+/// 업로드한 본인이나 관리자만 이미지 정보를 바꾸거나 지울 수 있다.
+/// 업로더 기록이 없는 예전 이미지는 관리자만 다룰 수 있다.
+pub fn ensure_image_owner(user: &AuthenticatedUser, image: &ImageDetailDb) -> Result<(), Error> {
+    if user.role == "admin" || image.uploaded_by.as_deref() == Some(user.user_id.as_str()) {
+        Ok(())
+    } else {
+        Err(Error::from_string(
+            "본인이 업로드한 이미지만 수정 또는 삭제할 수 있습니다.",
+            StatusCode::FORBIDDEN,
+        ))
+    }
+}
+
+/// 원본이나 variant 파일에 기록된 MIME 타입. 기록이 없는 파일이면 `None`이다.
+pub async fn stored_mime_type(db: &SqlitePool, file_name: &str) -> Result<Option<String>, Error> {
+    query_scalar(
+        r#"
+        SELECT mime_type FROM images WHERE file_name = ?
+        UNION ALL
+        SELECT mime_type FROM image_variants WHERE file_name = ?
+        LIMIT 1
+        "#,
+    )
+    .bind(file_name)
+    .bind(file_name)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| {
+        Error::from_string(
+            format!("이미지 형식 조회 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })
+}
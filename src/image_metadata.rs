@@ -0,0 +1,413 @@
+use std::{env, io::Cursor};
+
+use image::{
+    codecs::{jpeg::JpegEncoder, png::PngEncoder, webp::WebPEncoder},
+    imageops::FilterType,
+    metadata::Orientation,
+    DynamicImage, ImageDecoder, ImageFormat, ImageReader,
+};
+
+const REENCODE_JPEG_QUALITY: u8 = 90;
+const BLURHASH_COMPONENTS_X: u32 = 4;
+const BLURHASH_COMPONENTS_Y: u32 = 3;
+const SAMPLE_SIZE: u32 = 32;
+
+#[derive(Debug)]
+pub struct ProcessedImage {
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub dominant_color: String,
+    pub blurhash: String,
+}
+
+/// `IMAGE_STRIP_METADATA=false`로 끄지 않는 한 업로드 파일의 EXIF/XMP/텍스트 메타데이터를 지운다.
+pub fn strip_metadata_enabled() -> bool {
+    env::var("IMAGE_STRIP_METADATA")
+        .map(|value| !value.trim().eq_ignore_ascii_case("false"))
+        .unwrap_or(true)
+}
+
+/// EXIF 방향을 픽셀에 반영하고(필요하면 재인코딩), 메타데이터를 지운 뒤 크기/대표색/blurhash를 계산한다.
+/// 래스터 이미지가 아니거나 디코딩할 수 없으면 `None`이다. 메타데이터를 지울 수 없을 때도 `None`이므로,
+/// 원본에 위치 정보가 남을 수 있는 포맷이면 호출하는 쪽에서 업로드를 거절한다.
+pub fn process_uploaded_image(bytes: &[u8], strip_metadata: bool) -> Option<ProcessedImage> {
+    let reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?;
+    let format = reader.format()?;
+    if !matches!(
+        format,
+        ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP | ImageFormat::Gif
+    ) {
+        return None;
+    }
+
+    let mut decoder = reader.into_decoder().ok()?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut image = DynamicImage::from_decoder(decoder).ok()?;
+
+    let processed_bytes = if orientation != Orientation::NoTransforms && format != ImageFormat::Gif
+    {
+        image.apply_orientation(orientation);
+        reencode(&image, format)?
+    } else if strip_metadata {
+        strip_metadata_bytes(bytes, format, &image)?
+    } else {
+        bytes.to_vec()
+    };
+
+    Some(ProcessedImage {
+        bytes: processed_bytes,
+        width: image.width(),
+        height: image.height(),
+        dominant_color: dominant_color(&image),
+        blurhash: blurhash(&image).unwrap_or_default(),
+    })
+}
+
+fn reencode(image: &DynamicImage, format: ImageFormat) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let result = match format {
+        ImageFormat::Jpeg => image.to_rgb8().write_with_encoder(JpegEncoder::new_with_quality(
+            &mut bytes,
+            REENCODE_JPEG_QUALITY,
+        )),
+        ImageFormat::Png => image.write_with_encoder(PngEncoder::new(&mut bytes)),
+        ImageFormat::WebP => image
+            .to_rgba8()
+            .write_with_encoder(WebPEncoder::new_lossless(&mut bytes)),
+        _ => return None,
+    };
+    result.ok().map(|_| bytes)
+}
+
+/// 세그먼트/청크만 골라 내 픽셀은 그대로 두고, 구조를 읽지 못하면 디코딩한 픽셀로 다시 인코딩한다.
+/// 메타데이터가 남은 원본 바이트로는 돌아가지 않는다. GIF는 EXIF를 담지 않으므로 그대로 쓴다.
+fn strip_metadata_bytes(
+    bytes: &[u8],
+    format: ImageFormat,
+    image: &DynamicImage,
+) -> Option<Vec<u8>> {
+    let stripped = match format {
+        ImageFormat::Jpeg => strip_jpeg_metadata(bytes),
+        ImageFormat::Png => strip_png_metadata(bytes),
+        ImageFormat::WebP => strip_webp_metadata(bytes),
+        ImageFormat::Gif => return Some(bytes.to_vec()),
+        _ => None,
+    };
+    stripped.or_else(|| reencode(image, format))
+}
+
+/// JPEG 세그먼트 중 APP1(EXIF/XMP), 그 밖의 APPn, COM을 버린다.
+/// 색 재현에 필요한 APP0(JFIF), APP2(ICC), APP14(Adobe)는 남긴다. 픽셀 데이터는 건드리지 않는다.
+pub fn strip_jpeg_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.len() < 4 || bytes[0..2] != [0xFF, 0xD8] {
+        return None;
+    }
+
+    let mut output = Vec::with_capacity(bytes.len());
+    output.extend_from_slice(&bytes[0..2]);
+    let mut index = 2;
+
+    while index + 2 <= bytes.len() {
+        if bytes[index] != 0xFF {
+            return None;
+        }
+        // 마커 앞의 채움 바이트(0xFF)는 몇 개든 올 수 있다. 버려도 같은 파일이다.
+        if bytes[index + 1] == 0xFF {
+            index += 1;
+            continue;
+        }
+        let marker = bytes[index + 1];
+
+        // TEM, RSTn은 길이 없는 단독 마커다.
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+            output.extend_from_slice(&bytes[index..index + 2]);
+            index += 2;
+            continue;
+        }
+        if index + 4 > bytes.len() {
+            return None;
+        }
+
+        // SOS 이후는 엔트로피 코딩된 데이터라 그대로 복사한다.
+        if marker == 0xDA {
+            output.extend_from_slice(&bytes[index..]);
+            return Some(output);
+        }
+
+        let length = u16::from_be_bytes([bytes[index + 2], bytes[index + 3]]) as usize;
+        let end = index + 2 + length;
+        if length < 2 || end > bytes.len() {
+            return None;
+        }
+
+        let segment = &bytes[index..end];
+        let payload = &bytes[index + 4..end];
+        let keep = match marker {
+            0xE0 | 0xEE => true,
+            0xE2 => payload.starts_with(b"ICC_PROFILE\0"),
+            0xE1 | 0xE3..=0xED | 0xEF | 0xFE => false,
+            _ => true,
+        };
+        if keep {
+            output.extend_from_slice(segment);
+        }
+        index = end;
+    }
+
+    None
+}
+
+/// PNG 청크 중 eXIf와 텍스트/시간 청크를 버린다.
+pub fn strip_png_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    if bytes.len() < SIGNATURE.len() || bytes[0..8] != SIGNATURE {
+        return None;
+    }
+
+    let mut output = Vec::with_capacity(bytes.len());
+    output.extend_from_slice(&SIGNATURE);
+    let mut index = 8;
+
+    while index + 12 <= bytes.len() {
+        let length = u32::from_be_bytes(bytes[index..index + 4].try_into().ok()?) as usize;
+        let end = index + 12 + length;
+        if end > bytes.len() {
+            return None;
+        }
+
+        let chunk_type = &bytes[index + 4..index + 8];
+        if !matches!(chunk_type, b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME") {
+            output.extend_from_slice(&bytes[index..end]);
+        }
+        if chunk_type == b"IEND" {
+            return Some(output);
+        }
+        index = end;
+    }
+
+    None
+}
+
+/// WebP RIFF 청크 중 EXIF/XMP를 버리고 VP8X 플래그와 RIFF 크기를 다시 맞춘다.
+pub fn strip_webp_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WEBP" {
+        return None;
+    }
+
+    let mut output = Vec::with_capacity(bytes.len());
+    output.extend_from_slice(&bytes[0..12]);
+    let mut index = 12;
+
+    while index + 8 <= bytes.len() {
+        let fourcc = &bytes[index..index + 4];
+        let size = u32::from_le_bytes(bytes[index + 4..index + 8].try_into().ok()?) as usize;
+        let end = index + 8 + size + (size % 2);
+        if index + 8 + size > bytes.len() {
+            return None;
+        }
+        let end = end.min(bytes.len());
+
+        match fourcc {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" => {
+                let start = output.len();
+                output.extend_from_slice(&bytes[index..end]);
+                // flags: bit 3 = EXIF, bit 2 = XMP
+                output[start + 8] &= !(0x08 | 0x04);
+            }
+            _ => output.extend_from_slice(&bytes[index..end]),
+        }
+        index = end;
+    }
+
+    let riff_size = (output.len() - 8) as u32;
+    output[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Some(output)
+}
+
+/// 작게 줄인 뒤 채널당 4비트로 양자화해 가장 많이 나온 색 묶음의 평균을 `#rrggbb`로 돌려준다.
+pub fn dominant_color(image: &DynamicImage) -> String {
+    let sample = image
+        .resize(SAMPLE_SIZE, SAMPLE_SIZE, FilterType::Triangle)
+        .to_rgba8();
+    let mut buckets: Vec<(u32, [u64; 3])> = vec![(0, [0; 3]); 4096];
+
+    for pixel in sample.pixels() {
+        let [r, g, b, a] = pixel.0;
+        if a < 128 {
+            continue;
+        }
+        let key = ((r as usize >> 4) << 8) | ((g as usize >> 4) << 4) | (b as usize >> 4);
+        let bucket = &mut buckets[key];
+        bucket.0 += 1;
+        bucket.1[0] += r as u64;
+        bucket.1[1] += g as u64;
+        bucket.1[2] += b as u64;
+    }
+
+    let Some((count, sums)) = buckets
+        .into_iter()
+        .filter(|(count, _)| *count > 0)
+        .max_by_key(|(count, _)| *count)
+    else {
+        return "#000000".to_string();
+    };
+
+    let count = count as u64;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        sums[0] / count,
+        sums[1] / count,
+        sums[2] / count
+    )
+}
+
+fn blurhash(image: &DynamicImage) -> Option<String> {
+    let sample = image
+        .resize(SAMPLE_SIZE, SAMPLE_SIZE, FilterType::Triangle)
+        .to_rgba8();
+    blurhash::encode(
+        BLURHASH_COMPONENTS_X,
+        BLURHASH_COMPONENTS_Y,
+        sample.width(),
+        sample.height(),
+        sample.as_raw(),
+    )
+    .ok()
+}
+
+/// Orientation=6(90도 회전)과 GPS IFD 포인터를 가진 최소 EXIF(APP1) 세그먼트를 SOI 뒤에 끼워 넣는다.
+#[cfg(test)]
+pub(crate) fn jpeg_with_exif(width: u32, height: u32) -> Vec<u8> {
+    let image = DynamicImage::ImageRgb8(image::RgbImage::from_fn(width, height, |x, _| {
+        if x < width / 2 {
+            image::Rgb([220, 30, 30])
+        } else {
+            image::Rgb([30, 30, 220])
+        }
+    }));
+    let mut jpeg = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+        .unwrap();
+
+    let mut tiff = Vec::new();
+    tiff.extend_from_slice(b"MM\0\x2a\0\0\0\x08");
+    tiff.extend_from_slice(&2u16.to_be_bytes());
+    // Orientation (0x0112), SHORT, count 1, value 6
+    tiff.extend_from_slice(&[0x01, 0x12, 0x00, 0x03, 0, 0, 0, 1, 0x00, 0x06, 0, 0]);
+    // GPSInfo (0x8825), LONG, count 1, offset 38
+    tiff.extend_from_slice(&[0x88, 0x25, 0x00, 0x04, 0, 0, 0, 1, 0, 0, 0, 38]);
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    // GPS IFD: GPSLatitudeRef = "N"
+    tiff.extend_from_slice(&1u16.to_be_bytes());
+    tiff.extend_from_slice(&[0x00, 0x01, 0x00, 0x02, 0, 0, 0, 2, b'N', 0, 0, 0]);
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+
+    let mut payload = b"Exif\0\0".to_vec();
+    payload.extend_from_slice(&tiff);
+
+    let mut output = jpeg[0..2].to_vec();
+    output.extend_from_slice(&[0xFF, 0xE1]);
+    output.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+    output.extend_from_slice(&payload);
+    output.extend_from_slice(&jpeg[2..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{DynamicImage, ImageFormat, RgbImage};
+
+    use super::{
+        jpeg_with_exif, process_uploaded_image, strip_jpeg_metadata, strip_png_metadata,
+        strip_webp_metadata,
+    };
+
+    #[test]
+    fn process_applies_exif_orientation_and_drops_exif() {
+        let original = jpeg_with_exif(8, 4);
+        assert!(original.windows(4).any(|window| window == b"Exif"));
+
+        let processed = process_uploaded_image(&original, true).expect("should process jpeg");
+
+        assert_eq!((processed.width, processed.height), (4, 8));
+        assert!(!processed.bytes.windows(4).any(|window| window == b"Exif"));
+        let decoded = image::load_from_memory(&processed.bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (4, 8));
+        assert!(processed.dominant_color.starts_with('#'));
+        assert!(!processed.blurhash.is_empty());
+    }
+
+    #[test]
+    fn strip_handles_jpeg_fill_bytes_and_never_keeps_exif() {
+        // 방향은 그대로(1)라 재인코딩 없이 세그먼트만 지우는 경로를 탄다.
+        let mut jpeg = jpeg_with_exif(8, 4);
+        let orientation = jpeg
+            .windows(10)
+            .position(|window| window == [0x01, 0x12, 0x00, 0x03, 0, 0, 0, 1, 0x00, 0x06])
+            .unwrap();
+        jpeg[orientation + 9] = 0x01;
+        // SOI 뒤 APP1 마커 앞에 채움 바이트를 넣는다.
+        jpeg.splice(2..2, [0xFF, 0xFF, 0xFF]);
+        assert!(image::load_from_memory(&jpeg).is_ok());
+
+        let stripped = strip_jpeg_metadata(&jpeg).expect("fill bytes should be skipped");
+        assert!(!stripped.windows(4).any(|window| window == b"Exif"));
+
+        let processed = process_uploaded_image(&jpeg, true).expect("should process jpeg");
+        assert_eq!((processed.width, processed.height), (8, 4));
+        assert!(!processed.bytes.windows(4).any(|window| window == b"Exif"));
+        assert!(image::load_from_memory(&processed.bytes).is_ok());
+    }
+
+    #[test]
+    fn strip_png_metadata_removes_text_chunks_only() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, image::Rgb([1, 2, 3])));
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        // IHDR 뒤에 tEXt 청크를 끼워 넣는다. CRC는 strip 대상이라 검증하지 않는다.
+        let mut with_text = png[0..33].to_vec();
+        let text = b"Commentsecret";
+        with_text.extend_from_slice(&(text.len() as u32).to_be_bytes());
+        with_text.extend_from_slice(b"tEXt");
+        with_text.extend_from_slice(text);
+        with_text.extend_from_slice(&[0, 0, 0, 0]);
+        with_text.extend_from_slice(&png[33..]);
+
+        let stripped = strip_png_metadata(&with_text).unwrap();
+        assert_eq!(stripped, png);
+    }
+
+    #[test]
+    fn strip_webp_metadata_drops_exif_chunk_and_fixes_sizes() {
+        let mut webp = b"RIFF\0\0\0\0WEBP".to_vec();
+        webp.extend_from_slice(b"VP8X");
+        webp.extend_from_slice(&10u32.to_le_bytes());
+        webp.extend_from_slice(&[0x08, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        webp.extend_from_slice(b"EXIF");
+        webp.extend_from_slice(&3u32.to_le_bytes());
+        webp.extend_from_slice(&[1, 2, 3, 0]);
+        let riff_size = (webp.len() - 8) as u32;
+        webp[4..8].copy_from_slice(&riff_size.to_le_bytes());
+
+        let stripped = strip_webp_metadata(&webp).unwrap();
+        assert_eq!(stripped.len(), 12 + 18);
+        assert_eq!(&stripped[4..8], &22u32.to_le_bytes());
+        assert_eq!(stripped[20], 0);
+    }
+
+    #[test]
+    fn non_raster_uploads_are_left_alone() {
+        assert!(process_uploaded_image(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>", true).is_none());
+        assert!(process_uploaded_image(b"fake png bytes", true).is_none());
+    }
+}
@@ -0,0 +1,167 @@
+use poem::{http::StatusCode, Error};
+use sqlx::{query, SqlitePool};
+use uuid::Uuid;
+
+use crate::{
+    image_dedup::{content_hash, register_blob},
+    image_gc::IMAGE_PUBLIC_PREFIX,
+    image_metadata::{process_uploaded_image, strip_metadata_enabled},
+    image_validation::{sanitize_svg, validate_image_upload, DetectedImageType},
+    image_variants::{
+        build_srcsets_with_original, create_image_variants, list_image_variants, VariantConfig,
+    },
+    models::{UploadImageQueryParmas, UploadImageResponse},
+    storage::UploadStorage,
+};
+
+/// 업로드된 파일 하나를 검증, 정리해 저장하고 `images` 행과 variant를 만든다.
+/// 단건/여러 건 multipart 업로드와 이어 올리기 세션 완료가 모두 이 경로를 쓴다.
+pub async fn store_uploaded_image(
+    db: &SqlitePool,
+    storage: &dyn UploadStorage,
+    user_id: &str,
+    origin_name: &str,
+    content_type: &str,
+    file_bytes: Vec<u8>,
+    params: &UploadImageQueryParmas,
+) -> Result<UploadImageResponse, Error> {
+    if !content_type.starts_with("image/") {
+        return Err(Error::from_string(
+            format!("이미지 파일만 업로드할 수 있습니다: {}", content_type),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if file_bytes.is_empty() {
+        return Err(Error::from_string(
+            "비어 있는 파일은 업로드할 수 없습니다.",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    // 확장자와 저장할 MIME 타입은 클라이언트가 보낸 값이 아니라 파일 내용으로 정한다.
+    let detected = validate_image_upload(&file_bytes, content_type)?;
+    let extension = detected.extension();
+    let content_type = detected.mime_type().to_string();
+    let file_bytes = if detected == DetectedImageType::Svg {
+        sanitize_svg(&file_bytes)?
+    } else {
+        file_bytes
+    };
+
+    // 방향 보정과 메타데이터 제거를 마친 바이트를 저장하고, variant도 여기서 만든다.
+    let strip_metadata = strip_metadata_enabled();
+    let processed = process_uploaded_image(&file_bytes, strip_metadata);
+    // EXIF를 담을 수 있는 래스터 포맷인데 정리하지 못했으면 위치 정보가 남지 않도록 원본을 저장하지 않는다.
+    if processed.is_none()
+        && strip_metadata
+        && matches!(
+            detected,
+            DetectedImageType::Jpeg | DetectedImageType::Png | DetectedImageType::Webp
+        )
+    {
+        return Err(Error::from_string(
+            "이미지를 해석할 수 없어 메타데이터를 지울 수 없습니다.",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    let stored_bytes = match &processed {
+        Some(processed) => processed.bytes.clone(),
+        None => file_bytes,
+    };
+    let width = processed.as_ref().map(|processed| processed.width);
+    let height = processed.as_ref().map(|processed| processed.height);
+    let dominant_color = processed
+        .as_ref()
+        .map(|processed| processed.dominant_color.clone());
+    let blurhash = processed
+        .as_ref()
+        .map(|processed| processed.blurhash.clone())
+        .filter(|blurhash| !blurhash.is_empty());
+
+    // 같은 내용이 이미 저장돼 있으면 그 파일과 공개 경로를 그대로 쓴다.
+    let content_hash = content_hash(&stored_bytes);
+    let (file_name, is_new_blob) = register_blob(
+        db,
+        &content_hash,
+        &format!("{}.{}", Uuid::new_v4(), extension),
+        stored_bytes.len() as u64,
+    )
+    .await?;
+    let file_path = storage.location(&file_name);
+
+    if is_new_blob || storage.head(&file_name).await?.is_none() {
+        storage
+            .put(&file_name, stored_bytes.clone(), &content_type)
+            .await?;
+    }
+
+    let image_id = Uuid::new_v4().to_string();
+
+    let post_id = params.post_id.clone();
+
+    let image_type = params.image_type.clone().unwrap_or(String::from("in_post"));
+
+    let result = query(
+        r#"
+        INSERT INTO images (
+            image_id, post_id, file_name, origin_name, file_path, mime_type, image_type,
+            width, height, byte_size, dominant_color, blurhash, uploaded_by, content_hash
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&image_id)
+    .bind(&post_id)
+    .bind(&file_name)
+    .bind(origin_name)
+    .bind(&file_path)
+    .bind(&content_type)
+    .bind(&image_type)
+    .bind(width.map(i64::from))
+    .bind(height.map(i64::from))
+    .bind(stored_bytes.len() as i64)
+    .bind(&dominant_color)
+    .bind(&blurhash)
+    .bind(user_id)
+    .bind(&content_hash)
+    .execute(db)
+    .await;
+
+    result.map_err(|err| {
+        eprintln!("Error saving image: {}", err);
+        Error::from_string(
+            format!("Error upload image: {}", err),
+            poem::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?;
+
+    println!("이미지 저장 완료: {}", file_path);
+
+    let variants = if is_new_blob {
+        create_image_variants(
+            db,
+            storage,
+            &image_id,
+            &file_name,
+            stored_bytes.clone(),
+            VariantConfig::from_env(),
+        )
+        .await?
+    } else {
+        list_image_variants(db, &image_id).await?
+    };
+    let srcset = build_srcsets_with_original(&file_name, &content_type, width, height, &variants);
+
+    Ok(UploadImageResponse {
+        image_id,
+        image_path: format!("{}{}", IMAGE_PUBLIC_PREFIX, file_name),
+        width,
+        height,
+        byte_size: stored_bytes.len() as u64,
+        dominant_color,
+        blurhash,
+        variants,
+        srcset,
+    })
+}
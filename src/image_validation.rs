@@ -0,0 +1,593 @@
+use poem::{http::StatusCode, Error};
+
+/// 파일 앞부분(매직 바이트)으로 판별한 이미지 종류.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DetectedImageType {
+    Png,
+    Jpeg,
+    Gif,
+    Webp,
+    Avif,
+    Svg,
+}
+
+impl DetectedImageType {
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Gif => "image/gif",
+            Self::Webp => "image/webp",
+            Self::Avif => "image/avif",
+            Self::Svg => "image/svg+xml",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Gif => "gif",
+            Self::Webp => "webp",
+            Self::Avif => "avif",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+/// 허용하는 이미지 형식만 판별한다. 그 밖의 파일(HTML, 스크립트, 실행 파일 등)은 `None`이다.
+pub fn detect_image_type(bytes: &[u8]) -> Option<DetectedImageType> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some(DetectedImageType::Png);
+    }
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some(DetectedImageType::Jpeg);
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some(DetectedImageType::Gif);
+    }
+    if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some(DetectedImageType::Webp);
+    }
+    if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" && matches!(&bytes[8..12], b"avif" | b"avis") {
+        return Some(DetectedImageType::Avif);
+    }
+    if is_svg_document(bytes) {
+        return Some(DetectedImageType::Svg);
+    }
+
+    None
+}
+
+/// 클라이언트가 보낸 MIME 타입을 비교용으로 정규화한다(`image/jpg` → `image/jpeg` 등).
+fn normalize_mime_type(content_type: &str) -> String {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    match mime.as_str() {
+        "image/jpg" | "image/pjpeg" => "image/jpeg".to_string(),
+        "image/x-png" => "image/png".to_string(),
+        "image/svg" => "image/svg+xml".to_string(),
+        _ => mime,
+    }
+}
+
+#[derive(Debug)]
+pub enum ImageUploadError {
+    Unsupported,
+    Mismatch {
+        detected: DetectedImageType,
+        claimed: String,
+    },
+    InvalidSvg,
+}
+
+impl From<ImageUploadError> for Error {
+    fn from(err: ImageUploadError) -> Self {
+        match err {
+            ImageUploadError::Unsupported => Error::from_string(
+                "지원하지 않는 이미지 형식입니다. PNG, JPEG, GIF, WebP, AVIF, SVG만 업로드할 수 있습니다.",
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ),
+            ImageUploadError::Mismatch { detected, claimed } => Error::from_string(
+                format!(
+                    "파일 내용({})이 요청한 형식({})과 다릅니다.",
+                    detected.mime_type(),
+                    claimed
+                ),
+                StatusCode::BAD_REQUEST,
+            ),
+            ImageUploadError::InvalidSvg => Error::from_string(
+                "SVG 문서를 해석할 수 없습니다.",
+                StatusCode::BAD_REQUEST,
+            ),
+        }
+    }
+}
+
+/// 바이트로 판별한 형식이 허용 목록에 있고, 클라이언트가 밝힌 형식과 같은지 확인한다.
+pub fn validate_image_upload(
+    bytes: &[u8],
+    claimed_content_type: &str,
+) -> Result<DetectedImageType, ImageUploadError> {
+    let detected = detect_image_type(bytes).ok_or(ImageUploadError::Unsupported)?;
+
+    if normalize_mime_type(claimed_content_type) != detected.mime_type() {
+        return Err(ImageUploadError::Mismatch {
+            detected,
+            claimed: claimed_content_type.to_string(),
+        });
+    }
+
+    Ok(detected)
+}
+
+fn is_svg_document(bytes: &[u8]) -> bool {
+    let Ok(text) = std::str::from_utf8(&bytes[..bytes.len().min(4096)]).or_else(|err| {
+        // 앞부분만 잘랐을 때 멀티바이트 문자가 끊길 수 있다.
+        std::str::from_utf8(&bytes[..err.valid_up_to()])
+    }) else {
+        return false;
+    };
+
+    let mut rest = text.trim_start_matches('\u{feff}').trim_start();
+    loop {
+        if let Some(after) = rest.strip_prefix("<?") {
+            let Some(end) = after.find("?>") else {
+                return false;
+            };
+            rest = after[end + 2..].trim_start();
+        } else if let Some(after) = rest.strip_prefix("<!--") {
+            let Some(end) = after.find("-->") else {
+                return false;
+            };
+            rest = after[end + 3..].trim_start();
+        } else if rest.len() >= 9 && rest[..9].eq_ignore_ascii_case("<!doctype") {
+            let Some(end) = rest.find('>') else {
+                return false;
+            };
+            rest = rest[end + 1..].trim_start();
+        } else {
+            break;
+        }
+    }
+
+    rest.strip_prefix("<svg")
+        .and_then(|after| after.chars().next())
+        .is_some_and(|ch| ch.is_whitespace() || ch == '>' || ch == '/')
+}
+
+/// 통째로 지우는 요소. 스크립트 실행, HTML 삽입, 외부 문서 로드에 쓰일 수 있다.
+const BLOCKED_ELEMENTS: [&str; 11] = [
+    "script",
+    "foreignobject",
+    "iframe",
+    "embed",
+    "object",
+    "audio",
+    "video",
+    "handler",
+    "listener",
+    "set",
+    "animate",
+];
+
+/// 링크를 담는 속성(접두사를 뗀 이름). 문서 안 조각(`#id`)이나 래스터 data URI만 남긴다.
+const LINK_ATTRIBUTES: [&str; 3] = ["href", "src", "action"];
+
+/// `xmlns`/`xmlns:*`로 선언할 수 있는 네임스페이스. 다른 네임스페이스(XHTML 등)는 선언을 지운다.
+const ALLOWED_NAMESPACES: [&str; 2] =
+    ["http://www.w3.org/2000/svg", "http://www.w3.org/1999/xlink"];
+
+/// `s:script`처럼 네임스페이스 접두사가 붙은 이름에서 접두사를 뗀 소문자 이름.
+fn local_name(name: &str) -> String {
+    name.rsplit(':').next().unwrap_or(name).to_ascii_lowercase()
+}
+
+/// SVG에서 스크립트, 이벤트 핸들러(`on*`), 외부 참조를 지운 문서를 돌려준다.
+/// 주석, DOCTYPE(엔티티 선언 포함), XML 선언 외의 처리 지시문도 버린다.
+pub fn sanitize_svg(bytes: &[u8]) -> Result<Vec<u8>, ImageUploadError> {
+    let text = std::str::from_utf8(bytes).map_err(|_| ImageUploadError::InvalidSvg)?;
+    let text = text.trim_start_matches('\u{feff}');
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    // 지우는 중인 요소 이름과 같은 이름이 중첩된 깊이
+    let mut skipping: Option<(String, usize)> = None;
+
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            if skipping.is_none() {
+                output.push_str(rest);
+            }
+            break;
+        };
+        if skipping.is_none() {
+            output.push_str(&rest[..start]);
+        }
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("<!--") {
+            let end = after.find("-->").ok_or(ImageUploadError::InvalidSvg)?;
+            rest = &after[end + 3..];
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after.find("]]>").ok_or(ImageUploadError::InvalidSvg)?;
+            if skipping.is_none() {
+                output.push_str(&rest[..9 + end + 3]);
+            }
+            rest = &after[end + 3..];
+            continue;
+        }
+        if rest.starts_with("<!") {
+            rest = skip_declaration(rest).ok_or(ImageUploadError::InvalidSvg)?;
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("<?") {
+            let end = after.find("?>").ok_or(ImageUploadError::InvalidSvg)?;
+            if skipping.is_none() && output.trim().is_empty() && after.starts_with("xml ") {
+                output.push_str(&rest[..2 + end + 2]);
+            }
+            rest = &after[end + 2..];
+            continue;
+        }
+
+        let (tag, after) = parse_tag(rest).ok_or(ImageUploadError::InvalidSvg)?;
+        rest = after;
+
+        if let Some((name, depth)) = skipping.as_mut() {
+            if tag.name == *name {
+                if tag.is_closing {
+                    *depth -= 1;
+                } else if !tag.self_closing {
+                    *depth += 1;
+                }
+                if *depth == 0 {
+                    skipping = None;
+                }
+            }
+            continue;
+        }
+
+        let lower_name = local_name(&tag.name);
+        if BLOCKED_ELEMENTS.contains(&lower_name.as_str())
+            || lower_name.starts_with("animate")
+            || (lower_name == "style" && !tag.is_closing && !tag.self_closing && {
+                let close = rest.find("</").unwrap_or(rest.len());
+                is_unsafe_css(&rest[..close])
+            })
+        {
+            if !tag.is_closing && !tag.self_closing {
+                skipping = Some((tag.name, 1));
+            }
+            continue;
+        }
+
+        output.push_str(&tag.render());
+    }
+
+    if skipping.is_some() || !is_svg_document(output.as_bytes()) {
+        return Err(ImageUploadError::InvalidSvg);
+    }
+
+    Ok(output.into_bytes())
+}
+
+struct Tag {
+    name: String,
+    attributes: Vec<(String, Option<String>)>,
+    is_closing: bool,
+    self_closing: bool,
+}
+
+impl Tag {
+    fn render(&self) -> String {
+        if self.is_closing {
+            return format!("</{}>", self.name);
+        }
+
+        let mut rendered = format!("<{}", self.name);
+        for (name, value) in &self.attributes {
+            if !is_safe_attribute(name, value.as_deref()) {
+                continue;
+            }
+            match value {
+                Some(value) => {
+                    rendered.push_str(&format!(" {}=\"{}\"", name, value.replace('"', "&quot;")))
+                }
+                None => rendered.push_str(&format!(" {}", name)),
+            }
+        }
+        rendered.push_str(if self.self_closing { "/>" } else { ">" });
+        rendered
+    }
+}
+
+/// `<`로 시작하는 태그 하나를 읽어 태그와 나머지 문자열을 돌려준다.
+fn parse_tag(input: &str) -> Option<(Tag, &str)> {
+    let mut rest = input.strip_prefix('<')?;
+    let is_closing = rest.starts_with('/');
+    if is_closing {
+        rest = &rest[1..];
+    }
+
+    let name_end = rest
+        .find(|ch: char| ch.is_whitespace() || ch == '>' || ch == '/')
+        .unwrap_or(rest.len());
+    let name = rest[..name_end].to_string();
+    if name.is_empty() {
+        return None;
+    }
+    rest = &rest[name_end..];
+
+    let mut attributes = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>") {
+            return Some((
+                Tag {
+                    name,
+                    attributes,
+                    is_closing,
+                    self_closing: true,
+                },
+                after,
+            ));
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return Some((
+                Tag {
+                    name,
+                    attributes,
+                    is_closing,
+                    self_closing: false,
+                },
+                after,
+            ));
+        }
+
+        let attr_end = rest
+            .find(|ch: char| ch.is_whitespace() || matches!(ch, '=' | '>' | '/'))
+            .unwrap_or(rest.len());
+        if attr_end == 0 {
+            return None;
+        }
+        let attr_name = rest[..attr_end].to_string();
+        rest = rest[attr_end..].trim_start();
+
+        let Some(after_eq) = rest.strip_prefix('=') else {
+            attributes.push((attr_name, None));
+            continue;
+        };
+        let after_eq = after_eq.trim_start();
+        let quote = after_eq.chars().next()?;
+        let (value, after_value) = if quote == '"' || quote == '\'' {
+            let end = after_eq[1..].find(quote)?;
+            (&after_eq[1..1 + end], &after_eq[1 + end + 1..])
+        } else {
+            let end = after_eq
+                .find(|ch: char| ch.is_whitespace() || ch == '>')
+                .unwrap_or(after_eq.len());
+            (&after_eq[..end], &after_eq[end..])
+        };
+        attributes.push((attr_name, Some(value.to_string())));
+        rest = after_value;
+    }
+}
+
+/// `<!DOCTYPE ...[ ... ]>`처럼 내부 대괄호가 있는 선언까지 건너뛴다.
+fn skip_declaration(input: &str) -> Option<&str> {
+    let mut bracket_depth = 0usize;
+    for (index, ch) in input.char_indices() {
+        match ch {
+            '[' => bracket_depth += 1,
+            ']' => bracket_depth = bracket_depth.saturating_sub(1),
+            '>' if bracket_depth == 0 => return Some(&input[index + 1..]),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_safe_attribute(name: &str, value: Option<&str>) -> bool {
+    let value = value.unwrap_or_default();
+    let qualified = name.to_ascii_lowercase();
+    if qualified == "xmlns" || qualified.starts_with("xmlns:") {
+        return ALLOWED_NAMESPACES.contains(&value.trim());
+    }
+
+    let lower_name = local_name(name);
+    if lower_name.starts_with("on") {
+        return false;
+    }
+
+    if LINK_ATTRIBUTES.contains(&lower_name.as_str()) {
+        return is_internal_reference(value);
+    }
+    if lower_name == "style" || value.to_ascii_lowercase().contains("url(") {
+        return !is_unsafe_css(value);
+    }
+
+    true
+}
+
+fn is_internal_reference(value: &str) -> bool {
+    let value = value.trim().to_ascii_lowercase();
+    value.starts_with('#')
+        || [
+            "data:image/png",
+            "data:image/jpeg",
+            "data:image/gif",
+            "data:image/webp",
+        ]
+        .iter()
+        .any(|prefix| value.starts_with(prefix))
+}
+
+/// 외부 리소스를 부르는 CSS(`@import`, 조각이 아닌 `url(...)`)나 스크립트 URL이 있는지 본다.
+fn is_unsafe_css(css: &str) -> bool {
+    let lower = css.to_ascii_lowercase();
+    if lower.contains("@import")
+        || lower.contains("javascript:")
+        || lower.contains("expression(")
+        || lower.contains('&')
+        || lower.contains('\\')
+    {
+        return true;
+    }
+
+    let mut rest = lower.as_str();
+    while let Some(index) = rest.find("url(") {
+        rest = &rest[index + 4..];
+        let target = rest.trim_start().trim_start_matches(['"', '\'']);
+        if !is_internal_reference(target) {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use poem::{http::StatusCode, Error};
+
+    use super::{detect_image_type, sanitize_svg, validate_image_upload, DetectedImageType};
+
+    #[test]
+    fn detect_image_type_reads_magic_bytes() {
+        assert_eq!(
+            detect_image_type(b"\x89PNG\r\n\x1a\n rest"),
+            Some(DetectedImageType::Png)
+        );
+        assert_eq!(
+            detect_image_type(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(DetectedImageType::Jpeg)
+        );
+        assert_eq!(
+            detect_image_type(b"GIF89a..."),
+            Some(DetectedImageType::Gif)
+        );
+        assert_eq!(
+            detect_image_type(b"RIFF\x10\0\0\0WEBPVP8 "),
+            Some(DetectedImageType::Webp)
+        );
+        assert_eq!(
+            detect_image_type(b"\0\0\0\x1cftypavif\0\0\0\0"),
+            Some(DetectedImageType::Avif)
+        );
+        assert_eq!(
+            detect_image_type(
+                b"\xef\xbb\xbf<?xml version=\"1.0\"?>\n<!-- logo -->\n<!DOCTYPE svg>\n<svg xmlns=\"http://www.w3.org/2000/svg\"/>"
+            ),
+            Some(DetectedImageType::Svg)
+        );
+        assert_eq!(detect_image_type(b"<html><script>alert(1)</script>"), None);
+        assert_eq!(detect_image_type(b"<svgfoo/>"), None);
+        assert_eq!(detect_image_type(b"fake png bytes"), None);
+    }
+
+    #[test]
+    fn validate_image_upload_rejects_disallowed_and_mismatched_types() {
+        let html = Error::from(validate_image_upload(b"<html></html>", "image/png").unwrap_err());
+        assert_eq!(html.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let mismatch = Error::from(validate_image_upload(b"GIF89a...", "image/png").unwrap_err());
+        assert_eq!(mismatch.status(), StatusCode::BAD_REQUEST);
+
+        assert_eq!(
+            validate_image_upload(&[0xFF, 0xD8, 0xFF, 0xE0], "image/jpg").unwrap(),
+            DetectedImageType::Jpeg
+        );
+    }
+
+    #[test]
+    fn sanitize_svg_removes_scripts_handlers_and_external_refs() {
+        let svg = r##"<?xml version="1.0"?>
+<!DOCTYPE svg [<!ENTITY xxe SYSTEM "file:///etc/passwd">]>
+<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" onload="alert(1)">
+  <script type="text/javascript"><![CDATA[alert(1)]]></script>
+  <style>@import url(https://evil.example/x.css);</style>
+  <style>.a { fill: url(#grad); }</style>
+  <defs><linearGradient id="grad"/></defs>
+  <a href="javascript:alert(1)"><circle r="4" onclick='alert(2)' style="fill:url(https://evil.example/p)"/></a>
+  <use xlink:href="#grad"/>
+  <image href="https://evil.example/tracker.png"/>
+  <foreignObject><div xmlns="http://www.w3.org/1999/xhtml"><script>alert(3)</script></div></foreignObject>
+  <set attributeName="href" to="javascript:alert(4)"/>
+  <animate attributeName="href" values="javascript:alert(5)"></animate>
+  <!-- comment -->
+  <text x="0">안녕 &amp; bye</text>
+</svg>"##;
+
+        let sanitized = String::from_utf8(sanitize_svg(svg.as_bytes()).unwrap()).unwrap();
+
+        for forbidden in [
+            "script",
+            "alert",
+            "onload",
+            "onclick",
+            "evil.example",
+            "ENTITY",
+            "foreignObject",
+            "comment",
+            "<set",
+            "<animate",
+        ] {
+            assert!(
+                !sanitized.contains(forbidden),
+                "{} should be removed: {}",
+                forbidden,
+                sanitized
+            );
+        }
+        assert!(sanitized.starts_with("<?xml version=\"1.0\"?>"));
+        assert!(sanitized.contains(".a { fill: url(#grad); }"));
+        assert!(sanitized.contains("<use xlink:href=\"#grad\"/>"));
+        assert!(sanitized.contains("<circle r=\"4\"/>"));
+        assert!(sanitized.contains("<text x=\"0\">안녕 &amp; bye</text>"));
+    }
+
+    #[test]
+    fn sanitize_svg_checks_names_without_namespace_prefix() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:s="http://www.w3.org/2000/svg" xmlns:h="http://www.w3.org/1999/xhtml" xmlns:l="http://www.w3.org/1999/xlink">
+  <s:script>alert(1)</s:script>
+  <h:script>alert(2)</h:script>
+  <s:foreignObject><h:iframe src="https://evil.example/"/></s:foreignObject>
+  <a l:href="javascript:alert(3)" s:onclick="alert(4)"><s:circle r="4"/></a>
+  <use l:href="#dot"/>
+</svg>"##;
+
+        let sanitized = String::from_utf8(sanitize_svg(svg.as_bytes()).unwrap()).unwrap();
+
+        for forbidden in [
+            "script",
+            "alert",
+            "foreignObject",
+            "iframe",
+            "evil.example",
+            "xhtml",
+            "onclick",
+        ] {
+            assert!(
+                !sanitized.contains(forbidden),
+                "{} should be removed: {}",
+                forbidden,
+                sanitized
+            );
+        }
+        assert!(sanitized.contains("xmlns:s=\"http://www.w3.org/2000/svg\""));
+        assert!(sanitized.contains("<a><s:circle r=\"4\"/></a>"));
+        assert!(sanitized.contains("<use l:href=\"#dot\"/>"));
+    }
+
+    #[test]
+    fn sanitize_svg_rejects_non_svg_and_broken_documents() {
+        assert!(sanitize_svg(b"<html><body/></html>").is_err());
+        assert!(sanitize_svg(b"<svg><script>alert(1)").is_err());
+        assert!(sanitize_svg(b"<svg width=\"1").is_err());
+    }
+}
@@ -0,0 +1,694 @@
+use std::{
+    collections::HashMap,
+    env,
+    io::Cursor,
+    path::Path,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use image::{
+    codecs::{avif::AvifEncoder, jpeg::JpegEncoder, png::PngEncoder, webp::WebPEncoder},
+    imageops::FilterType,
+    DynamicImage, ImageFormat, ImageReader,
+};
+use poem::{http::StatusCode, Error};
+use sqlx::{query, query_as, FromRow, SqlitePool};
+
+use crate::{
+    image_gc::IMAGE_PUBLIC_PREFIX,
+    models::{ImageSrcsetResponse, ImageVariantResponse},
+    storage::UploadStorage,
+};
+
+const DEFAULT_WIDTHS: [u32; 3] = [320, 768, 1280];
+const DEFAULT_FORMATS: [VariantFormat; 3] =
+    [VariantFormat::Original, VariantFormat::Webp, VariantFormat::Avif];
+const JPEG_QUALITY: u8 = 82;
+const AVIF_SPEED: u8 = 8;
+const AVIF_QUALITY: u8 = 70;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VariantFormat {
+    Original,
+    Webp,
+    Avif,
+}
+
+impl VariantFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "original" => Some(Self::Original),
+            "webp" => Some(Self::Webp),
+            "avif" => Some(Self::Avif),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Original => "original",
+            Self::Webp => "webp",
+            Self::Avif => "avif",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct VariantConfig {
+    pub widths: Vec<u32>,
+    pub formats: Vec<VariantFormat>,
+}
+
+impl VariantConfig {
+    /// `IMAGE_VARIANT_WIDTHS=320,768,1280`, `IMAGE_VARIANT_FORMATS=original,webp,avif`
+    pub fn from_env() -> Self {
+        let widths = env::var("IMAGE_VARIANT_WIDTHS")
+            .ok()
+            .map(|value| {
+                value
+                    .split(',')
+                    .filter_map(|width| width.trim().parse::<u32>().ok())
+                    .filter(|width| *width > 0)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_else(|| DEFAULT_WIDTHS.to_vec());
+
+        let formats = env::var("IMAGE_VARIANT_FORMATS")
+            .ok()
+            .map(|value| {
+                value
+                    .split(',')
+                    .filter_map(VariantFormat::parse)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_else(|| DEFAULT_FORMATS.to_vec());
+
+        Self::new(widths, formats)
+    }
+
+    pub fn new(mut widths: Vec<u32>, mut formats: Vec<VariantFormat>) -> Self {
+        widths.sort_unstable();
+        widths.dedup();
+        formats.sort_unstable();
+        formats.dedup();
+        Self { widths, formats }
+    }
+
+    /// 요청한 너비 이상인 설정값 중 가장 작은 것을 고른다. 없으면 원본 너비를 쓴다.
+    pub fn target_width(&self, requested: u32, original_width: u32) -> u32 {
+        self.widths
+            .iter()
+            .copied()
+            .find(|width| *width >= requested && *width < original_width)
+            .unwrap_or(original_width)
+    }
+}
+
+#[derive(Debug)]
+pub struct RenderedVariant {
+    pub file_name: String,
+    pub width: u32,
+    pub height: u32,
+    pub format: VariantFormat,
+    pub mime_type: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Debug, FromRow)]
+struct SourceImageRow {
+    image_id: String,
+    file_name: String,
+    width: Option<i64>,
+}
+
+/// 리사이즈/재인코딩이 가능한 래스터 포맷인지 확인한다. SVG와 애니메이션 GIF는 원본만 쓴다.
+pub fn supports_variants(format: ImageFormat) -> bool {
+    matches!(
+        format,
+        ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP
+    )
+}
+
+pub fn decode_image(bytes: &[u8]) -> Result<(DynamicImage, ImageFormat), String> {
+    let reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|err| err.to_string())?;
+    let format = reader
+        .format()
+        .ok_or_else(|| "이미지 포맷을 알 수 없습니다.".to_string())?;
+
+    if !supports_variants(format) {
+        return Err(format!("variant를 만들 수 없는 포맷입니다: {:?}", format));
+    }
+
+    let image = reader.decode().map_err(|err| err.to_string())?;
+    Ok((image, format))
+}
+
+pub fn variant_file_name(stem: &str, width: u32, extension: &str) -> String {
+    format!("{}-w{}.{}", stem, width, extension)
+}
+
+/// 설정된 너비(원본보다 작은 것)마다 각 포맷으로 인코딩하고,
+/// 원본 포맷이 아닌 포맷은 원본 너비 사본도 함께 만든다.
+pub fn render_variants(
+    image: &DynamicImage,
+    original_format: ImageFormat,
+    stem: &str,
+    config: &VariantConfig,
+) -> Result<Vec<RenderedVariant>, String> {
+    let original_width = image.width();
+    let mut widths: Vec<u32> = config
+        .widths
+        .iter()
+        .copied()
+        .filter(|width| *width < original_width)
+        .collect();
+    widths.push(original_width);
+
+    let mut variants = Vec::new();
+    for width in widths {
+        for format in &config.formats {
+            if width == original_width && resolved_format(*format, original_format) == original_format
+            {
+                continue;
+            }
+            variants.push(render_variant(image, original_format, stem, width, *format)?);
+        }
+    }
+
+    Ok(variants)
+}
+
+pub fn render_variant(
+    image: &DynamicImage,
+    original_format: ImageFormat,
+    stem: &str,
+    width: u32,
+    format: VariantFormat,
+) -> Result<RenderedVariant, String> {
+    let resized = if width < image.width() {
+        let height = ((image.height() as f64) * (width as f64) / (image.width() as f64))
+            .round()
+            .max(1.0) as u32;
+        image.resize_exact(width, height, FilterType::Lanczos3)
+    } else {
+        image.clone()
+    };
+
+    let output_format = resolved_format(format, original_format);
+    let mut bytes = Vec::new();
+    match output_format {
+        ImageFormat::Jpeg => resized
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY)),
+        ImageFormat::Png => resized.write_with_encoder(PngEncoder::new(&mut bytes)),
+        ImageFormat::WebP => resized
+            .to_rgba8()
+            .write_with_encoder(WebPEncoder::new_lossless(&mut bytes)),
+        ImageFormat::Avif => resized.to_rgba8().write_with_encoder(
+            AvifEncoder::new_with_speed_quality(&mut bytes, AVIF_SPEED, AVIF_QUALITY),
+        ),
+        other => return Err(format!("지원하지 않는 출력 포맷입니다: {:?}", other)),
+    }
+    .map_err(|err| err.to_string())?;
+
+    let extension = output_format.extensions_str()[0];
+    Ok(RenderedVariant {
+        file_name: variant_file_name(stem, resized.width(), extension),
+        width: resized.width(),
+        height: resized.height(),
+        format,
+        mime_type: output_format.to_mime_type(),
+        bytes,
+    })
+}
+
+fn resolved_format(format: VariantFormat, original_format: ImageFormat) -> ImageFormat {
+    match format {
+        VariantFormat::Original => original_format,
+        VariantFormat::Webp => ImageFormat::WebP,
+        VariantFormat::Avif => ImageFormat::Avif,
+    }
+}
+
+/// 업로드 직후 호출된다. 디코딩할 수 없는 파일이면 variant 없이 빈 목록을 돌려준다.
+pub async fn create_image_variants(
+    db: &SqlitePool,
+    storage: &dyn UploadStorage,
+    image_id: &str,
+    file_name: &str,
+    bytes: Vec<u8>,
+    config: VariantConfig,
+) -> Result<Vec<ImageVariantResponse>, Error> {
+    let stem = file_stem(file_name).to_string();
+    let rendered = tokio::task::spawn_blocking(move || {
+        let (image, format) = decode_image(&bytes)?;
+        render_variants(&image, format, &stem, &config)
+    })
+    .await
+    .map_err(|err| {
+        Error::from_string(
+            format!("이미지 변환 작업 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?;
+
+    let rendered = match rendered {
+        Ok(rendered) => rendered,
+        Err(err) => {
+            println!("이미지 variant 생성 건너뜀 ({}): {}", file_name, err);
+            return Ok(Vec::new());
+        }
+    };
+
+    let mut variants = Vec::new();
+    for variant in rendered {
+        variants.push(store_variant(db, storage, image_id, variant).await?);
+    }
+
+    Ok(variants)
+}
+
+/// 이미지에 딸린 variant를 포맷, 너비 순으로 돌려준다.
+/// 같은 파일을 공유하는 이미지는 variant도 함께 쓴다.
+pub async fn list_image_variants(
+    db: &SqlitePool,
+    image_id: &str,
+) -> Result<Vec<ImageVariantResponse>, Error> {
+    let rows: Vec<(String, i64, i64, String, String)> = query_as(
+        r#"
+        SELECT v.file_name, v.width, v.height, v.format, v.mime_type
+        FROM image_variants v
+        JOIN images owner ON owner.image_id = v.image_id
+        JOIN images this ON this.file_name = owner.file_name
+        WHERE this.image_id = ?
+        ORDER BY v.format, v.width
+        "#,
+    )
+    .bind(image_id)
+    .fetch_all(db)
+    .await
+    .map_err(|err| {
+        Error::from_string(
+            format!("이미지 variant 조회 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(file_name, width, height, format, mime_type)| ImageVariantResponse {
+                image_path: format!("{}{}", IMAGE_PUBLIC_PREFIX, file_name),
+                width: width as u32,
+                height: height as u32,
+                format,
+                mime_type,
+            },
+        )
+        .collect())
+}
+
+/// 원본 파일도 자기 너비의 srcset 후보가 되도록 variant 목록에 끼워 srcset을 만든다.
+pub fn build_srcsets_with_original(
+    file_name: &str,
+    mime_type: &str,
+    width: Option<u32>,
+    height: Option<u32>,
+    variants: &[ImageVariantResponse],
+) -> Vec<ImageSrcsetResponse> {
+    let mut candidates = variants.to_vec();
+    if let (Some(width), Some(height)) = (width, height) {
+        candidates.push(ImageVariantResponse {
+            image_path: format!("{}{}", IMAGE_PUBLIC_PREFIX, file_name),
+            width,
+            height,
+            format: VariantFormat::Original.as_str().to_string(),
+            mime_type: mime_type.to_string(),
+        });
+    }
+    build_srcsets(&candidates)
+}
+
+/// 포맷(MIME)별로 묶어 `<source srcset>`에 바로 넣을 수 있는 문자열을 만든다.
+pub fn build_srcsets(variants: &[ImageVariantResponse]) -> Vec<ImageSrcsetResponse> {
+    let mut srcsets: Vec<ImageSrcsetResponse> = Vec::new();
+    let mut sorted: Vec<&ImageVariantResponse> = variants.iter().collect();
+    sorted.sort_by_key(|variant| variant.width);
+
+    for variant in sorted {
+        let candidate = format!("{} {}w", variant.image_path, variant.width);
+        match srcsets
+            .iter_mut()
+            .find(|srcset| srcset.mime_type == variant.mime_type)
+        {
+            Some(srcset) => {
+                srcset.srcset.push_str(", ");
+                srcset.srcset.push_str(&candidate);
+            }
+            None => srcsets.push(ImageSrcsetResponse {
+                mime_type: variant.mime_type.clone(),
+                srcset: candidate,
+            }),
+        }
+    }
+
+    srcsets
+}
+
+/// 같은 variant를 동시에 여러 번 인코딩하지 않도록 파일 이름마다 거는 잠금.
+fn render_lock(file_name: &str) -> Arc<tokio::sync::Mutex<()>> {
+    static LOCKS: OnceLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
+    let mut locks = LOCKS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    // 아무도 기다리지 않는 잠금은 치운다.
+    locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+    locks.entry(file_name.to_string()).or_default().clone()
+}
+
+/// 원본 너비와 확장자로 만들어질 variant 파일 이름을 미리 계산한다. 원본을 그대로 쓰면 `None`.
+fn expected_variant_file_name(
+    source: &SourceImageRow,
+    requested_width: Option<u32>,
+    format: VariantFormat,
+    config: &VariantConfig,
+) -> Option<Option<String>> {
+    let original_width = u32::try_from(source.width?).ok()?;
+    let original_format = ImageFormat::from_path(&source.file_name).ok()?;
+    if !supports_variants(original_format) {
+        return None;
+    }
+
+    let width = config.target_width(requested_width.unwrap_or(u32::MAX), original_width);
+    let output_format = resolved_format(format, original_format);
+    if width == original_width && output_format == original_format {
+        return Some(None);
+    }
+    Some(Some(variant_file_name(
+        file_stem(&source.file_name),
+        width,
+        output_format.extensions_str()[0],
+    )))
+}
+
+/// 이미 저장한 variant면 파일 이름을 돌려준다.
+async fn stored_variant(
+    db: &SqlitePool,
+    storage: &dyn UploadStorage,
+    file_name: &str,
+) -> Result<Option<String>, Error> {
+    let existing: Option<String> =
+        sqlx::query_scalar("SELECT file_name FROM image_variants WHERE file_name = ?")
+            .bind(file_name)
+            .fetch_optional(db)
+            .await
+            .map_err(|err| {
+                Error::from_string(
+                    format!("이미지 variant 조회 실패: {}", err),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )
+            })?;
+
+    match existing {
+        Some(existing) if storage.head(&existing).await?.is_some() => Ok(Some(existing)),
+        _ => Ok(None),
+    }
+}
+
+/// `/images/<file>?w=&format=` 요청에 맞는 파일 이름을 찾는다.
+/// 이미 저장한 variant는 원본을 읽지 않고 바로 돌려주고, 없는 조합만 원본에서 만들어 저장한다.
+/// 같은 조합을 동시에 요청해도 인코딩은 한 번만 한다.
+pub async fn resolve_variant_file(
+    db: &SqlitePool,
+    storage: &dyn UploadStorage,
+    file_name: &str,
+    requested_width: Option<u32>,
+    requested_format: Option<VariantFormat>,
+    config: &VariantConfig,
+) -> Result<String, Error> {
+    let source = query_as::<_, SourceImageRow>(
+        "SELECT image_id, file_name, width FROM images WHERE file_name = ? LIMIT 1",
+    )
+    .bind(file_name)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| {
+        Error::from_string(
+            format!("이미지 조회 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?
+    .ok_or_else(|| Error::from_string("이미지를 찾을 수 없습니다.", StatusCode::NOT_FOUND))?;
+
+    let format = requested_format.unwrap_or(VariantFormat::Original);
+    if requested_format.is_some() && !config.formats.contains(&format) {
+        return Err(Error::from_string(
+            format!("허용되지 않은 이미지 포맷입니다: {}", format.as_str()),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    // 너비를 기록하지 않은 예전 이미지는 원본을 디코딩해 봐야 이름을 알 수 있어서 요청 값으로 잠근다.
+    let expected = expected_variant_file_name(&source, requested_width, format, config);
+    let lock_key = match &expected {
+        Some(None) => return Ok(source.file_name),
+        Some(Some(expected)) => {
+            if let Some(existing) = stored_variant(db, storage, expected).await? {
+                return Ok(existing);
+            }
+            expected.clone()
+        }
+        None => format!(
+            "{}?w={}&format={}",
+            source.file_name,
+            requested_width.unwrap_or_default(),
+            format.as_str()
+        ),
+    };
+    let _render_guard = render_lock(&lock_key).lock_owned().await;
+    // 기다리는 동안 다른 요청이 만들었을 수 있다.
+    if let Some(Some(expected)) = &expected {
+        if let Some(existing) = stored_variant(db, storage, expected).await? {
+            return Ok(existing);
+        }
+    }
+
+    let original_bytes = storage
+        .get(&source.file_name)
+        .await?
+        .ok_or_else(|| Error::from_string("이미지를 찾을 수 없습니다.", StatusCode::NOT_FOUND))?;
+
+    let config = config.clone();
+    let stem = file_stem(&source.file_name).to_string();
+    let (original_size, rendered) = tokio::task::spawn_blocking(move || {
+        let (image, original_format) = decode_image(&original_bytes)?;
+        let original_size = (image.width(), image.height());
+        let width = config.target_width(requested_width.unwrap_or(u32::MAX), image.width());
+        if width == image.width() && resolved_format(format, original_format) == original_format {
+            return Ok((original_size, None));
+        }
+        render_variant(&image, original_format, &stem, width, format)
+            .map(|rendered| (original_size, Some(rendered)))
+    })
+    .await
+    .map_err(|err| {
+        Error::from_string(
+            format!("이미지 변환 작업 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?
+    .map_err(|err| Error::from_string(err, StatusCode::UNPROCESSABLE_ENTITY))?;
+
+    if expected.is_none() {
+        // 다음 요청부터는 디코딩 없이 저장한 variant를 찾을 수 있게 원본 크기를 채워 둔다.
+        query("UPDATE images SET width = ?, height = ? WHERE file_name = ? AND width IS NULL")
+            .bind(original_size.0 as i64)
+            .bind(original_size.1 as i64)
+            .bind(&source.file_name)
+            .execute(db)
+            .await
+            .map_err(|err| {
+                Error::from_string(
+                    format!("이미지 크기 기록 실패: {}", err),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )
+            })?;
+    }
+
+    let Some(rendered) = rendered else {
+        return Ok(source.file_name);
+    };
+
+    if expected.is_none() {
+        if let Some(existing) = stored_variant(db, storage, &rendered.file_name).await? {
+            return Ok(existing);
+        }
+    }
+
+    let stored = store_variant(db, storage, &source.image_id, rendered).await?;
+    Ok(stored
+        .image_path
+        .trim_start_matches(IMAGE_PUBLIC_PREFIX)
+        .to_string())
+}
+
+async fn store_variant(
+    db: &SqlitePool,
+    storage: &dyn UploadStorage,
+    image_id: &str,
+    variant: RenderedVariant,
+) -> Result<ImageVariantResponse, Error> {
+    storage
+        .put(&variant.file_name, variant.bytes.clone(), variant.mime_type)
+        .await?;
+
+    query(
+        r#"
+        INSERT INTO image_variants (image_id, file_name, width, height, format, mime_type, byte_size)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(file_name) DO UPDATE SET
+            width = excluded.width,
+            height = excluded.height,
+            byte_size = excluded.byte_size
+        "#,
+    )
+    .bind(image_id)
+    .bind(&variant.file_name)
+    .bind(variant.width as i64)
+    .bind(variant.height as i64)
+    .bind(variant.format.as_str())
+    .bind(variant.mime_type)
+    .bind(variant.bytes.len() as i64)
+    .execute(db)
+    .await
+    .map_err(|err| {
+        Error::from_string(
+            format!("이미지 variant 기록 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?;
+
+    Ok(ImageVariantResponse {
+        image_path: format!("{}{}", IMAGE_PUBLIC_PREFIX, variant.file_name),
+        width: variant.width,
+        height: variant.height,
+        format: variant.format.as_str().to_string(),
+        mime_type: variant.mime_type.to_string(),
+    })
+}
+
+fn file_stem(file_name: &str) -> &str {
+    Path::new(file_name)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, ImageFormat, RgbaImage};
+
+    use super::{build_srcsets, render_variant, render_variants, VariantConfig, VariantFormat};
+    use crate::models::ImageVariantResponse;
+
+    fn sample_image(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+            image::Rgba([(x % 256) as u8, (y % 256) as u8, 128, 255])
+        }))
+    }
+
+    #[test]
+    fn render_variants_skips_widths_larger_than_original() {
+        let config = VariantConfig::new(
+            vec![1280, 32, 64],
+            vec![VariantFormat::Original, VariantFormat::Webp],
+        );
+        let variants =
+            render_variants(&sample_image(100, 50), ImageFormat::Png, "abc", &config).unwrap();
+
+        let names: Vec<_> = variants.iter().map(|v| v.file_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "abc-w32.png",
+                "abc-w32.webp",
+                "abc-w64.png",
+                "abc-w64.webp",
+                "abc-w100.webp"
+            ]
+        );
+        assert_eq!(variants[0].height, 16);
+        assert_eq!(variants[4].mime_type, "image/webp");
+    }
+
+    #[test]
+    fn render_variant_encodes_avif() {
+        let variant = render_variant(
+            &sample_image(16, 16),
+            ImageFormat::Jpeg,
+            "abc",
+            8,
+            VariantFormat::Avif,
+        )
+        .unwrap();
+
+        assert_eq!(variant.file_name, "abc-w8.avif");
+        assert_eq!(variant.mime_type, "image/avif");
+        assert!(!variant.bytes.is_empty());
+    }
+
+    #[test]
+    fn variant_config_drops_duplicate_formats_in_any_order() {
+        let config = VariantConfig::new(
+            vec![64, 32, 64],
+            vec![
+                VariantFormat::Webp,
+                VariantFormat::Original,
+                VariantFormat::Webp,
+            ],
+        );
+        assert_eq!(config.widths, vec![32, 64]);
+        assert_eq!(
+            config.formats,
+            vec![VariantFormat::Original, VariantFormat::Webp]
+        );
+    }
+
+    #[test]
+    fn target_width_picks_smallest_configured_width_that_fits() {
+        let config = VariantConfig::new(vec![320, 768, 1280], vec![VariantFormat::Webp]);
+
+        assert_eq!(config.target_width(100, 2000), 320);
+        assert_eq!(config.target_width(700, 2000), 768);
+        assert_eq!(config.target_width(1500, 2000), 2000);
+        assert_eq!(config.target_width(700, 500), 500);
+    }
+
+    #[test]
+    fn build_srcsets_groups_by_mime_type_in_width_order() {
+        let variant = |path: &str, width: u32, mime_type: &str| ImageVariantResponse {
+            image_path: path.to_string(),
+            width,
+            height: width / 2,
+            format: "webp".to_string(),
+            mime_type: mime_type.to_string(),
+        };
+
+        let srcsets = build_srcsets(&[
+            variant("/images/a-w768.webp", 768, "image/webp"),
+            variant("/images/a-w320.webp", 320, "image/webp"),
+            variant("/images/a-w320.jpg", 320, "image/jpeg"),
+        ]);
+
+        assert_eq!(srcsets.len(), 2);
+        assert_eq!(srcsets[0].mime_type, "image/webp");
+        assert_eq!(
+            srcsets[0].srcset,
+            "/images/a-w320.webp 320w, /images/a-w768.webp 768w"
+        );
+        assert_eq!(srcsets[1].srcset, "/images/a-w320.jpg 320w");
+    }
+}
@@ -0,0 +1,691 @@
+//! `PortfolioDocument`와 [JSON Resume](https://jsonresume.org/schema) 사이의 변환.
+//!
+//! 표준 필드로 옮길 수 있는 값은 표준 필드에 넣고, 표준에 자리가 없는 값(프로젝트 slug, 링크 이름,
+//! 회사별 세부 항목 등)은 각 객체의 `x-portfolio` 확장 필드에 담는다. 다른 도구는 확장 필드를 무시하고,
+//! 다시 가져올 때는 확장 필드가 있으면 그것을 우선한다.
+
+use serde_json::{json, Map, Value};
+
+use crate::models::{
+    PortfolioCareerCompany, PortfolioCareerItem, PortfolioCareerSection, PortfolioDocument,
+    PortfolioIdentity, PortfolioIntroSection, PortfolioLink, PortfolioProject,
+    PortfolioTechStackItem,
+};
+use crate::portfolio::parse_period;
+
+pub const JSON_RESUME_SCHEMA_URL: &str =
+    "https://raw.githubusercontent.com/jsonresume/resume-schema/v1.0.0/schema.json";
+
+const EXTENSION_KEY: &str = "x-portfolio";
+const GITHUB_NETWORK: &str = "GitHub";
+const TECH_STACK_SKILL_NAME: &str = "Tech stack";
+const DEFAULT_LINK_LABEL: &str = "Link";
+const DEFAULT_CAREER_LABEL: &str = "경력";
+
+const RESUME_KEYS: [&str; 6] = ["$schema", "basics", "work", "projects", "skills", "meta"];
+const BASICS_KEYS: [&str; 4] = ["name", "email", "summary", "profiles"];
+const WORK_KEYS: [&str; 6] = [
+    "name",
+    "position",
+    "summary",
+    "startDate",
+    "endDate",
+    "highlights",
+];
+const PROJECT_KEYS: [&str; 7] = [
+    "name",
+    "description",
+    "startDate",
+    "endDate",
+    "highlights",
+    "keywords",
+    "url",
+];
+const SKILL_KEYS: [&str; 2] = ["name", "keywords"];
+
+#[derive(Debug)]
+pub enum JsonResumeError {
+    /// 최상위가 객체가 아니거나 `basics.name`이 없다.
+    Invalid(String),
+}
+
+impl From<JsonResumeError> for poem::Error {
+    fn from(err: JsonResumeError) -> Self {
+        match err {
+            JsonResumeError::Invalid(message) => {
+                poem::Error::from_string(message, poem::http::StatusCode::BAD_REQUEST)
+            }
+        }
+    }
+}
+
+/// 문서를 JSON Resume으로 바꾼다. 두 번째 값은 표준 필드로 옮기지 못해 `x-portfolio`에만 담긴 문서 위치(JSON Pointer)다.
+pub fn export_json_resume(document: &PortfolioDocument) -> (Value, Vec<String>) {
+    let mut unmapped = Vec::new();
+
+    let mut basics = Map::new();
+    basics.insert("name".into(), json!(document.identity.name));
+    if !document.identity.email.is_empty() {
+        basics.insert("email".into(), json!(document.identity.email));
+    }
+    if let Some(intro) = &document.intro {
+        basics.insert("summary".into(), json!(intro.content));
+    }
+    if !document.identity.github_url.is_empty() {
+        basics.insert(
+            "profiles".into(),
+            json!([{
+                "network": GITHUB_NETWORK,
+                "username": github_username(&document.identity.github_url),
+                "url": document.identity.github_url,
+            }]),
+        );
+    }
+
+    let mut projects = Vec::with_capacity(document.featured_projects.len());
+    for (index, project) in document.featured_projects.iter().enumerate() {
+        let pointer = format!("/featured_projects/{}", index);
+        let mut entry = Map::new();
+        entry.insert("name".into(), json!(project.title));
+        entry.insert("description".into(), json!(project.summary));
+        insert_period(&mut entry, &project.period, &pointer, &mut unmapped);
+        entry.insert("highlights".into(), json!(project.highlights));
+        entry.insert("keywords".into(), json!(project.stack));
+        if let Some(link) = project.links.first() {
+            entry.insert("url".into(), json!(link.url));
+        }
+        entry.insert(
+            EXTENSION_KEY.into(),
+            json!({
+                "slug": project.slug,
+                "period": project.period,
+                "links": project.links,
+                "posts": project.posts,
+            }),
+        );
+        if !project.posts.is_empty() {
+            unmapped.push(format!("{}/posts", pointer));
+        }
+
+        unmapped.push(format!("{}/slug", pointer));
+        for (link_index, _) in project.links.iter().enumerate() {
+            if link_index == 0 {
+                unmapped.push(format!("{}/links/0/label", pointer));
+            } else {
+                unmapped.push(format!("{}/links/{}", pointer, link_index));
+            }
+        }
+        projects.push(Value::Object(entry));
+    }
+
+    let mut work = Vec::new();
+    if let Some(career) = &document.career {
+        unmapped.push("/career/summary_label".to_string());
+        unmapped.push("/career/summary_value".to_string());
+
+        for (index, company) in career.companies.iter().enumerate() {
+            let pointer = format!("/career/companies/{}", index);
+            let mut entry = Map::new();
+            entry.insert("name".into(), json!(company.company));
+            entry.insert("position".into(), json!(company.position));
+            entry.insert("summary".into(), json!(company.role));
+            insert_period(&mut entry, &company.period, &pointer, &mut unmapped);
+            let highlights: Vec<&String> = company
+                .items
+                .iter()
+                .flat_map(|item| item.bullets.iter())
+                .collect();
+            entry.insert("highlights".into(), json!(highlights));
+            entry.insert(
+                EXTENSION_KEY.into(),
+                json!({
+                    "period": company.period,
+                    "employment_type": company.employment_type,
+                    "items": company.items,
+                }),
+            );
+
+            unmapped.push(format!("{}/employment_type", pointer));
+            for (item_index, item) in company.items.iter().enumerate() {
+                unmapped.push(format!("{}/items/{}/title", pointer, item_index));
+                if item.period.is_some() {
+                    unmapped.push(format!("{}/items/{}/period", pointer, item_index));
+                }
+            }
+            work.push(Value::Object(entry));
+        }
+    }
+
+    let mut skills = Vec::new();
+    if let Some(intro) = &document.intro {
+        if !intro.tech_stack.is_empty() {
+            let names: Vec<&String> = intro.tech_stack.iter().map(|item| &item.name).collect();
+            skills.push(json!({ "name": TECH_STACK_SKILL_NAME, "keywords": names }));
+            for (index, _) in intro.tech_stack.iter().enumerate() {
+                unmapped.push(format!("/intro/tech_stack/{}/icon_url", index));
+            }
+        }
+    }
+
+    let mut extension = Map::new();
+    extension.insert("slug".into(), json!(document.slug));
+    extension.insert("version".into(), json!(document.version));
+    if let Some(career) = &document.career {
+        extension.insert(
+            "career".into(),
+            json!({
+                "summary_label": career.summary_label,
+                "summary_value": career.summary_value,
+            }),
+        );
+    }
+    if let Some(intro) = &document.intro {
+        extension.insert("tech_stack".into(), json!(intro.tech_stack));
+    }
+
+    let mut resume = Map::new();
+    resume.insert("$schema".into(), json!(JSON_RESUME_SCHEMA_URL));
+    resume.insert("basics".into(), Value::Object(basics));
+    resume.insert("work".into(), Value::Array(work));
+    resume.insert("projects".into(), Value::Array(projects));
+    resume.insert("skills".into(), Value::Array(skills));
+    resume.insert(
+        "meta".into(),
+        json!({ "version": format!("v{}", document.version) }),
+    );
+    resume.insert(EXTENSION_KEY.into(), Value::Object(extension));
+
+    (Value::Object(resume), unmapped)
+}
+
+/// JSON Resume을 문서로 바꾼다. 두 번째 값은 옮길 곳이 없어 버린 이력서 위치(JSON Pointer)다.
+/// `lang`은 끝나지 않은 기간을 `현재`/`Present` 중 무엇으로 적을지 고르는 데만 쓴다.
+pub fn import_json_resume(
+    resume: &Value,
+    slug: &str,
+    lang: &str,
+) -> Result<(PortfolioDocument, Vec<String>), JsonResumeError> {
+    let Some(resume) = resume.as_object() else {
+        return Err(JsonResumeError::Invalid(
+            "JSON Resume은 객체여야 합니다.".to_string(),
+        ));
+    };
+    let mut unmapped = Vec::new();
+    report_unknown_keys(resume, &RESUME_KEYS, "", &mut unmapped);
+
+    let extension = object_at(resume, EXTENSION_KEY);
+    let basics = object_at(resume, "basics");
+    let name = basics
+        .and_then(|basics| str_at(basics, "name"))
+        .filter(|name| !name.trim().is_empty())
+        .ok_or_else(|| JsonResumeError::Invalid("basics.name이 필요합니다.".to_string()))?;
+
+    let mut github_url = String::new();
+    if let Some(basics) = basics {
+        report_unknown_keys(basics, &BASICS_KEYS, "/basics", &mut unmapped);
+        for (index, profile) in array_at(basics, "profiles").iter().enumerate() {
+            let is_github = profile
+                .get("network")
+                .and_then(Value::as_str)
+                .is_some_and(|network| network.eq_ignore_ascii_case(GITHUB_NETWORK));
+            match profile.get("url").and_then(Value::as_str) {
+                Some(url) if is_github && github_url.is_empty() => github_url = url.to_string(),
+                _ => unmapped.push(format!("/basics/profiles/{}", index)),
+            }
+        }
+    }
+
+    let identity = PortfolioIdentity {
+        name: name.to_string(),
+        email: basics
+            .and_then(|basics| str_at(basics, "email"))
+            .unwrap_or_default()
+            .to_string(),
+        github_url,
+    };
+
+    let mut featured_projects = Vec::new();
+    for (index, project) in array_at(resume, "projects").iter().enumerate() {
+        let pointer = format!("/projects/{}", index);
+        let Some(project) = project.as_object() else {
+            unmapped.push(pointer);
+            continue;
+        };
+        let Some(title) = str_at(project, "name") else {
+            unmapped.push(pointer);
+            continue;
+        };
+        report_unknown_keys(project, &PROJECT_KEYS, &pointer, &mut unmapped);
+        let project_extension = object_at(project, EXTENSION_KEY);
+
+        let links = match project_extension.and_then(|ext| ext.get("links")) {
+            Some(links) => {
+                serde_json::from_value::<Vec<PortfolioLink>>(links.clone()).unwrap_or_default()
+            }
+            None => str_at(project, "url")
+                .map(|url| {
+                    vec![PortfolioLink {
+                        label: DEFAULT_LINK_LABEL.to_string(),
+                        url: url.to_string(),
+                    }]
+                })
+                .unwrap_or_default(),
+        };
+
+        featured_projects.push(PortfolioProject {
+            slug: project_extension
+                .and_then(|ext| str_at(ext, "slug"))
+                .map(str::to_string)
+                .unwrap_or_else(|| project_slug(title, index)),
+            title: title.to_string(),
+            period: period_of(project, project_extension, lang),
+            summary: str_at(project, "description")
+                .unwrap_or_default()
+                .to_string(),
+            stack: strings_at(project, "keywords"),
+            highlights: strings_at(project, "highlights"),
+            links,
+            posts: project_extension
+                .and_then(|ext| ext.get("posts"))
+                .and_then(|posts| serde_json::from_value(posts.clone()).ok())
+                .unwrap_or_default(),
+        });
+    }
+
+    let mut companies = Vec::new();
+    for (index, work) in array_at(resume, "work").iter().enumerate() {
+        let pointer = format!("/work/{}", index);
+        let Some(work) = work.as_object() else {
+            unmapped.push(pointer);
+            continue;
+        };
+        let Some(company) = str_at(work, "name") else {
+            unmapped.push(pointer);
+            continue;
+        };
+        report_unknown_keys(work, &WORK_KEYS, &pointer, &mut unmapped);
+        let work_extension = object_at(work, EXTENSION_KEY);
+
+        let items = match work_extension.and_then(|ext| ext.get("items")) {
+            Some(items) => serde_json::from_value::<Vec<PortfolioCareerItem>>(items.clone())
+                .unwrap_or_default(),
+            None => {
+                let bullets = strings_at(work, "highlights");
+                if bullets.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![PortfolioCareerItem {
+                        title: str_at(work, "position").unwrap_or_default().to_string(),
+                        period: None,
+                        bullets,
+                    }]
+                }
+            }
+        };
+
+        companies.push(PortfolioCareerCompany {
+            company: company.to_string(),
+            period: period_of(work, work_extension, lang),
+            employment_type: work_extension
+                .and_then(|ext| str_at(ext, "employment_type"))
+                .unwrap_or_default()
+                .to_string(),
+            role: str_at(work, "summary").unwrap_or_default().to_string(),
+            position: str_at(work, "position").unwrap_or_default().to_string(),
+            items,
+        });
+    }
+
+    let career_extension = extension.and_then(|ext| object_at(ext, "career"));
+    let career = if companies.is_empty() && career_extension.is_none() {
+        None
+    } else {
+        Some(PortfolioCareerSection {
+            summary_label: career_extension
+                .and_then(|ext| str_at(ext, "summary_label"))
+                .unwrap_or(DEFAULT_CAREER_LABEL)
+                .to_string(),
+            summary_value: career_extension
+                .and_then(|ext| str_at(ext, "summary_value"))
+                .unwrap_or_default()
+                .to_string(),
+            companies,
+        })
+    };
+
+    let icons: Vec<PortfolioTechStackItem> = extension
+        .and_then(|ext| ext.get("tech_stack"))
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default();
+    let mut tech_stack = Vec::new();
+    for (index, skill) in array_at(resume, "skills").iter().enumerate() {
+        let pointer = format!("/skills/{}", index);
+        let Some(skill) = skill.as_object() else {
+            unmapped.push(pointer);
+            continue;
+        };
+        report_unknown_keys(skill, &SKILL_KEYS, &pointer, &mut unmapped);
+        for name in strings_at(skill, "keywords") {
+            let icon_url = icons
+                .iter()
+                .find(|icon| icon.name == name)
+                .map(|icon| icon.icon_url.clone())
+                .unwrap_or_default();
+            tech_stack.push(PortfolioTechStackItem { name, icon_url });
+        }
+    }
+
+    let summary = basics.and_then(|basics| str_at(basics, "summary"));
+    let intro = if summary.is_none() && tech_stack.is_empty() {
+        None
+    } else {
+        Some(PortfolioIntroSection {
+            content: summary.unwrap_or_default().to_string(),
+            tech_stack,
+        })
+    };
+
+    let version = extension
+        .and_then(|ext| ext.get("version"))
+        .and_then(Value::as_i64)
+        .and_then(|version| i32::try_from(version).ok())
+        .unwrap_or(1);
+
+    Ok((
+        PortfolioDocument {
+            slug: slug.to_string(),
+            version,
+            identity,
+            featured_projects,
+            career,
+            intro,
+        },
+        unmapped,
+    ))
+}
+
+fn object_at<'a>(object: &'a Map<String, Value>, key: &str) -> Option<&'a Map<String, Value>> {
+    object.get(key).and_then(Value::as_object)
+}
+
+fn array_at<'a>(object: &'a Map<String, Value>, key: &str) -> &'a [Value] {
+    object
+        .get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn str_at<'a>(object: &'a Map<String, Value>, key: &str) -> Option<&'a str> {
+    object.get(key).and_then(Value::as_str)
+}
+
+fn strings_at(object: &Map<String, Value>, key: &str) -> Vec<String> {
+    array_at(object, key)
+        .iter()
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .collect()
+}
+
+/// 비어 있지 않은데 옮길 곳이 없는 키를 보고한다. 빈 배열, 빈 객체, `null`은 잃을 것이 없으니 건너뛴다.
+fn report_unknown_keys(
+    object: &Map<String, Value>,
+    known: &[&str],
+    pointer: &str,
+    unmapped: &mut Vec<String>,
+) {
+    for (key, value) in object {
+        let empty = match value {
+            Value::Null => true,
+            Value::Array(items) => items.is_empty(),
+            Value::Object(fields) => fields.is_empty(),
+            Value::String(text) => text.is_empty(),
+            _ => false,
+        };
+        if empty || key == EXTENSION_KEY || known.contains(&key.as_str()) {
+            continue;
+        }
+        unmapped.push(format!(
+            "{}/{}",
+            pointer,
+            key.replace('~', "~0").replace('/', "~1")
+        ));
+    }
+}
+
+fn github_username(url: &str) -> String {
+    url.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn project_slug(title: &str, index: usize) -> String {
+    let mut slug = String::new();
+    for ch in title.chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        format!("project-{}", index + 1)
+    } else {
+        slug.to_string()
+    }
+}
+
+/// `2020.01 - 2022.12` 같은 기간을 JSON Resume의 `startDate`/`endDate`로 나눈다.
+/// 끝이 `현재`/`Present`면 `endDate`를 비운다. 해석하지 못하면 기간 위치를 보고한다.
+fn insert_period(
+    entry: &mut Map<String, Value>,
+    period: &str,
+    pointer: &str,
+    unmapped: &mut Vec<String>,
+) {
+    match parse_period(period) {
+        Some((start, end)) => {
+            entry.insert("startDate".into(), json!(start));
+            if let Some(end) = end {
+                entry.insert("endDate".into(), json!(end));
+            }
+        }
+        None if period.trim().is_empty() => {}
+        None => unmapped.push(format!("{}/period", pointer)),
+    }
+}
+
+fn period_of(
+    entry: &Map<String, Value>,
+    extension: Option<&Map<String, Value>>,
+    lang: &str,
+) -> String {
+    if let Some(period) = extension.and_then(|ext| str_at(ext, "period")) {
+        return period.to_string();
+    }
+
+    let Some(start) = str_at(entry, "startDate") else {
+        return String::new();
+    };
+    let end = match str_at(entry, "endDate") {
+        Some(end) => end.replace('-', "."),
+        None if lang == "en" => "Present".to_string(),
+        None => "현재".to_string(),
+    };
+
+    format!("{} - {}", start.replace('-', "."), end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_json_resume, import_json_resume};
+    use crate::models::PortfolioDocument;
+    use crate::portfolio::parse_period;
+    use serde_json::json;
+
+    fn document() -> PortfolioDocument {
+        serde_json::from_value(json!({
+            "slug": "dev",
+            "version": 3,
+            "identity": {
+                "name": "TYANGE",
+                "email": "usun16@gmail.com",
+                "github_url": "https://github.com/tyange"
+            },
+            "featured_projects": [
+                {
+                    "slug": "cms",
+                    "title": "Tyange CMS",
+                    "period": "2023.04 - 현재",
+                    "summary": "블로그와 포트폴리오를 관리하는 CMS",
+                    "stack": ["Rust", "SQLite"],
+                    "highlights": ["초안과 발행 분리"],
+                    "links": [
+                        { "label": "GitHub", "url": "https://github.com/tyange/cms" },
+                        { "label": "Demo", "url": "https://cms.tyange.com" }
+                    ]
+                }
+            ],
+            "career": {
+                "summary_label": "경력",
+                "summary_value": "4년",
+                "companies": [
+                    {
+                        "company": "테스트 회사",
+                        "period": "2020.01 - 2022.12",
+                        "employment_type": "정규직",
+                        "role": "프론트엔드 개발",
+                        "position": "선임",
+                        "items": [
+                            {
+                                "title": "디자인 시스템",
+                                "period": "2021.01 - 2022.12",
+                                "bullets": ["컴포넌트 40종 정리", "문서 사이트 구축"]
+                            }
+                        ]
+                    }
+                ]
+            },
+            "intro": {
+                "content": "안녕하세요",
+                "tech_stack": [
+                    { "name": "Rust", "icon_url": "https://example.com/rust.svg" }
+                ]
+            }
+        }))
+        .expect("valid document")
+    }
+
+    #[test]
+    fn round_trip_keeps_projects_career_and_links() {
+        let original = document();
+        let (resume, unmapped) = export_json_resume(&original);
+
+        assert_eq!(resume["basics"]["profiles"][0]["username"], "tyange");
+        assert_eq!(resume["projects"][0]["startDate"], "2023-04");
+        assert!(resume["projects"][0].get("endDate").is_none());
+        assert_eq!(resume["work"][0]["endDate"], "2022-12");
+        assert_eq!(
+            resume["work"][0]["highlights"],
+            json!(["컴포넌트 40종 정리", "문서 사이트 구축"])
+        );
+        assert!(unmapped.contains(&"/featured_projects/0/links/1".to_string()));
+        assert!(unmapped.contains(&"/intro/tech_stack/0/icon_url".to_string()));
+
+        let (restored, import_unmapped) =
+            import_json_resume(&resume, "dev", "ko").expect("import should succeed");
+        assert!(import_unmapped.is_empty(), "{:?}", import_unmapped);
+        assert_eq!(
+            serde_json::to_value(&restored).unwrap(),
+            serde_json::to_value(&original).unwrap()
+        );
+    }
+
+    #[test]
+    fn standard_fields_alone_keep_the_history() {
+        let (mut resume, _) = export_json_resume(&document());
+        // 확장 필드를 모르는 도구를 거친 이력서를 흉내 낸다.
+        strip_extensions(&mut resume);
+
+        let (restored, _) =
+            import_json_resume(&resume, "dev", "en").expect("import should succeed");
+        let project = &restored.featured_projects[0];
+        assert_eq!(project.title, "Tyange CMS");
+        assert_eq!(project.slug, "tyange-cms");
+        assert_eq!(project.period, "2023.04 - Present");
+        assert_eq!(project.links.len(), 1);
+        assert_eq!(project.links[0].url, "https://github.com/tyange/cms");
+
+        let company = &restored.career.as_ref().unwrap().companies[0];
+        assert_eq!(company.company, "테스트 회사");
+        assert_eq!(company.period, "2020.01 - 2022.12");
+        assert_eq!(company.role, "프론트엔드 개발");
+        assert_eq!(
+            company.items[0].bullets,
+            vec!["컴포넌트 40종 정리", "문서 사이트 구축"]
+        );
+        assert_eq!(restored.identity.github_url, "https://github.com/tyange");
+    }
+
+    #[test]
+    fn import_reports_fields_without_a_home() {
+        let resume = json!({
+            "basics": {
+                "name": "Someone",
+                "phone": "010-0000-0000",
+                "profiles": [
+                    { "network": "Twitter", "url": "https://twitter.com/someone" }
+                ]
+            },
+            "education": [{ "institution": "Somewhere" }],
+            "awards": [],
+            "work": [{ "name": "Acme", "location": "Seoul", "position": "Engineer" }],
+            "projects": [{ "description": "이름 없는 프로젝트" }]
+        });
+
+        let (document, unmapped) =
+            import_json_resume(&resume, "design", "ko").expect("import should succeed");
+        assert_eq!(document.slug, "design");
+        assert_eq!(
+            unmapped,
+            vec![
+                "/education",
+                "/basics/phone",
+                "/basics/profiles/0",
+                "/projects/0",
+                "/work/0/location",
+            ]
+        );
+
+        assert!(import_json_resume(&json!({ "basics": {} }), "dev", "ko").is_err());
+        assert!(import_json_resume(&json!([]), "dev", "ko").is_err());
+    }
+
+    #[test]
+    fn periods_accept_common_separators() {
+        assert_eq!(
+            parse_period("2020.01 ~ 2022.12"),
+            Some(("2020-01".to_string(), Some("2022-12".to_string())))
+        );
+        assert_eq!(
+            parse_period("2021-03 - Present"),
+            Some(("2021-03".to_string(), None))
+        );
+        assert_eq!(parse_period("작년 봄"), None);
+    }
+
+    fn strip_extensions(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                map.remove("x-portfolio");
+                map.values_mut().for_each(strip_extensions);
+            }
+            serde_json::Value::Array(items) => items.iter_mut().for_each(strip_extensions),
+            _ => {}
+        }
+    }
+}
@@ -2,3 +2,4 @@ pub mod auth;
 pub mod blog_redeploy;
 pub mod db;
 pub mod models;
+pub mod storage;
@@ -0,0 +1,413 @@
+//! 비밀번호 로그인 무차별 대입 방어.
+//!
+//! 입력한 아이디(소문자)와 클라이언트 IP마다 연속 실패 횟수를 `login_throttles`에 센다. 한도를 넘으면
+//! 30초부터 실패할 때마다 두 배씩(최대 1시간) 잠그고, 잠긴 동안은 비밀번호가 맞아도 `429`다.
+//! 아이디 카운터는 가입 여부와 상관없이 세므로 잠금 응답으로도 계정이 있는지 알 수 없다.
+//! 로그인에 성공하면 그 아이디의 카운터만 지우고, IP 카운터는 마지막 실패 후 하루가 지나야 처음부터 센다.
+//!
+//! 비밀번호 재설정 요청도 같은 테이블에 `reset_account`/`reset_ip` scope로 따로 센다. 한도를 넘은 요청은
+//! 메일을 보내지 않고 이미 보낸 토큰도 그대로 두지만, 응답은 평소와 같다.
+
+use std::env;
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use poem::{
+    http::{header, StatusCode},
+    Error, Request, Response,
+};
+use sqlx::{query, query_scalar, QueryBuilder, Sqlite, SqlitePool};
+
+use crate::models::{
+    LoginAuditEventItem, LoginAuditEventQuery, LoginLockoutItem, LoginLockoutListQuery,
+};
+
+const SCOPE_ACCOUNT: &str = "account";
+const SCOPE_IP: &str = "ip";
+const SCOPE_RESET_ACCOUNT: &str = "reset_account";
+const SCOPE_RESET_IP: &str = "reset_ip";
+const EVENT_FAILED: &str = "failed";
+const EVENT_BLOCKED: &str = "blocked";
+const EVENT_CLEARED: &str = "cleared";
+
+const DEFAULT_ACCOUNT_MAX_FAILURES: i64 = 5;
+const DEFAULT_IP_MAX_FAILURES: i64 = 20;
+const BASE_LOCKOUT_SECONDS: i64 = 30;
+const MAX_LOCKOUT_SECONDS: i64 = 60 * 60;
+/// 마지막 실패 뒤 이만큼 지나면 카운터를 처음부터 센다.
+const FAILURE_WINDOW_HOURS: i64 = 24;
+const DEFAULT_RESET_ACCOUNT_MAX_REQUESTS: i64 = 3;
+const DEFAULT_RESET_IP_MAX_REQUESTS: i64 = 10;
+/// 재설정 요청은 마지막 요청 뒤 이만큼 지나면 처음부터 센다.
+const RESET_WINDOW_MINUTES: i64 = 60;
+const AUDIT_LIMIT_DEFAULT: u32 = 50;
+const AUDIT_LIMIT_MAX: u32 = 200;
+const LOCKOUT_LIST_LIMIT: i64 = 200;
+
+fn internal_error(message: String) -> Error {
+    Error::from_string(message, StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn timestamp(at: chrono::DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn env_count(key: &str, default: i64) -> i64 {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(default)
+}
+
+/// `LOGIN_ACCOUNT_MAX_FAILURES`(기본 5). 이만큼 연속으로 틀리면 그 아이디를 잠근다.
+pub fn account_max_failures() -> i64 {
+    env_count("LOGIN_ACCOUNT_MAX_FAILURES", DEFAULT_ACCOUNT_MAX_FAILURES)
+}
+
+/// `LOGIN_IP_MAX_FAILURES`(기본 20). 여러 아이디를 번갈아 시도하는 경우를 막는다.
+pub fn ip_max_failures() -> i64 {
+    env_count("LOGIN_IP_MAX_FAILURES", DEFAULT_IP_MAX_FAILURES)
+}
+
+fn max_failures(scope: &str) -> i64 {
+    if scope == SCOPE_IP {
+        ip_max_failures()
+    } else {
+        account_max_failures()
+    }
+}
+
+/// 한도에 닿은 뒤 `failure_count`번째 실패의 잠금 시간(초).
+fn lockout_seconds(failure_count: i64, max_failures: i64) -> i64 {
+    let doublings = (failure_count - max_failures).clamp(0, 16) as u32;
+    (BASE_LOCKOUT_SECONDS * 2i64.pow(doublings)).min(MAX_LOCKOUT_SECONDS)
+}
+
+/// 카운터 키로 쓰는 아이디. 대소문자와 앞뒤 공백만 달리 해서 한도를 피하지 못하게 한다.
+pub fn account_key(user_id: &str) -> String {
+    user_id.trim().to_lowercase()
+}
+
+/// 클라이언트 IP. `TRUST_PROXY_HEADERS=true`면 리버스 프록시가 넣은 `X-Forwarded-For` 첫 값을 쓴다.
+/// 프록시 뒤가 아닌데 켜면 헤더를 바꿔 가며 IP 한도를 피할 수 있다.
+pub fn client_ip(req: &Request) -> String {
+    let trust_proxy = env::var("TRUST_PROXY_HEADERS")
+        .map(|value| value.trim().eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+    if trust_proxy {
+        let forwarded = req
+            .headers()
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .map(str::trim)
+            .filter(|value| !value.is_empty());
+        if let Some(forwarded) = forwarded {
+            return forwarded.to_string();
+        }
+    }
+
+    match req.remote_addr().as_socket_addr() {
+        Some(addr) => addr.ip().to_string(),
+        None => req.remote_addr().to_string(),
+    }
+}
+
+/// 잠금 중일 때의 응답. 아이디가 있든 없든 같은 문구와 `Retry-After`를 준다.
+pub fn too_many_attempts(retry_after_seconds: i64) -> Error {
+    Error::from_response(
+        Response::builder()
+            .status(StatusCode::TOO_MANY_REQUESTS)
+            .header(header::RETRY_AFTER, retry_after_seconds.max(1).to_string())
+            .body("로그인 시도가 너무 많습니다. 잠시 후 다시 시도해 주세요."),
+    )
+}
+
+/// 아이디나 IP가 잠겨 있으면 남은 시간(초)을 돌려준다.
+pub async fn locked_for(
+    db: &SqlitePool,
+    account: &str,
+    ip_address: &str,
+) -> Result<Option<i64>, Error> {
+    let now = Utc::now();
+    let locked_until: Option<String> = query_scalar(
+        r#"
+        SELECT MAX(locked_until) FROM login_throttles
+        WHERE ((scope = ? AND throttle_key = ?) OR (scope = ? AND throttle_key = ?))
+          AND locked_until > ?
+        "#,
+    )
+    .bind(SCOPE_ACCOUNT)
+    .bind(account)
+    .bind(SCOPE_IP)
+    .bind(ip_address)
+    .bind(timestamp(now))
+    .fetch_one(db)
+    .await
+    .map_err(|err| internal_error(format!("로그인 잠금 조회 실패: {}", err)))?;
+
+    Ok(locked_until
+        .and_then(|value| NaiveDateTime::parse_from_str(&value, "%Y-%m-%d %H:%M:%S").ok())
+        .map(|until| (until.and_utc() - now).num_seconds().max(1)))
+}
+
+/// 카운터를 하나 올리고 새 값을 돌려준다. 마지막으로 센 때가 `window_start` 이전이면 1부터 다시 센다.
+async fn bump_counter(
+    db: &SqlitePool,
+    scope: &str,
+    key: &str,
+    now: chrono::DateTime<Utc>,
+    window_start: &str,
+) -> Result<i64, sqlx::Error> {
+    query_scalar(
+        r#"
+        INSERT INTO login_throttles (scope, throttle_key, failure_count, last_failed_at)
+        VALUES (?, ?, 1, ?)
+        ON CONFLICT(scope, throttle_key) DO UPDATE
+        SET failure_count = CASE
+                WHEN login_throttles.last_failed_at <= ? THEN 1
+                ELSE login_throttles.failure_count + 1
+            END,
+            last_failed_at = excluded.last_failed_at
+        RETURNING failure_count
+        "#,
+    )
+    .bind(scope)
+    .bind(key)
+    .bind(timestamp(now))
+    .bind(window_start)
+    .fetch_one(db)
+    .await
+}
+
+async fn count_failure(db: &SqlitePool, scope: &str, key: &str) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let window_start = timestamp(now - Duration::hours(FAILURE_WINDOW_HOURS));
+    let failure_count = bump_counter(db, scope, key, now, &window_start).await?;
+
+    let max_failures = max_failures(scope);
+    if failure_count >= max_failures {
+        let locked_until = now + Duration::seconds(lockout_seconds(failure_count, max_failures));
+        query("UPDATE login_throttles SET locked_until = ? WHERE scope = ? AND throttle_key = ?")
+            .bind(timestamp(locked_until))
+            .bind(scope)
+            .bind(key)
+            .execute(db)
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn record_event(
+    db: &SqlitePool,
+    event: &str,
+    user_id: Option<&str>,
+    ip_address: Option<&str>,
+    actor_id: Option<&str>,
+) -> Result<(), Error> {
+    query(
+        r#"
+        INSERT INTO login_audit_events (event, user_id, ip_address, actor_id, created_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(event)
+    .bind(user_id)
+    .bind(ip_address)
+    .bind(actor_id)
+    .bind(timestamp(Utc::now()))
+    .execute(db)
+    .await
+    .map_err(|err| internal_error(format!("로그인 감사 기록 실패: {}", err)))?;
+
+    Ok(())
+}
+
+/// 비밀번호나 2단계 인증 코드가 틀렸거나 없는 아이디일 때. 아이디와 IP 카운터를 올리고 감사 기록을 남긴다.
+pub async fn record_failure(db: &SqlitePool, account: &str, ip_address: &str) -> Result<(), Error> {
+    for (scope, key) in [(SCOPE_ACCOUNT, account), (SCOPE_IP, ip_address)] {
+        count_failure(db, scope, key)
+            .await
+            .map_err(|err| internal_error(format!("로그인 실패 기록 실패: {}", err)))?;
+    }
+    record_event(db, EVENT_FAILED, Some(account), Some(ip_address), None).await
+}
+
+/// 잠긴 동안 들어온 시도. 카운터는 올리지 않고 기록만 남긴다.
+pub async fn record_blocked(db: &SqlitePool, account: &str, ip_address: &str) -> Result<(), Error> {
+    record_event(db, EVENT_BLOCKED, Some(account), Some(ip_address), None).await
+}
+
+/// 로그인을 마치면(2단계 인증까지) 그 아이디의 카운터를 지운다. IP 카운터는 그대로 둔다.
+pub async fn record_success(db: &SqlitePool, account: &str) -> Result<(), Error> {
+    query("DELETE FROM login_throttles WHERE scope = ? AND throttle_key = ?")
+        .bind(SCOPE_ACCOUNT)
+        .bind(account)
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("로그인 잠금 해제 실패: {}", err)))?;
+
+    Ok(())
+}
+
+/// 비밀번호 재설정 요청을 아이디와 IP마다 센다. 어느 쪽이든 한도를 넘었으면 `false`다.
+/// 한도는 `PASSWORD_RESET_ACCOUNT_MAX_REQUESTS`(기본 3), `PASSWORD_RESET_IP_MAX_REQUESTS`(기본 10)다.
+pub async fn allow_password_reset_request(
+    db: &SqlitePool,
+    account: &str,
+    ip_address: &str,
+) -> Result<bool, Error> {
+    let now = Utc::now();
+    let window_start = timestamp(now - Duration::minutes(RESET_WINDOW_MINUTES));
+    let limits = [
+        (
+            SCOPE_RESET_ACCOUNT,
+            account,
+            env_count(
+                "PASSWORD_RESET_ACCOUNT_MAX_REQUESTS",
+                DEFAULT_RESET_ACCOUNT_MAX_REQUESTS,
+            ),
+        ),
+        (
+            SCOPE_RESET_IP,
+            ip_address,
+            env_count("PASSWORD_RESET_IP_MAX_REQUESTS", DEFAULT_RESET_IP_MAX_REQUESTS),
+        ),
+    ];
+
+    let mut allowed = true;
+    for (scope, key, max_requests) in limits {
+        let request_count = bump_counter(db, scope, key, now, &window_start)
+            .await
+            .map_err(|err| internal_error(format!("재설정 요청 기록 실패: {}", err)))?;
+        if request_count > max_requests {
+            allowed = false;
+        }
+    }
+
+    Ok(allowed)
+}
+
+pub async fn list_lockouts(
+    db: &SqlitePool,
+    params: &LoginLockoutListQuery,
+) -> Result<Vec<LoginLockoutItem>, Error> {
+    let now = timestamp(Utc::now());
+    let mut builder = QueryBuilder::<Sqlite>::new(
+        "SELECT scope, throttle_key, failure_count, last_failed_at, locked_until, \
+         (locked_until IS NOT NULL AND locked_until > ",
+    );
+    builder
+        .push_bind(now.clone())
+        .push(") AS locked FROM login_throttles WHERE scope IN (")
+        .push_bind(SCOPE_ACCOUNT)
+        .push(", ")
+        .push_bind(SCOPE_IP)
+        .push(")");
+    if let Some(scope) = params.scope.as_deref() {
+        builder.push(" AND scope = ").push_bind(scope);
+    }
+    if params.locked_only.unwrap_or(false) {
+        builder.push(" AND locked_until > ").push_bind(now);
+    }
+    builder
+        .push(" ORDER BY last_failed_at DESC LIMIT ")
+        .push_bind(LOCKOUT_LIST_LIMIT);
+
+    builder
+        .build_query_as::<LoginLockoutItem>()
+        .fetch_all(db)
+        .await
+        .map_err(|err| internal_error(format!("로그인 잠금 목록 조회 실패: {}", err)))
+}
+
+/// 관리자가 카운터와 잠금을 지운다. 지운 것이 없으면 `404`.
+pub async fn clear_lockout(
+    db: &SqlitePool,
+    scope: &str,
+    key: &str,
+    actor_id: &str,
+) -> Result<(), Error> {
+    if scope != SCOPE_ACCOUNT && scope != SCOPE_IP {
+        return Err(Error::from_string(
+            "scope는 account 또는 ip여야 합니다.",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    let key = if scope == SCOPE_ACCOUNT {
+        account_key(key)
+    } else {
+        key.trim().to_string()
+    };
+
+    let result = query("DELETE FROM login_throttles WHERE scope = ? AND throttle_key = ?")
+        .bind(scope)
+        .bind(&key)
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("로그인 잠금 해제 실패: {}", err)))?;
+    if result.rows_affected() == 0 {
+        return Err(Error::from_string(
+            "해당 로그인 잠금을 찾을 수 없습니다.",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    let (user_id, ip_address) = if scope == SCOPE_ACCOUNT {
+        (Some(key.as_str()), None)
+    } else {
+        (None, Some(key.as_str()))
+    };
+    record_event(db, EVENT_CLEARED, user_id, ip_address, Some(actor_id)).await
+}
+
+pub async fn list_audit_events(
+    db: &SqlitePool,
+    params: &LoginAuditEventQuery,
+) -> Result<Vec<LoginAuditEventItem>, Error> {
+    let limit = params
+        .limit
+        .unwrap_or(AUDIT_LIMIT_DEFAULT)
+        .clamp(1, AUDIT_LIMIT_MAX);
+    let mut builder = QueryBuilder::<Sqlite>::new(
+        "SELECT event_id AS id, event, user_id, ip_address, actor_id, created_at \
+         FROM login_audit_events WHERE 1 = 1",
+    );
+    if let Some(user_id) = params.user_id.as_deref() {
+        builder
+            .push(" AND user_id = ")
+            .push_bind(account_key(user_id));
+    }
+    if let Some(ip_address) = params.ip_address.as_deref() {
+        builder.push(" AND ip_address = ").push_bind(ip_address);
+    }
+    if let Some(event) = params.event.as_deref() {
+        builder.push(" AND event = ").push_bind(event);
+    }
+    builder
+        .push(" ORDER BY event_id DESC LIMIT ")
+        .push_bind(i64::from(limit))
+        .push(" OFFSET ")
+        .push_bind(i64::from(params.offset.unwrap_or(0)));
+
+    builder
+        .build_query_as::<LoginAuditEventItem>()
+        .fetch_all(db)
+        .await
+        .map_err(|err| internal_error(format!("로그인 감사 기록 조회 실패: {}", err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lockout_doubles_from_the_limit_and_caps_at_an_hour() {
+        assert_eq!(lockout_seconds(5, 5), 30);
+        assert_eq!(lockout_seconds(6, 5), 60);
+        assert_eq!(lockout_seconds(8, 5), 240);
+        assert_eq!(lockout_seconds(20, 5), MAX_LOCKOUT_SECONDS);
+        assert_eq!(lockout_seconds(500, 5), MAX_LOCKOUT_SECONDS);
+    }
+}
@@ -0,0 +1,268 @@
+//! 메일 발송 outbox.
+//!
+//! 핸들러는 `email_outbox`에 행만 쌓고 응답한다. 실제 SMTP 전송은 워커가 맡으므로 메일 서버가 느리거나
+//! 잠시 내려가도 요청이 실패하지 않고, 다음 주기에 다시 보낸다.
+
+use std::{env, time::Duration};
+
+use lettre::{
+    message::{
+        header::{ContentTransferEncoding, ContentType},
+        Body, Mailbox,
+    },
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use sqlx::{query, query_as, FromRow, SqlitePool};
+use tokio::time::interval;
+
+/// 이 횟수만큼 실패하면 `failed`로 두고 더 보내지 않는다.
+pub const MAX_EMAIL_ATTEMPTS: i64 = 5;
+
+const DEFAULT_SMTP_PORT: u16 = 587;
+const DEFAULT_INTERVAL_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// 평문 연결. 로컬 개발용 SMTP나 테스트용 서버에만 쓴다.
+    None,
+    StartTls,
+    Tls,
+}
+
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+}
+
+impl SmtpConfig {
+    /// `SMTP_HOST`와 `SMTP_FROM`이 모두 있어야 발송을 켠다.
+    pub fn from_env() -> Option<Self> {
+        let read = |name: &str| {
+            env::var(name)
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        let host = read("SMTP_HOST")?;
+        let from = read("SMTP_FROM")?;
+        let security = match read("SMTP_SECURITY").as_deref() {
+            Some("none") => SmtpSecurity::None,
+            Some("tls") => SmtpSecurity::Tls,
+            _ => SmtpSecurity::StartTls,
+        };
+        let port = read("SMTP_PORT")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(match security {
+                SmtpSecurity::Tls => 465,
+                _ => DEFAULT_SMTP_PORT,
+            });
+
+        Some(Self {
+            host,
+            port,
+            security,
+            username: read("SMTP_USERNAME"),
+            password: read("SMTP_PASSWORD"),
+            from,
+        })
+    }
+}
+
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(config: &SmtpConfig) -> Result<Self, String> {
+        let builder = match config.security {
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+            }
+            SmtpSecurity::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                    .map_err(|err| format!("SMTP STARTTLS 설정 실패: {}", err))?
+            }
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)
+                .map_err(|err| format!("SMTP TLS 설정 실패: {}", err))?,
+        };
+        let mut builder = builder.port(config.port);
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        let from = config
+            .from
+            .parse::<Mailbox>()
+            .map_err(|err| format!("SMTP_FROM 주소가 올바르지 않습니다: {}", err))?;
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+
+    async fn send(&self, email: &OutboxEmail) -> Result<(), String> {
+        let to = email
+            .recipient
+            .parse::<Mailbox>()
+            .map_err(|err| format!("받는 주소가 올바르지 않습니다: {}", err))?;
+        // 본문은 대부분 한글이라 인코딩을 base64로 고정한다.
+        let body = Body::new_with_encoding(email.body.clone(), ContentTransferEncoding::Base64)
+            .map_err(|_| "메일 본문 인코딩 실패".to_string())?;
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&email.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body)
+            .map_err(|err| format!("메일 작성 실패: {}", err))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|err| format!("SMTP 전송 실패: {}", err))
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct OutboxEmail {
+    pub email_id: i64,
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct OutboxDeliveryResult {
+    pub sent: usize,
+    pub failed: usize,
+}
+
+/// 보낼 메일을 outbox에 쌓는다. `kind`는 로그와 조회용 분류다(예: `password_reset`).
+pub async fn enqueue_email(
+    db: &SqlitePool,
+    kind: &str,
+    recipient: &str,
+    subject: &str,
+    body: &str,
+) -> Result<i64, sqlx::Error> {
+    let result = query(
+        r#"
+        INSERT INTO email_outbox (kind, recipient, subject, body)
+        VALUES (?, ?, ?, ?)
+        "#,
+    )
+    .bind(kind)
+    .bind(recipient)
+    .bind(subject)
+    .bind(body)
+    .execute(db)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// 대기 중인 메일을 오래된 순서로 보낸다. 실패하면 횟수와 오류를 남기고, 한도에 이르면 `failed`로 둔다.
+pub async fn deliver_pending_emails(
+    db: &SqlitePool,
+    mailer: &SmtpMailer,
+) -> Result<OutboxDeliveryResult, sqlx::Error> {
+    let pending = query_as::<_, OutboxEmail>(
+        r#"
+        SELECT email_id, recipient, subject, body
+        FROM email_outbox
+        WHERE status = 'pending'
+        ORDER BY email_id
+        LIMIT 50
+        "#,
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut result = OutboxDeliveryResult::default();
+    for email in pending {
+        match mailer.send(&email).await {
+            Ok(()) => {
+                query(
+                    r#"
+                    UPDATE email_outbox
+                    SET status = 'sent', attempts = attempts + 1, last_error = NULL,
+                        sent_at = CURRENT_TIMESTAMP
+                    WHERE email_id = ?
+                    "#,
+                )
+                .bind(email.email_id)
+                .execute(db)
+                .await?;
+                result.sent += 1;
+            }
+            Err(err) => {
+                eprintln!(
+                    "email outbox 전송 실패 (email_id={}): {}",
+                    email.email_id, err
+                );
+                query(
+                    r#"
+                    UPDATE email_outbox
+                    SET attempts = attempts + 1, last_error = ?,
+                        status = CASE WHEN attempts + 1 >= ? THEN 'failed' ELSE status END
+                    WHERE email_id = ?
+                    "#,
+                )
+                .bind(&err)
+                .bind(MAX_EMAIL_ATTEMPTS)
+                .bind(email.email_id)
+                .execute(db)
+                .await?;
+                result.failed += 1;
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// SMTP 설정이 있을 때만 `EMAIL_OUTBOX_INTERVAL_SECS`(기본 30초)마다 outbox를 비운다.
+pub fn start_email_outbox_worker(db: SqlitePool) {
+    let Some(config) = SmtpConfig::from_env() else {
+        println!("SMTP_HOST/SMTP_FROM 환경변수가 비어 있어 email outbox를 보내지 않습니다.");
+        return;
+    };
+    let mailer = match SmtpMailer::new(&config) {
+        Ok(mailer) => mailer,
+        Err(err) => {
+            eprintln!("email outbox 워커를 시작하지 못했습니다: {}", err);
+            return;
+        }
+    };
+    let interval_secs = env::var("EMAIL_OUTBOX_INTERVAL_SECS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_INTERVAL_SECS);
+
+    tokio::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(interval_secs));
+
+        loop {
+            ticker.tick().await;
+            match deliver_pending_emails(&db, &mailer).await {
+                Ok(OutboxDeliveryResult { sent: 0, failed: 0 }) => {}
+                Ok(result) => println!(
+                    "email outbox delivered: sent={}, failed={}",
+                    result.sent, result.failed
+                ),
+                Err(err) => eprintln!("email outbox delivery failed: {}", err),
+            }
+        }
+    });
+}
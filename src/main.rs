@@ -1,13 +1,37 @@
+mod account_deletion;
+mod attachments;
 mod blog_redeploy;
 #[cfg(test)]
 mod budget;
 mod budget_periods;
 mod card_excel;
+mod data_export;
 mod db;
+mod image_dedup;
+mod image_gc;
+mod image_library;
+mod image_metadata;
+mod image_upload;
+mod image_validation;
+mod image_variants;
+mod json_resume;
+mod login_throttle;
+mod mail_outbox;
 mod middlewares;
 mod models;
+mod passkeys;
+mod password_reset;
+mod portfolio;
+mod portfolio_posts;
+mod portfolio_render;
+mod portfolio_schema;
 mod routes;
 mod rss_push;
+mod sessions;
+mod storage;
+mod two_factor;
+mod upload_sessions;
+mod user_admin;
 mod utils;
 
 use dotenv::dotenv;
@@ -16,51 +40,123 @@ use middlewares::api_key_middleware::JwtOrApiKeyAuth;
 use middlewares::auth_middleware::Auth;
 use std::{env, fs, sync::Arc};
 
+use crate::routes::cancel_my_account_deletion::cancel_my_account_deletion;
+use crate::routes::confirm_my_totp_enrollment::confirm_my_totp_enrollment;
+use crate::routes::confirm_password_reset::confirm_password_reset;
 use crate::routes::create_api_key::create_api_key_handler;
 use crate::routes::create_budget_plan::create_budget_plan;
 use crate::routes::create_match::create_match;
 use crate::routes::create_match_message::create_match_message;
+use crate::routes::create_my_data_export::create_my_data_export;
+use crate::routes::create_my_passkey::create_my_passkey;
+use crate::routes::create_my_passkey_options::create_my_passkey_options;
+use crate::routes::create_passkey_login_options::create_passkey_login_options;
 use crate::routes::create_rss_source::create_rss_source;
 use crate::routes::create_spending::create_spending;
+use crate::routes::create_upload_session::create_upload_session;
+use crate::routes::delete_admin_user::delete_admin_user;
 use crate::routes::delete_all_spending::delete_all_spending;
 use crate::routes::delete_api_key::delete_api_key;
+use crate::routes::delete_attachment::delete_attachment;
+use crate::routes::delete_my_account::delete_my_account;
 use crate::routes::delete_my_match::delete_my_match;
+use crate::routes::delete_my_passkey::delete_my_passkey;
+use crate::routes::delete_my_session::delete_my_session;
+use crate::routes::delete_image::delete_image;
+use crate::routes::delete_login_lockout::delete_login_lockout;
 use crate::routes::delete_portfolio::delete_portfolio;
+use crate::routes::delete_portfolio_draft::delete_portfolio_draft;
 use crate::routes::delete_post::delete_post;
 use crate::routes::delete_push_subscription::delete_push_subscription;
 use crate::routes::delete_rss_subscription::delete_rss_subscription;
 use crate::routes::delete_spending::delete_spending;
+use crate::routes::delete_upload_session::delete_upload_session;
+use crate::routes::diff_portfolio_versions::diff_portfolio_versions;
+use crate::routes::disable_my_totp::disable_my_totp;
+use crate::routes::download_attachment::download_attachment;
+use crate::routes::download_data_export::download_data_export;
+use crate::routes::export_portfolio_json_resume::export_portfolio_json_resume;
+use crate::routes::finalize_upload_session::finalize_upload_session;
+use crate::routes::force_admin_user_password_reset::force_admin_user_password_reset;
+use crate::routes::get_admin_user::get_admin_user;
+use crate::routes::get_admin_users::get_admin_users;
 use crate::routes::get_all_posts::get_all_posts;
 use crate::routes::get_api_keys::get_api_keys;
+use crate::routes::get_attachments::get_attachments;
 use crate::routes::get_budget::get_budget;
 use crate::routes::get_count_with_tags::get_count_with_tags;
 use crate::routes::get_feed_items::get_feed_items;
+use crate::routes::get_image::get_image;
+use crate::routes::get_image_detail::get_image_detail;
+use crate::routes::get_image_orphans::get_image_orphans;
+use crate::routes::get_images::get_images;
+use crate::routes::get_login_audit_events::get_login_audit_events;
+use crate::routes::get_login_lockouts::get_login_lockouts;
 use crate::routes::get_match_messages::get_match_messages;
+use crate::routes::get_my_data_export::get_my_data_export;
 use crate::routes::get_my_match::get_my_match;
+use crate::routes::get_my_passkeys::get_my_passkeys;
+use crate::routes::get_my_sessions::get_my_sessions;
+use crate::routes::get_my_two_factor::get_my_two_factor;
 use crate::routes::get_portfolio::get_portfolio;
+use crate::routes::get_portfolio_draft::get_portfolio_draft;
+use crate::routes::get_portfolio_html::get_portfolio_html;
+use crate::routes::get_portfolio_pdf::get_portfolio_pdf;
+use crate::routes::get_portfolio_schema::get_portfolio_schema;
+use crate::routes::get_portfolio_version::get_portfolio_version;
+use crate::routes::get_portfolio_versions::get_portfolio_versions;
+use crate::routes::get_portfolios::get_portfolios;
 use crate::routes::get_posts_with_tags::get_posts_with_tags;
 use crate::routes::get_push_public_key::get_push_public_key;
 use crate::routes::get_push_subscriptions::get_push_subscriptions;
 use crate::routes::get_rss_sources::get_rss_sources;
+use crate::routes::get_security_settings::get_security_settings;
 use crate::routes::get_spending::get_spending;
 use crate::routes::get_tags_with_category::get_tags_with_category;
+use crate::routes::get_upload_session::get_upload_session;
+use crate::routes::import_portfolio_json_resume::import_portfolio_json_resume;
 use crate::routes::import_spending_excel::{commit_spending_import, preview_spending_import};
+use crate::routes::login_passkey::login_passkey;
+use crate::routes::login_two_factor::login_two_factor;
+use crate::routes::logout::logout;
 use crate::routes::me::me;
+use crate::routes::preview_portfolio::preview_portfolio;
+use crate::routes::publish_portfolio::publish_portfolio;
+use crate::routes::refresh::refresh;
+use crate::routes::regenerate_my_recovery_codes::regenerate_my_recovery_codes;
+use crate::routes::request_password_reset::request_password_reset;
+use crate::routes::reset_admin_user_two_factor::reset_admin_user_two_factor;
 use crate::routes::respond_match::respond_match;
+use crate::routes::restore_portfolio_version::restore_portfolio_version;
+use crate::routes::run_image_gc::run_image_gc;
+use crate::routes::run_image_hash_backfill::run_image_hash_backfill;
 use crate::routes::signup::signup;
+use crate::routes::start_my_totp_enrollment::start_my_totp_enrollment;
 use crate::routes::update_active_budget::update_active_budget;
+use crate::routes::update_admin_user::update_admin_user;
+use crate::routes::update_attachment::update_attachment;
+use crate::routes::update_image::update_image;
+use crate::routes::update_my_password::update_my_password;
+use crate::routes::update_my_passkey::update_my_passkey;
 use crate::routes::update_my_profile::update_my_profile;
-use crate::routes::update_portfolio::update_portfolio;
-use crate::routes::update_portfolio_section::update_portfolio_section;
+use crate::routes::update_portfolio_draft::update_portfolio_draft;
+use crate::routes::update_portfolio_draft_section::update_portfolio_draft_section;
 use crate::routes::update_post::update_post;
+use crate::routes::update_security_settings::update_security_settings;
 use crate::routes::update_spending::update_spending;
+use crate::routes::upload_attachment::upload_attachment;
 use crate::routes::upload_image::upload_image;
+use crate::routes::upload_images_batch::upload_images_batch;
+use crate::routes::upload_session_chunk::upload_session_chunk;
 use crate::routes::upsert_push_subscription::upsert_push_subscription;
+use crate::routes::validate_portfolio::validate_portfolio;
 use crate::{models::AppState, routes::add_user::add_user};
+use account_deletion::start_account_deletion_worker;
+use attachments::attachment_size_limit;
+use data_export::start_data_export_worker;
 use db::init_db;
 use poem::{
     delete,
-    endpoint::StaticFilesEndpoint,
     get, handler,
     http::StatusCode,
     listener::TcpListener,
@@ -71,8 +167,13 @@ use routes::{
     get_post::get_post, get_posts::get_posts, login::login, login_google::login_google,
     upload_post::upload_post,
 };
+use image_dedup::start_content_hash_backfill;
+use image_gc::start_image_gc_worker;
+use mail_outbox::start_email_outbox_worker;
 use rss_push::start_polling_worker;
+use upload_sessions::start_upload_session_cleanup_worker;
 use sqlx::SqlitePool;
+use storage::{migrate_storage, storage_from_name};
 
 #[handler]
 fn return_str() -> &'static str {
@@ -94,6 +195,49 @@ fn upload_size_limit() -> usize {
         .unwrap_or(DEFAULT_UPLOAD_MAX_BYTES)
 }
 
+/// `tyange-cms-api migrate-storage --from local --to s3`
+/// 한 저장소의 업로드 파일을 다른 저장소로 옮기고 `images.file_path`를 갱신한다.
+async fn run_storage_migration(db: &SqlitePool) -> Result<(), std::io::Error> {
+    let args: Vec<String> = env::args().skip(2).collect();
+    let option = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .and_then(|index| args.get(index + 1))
+            .cloned()
+    };
+    let invalid_input = |message: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, message);
+
+    let (Some(from), Some(to)) = (option("--from"), option("--to")) else {
+        return Err(invalid_input(
+            "사용법: tyange-cms-api migrate-storage --from <local|s3> --to <local|s3>".to_string(),
+        ));
+    };
+    let source = storage_from_name(&from).map_err(invalid_input)?;
+    let target = storage_from_name(&to).map_err(invalid_input)?;
+
+    let report = migrate_storage(db, source.as_ref(), target.as_ref())
+        .await
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+
+    println!(
+        "storage migration finished ({} -> {}): copied={}, skipped={}, failed={}",
+        source.backend_name(),
+        target.backend_name(),
+        report.copied.len(),
+        report.skipped.len(),
+        report.failed.len()
+    );
+    for key in &report.failed {
+        eprintln!("failed: {}", key);
+    }
+
+    if report.failed.is_empty() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other("일부 파일을 옮기지 못했습니다."))
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), std::io::Error> {
     dotenv().ok();
@@ -115,12 +259,22 @@ async fn main() -> Result<(), std::io::Error> {
         .await
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
 
-    start_polling_worker(db.clone());
+    if env::args().nth(1).as_deref() == Some("migrate-storage") {
+        return run_storage_migration(&db).await;
+    }
 
-    let state = Arc::new(AppState::new(db));
+    let state = Arc::new(AppState::new(db.clone()));
+    println!("Upload storage: {}", state.storage.backend_name());
+
+    start_polling_worker(db.clone());
+    start_content_hash_backfill(db.clone(), state.storage.clone());
+    start_image_gc_worker(db.clone(), state.storage.clone());
+    start_upload_session_cleanup_worker(db.clone());
+    start_email_outbox_worker(db);
+    start_account_deletion_worker(state.clone());
+    start_data_export_worker(state.clone());
 
     fn configure_routes() -> Route {
-        let upload_base_path = env::var("UPLOAD_PATH").unwrap_or(String::from(".uploads/images"));
         let upload_max_bytes = upload_size_limit();
 
         Route::new()
@@ -137,19 +291,70 @@ async fn main() -> Result<(), std::io::Error> {
             .at(
                 "/portfolio",
                 get(get_portfolio)
-                    .put(update_portfolio.with(AdminOnly).with(Auth))
+                    .put(update_portfolio_draft.with(AdminOnly).with(Auth))
                     .delete(delete_portfolio.with(AdminOnly).with(Auth)),
             )
+            .at("/portfolio/pdf", get(get_portfolio_pdf))
+            .at("/portfolio/html", get(get_portfolio_html))
+            .at("/portfolio/schema", get(get_portfolio_schema))
+            .at("/portfolio/validate", post(validate_portfolio))
+            .at("/portfolios", get(get_portfolios))
             .at(
                 "/portfolio/update",
-                put(update_portfolio).with(AdminOnly).with(Auth),
+                put(update_portfolio_draft).with(AdminOnly).with(Auth),
             )
             .at(
                 "/portfolio/sections/:section_key",
-                put(update_portfolio_section)
+                put(update_portfolio_draft_section)
+                    .with(AdminOnly)
+                    .with(Auth),
+            )
+            .at(
+                "/portfolio/draft",
+                get(get_portfolio_draft)
+                    .put(update_portfolio_draft)
+                    .delete(delete_portfolio_draft)
                     .with(AdminOnly)
                     .with(Auth),
             )
+            .at(
+                "/portfolio/draft/sections/:section_key",
+                put(update_portfolio_draft_section)
+                    .with(AdminOnly)
+                    .with(Auth),
+            )
+            .at(
+                "/portfolio/json-resume",
+                get(export_portfolio_json_resume).post(
+                    import_portfolio_json_resume
+                        .with(AdminOnly)
+                        .with(Auth),
+                ),
+            )
+            .at(
+                "/portfolio/preview",
+                get(preview_portfolio).with(AdminOnly).with(Auth),
+            )
+            .at(
+                "/portfolio/publish",
+                post(publish_portfolio).with(AdminOnly).with(Auth),
+            )
+            .at(
+                "/portfolio/versions",
+                get(get_portfolio_versions).with(AdminOnly).with(Auth),
+            )
+            .at(
+                "/portfolio/versions/diff",
+                get(diff_portfolio_versions).with(AdminOnly).with(Auth),
+            )
+            .at(
+                "/portfolio/versions/:version_number",
+                get(get_portfolio_version).with(AdminOnly).with(Auth),
+            )
+            .at(
+                "/portfolio/versions/:version_number/restore",
+                post(restore_portfolio_version).with(AdminOnly).with(Auth),
+            )
             .at(
                 "/upload-image",
                 post(upload_image)
@@ -162,11 +367,88 @@ async fn main() -> Result<(), std::io::Error> {
                     .with(SizeLimit::new(upload_max_bytes))
                     .with(Auth),
             )
+            .at(
+                "/images/upload/batch",
+                post(upload_images_batch)
+                    .with(SizeLimit::new(upload_max_bytes))
+                    .with(Auth),
+            )
+            .at("/images/upload-sessions", post(create_upload_session).with(Auth))
+            .at(
+                "/images/upload-sessions/:session_id",
+                get(get_upload_session.with(Auth))
+                    .put(
+                        upload_session_chunk
+                            .with(SizeLimit::new(upload_max_bytes))
+                            .with(Auth),
+                    )
+                    .delete(delete_upload_session.with(Auth)),
+            )
+            .at(
+                "/images/upload-sessions/:session_id/finalize",
+                post(finalize_upload_session).with(Auth),
+            )
+            .at(
+                "/attachments",
+                get(get_attachments.with(Auth)).post(
+                    upload_attachment
+                        .with(SizeLimit::new(attachment_size_limit()))
+                        .with(Auth),
+                ),
+            )
+            .at(
+                "/attachments/:attachment_id",
+                put(update_attachment).delete(delete_attachment).with(Auth),
+            )
+            .at(
+                "/attachments/:attachment_id/download",
+                get(download_attachment),
+            )
             .at("/login", post(login))
             .at("/login/google", post(login_google))
+            .at("/login/2fa", post(login_two_factor))
+            .at("/login/passkey", post(login_passkey))
+            .at("/login/passkey/options", post(create_passkey_login_options))
+            .at("/refresh", post(refresh))
+            .at("/logout", post(logout))
             .at("/signup", post(signup))
-            .at("/me", get(me).with(Auth))
+            .at("/me", get(me).delete(delete_my_account).with(Auth))
+            .at("/me/deletion", delete(cancel_my_account_deletion).with(Auth))
+            .at("/me/exports", post(create_my_data_export).with(Auth))
+            .at("/me/exports/:export_id", get(get_my_data_export).with(Auth))
+            .at("/exports/:export_id/download", get(download_data_export))
             .at("/me/profile", put(update_my_profile).with(Auth))
+            .at("/me/password", put(update_my_password).with(Auth))
+            .at("/me/2fa", get(get_my_two_factor).with(Auth))
+            .at("/me/2fa/totp", post(start_my_totp_enrollment).with(Auth))
+            .at(
+                "/me/2fa/totp/confirm",
+                post(confirm_my_totp_enrollment).with(Auth),
+            )
+            .at("/me/2fa/totp/disable", post(disable_my_totp).with(Auth))
+            .at(
+                "/me/2fa/recovery-codes",
+                post(regenerate_my_recovery_codes).with(Auth),
+            )
+            .at(
+                "/me/passkeys",
+                get(get_my_passkeys).post(create_my_passkey).with(Auth),
+            )
+            .at(
+                "/me/passkeys/options",
+                post(create_my_passkey_options).with(Auth),
+            )
+            .at(
+                "/me/passkeys/:passkey_id",
+                put(update_my_passkey).delete(delete_my_passkey).with(Auth),
+            )
+            .at("/me/sessions", get(get_my_sessions).with(Auth))
+            .at(
+                "/me/sessions/:session_id",
+                delete(delete_my_session).with(Auth),
+            )
+            .at("/password-reset", post(request_password_reset))
+            .at("/password-reset/confirm", post(confirm_password_reset))
             .at("/match/request", post(create_match).with(Auth))
             .at(
                 "/match/me",
@@ -197,10 +479,65 @@ async fn main() -> Result<(), std::io::Error> {
             )
             .at("/push/public-key", get(get_push_public_key))
             .at("/admin/add-user", post(add_user).with(AdminOnly).with(Auth))
+            .at(
+                "/admin/users",
+                get(get_admin_users).with(AdminOnly).with(Auth),
+            )
+            .at(
+                "/admin/users/:user_id",
+                get(get_admin_user)
+                    .put(update_admin_user)
+                    .delete(delete_admin_user)
+                    .with(AdminOnly)
+                    .with(Auth),
+            )
+            .at(
+                "/admin/users/:user_id/password-reset",
+                post(force_admin_user_password_reset)
+                    .with(AdminOnly)
+                    .with(Auth),
+            )
+            .at(
+                "/admin/users/:user_id/2fa",
+                delete(reset_admin_user_two_factor)
+                    .with(AdminOnly)
+                    .with(Auth),
+            )
+            .at(
+                "/admin/security-settings",
+                get(get_security_settings)
+                    .put(update_security_settings)
+                    .with(AdminOnly)
+                    .with(Auth),
+            )
+            .at(
+                "/admin/login-lockouts",
+                get(get_login_lockouts).with(AdminOnly).with(Auth),
+            )
+            .at(
+                "/admin/login-lockouts/:scope/:key",
+                delete(delete_login_lockout).with(AdminOnly).with(Auth),
+            )
+            .at(
+                "/admin/login-audit",
+                get(get_login_audit_events).with(AdminOnly).with(Auth),
+            )
             .at(
                 "/admin/posts",
                 get(get_all_posts).with(AdminOnly).with(Auth),
             )
+            .at(
+                "/admin/images/orphans",
+                get(get_image_orphans).with(AdminOnly).with(Auth),
+            )
+            .at(
+                "/admin/images/gc",
+                post(run_image_gc).with(AdminOnly).with(Auth),
+            )
+            .at(
+                "/admin/images/backfill-hashes",
+                post(run_image_hash_backfill).with(AdminOnly).with(Auth),
+            )
             .at(
                 "/api-keys",
                 post(create_api_key_handler).get(get_api_keys).with(Auth),
@@ -233,7 +570,14 @@ async fn main() -> Result<(), std::io::Error> {
                 "/budget/spending/:record_id",
                 put(update_spending.with(Auth)).delete(delete_spending.with(Auth)),
             )
-            .nest("/images", StaticFilesEndpoint::new(upload_base_path))
+            .at("/images/:file_name", get(get_image))
+            .at("/images", get(get_images).with(Auth))
+            .at(
+                "/image/:image_id",
+                get(get_image_detail.with(Auth))
+                    .put(update_image.with(Auth))
+                    .delete(delete_image.with(Auth)),
+            )
             .at("/*path", options(options_handler))
     }
 
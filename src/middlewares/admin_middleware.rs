@@ -1,5 +1,54 @@
-use poem::{Endpoint, Error, Middleware, Request};
-use tyange_cms_api::auth::authorization::{current_user, ensure_admin};
+use poem::{http::StatusCode, Endpoint, Error, Middleware, Request};
+use tyange_cms_api::auth::account::account_role;
+use tyange_cms_api::auth::authorization::{current_user, ensure_admin, AuthenticatedUser};
+
+use crate::{middlewares::auth_middleware::request_state, two_factor::admin_setup_required};
+
+/// 토큰의 역할 claim은 발급 당시 값이라 DB의 지금 역할도 관리자인지 다시 본다.
+/// 계정 행이 없으면 토큰의 역할을 따른다.
+async fn ensure_admin_role_current(req: &Request, user: &AuthenticatedUser) -> Result<(), Error> {
+    let state = request_state(req)?;
+
+    let role = account_role(&state.db, &user.user_id)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("계정 역할 조회 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+    if role.is_some_and(|role| role != "admin") {
+        return Err(Error::from_string(
+            "관리자만 접근할 수 있습니다.",
+            StatusCode::FORBIDDEN,
+        ));
+    }
+
+    Ok(())
+}
+
+/// 관리자 2단계 인증 필수 설정이 켜져 있으면 아직 등록하지 않은 관리자는 막는다.
+/// 등록(`/me/2fa/...`)은 `Auth`만 거치므로 막히지 않는다.
+async fn ensure_admin_two_factor(req: &Request, user: &AuthenticatedUser) -> Result<(), Error> {
+    let state = request_state(req)?;
+
+    let setup_required = admin_setup_required(&state.db, &user.user_id, &user.role)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("2단계 인증 조회 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+    if setup_required {
+        return Err(Error::from_string(
+            "관리자 계정은 2단계 인증을 등록해야 합니다.",
+            StatusCode::FORBIDDEN,
+        ));
+    }
+
+    Ok(())
+}
 
 pub struct AdminOnly;
 
@@ -21,6 +70,8 @@ impl<E: Endpoint> Endpoint for AdminOnlyImpl<E> {
     async fn call(&self, req: Request) -> Result<Self::Output, Error> {
         let user = current_user(&req)?;
         ensure_admin(user)?;
+        ensure_admin_role_current(&req, user).await?;
+        ensure_admin_two_factor(&req, user).await?;
         self.ep.call(req).await
     }
 }
@@ -5,7 +5,12 @@ use tyange_cms_api::auth::{
     authorization::AuthenticatedUser,
 };
 
-use crate::{middlewares::auth_middleware::require_jwt_user, models::AppState};
+use crate::{
+    middlewares::auth_middleware::{
+        ensure_account_enabled, ensure_session_active, require_jwt_user,
+    },
+    models::AppState,
+};
 
 pub struct JwtOrApiKeyAuth;
 
@@ -61,6 +66,7 @@ async fn authenticated_user_from_api_key(req: &Request) -> Result<AuthenticatedU
     Ok(AuthenticatedUser {
         user_id: record.user_id,
         role: record.role,
+        session_id: None,
     })
 }
 
@@ -73,6 +79,8 @@ impl<E: Endpoint> Endpoint for JwtOrApiKeyAuthImpl<E> {
         } else {
             authenticated_user_from_api_key(&req).await?
         };
+        ensure_account_enabled(&req, &user).await?;
+        ensure_session_active(&req, &user).await?;
 
         req.extensions_mut().insert(user);
         self.ep.call(req).await
@@ -1,8 +1,12 @@
-use std::env;
+use std::{env, sync::Arc};
 
 use poem::{http::StatusCode, Endpoint, Error, Middleware, Request};
+use tyange_cms_api::auth::account::is_account_disabled;
 use tyange_cms_api::auth::authorization::AuthenticatedUser;
 use tyange_cms_api::auth::jwt::Claims;
+use tyange_cms_api::auth::refresh_token::{is_session_active, touch_session};
+
+use crate::{models::AppState, routes::login::account_disabled_error};
 
 fn authenticated_user_from_jwt(req: &Request) -> Result<AuthenticatedUser, Error> {
     let token = req.headers().get("Authorization").ok_or_else(|| {
@@ -29,6 +33,7 @@ fn authenticated_user_from_jwt(req: &Request) -> Result<AuthenticatedUser, Error
         Ok(AuthenticatedUser {
             user_id: claims.claims.sub,
             role: claims.claims.role,
+            session_id: claims.claims.family_id,
         })
     } else {
         Err(Error::from_string(
@@ -42,6 +47,66 @@ pub fn require_jwt_user(req: &Request) -> Result<AuthenticatedUser, Error> {
     authenticated_user_from_jwt(req)
 }
 
+/// 미들웨어가 DB를 보려면 `AppState`가 있어야 한다. 없으면 확인을 건너뛰지 않고 `500`으로 막는다.
+pub fn request_state(req: &Request) -> Result<&Arc<AppState>, Error> {
+    req.data::<Arc<AppState>>().ok_or_else(|| {
+        eprintln!("Server configuration error: AppState is not attached to the route");
+        Error::from_string(
+            "Server configuration error.",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })
+}
+
+/// 관리자가 비활성화한 계정은 토큰이 아직 유효해도 막는다.
+pub async fn ensure_account_enabled(req: &Request, user: &AuthenticatedUser) -> Result<(), Error> {
+    let state = request_state(req)?;
+
+    let disabled = is_account_disabled(&state.db, &user.user_id)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("계정 상태 조회 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+    if disabled {
+        return Err(account_disabled_error());
+    }
+
+    Ok(())
+}
+
+/// 폐기된 세션(로그아웃, 다른 기기에서 끊음, 비밀번호 변경)의 access 토큰은 만료 전이어도 막는다.
+/// 세션 ID가 없는 토큰(API key 등)은 확인하지 않는다.
+pub async fn ensure_session_active(req: &Request, user: &AuthenticatedUser) -> Result<(), Error> {
+    let Some(session_id) = &user.session_id else {
+        return Ok(());
+    };
+    let state = request_state(req)?;
+
+    let session_error = |err: sqlx::Error| {
+        Error::from_string(
+            format!("세션 조회 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    };
+    let active = is_session_active(&state.db, &user.user_id, session_id)
+        .await
+        .map_err(session_error)?;
+    if !active {
+        return Err(Error::from_string(
+            "로그아웃된 세션입니다. 다시 로그인해 주세요.",
+            StatusCode::UNAUTHORIZED,
+        ));
+    }
+    touch_session(&state.db, session_id)
+        .await
+        .map_err(session_error)?;
+
+    Ok(())
+}
+
 pub struct Auth;
 
 impl<E: Endpoint> Middleware<E> for Auth {
@@ -61,6 +126,8 @@ impl<E: Endpoint> Endpoint for AuthImpl<E> {
 
     async fn call(&self, mut req: Request) -> Result<Self::Output, Error> {
         let user = authenticated_user_from_jwt(&req)?;
+        ensure_account_enabled(&req, &user).await?;
+        ensure_session_active(&req, &user).await?;
         req.extensions_mut().insert(user);
         self.ep.call(req).await
     }
@@ -1,26 +1,42 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::{FromRow, Pool, Sqlite};
 use std::collections::HashMap;
 
 use crate::blog_redeploy::BlogRedeployService;
+use crate::storage::{storage_from_env, SharedStorage};
 
 pub struct AppState {
     pub db: Pool<Sqlite>,
     pub blog_redeploy: BlogRedeployService,
+    pub storage: SharedStorage,
 }
 
 impl AppState {
+    /// `STORAGE_BACKEND` 설정이 잘못되었으면 서버를 띄우지 않는다.
     pub fn new(db: Pool<Sqlite>) -> Self {
         Self {
             db,
             blog_redeploy: BlogRedeployService::from_env(),
+            storage: storage_from_env().unwrap_or_else(|err| panic!("{}", err)),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn new_with_storage(db: Pool<Sqlite>, storage: SharedStorage) -> Self {
+        Self {
+            storage,
+            ..Self::new(db)
         }
     }
 
     #[cfg(test)]
     pub fn new_with_blog_redeploy(db: Pool<Sqlite>, blog_redeploy: BlogRedeployService) -> Self {
-        Self { db, blog_redeploy }
+        Self {
+            blog_redeploy,
+            ..Self::new(db)
+        }
     }
 }
 
@@ -40,9 +56,12 @@ pub struct Post {
     pub tags: Vec<TagWithCategory>,
     pub content: String,
     pub status: String,
+    /// 이 글을 가리키는 포트폴리오 프로젝트. `GET /post/:post_id`에서만 채운다.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub portfolio_projects: Option<Vec<PortfolioPostReference>>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct PostItem {
     pub post_id: String,
     pub title: String,
@@ -84,11 +103,92 @@ pub struct GoogleLoginRequest {
     pub id_token: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PasswordResetRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PasswordResetConfirmRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+/// `POST /refresh`, `POST /logout` 요청.
+#[derive(Debug, Deserialize)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct LoginResponse {
     pub access_token: String,
     pub refresh_token: String,
     pub user_role: String,
+    /// 관리자에게 2단계 인증이 필수인데 아직 등록하지 않았을 때만 `true`로 붙는다.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub two_factor_setup_required: bool,
+}
+
+/// 비밀번호는 맞았지만 2단계 인증이 남은 로그인. `two_factor_token`으로 `POST /login/2fa`를 부른다.
+#[derive(Debug, Serialize)]
+pub struct TwoFactorChallengeResponse {
+    pub two_factor_required: bool,
+    pub two_factor_token: String,
+    pub expires_at: String,
+}
+
+/// `POST /login`, `POST /login/google` 응답. 2단계 인증을 켠 계정은 토큰 대신 challenge를 받는다.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginOutcome {
+    Tokens(LoginResponse),
+    TwoFactor(TwoFactorChallengeResponse),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TwoFactorLoginRequest {
+    pub two_factor_token: String,
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+/// TOTP 코드나 복구 코드 중 하나를 받는 요청. 등록 확인에는 `code`만 쓴다.
+#[derive(Debug, Deserialize)]
+pub struct TwoFactorCodeRequest {
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TotpEnrollmentResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TwoFactorStatusResponse {
+    pub totp_enabled: bool,
+    pub confirmed_at: Option<String>,
+    pub recovery_codes_remaining: i64,
+    /// 관리자 2단계 인증 필수 설정이 이 계정에 걸려 있는지.
+    pub required: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SecuritySettings {
+    pub require_admin_totp: bool,
 }
 
 #[derive(Debug, Serialize, FromRow)]
@@ -98,6 +198,155 @@ pub struct MeResponse {
     pub display_name: Option<String>,
     pub avatar_url: Option<String>,
     pub bio: Option<String>,
+    /// 탈퇴를 예약했으면 실제로 지워지는 시각.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deletion_scheduled_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountDeletionResponse {
+    pub user_id: String,
+    pub deletion_requested_at: String,
+    pub deletion_scheduled_at: String,
+    /// 지워질 때 작성한 글을 처리하는 방식(`reassign`/`delete`).
+    pub post_policy: String,
+}
+
+/// `q`는 `user_id`와 `display_name`에서 대소문자 없이 찾는다. `status`는 `active`/`disabled`.
+#[derive(Debug, Deserialize)]
+pub struct AdminUserListQuery {
+    pub q: Option<String>,
+    pub role: Option<String>,
+    pub status: Option<String>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct AdminUserItem {
+    pub user_id: String,
+    pub user_role: String,
+    pub auth_provider: String,
+    pub display_name: Option<String>,
+    pub created_at: Option<String>,
+    pub last_login_at: Option<String>,
+    pub disabled_at: Option<String>,
+    pub disabled_reason: Option<String>,
+    pub deletion_scheduled_at: Option<String>,
+    pub totp_enabled: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminUserListResponse {
+    pub users: Vec<AdminUserItem>,
+    pub total_count: i64,
+}
+
+/// 로그인 한 번(refresh token family)이 세션 하나다.
+#[derive(Debug, Serialize, FromRow)]
+pub struct AdminSessionItem {
+    pub family_id: String,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub revoked_at: Option<String>,
+    pub revoked_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminUserDetailResponse {
+    #[serde(flatten)]
+    pub user: AdminUserItem,
+    pub api_keys: Vec<ApiKeyResponse>,
+    pub sessions: Vec<AdminSessionItem>,
+}
+
+/// 보내지 않은 필드는 그대로 둔다. `disabled: true`면 그 계정의 로그인 세션을 모두 폐기한다.
+#[derive(Debug, Deserialize)]
+pub struct UpdateAdminUserRequest {
+    pub user_role: Option<String>,
+    pub disabled: Option<bool>,
+    pub disabled_reason: Option<String>,
+}
+
+/// 로그인 실패 카운터 하나. `scope`는 `account`(입력한 아이디, 소문자) 또는 `ip`.
+#[derive(Debug, Serialize, FromRow)]
+pub struct LoginLockoutItem {
+    pub scope: String,
+    pub throttle_key: String,
+    pub failure_count: i64,
+    pub last_failed_at: String,
+    pub locked_until: Option<String>,
+    /// 지금 잠겨 있는지. `locked_until`이 지났으면 `false`다.
+    pub locked: bool,
+}
+
+/// `locked_only=true`면 지금 잠긴 것만 보여 준다.
+#[derive(Debug, Deserialize)]
+pub struct LoginLockoutListQuery {
+    pub scope: Option<String>,
+    pub locked_only: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoginLockoutListResponse {
+    pub lockouts: Vec<LoginLockoutItem>,
+}
+
+/// 로그인 감사 기록. `event`는 `failed`(비밀번호 틀림, 없는 계정), `blocked`(잠긴 동안의 시도),
+/// `cleared`(관리자가 잠금 해제) 중 하나다.
+#[derive(Debug, Serialize, FromRow)]
+pub struct LoginAuditEventItem {
+    pub id: i64,
+    pub event: String,
+    pub user_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub actor_id: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginAuditEventQuery {
+    pub user_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub event: Option<String>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoginAuditEventListResponse {
+    pub events: Vec<LoginAuditEventItem>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct DataExportRow {
+    pub export_id: String,
+    pub user_id: String,
+    pub status: String,
+    pub byte_size: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DataExportResponse {
+    pub export_id: String,
+    /// `pending`, `running`, `ready`, `failed`, `expired` 중 하나.
+    pub status: String,
+    pub byte_size: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+    pub expires_at: Option<String>,
+    /// `ready`일 때만 있다. 로그인 없이 받을 수 있고 `expires_at`이 지나면 더 쓸 수 없다.
+    pub download_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DataExportDownloadQuery {
+    pub signature: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -136,11 +385,258 @@ pub struct UpdatePostRequest {
 #[derive(Debug, Serialize)]
 pub struct DeletePostResponse {
     pub post_id: String,
+    /// 글이 지워져 더는 아무 글도 가리키지 않게 된 포트폴리오 프로젝트의 참조.
+    pub dangling_portfolio_references: Vec<PortfolioPostReference>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct UploadImageResponse {
+    pub image_id: String,
+    pub image_path: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub byte_size: u64,
+    pub dominant_color: Option<String>,
+    pub blurhash: Option<String>,
+    pub variants: Vec<ImageVariantResponse>,
+    pub srcset: Vec<ImageSrcsetResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchUploadImageItem {
+    pub origin_name: String,
+    pub status: bool,
+    pub status_code: u16,
+    pub data: Option<UploadImageResponse>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchUploadImageResponse {
+    pub uploaded_count: usize,
+    pub failed_count: usize,
+    pub results: Vec<BatchUploadImageItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateUploadSessionRequest {
+    pub file_name: String,
+    pub content_type: String,
+    pub total_size: u64,
+    pub post_id: Option<String>,
+    pub image_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UploadChunkQuery {
+    pub offset: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FinalizeUploadSessionRequest {
+    pub sha256: String,
+}
+
+#[derive(Debug, FromRow)]
+pub struct UploadSessionRow {
+    pub session_id: String,
+    pub user_id: String,
+    pub origin_name: String,
+    pub content_type: String,
+    pub total_size: i64,
+    pub received_size: i64,
+    pub post_id: Option<String>,
+    pub image_type: Option<String>,
+    pub status: String,
+    pub image_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UploadSessionResponse {
+    pub session_id: String,
+    pub origin_name: String,
+    pub content_type: String,
+    pub total_size: u64,
+    pub received_size: u64,
+    pub status: String,
+    pub image_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, FromRow)]
+pub struct AttachmentDb {
+    pub attachment_id: String,
+    pub post_id: Option<String>,
+    pub file_name: String,
+    pub origin_name: String,
+    pub mime_type: String,
+    pub kind: String,
+    pub byte_size: i64,
+    pub uploaded_by: String,
+    pub uploaded_at: String,
+    pub download_count: i64,
+    pub last_downloaded_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AttachmentResponse {
+    pub attachment_id: String,
+    pub post_id: Option<String>,
+    pub download_path: String,
+    pub origin_name: String,
+    pub mime_type: String,
+    pub kind: String,
+    pub byte_size: u64,
+    pub uploaded_by: String,
+    pub uploaded_at: String,
+    pub download_count: u64,
+    pub last_downloaded_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UploadAttachmentQuery {
+    pub post_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AttachmentListQuery {
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+    pub post_id: Option<String>,
+    pub kind: Option<String>,
+    pub uploaded_by: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AttachmentListResponse {
+    pub attachments: Vec<AttachmentResponse>,
+    pub total_count: i64,
+}
+
+/// `post_id`에 빈 문자열을 보내면 글과의 연결을 끊는다.
+#[derive(Debug, Deserialize)]
+pub struct UpdateAttachmentRequest {
+    pub post_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AttachmentDownloadQuery {
+    pub inline: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteAttachmentResponse {
+    pub attachment_id: String,
+}
+
+#[derive(Debug, FromRow)]
+pub struct ImageDetailDb {
+    pub image_id: String,
+    pub post_id: Option<String>,
+    pub file_name: String,
+    pub origin_name: String,
+    pub mime_type: String,
+    pub image_type: String,
+    pub uploaded_by: Option<String>,
+    pub uploaded_at: Option<String>,
+    pub alt_text: Option<String>,
+    pub caption: Option<String>,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub byte_size: Option<i64>,
+    pub dominant_color: Option<String>,
+    pub blurhash: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageItemResponse {
+    pub image_id: String,
+    pub post_id: Option<String>,
+    pub image_path: String,
+    pub origin_name: String,
+    pub mime_type: String,
+    pub image_type: String,
+    pub uploaded_by: Option<String>,
+    pub uploaded_at: Option<String>,
+    pub alt_text: Option<String>,
+    pub caption: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub byte_size: Option<u64>,
+    pub dominant_color: Option<String>,
+    pub blurhash: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageDetailResponse {
+    #[serde(flatten)]
+    pub image: ImageItemResponse,
+    pub variants: Vec<ImageVariantResponse>,
+    pub srcset: Vec<ImageSrcsetResponse>,
+    pub references: Vec<ImageReference>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageListQuery {
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+    pub post_id: Option<String>,
+    pub image_type: Option<String>,
+    pub uploaded_by: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageListResponse {
+    pub images: Vec<ImageItemResponse>,
+    pub total_count: i64,
+}
+
+/// `post_id`에 빈 문자열을 보내면 글과의 연결을 끊는다. 보내지 않은 필드는 그대로 둔다.
+#[derive(Debug, Deserialize)]
+pub struct UpdateImageRequest {
+    pub alt_text: Option<String>,
+    pub caption: Option<String>,
+    pub post_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageHashBackfillResponse {
+    pub dry_run: bool,
+    pub hashed_count: usize,
+    pub merged_image_ids: Vec<String>,
+    pub missing_image_ids: Vec<String>,
+    pub removed_files: Vec<String>,
+    pub freed_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteImageResponse {
+    pub image_id: String,
+    pub removed_files: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ImageVariantResponse {
     pub image_path: String,
+    pub width: u32,
+    pub height: u32,
+    pub format: String,
+    pub mime_type: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageSrcsetResponse {
+    pub mime_type: String,
+    pub srcset: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageVariantQuery {
+    pub w: Option<u32>,
+    pub format: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -149,6 +645,63 @@ pub struct UploadImageQueryParmas {
     pub image_type: Option<String>,
 }
 
+#[derive(Debug, Serialize, Clone)]
+pub struct ImageReference {
+    pub source_type: String,
+    pub source_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrphanedImageResponse {
+    pub image_id: String,
+    pub post_id: Option<String>,
+    pub file_name: String,
+    pub origin_name: String,
+    pub image_type: String,
+    pub uploaded_at: Option<String>,
+    pub file_exists: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MissingImageFileResponse {
+    pub image_id: String,
+    pub file_name: String,
+    pub references: Vec<ImageReference>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UntrackedImageFileResponse {
+    pub file_name: String,
+    pub byte_size: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageOrphanReportResponse {
+    pub min_age_hours: i64,
+    pub orphaned_images: Vec<OrphanedImageResponse>,
+    pub missing_files: Vec<MissingImageFileResponse>,
+    pub untracked_files: Vec<UntrackedImageFileResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageGcQuery {
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageHashBackfillQuery {
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageGcResponse {
+    pub dry_run: bool,
+    pub removed_image_ids: Vec<String>,
+    pub removed_files: Vec<String>,
+    pub freed_bytes: u64,
+    pub report: ImageOrphanReportResponse,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AddUserRequest {
     pub user_id: String,
@@ -162,23 +715,100 @@ pub struct SignupRequest {
     pub password: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// 기간 필드의 스키마. `portfolio-period`는 이 API가 정한 형식으로, `2020.01 - 2022.12`처럼 시작과 끝을
+/// ` - `, `~`, `–`로 잇고 끝은 `현재`/`Present`일 수 있다.
+fn period_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    let mut schema = gen.subschema_for::<String>().into_object();
+    schema.format = Some("portfolio-period".to_string());
+    schema.metadata().description = Some("예: 2020.01 - 2022.12, 2023.03 - 현재".to_string());
+    schema.into()
+}
+
+fn optional_period_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    let mut schema = gen.subschema_for::<Option<String>>().into_object();
+    schema.format = Some("portfolio-period".to_string());
+    schema.metadata().description = Some("예: 2020.01 - 2022.12, 2023.03 - 현재".to_string());
+    schema.into()
+}
+
+/// 비워 둘 수 있지만 채우면 `format`을 지켜야 하는 문자열의 스키마.
+fn optional_format_schema(
+    gen: &mut schemars::gen::SchemaGenerator,
+    format: &str,
+) -> schemars::schema::Schema {
+    let mut schema = gen.subschema_for::<String>().into_object();
+    let empty = schemars::schema::SchemaObject {
+        string: Some(Box::new(schemars::schema::StringValidation {
+            max_length: Some(0),
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+    let formatted = schemars::schema::SchemaObject {
+        format: Some(format.to_string()),
+        ..Default::default()
+    };
+    schema.subschemas().any_of = Some(vec![formatted.into(), empty.into()]);
+    schema.into()
+}
+
+/// 아이콘/GitHub 주소. 업로드 API가 돌려주는 `/images/...` 같은 사이트 기준 경로도 받는다.
+fn optional_url_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    optional_format_schema(gen, "portfolio-url")
+}
+
+fn optional_email_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    optional_format_schema(gen, "email")
+}
+
+/// 포트폴리오 slug의 스키마. 영문 소문자, 숫자, `-`만 쓴다.
+fn portfolio_slug_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    let mut schema = gen.subschema_for::<String>().into_object();
+    schema.format = Some("portfolio-slug".to_string());
+    schema.string().min_length = Some(1);
+    schema.string().max_length = Some(64);
+    schema.into()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PortfolioLink {
+    #[schemars(length(min = 1))]
     pub label: String,
+    #[schemars(url)]
     pub url: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PortfolioIdentity {
+    #[schemars(length(min = 1))]
     pub name: String,
+    #[schemars(schema_with = "optional_email_schema")]
     pub email: String,
+    #[schemars(schema_with = "optional_url_schema")]
     pub github_url: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// 프로젝트가 가리키는 블로그 글. `post_id`는 글 하나를, `tag`는 그 태그가 붙은 글 전부를 고른다.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, PartialEq)]
+#[serde(untagged)]
+pub enum PortfolioPostRef {
+    Post {
+        #[schemars(length(min = 1))]
+        post_id: String,
+    },
+    Tag {
+        #[schemars(length(min = 1))]
+        tag: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PortfolioProject {
+    #[schemars(length(min = 1))]
     pub slug: String,
+    #[schemars(length(min = 1))]
     pub title: String,
+    #[schemars(schema_with = "period_schema")]
     pub period: String,
     pub summary: String,
     #[serde(default)]
@@ -187,20 +817,26 @@ pub struct PortfolioProject {
     pub highlights: Vec<String>,
     #[serde(default)]
     pub links: Vec<PortfolioLink>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub posts: Vec<PortfolioPostRef>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PortfolioCareerItem {
+    #[schemars(length(min = 1))]
     pub title: String,
     #[serde(default)]
+    #[schemars(schema_with = "optional_period_schema")]
     pub period: Option<String>,
     #[serde(default)]
     pub bullets: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PortfolioCareerCompany {
+    #[schemars(length(min = 1))]
     pub company: String,
+    #[schemars(schema_with = "period_schema")]
     pub period: String,
     pub employment_type: String,
     pub role: String,
@@ -209,7 +845,7 @@ pub struct PortfolioCareerCompany {
     pub items: Vec<PortfolioCareerItem>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PortfolioCareerSection {
     pub summary_label: String,
     pub summary_value: String,
@@ -217,28 +853,35 @@ pub struct PortfolioCareerSection {
     pub companies: Vec<PortfolioCareerCompany>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PortfolioTechStackItem {
+    #[schemars(length(min = 1))]
     pub name: String,
+    #[schemars(schema_with = "optional_url_schema")]
     pub icon_url: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PortfolioIntroSection {
     pub content: String,
     #[serde(default)]
     pub tech_stack: Vec<PortfolioTechStackItem>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PortfolioMeta {
+    #[schemars(schema_with = "portfolio_slug_schema")]
     pub slug: String,
+    #[schemars(range(min = 1))]
     pub version: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// 포트폴리오 문서. `GET /portfolio/schema`의 JSON Schema가 이 구조에서 만들어진다.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PortfolioDocument {
+    #[schemars(schema_with = "portfolio_slug_schema")]
     pub slug: String,
+    #[schemars(range(min = 1))]
     pub version: i32,
     pub identity: PortfolioIdentity,
     #[serde(default)]
@@ -253,6 +896,7 @@ pub struct PortfolioDocument {
 pub struct PortfolioMasterRow {
     pub portfolio_id: i32,
     pub slug: String,
+    pub lang: String,
     pub created_at: String,
 }
 
@@ -270,14 +914,198 @@ pub struct PortfolioSectionRow {
 pub struct PortfolioResponse {
     pub portfolio_id: i32,
     pub slug: String,
+    pub lang: String,
     pub content: PortfolioDocument,
     pub created_at: String,
     pub updated_at: String,
+    /// `expand=posts`로 요청했을 때만 담는 프로젝트별 공개 글 요약.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_posts: Option<Vec<PortfolioProjectPosts>>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PortfolioProjectPosts {
+    pub project_slug: String,
+    pub posts: Vec<PostItem>,
 }
 
+/// 글과 포트폴리오 프로젝트의 연결. `matched_by`는 `post_id` 또는 `tag`이고,
+/// `pointer`는 문서 안에서 그 참조가 있는 위치다(예: `/featured_projects/0/posts/1`).
+#[derive(Debug, Serialize, Clone)]
+pub struct PortfolioPostReference {
+    pub portfolio_slug: String,
+    pub lang: String,
+    pub project_slug: String,
+    pub project_title: String,
+    pub matched_by: String,
+    pub pointer: String,
+}
+
+/// `expand=posts`면 프로젝트마다 연결된 공개 글을 함께 돌려준다.
+#[derive(Debug, Default, Deserialize)]
+pub struct PortfolioExpandQuery {
+    pub expand: Option<String>,
+}
+
+/// `content`는 먼저 스키마로 검사한 뒤 `PortfolioDocument`로 읽는다. 그래야 틀린 곳을 한 번에 모두 알려 줄 수 있다.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpdatePortfolioRequest {
+    pub content: Value,
+}
+
+/// 어느 포트폴리오를 다룰지 고르는 쿼리. 비우면 `dev`/`ko`다.
+#[derive(Debug, Default, Deserialize)]
+pub struct PortfolioSelectorQuery {
+    pub slug: Option<String>,
+    pub lang: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PortfolioSummary {
+    pub portfolio_id: i32,
+    pub slug: String,
+    pub lang: String,
+    pub created_at: String,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct PortfolioVersionRow {
+    pub version_number: i64,
+    pub slug: String,
+    pub lang: String,
+    pub content: String,
+    pub source: String,
+    pub created_at: String,
+}
+
+/// `source`는 스냅샷을 만든 경로다: `publish`. 초안 도입 전 기록에는 `update`, `section:<key>`, `restore:<번호>`도 있다.
+#[derive(Debug, Serialize)]
+pub struct PortfolioVersionSummary {
+    pub version_number: i64,
+    pub document_version: i32,
+    pub source: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortfolioVersionResponse {
+    pub version_number: i64,
+    pub slug: String,
+    pub lang: String,
+    pub source: String,
+    pub created_at: String,
+    pub content: PortfolioDocument,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PortfolioVersionDiffQuery {
+    pub from: i64,
+    pub to: i64,
+}
+
+/// `change`는 `added`, `removed`, `changed`, `unchanged` 중 하나다.
+#[derive(Debug, Serialize)]
+pub struct PortfolioSectionDiff {
+    pub section_key: String,
+    pub change: String,
+    pub changed_paths: Vec<String>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortfolioVersionDiffResponse {
+    pub from: i64,
+    pub to: i64,
+    pub sections: Vec<PortfolioSectionDiff>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestorePortfolioVersionResponse {
+    pub restored_from: i64,
+    pub draft: PortfolioDraftResponse,
+}
+
+#[derive(Debug, FromRow)]
+pub struct PortfolioDraftRow {
+    pub slug: String,
+    pub lang: String,
+    pub content: String,
+    pub updated_by: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortfolioDraftResponse {
+    pub slug: String,
+    pub lang: String,
+    pub content: PortfolioDocument,
+    pub updated_by: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// `source`는 미리보기에 쓴 문서다: 초안이 있으면 `draft`, 없으면 `live`.
+#[derive(Debug, Serialize)]
+pub struct PortfolioPreviewResponse {
+    pub slug: String,
+    pub lang: String,
+    pub source: String,
     pub content: PortfolioDocument,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PublishPortfolioResponse {
+    pub version_number: i64,
+    pub portfolio: PortfolioResponse,
+}
+
+/// `unmapped`는 JSON Resume 표준 필드로 옮기지 못해 `x-portfolio` 확장 필드에만 담긴 문서 위치(JSON Pointer)다.
+#[derive(Debug, Serialize)]
+pub struct JsonResumeExportResponse {
+    pub slug: String,
+    pub lang: String,
+    pub resume: Value,
+    pub unmapped: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JsonResumeImportQuery {
+    pub dry_run: Option<bool>,
+}
+
+/// `unmapped`는 문서에 옮길 곳이 없어 버린 이력서 위치(JSON Pointer)다.
+/// `dry_run`이면 변환 결과만 돌려주고 `draft`는 비어 있다.
+#[derive(Debug, Serialize)]
+pub struct JsonResumeImportResponse {
+    pub slug: String,
+    pub lang: String,
+    pub dry_run: bool,
+    pub unmapped: Vec<String>,
+    pub document: PortfolioDocument,
+    pub draft: Option<PortfolioDraftResponse>,
+}
+
+/// `pointer`는 문서 기준 JSON Pointer다(예: `/featured_projects/0/period`).
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct PortfolioValidationIssue {
+    pub pointer: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortfolioValidationResponse {
+    pub valid: bool,
+    pub errors: Vec<PortfolioValidationIssue>,
+}
+
+/// `section_key`를 주면 `content`를 문서 전체가 아니라 그 섹션으로 보고 검사한다.
+#[derive(Debug, Default, Deserialize)]
+pub struct PortfolioValidationQuery {
+    pub section_key: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -303,7 +1131,7 @@ pub struct TagsWithCategory {
     pub tags: Vec<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct TagWithCategory {
     pub tag: String,
     pub category: String,
@@ -494,6 +1322,146 @@ pub struct ApiKeyResponse {
     pub revoked_at: Option<String>,
 }
 
+/// `PublicKeyCredential.parseCreationOptionsFromJSON()`에 그대로 넣을 수 있는 등록 옵션.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyCreationOptions {
+    pub challenge: String,
+    pub rp: PasskeyRpEntity,
+    pub user: PasskeyUserEntity,
+    pub pub_key_cred_params: Vec<PasskeyCredentialParameter>,
+    pub timeout: u64,
+    pub attestation: String,
+    pub authenticator_selection: PasskeyAuthenticatorSelection,
+    pub exclude_credentials: Vec<PasskeyCredentialDescriptor>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PasskeyRpEntity {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyUserEntity {
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PasskeyCredentialParameter {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub alg: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyAuthenticatorSelection {
+    pub resident_key: String,
+    pub user_verification: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PasskeyCredentialDescriptor {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub id: String,
+}
+
+/// `PublicKeyCredential.parseRequestOptionsFromJSON()`에 그대로 넣을 수 있는 로그인 옵션.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyRequestOptions {
+    pub challenge: String,
+    pub rp_id: String,
+    pub timeout: u64,
+    pub user_verification: String,
+    pub allow_credentials: Vec<PasskeyCredentialDescriptor>,
+}
+
+/// `navigator.credentials.create()` 결과의 `toJSON()`.
+#[derive(Debug, Deserialize)]
+pub struct PasskeyAttestationCredential {
+    pub id: String,
+    pub response: PasskeyAttestationResponse,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PasskeyAttestationResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    #[serde(rename = "attestationObject")]
+    pub attestation_object: String,
+}
+
+/// `navigator.credentials.get()` 결과의 `toJSON()`.
+#[derive(Debug, Deserialize)]
+pub struct PasskeyAssertionCredential {
+    pub id: String,
+    pub response: PasskeyAssertionResponse,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PasskeyAssertionResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    #[serde(rename = "authenticatorData")]
+    pub authenticator_data: String,
+    pub signature: String,
+    #[serde(default, rename = "userHandle")]
+    pub user_handle: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePasskeyRequest {
+    pub name: Option<String>,
+    pub credential: PasskeyAttestationCredential,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PasskeyLoginRequest {
+    pub credential: PasskeyAssertionCredential,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePasskeyRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PasskeyResponse {
+    pub id: i64,
+    pub name: String,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub revoked_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PasskeyListResponse {
+    pub passkeys: Vec<PasskeyResponse>,
+}
+
+/// 로그인 세션 하나. `id`는 `DELETE /me/sessions/:session_id`에 쓴다.
+#[derive(Debug, Serialize, FromRow)]
+pub struct SessionItem {
+    pub id: String,
+    pub created_at: String,
+    pub last_seen_at: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    /// 이 요청을 보낸 세션인지.
+    pub current: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionListResponse {
+    pub sessions: Vec<SessionItem>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ApiKeyListResponse {
     pub api_keys: Vec<ApiKeyResponse>,
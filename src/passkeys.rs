@@ -0,0 +1,454 @@
+//! 패스키(WebAuthn) 등록과 비밀번호 없는 로그인.
+//!
+//! challenge는 ceremony마다 `webauthn_challenges`에 5분 동안 두고 확인할 때 지워서 한 번만 쓴다.
+//! 로그인은 discoverable credential(`residentKey: "required"`)을 전제로 해서 `allowCredentials`를 비워 보낸다.
+
+use std::env;
+
+use chrono::{Duration, Utc};
+use poem::{http::StatusCode, Error};
+use sqlx::{query, query_as, query_scalar, FromRow, SqlitePool};
+use tyange_cms_api::auth::webauthn::{
+    client_data_challenge, decode_base64url, encode_base64url, verify_assertion,
+    verify_registration, RelyingParty, WebauthnError, COSE_ALG_ES256,
+};
+use uuid::Uuid;
+
+use crate::models::{
+    CreatePasskeyRequest, PasskeyAssertionCredential, PasskeyAuthenticatorSelection,
+    PasskeyCreationOptions, PasskeyCredentialDescriptor, PasskeyCredentialParameter,
+    PasskeyRequestOptions, PasskeyResponse, PasskeyRpEntity, PasskeyUserEntity,
+};
+
+const CHALLENGE_TTL_MINUTES: i64 = 5;
+const CEREMONY_TIMEOUT_MS: u64 = 5 * 60 * 1000;
+const PURPOSE_REGISTRATION: &str = "registration";
+const PURPOSE_AUTHENTICATION: &str = "authentication";
+const DEFAULT_PASSKEY_NAME: &str = "패스키";
+const MAX_PASSKEY_NAME_CHARS: usize = 100;
+const DEFAULT_RP_ID: &str = "localhost";
+const DEFAULT_RP_NAME: &str = "tyange-cms";
+const DEFAULT_ORIGIN: &str = "http://localhost:3000";
+
+const PASSKEY_COLUMNS: &str = "passkey_id AS id, name, created_at, last_used_at, revoked_at";
+
+#[derive(FromRow)]
+struct StoredPasskey {
+    passkey_id: i64,
+    user_id: String,
+    public_key: Vec<u8>,
+    sign_count: i64,
+    webauthn_user_handle: Option<String>,
+}
+
+fn internal_error(message: String) -> Error {
+    Error::from_string(message, StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn timestamp(at: chrono::DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn env_or(key: &str, default: &str) -> String {
+    env::var(key)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| default.to_string())
+}
+
+fn random_base64url() -> String {
+    let mut bytes = Vec::with_capacity(32);
+    bytes.extend_from_slice(Uuid::new_v4().as_bytes());
+    bytes.extend_from_slice(Uuid::new_v4().as_bytes());
+    encode_base64url(&bytes)
+}
+
+fn registration_error(err: WebauthnError) -> Error {
+    Error::from_string(err.to_string(), StatusCode::BAD_REQUEST)
+}
+
+fn login_failed() -> Error {
+    Error::from_string("패스키로 로그인할 수 없습니다.", StatusCode::UNAUTHORIZED)
+}
+
+fn challenge_expired(status: StatusCode) -> Error {
+    Error::from_string(
+        "패스키 요청 시간이 지났습니다. 처음부터 다시 시도해 주세요.",
+        status,
+    )
+}
+
+/// `WEBAUTHN_RP_ID`(기본 `localhost`), `WEBAUTHN_RP_NAME`(기본 `tyange-cms`),
+/// `WEBAUTHN_ORIGINS`(쉼표로 구분, 기본 `http://localhost:3000`).
+pub fn relying_party() -> RelyingParty {
+    let origins = env_or("WEBAUTHN_ORIGINS", DEFAULT_ORIGIN)
+        .split(',')
+        .map(|origin| origin.trim().trim_end_matches('/').to_string())
+        .filter(|origin| !origin.is_empty())
+        .collect();
+
+    RelyingParty {
+        id: env_or("WEBAUTHN_RP_ID", DEFAULT_RP_ID),
+        name: env_or("WEBAUTHN_RP_NAME", DEFAULT_RP_NAME),
+        origins,
+    }
+}
+
+/// 새 challenge를 저장한다. 만료된 challenge는 이때 정리한다.
+async fn create_challenge(
+    db: &SqlitePool,
+    purpose: &str,
+    user_id: Option<&str>,
+) -> Result<String, Error> {
+    let now = Utc::now();
+    let challenge = random_base64url();
+
+    query("DELETE FROM webauthn_challenges WHERE expires_at <= ?")
+        .bind(timestamp(now))
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("패스키 challenge 정리 실패: {}", err)))?;
+    query(
+        r#"
+        INSERT INTO webauthn_challenges (challenge, purpose, user_id, created_at, expires_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&challenge)
+    .bind(purpose)
+    .bind(user_id)
+    .bind(timestamp(now))
+    .bind(timestamp(now + Duration::minutes(CHALLENGE_TTL_MINUTES)))
+    .execute(db)
+    .await
+    .map_err(|err| internal_error(format!("패스키 challenge 저장 실패: {}", err)))?;
+
+    Ok(challenge)
+}
+
+/// challenge를 지우면서 꺼낸다. 없거나 만료됐으면 `None`, 있으면 만든 사용자(로그인용은 `None`).
+async fn consume_challenge(
+    db: &SqlitePool,
+    challenge: &str,
+    purpose: &str,
+) -> Result<Option<Option<String>>, Error> {
+    query_scalar(
+        r#"
+        DELETE FROM webauthn_challenges
+        WHERE challenge = ? AND purpose = ? AND expires_at > ?
+        RETURNING user_id
+        "#,
+    )
+    .bind(challenge)
+    .bind(purpose)
+    .bind(timestamp(Utc::now()))
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("패스키 challenge 확인 실패: {}", err)))
+}
+
+/// 인증기에 넘기는 `user.id`. 이메일이 인증기에 남지 않도록 처음 쓸 때 임의 값을 만든다.
+async fn user_handle(db: &SqlitePool, user_id: &str) -> Result<String, Error> {
+    query(
+        "UPDATE users SET webauthn_user_handle = ? WHERE user_id = ? AND webauthn_user_handle IS NULL",
+    )
+    .bind(random_base64url())
+    .bind(user_id)
+    .execute(db)
+    .await
+    .map_err(|err| internal_error(format!("패스키 사용자 식별자 저장 실패: {}", err)))?;
+
+    query_scalar("SELECT webauthn_user_handle FROM users WHERE user_id = ?")
+        .bind(user_id)
+        .fetch_optional(db)
+        .await
+        .map_err(|err| internal_error(format!("사용자 정보 조회 실패: {}", err)))?
+        .ok_or_else(|| Error::from_string("사용자를 찾을 수 없습니다.", StatusCode::NOT_FOUND))
+}
+
+/// 이름이 너무 길면 오류 문구를 돌려준다. 비어 있으면 기본 이름을 쓴다.
+fn normalize_name(name: Option<&str>) -> Result<String, String> {
+    let name = name.map(str::trim).filter(|name| !name.is_empty());
+    let Some(name) = name else {
+        return Ok(DEFAULT_PASSKEY_NAME.to_string());
+    };
+    if name.chars().count() > MAX_PASSKEY_NAME_CHARS {
+        return Err(format!(
+            "패스키 이름은 {}자 이하여야 합니다.",
+            MAX_PASSKEY_NAME_CHARS
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn invalid_name(message: String) -> Error {
+    Error::from_string(message, StatusCode::BAD_REQUEST)
+}
+
+/// 등록 옵션. 이미 등록한 패스키는 `excludeCredentials`로 보내 같은 인증기에 중복으로 만들지 않게 한다.
+pub async fn registration_options(
+    db: &SqlitePool,
+    user_id: &str,
+) -> Result<PasskeyCreationOptions, Error> {
+    let rp = relying_party();
+    let handle = user_handle(db, user_id).await?;
+    let existing: Vec<String> = query_scalar(
+        "SELECT credential_id FROM passkey_credentials WHERE user_id = ? AND revoked_at IS NULL",
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+    .map_err(|err| internal_error(format!("패스키 조회 실패: {}", err)))?;
+    let challenge = create_challenge(db, PURPOSE_REGISTRATION, Some(user_id)).await?;
+
+    Ok(PasskeyCreationOptions {
+        challenge,
+        rp: PasskeyRpEntity {
+            id: rp.id,
+            name: rp.name,
+        },
+        user: PasskeyUserEntity {
+            id: handle,
+            name: user_id.to_string(),
+            display_name: user_id.to_string(),
+        },
+        pub_key_cred_params: vec![PasskeyCredentialParameter {
+            kind: String::from("public-key"),
+            alg: COSE_ALG_ES256,
+        }],
+        timeout: CEREMONY_TIMEOUT_MS,
+        attestation: String::from("none"),
+        authenticator_selection: PasskeyAuthenticatorSelection {
+            resident_key: String::from("required"),
+            user_verification: String::from("required"),
+        },
+        exclude_credentials: existing
+            .into_iter()
+            .map(|id| PasskeyCredentialDescriptor {
+                kind: String::from("public-key"),
+                id,
+            })
+            .collect(),
+    })
+}
+
+/// 등록 응답을 확인하고 패스키를 저장한다. 검증에 실패하면 `400`.
+pub async fn register_passkey(
+    db: &SqlitePool,
+    user_id: &str,
+    payload: &CreatePasskeyRequest,
+) -> Result<PasskeyResponse, Error> {
+    let name = normalize_name(payload.name.as_deref()).map_err(invalid_name)?;
+    let response = &payload.credential.response;
+    let client_data_json =
+        decode_base64url(&response.client_data_json).map_err(registration_error)?;
+    let attestation_object =
+        decode_base64url(&response.attestation_object).map_err(registration_error)?;
+    let challenge = client_data_challenge(&client_data_json).map_err(registration_error)?;
+
+    let owner = consume_challenge(db, &challenge, PURPOSE_REGISTRATION).await?;
+    if owner.flatten().as_deref() != Some(user_id) {
+        return Err(challenge_expired(StatusCode::BAD_REQUEST));
+    }
+
+    let credential = verify_registration(
+        &relying_party(),
+        &challenge,
+        &client_data_json,
+        &attestation_object,
+    )
+    .map_err(registration_error)?;
+    let credential_id = encode_base64url(&credential.credential_id);
+    if credential_id != payload.credential.id.trim_end_matches('=') {
+        return Err(registration_error(WebauthnError::Encoding));
+    }
+
+    let passkey_id = query_scalar(
+        r#"
+        INSERT INTO passkey_credentials (user_id, credential_id, public_key, sign_count, name, created_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(credential_id) DO NOTHING
+        RETURNING passkey_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(&credential_id)
+    .bind(&credential.public_key)
+    .bind(i64::from(credential.sign_count))
+    .bind(&name)
+    .bind(timestamp(Utc::now()))
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("패스키 저장 실패: {}", err)))?
+    .ok_or_else(|| Error::from_string("이미 등록된 패스키입니다.", StatusCode::CONFLICT))?;
+
+    find_passkey(db, user_id, passkey_id)
+        .await?
+        .ok_or_else(|| internal_error(String::from("저장한 패스키를 찾을 수 없습니다.")))
+}
+
+/// 로그인 옵션. 사용자를 모르는 상태라 `allowCredentials`는 비워 두고 인증기가 패스키를 고르게 한다.
+pub async fn authentication_options(db: &SqlitePool) -> Result<PasskeyRequestOptions, Error> {
+    let challenge = create_challenge(db, PURPOSE_AUTHENTICATION, None).await?;
+
+    Ok(PasskeyRequestOptions {
+        challenge,
+        rp_id: relying_party().id,
+        timeout: CEREMONY_TIMEOUT_MS,
+        user_verification: String::from("required"),
+        allow_credentials: Vec::new(),
+    })
+}
+
+/// 로그인 응답을 확인하고 패스키 주인의 `user_id`를 돌려준다.
+/// 어느 단계에서 실패했는지 드러내지 않도록 실패는 모두 같은 `401`이다.
+pub async fn authenticate_passkey(
+    db: &SqlitePool,
+    credential: &PasskeyAssertionCredential,
+) -> Result<String, Error> {
+    let response = &credential.response;
+    let decoded = (
+        decode_base64url(&response.client_data_json),
+        decode_base64url(&response.authenticator_data),
+        decode_base64url(&response.signature),
+    );
+    let (Ok(client_data_json), Ok(authenticator_data), Ok(signature)) = decoded else {
+        return Err(login_failed());
+    };
+    let challenge = client_data_challenge(&client_data_json).map_err(|_| login_failed())?;
+    if consume_challenge(db, &challenge, PURPOSE_AUTHENTICATION)
+        .await?
+        .is_none()
+    {
+        return Err(challenge_expired(StatusCode::UNAUTHORIZED));
+    }
+
+    let stored: Option<StoredPasskey> = query_as(
+        r#"
+        SELECT p.passkey_id, p.user_id, p.public_key, p.sign_count, u.webauthn_user_handle
+        FROM passkey_credentials p
+        JOIN users u ON u.user_id = p.user_id
+        WHERE p.credential_id = ? AND p.revoked_at IS NULL
+        "#,
+    )
+    .bind(credential.id.trim_end_matches('='))
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("패스키 조회 실패: {}", err)))?;
+    let Some(stored) = stored else {
+        return Err(login_failed());
+    };
+    if let Some(claimed) = response.user_handle.as_deref() {
+        if Some(claimed.trim_end_matches('=')) != stored.webauthn_user_handle.as_deref() {
+            return Err(login_failed());
+        }
+    }
+
+    let new_sign_count = verify_assertion(
+        &relying_party(),
+        &challenge,
+        &client_data_json,
+        &authenticator_data,
+        &signature,
+        &stored.public_key,
+        u32::try_from(stored.sign_count).unwrap_or(u32::MAX),
+    )
+    .map_err(|_| login_failed())?;
+
+    // 같은 응답이 동시에 두 번 들어와도 횟수가 늘어난 쪽 하나만 통과한다.
+    let result = query(
+        r#"
+        UPDATE passkey_credentials
+        SET sign_count = ?, last_used_at = ?
+        WHERE passkey_id = ? AND revoked_at IS NULL AND (sign_count < ? OR ? = 0)
+        "#,
+    )
+    .bind(i64::from(new_sign_count))
+    .bind(timestamp(Utc::now()))
+    .bind(stored.passkey_id)
+    .bind(i64::from(new_sign_count))
+    .bind(i64::from(new_sign_count))
+    .execute(db)
+    .await
+    .map_err(|err| internal_error(format!("패스키 사용 기록 실패: {}", err)))?;
+    if result.rows_affected() == 0 {
+        return Err(login_failed());
+    }
+
+    Ok(stored.user_id)
+}
+
+async fn find_passkey(
+    db: &SqlitePool,
+    user_id: &str,
+    passkey_id: i64,
+) -> Result<Option<PasskeyResponse>, Error> {
+    query_as(&format!(
+        "SELECT {} FROM passkey_credentials WHERE passkey_id = ? AND user_id = ?",
+        PASSKEY_COLUMNS
+    ))
+    .bind(passkey_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("패스키 조회 실패: {}", err)))
+}
+
+/// 폐기한 패스키도 기록으로 함께 보여 준다.
+pub async fn list_passkeys(db: &SqlitePool, user_id: &str) -> Result<Vec<PasskeyResponse>, Error> {
+    query_as(&format!(
+        "SELECT {} FROM passkey_credentials WHERE user_id = ? ORDER BY passkey_id DESC",
+        PASSKEY_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+    .map_err(|err| internal_error(format!("패스키 목록 조회 실패: {}", err)))
+}
+
+pub async fn rename_passkey(
+    db: &SqlitePool,
+    user_id: &str,
+    passkey_id: i64,
+    name: &str,
+) -> Result<PasskeyResponse, Error> {
+    let name = normalize_name(Some(name)).map_err(invalid_name)?;
+    query("UPDATE passkey_credentials SET name = ? WHERE passkey_id = ? AND user_id = ?")
+        .bind(&name)
+        .bind(passkey_id)
+        .bind(user_id)
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("패스키 이름 변경 실패: {}", err)))?;
+
+    find_passkey(db, user_id, passkey_id)
+        .await?
+        .ok_or_else(passkey_not_found)
+}
+
+/// 패스키를 폐기한다. 이미 폐기했으면 처음 폐기한 시각을 그대로 둔다.
+pub async fn revoke_passkey(db: &SqlitePool, user_id: &str, passkey_id: i64) -> Result<(), Error> {
+    let result = query(
+        r#"
+        UPDATE passkey_credentials
+        SET revoked_at = COALESCE(revoked_at, ?)
+        WHERE passkey_id = ? AND user_id = ?
+        "#,
+    )
+    .bind(timestamp(Utc::now()))
+    .bind(passkey_id)
+    .bind(user_id)
+    .execute(db)
+    .await
+    .map_err(|err| internal_error(format!("패스키 폐기 실패: {}", err)))?;
+    if result.rows_affected() == 0 {
+        return Err(passkey_not_found());
+    }
+
+    Ok(())
+}
+
+fn passkey_not_found() -> Error {
+    Error::from_string("해당 패스키를 찾을 수 없습니다.", StatusCode::NOT_FOUND)
+}
@@ -0,0 +1,200 @@
+//! 비밀번호 변경과 메일 기반 재설정.
+//!
+//! 재설정 토큰은 메일로만 보내고 DB에는 SHA-256만 남긴다. 토큰은 한 번 쓰면 끝나고,
+//! 새로 요청하면 이전에 보낸 토큰은 더 쓸 수 없다.
+
+use std::env;
+
+use bcrypt::{hash, DEFAULT_COST};
+use chrono::{Duration, Utc};
+use poem::{http::StatusCode, Error};
+use sha2::{Digest, Sha256};
+use sqlx::{query, query_scalar, SqlitePool};
+use tyange_cms_api::auth::refresh_token::{
+    discard_unused_refresh_tokens, revoke_other_refresh_families, revoke_user_refresh_families,
+};
+use uuid::Uuid;
+
+use crate::mail_outbox::enqueue_email;
+
+const DEFAULT_RESET_TTL_MINUTES: i64 = 30;
+
+fn internal_error(message: String) -> Error {
+    Error::from_string(message, StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn timestamp(at: chrono::DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn reset_token_hash(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// `PASSWORD_RESET_TTL_MINUTES`(기본 30분).
+pub fn reset_ttl_minutes() -> i64 {
+    env::var("PASSWORD_RESET_TTL_MINUTES")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_RESET_TTL_MINUTES)
+}
+
+fn reset_email_body(token: &str, ttl_minutes: i64) -> String {
+    let action = match env::var("PASSWORD_RESET_URL")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    {
+        Some(url) => {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            format!(
+                "아래 링크에서 새 비밀번호를 설정해 주세요.\n\n{}{}token={}",
+                url, separator, token
+            )
+        }
+        None => format!(
+            "아래 재설정 코드를 입력해 새 비밀번호를 설정해 주세요.\n\n{}",
+            token
+        ),
+    };
+
+    format!(
+        "비밀번호 재설정을 요청하셨습니다.\n\n{}\n\n이 요청은 {}분 뒤에 만료되며 한 번만 쓸 수 있습니다. 요청하지 않으셨다면 이 메일을 무시해 주세요.\n",
+        action, ttl_minutes
+    )
+}
+
+/// 재설정 토큰을 만들어 메일을 outbox에 넣는다. 그 사용자의 아직 쓰지 않은 토큰은 무효로 만든다.
+pub async fn issue_password_reset(db: &SqlitePool, user_id: &str) -> Result<(), Error> {
+    let now = Utc::now();
+    let ttl_minutes = reset_ttl_minutes();
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|err| internal_error(format!("트랜잭션 시작 실패: {}", err)))?;
+
+    query("UPDATE password_reset_tokens SET used_at = ? WHERE user_id = ? AND used_at IS NULL")
+        .bind(timestamp(now))
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| internal_error(format!("이전 재설정 토큰 정리 실패: {}", err)))?;
+
+    query(
+        r#"
+        INSERT INTO password_reset_tokens (token_hash, user_id, expires_at)
+        VALUES (?, ?, ?)
+        "#,
+    )
+    .bind(reset_token_hash(&token))
+    .bind(user_id)
+    .bind(timestamp(now + Duration::minutes(ttl_minutes)))
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| internal_error(format!("재설정 토큰 저장 실패: {}", err)))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| internal_error(format!("트랜잭션 커밋 실패: {}", err)))?;
+
+    enqueue_email(
+        db,
+        "password_reset",
+        user_id,
+        "[tyange] 비밀번호 재설정",
+        &reset_email_body(&token, ttl_minutes),
+    )
+    .await
+    .map_err(|err| internal_error(format!("재설정 메일 등록 실패: {}", err)))?;
+
+    Ok(())
+}
+
+/// 아직 쓰지 않았고 만료되지 않은 토큰의 사용자. 토큰을 소모하지는 않는다.
+pub async fn find_password_reset_user(
+    db: &SqlitePool,
+    token: &str,
+) -> Result<Option<String>, Error> {
+    query_scalar(
+        r#"
+        SELECT user_id FROM password_reset_tokens
+        WHERE token_hash = ? AND used_at IS NULL AND expires_at > ?
+        "#,
+    )
+    .bind(reset_token_hash(token))
+    .bind(timestamp(Utc::now()))
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("재설정 토큰 조회 실패: {}", err)))
+}
+
+/// 토큰을 사용 처리한다. 동시에 들어온 요청 중 하나만 `true`를 받는다.
+pub async fn consume_password_reset(db: &SqlitePool, token: &str) -> Result<bool, Error> {
+    let result = query(
+        r#"
+        UPDATE password_reset_tokens
+        SET used_at = ?
+        WHERE token_hash = ? AND used_at IS NULL AND expires_at > ?
+        "#,
+    )
+    .bind(timestamp(Utc::now()))
+    .bind(reset_token_hash(token))
+    .bind(timestamp(Utc::now()))
+    .execute(db)
+    .await
+    .map_err(|err| internal_error(format!("재설정 토큰 사용 처리 실패: {}", err)))?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// 새 비밀번호를 bcrypt로 저장하고 그 사용자의 refresh token family를 모두 폐기한다.
+/// 정책 검사는 호출하는 쪽에서 먼저 한다.
+pub async fn replace_user_password(
+    db: &SqlitePool,
+    user_id: &str,
+    new_password: &str,
+    keep_session_id: Option<&str>,
+    reason: &str,
+) -> Result<(), Error> {
+    let hashed_password = hash(new_password, DEFAULT_COST)
+        .map_err(|err| internal_error(format!("Password hashing failed: {}", err)))?;
+
+    query(
+        r#"
+        UPDATE users
+        SET password = ?, auth_provider = COALESCE(NULLIF(auth_provider, ''), 'local')
+        WHERE user_id = ?
+        "#,
+    )
+    .bind(hashed_password)
+    .bind(user_id)
+    .execute(db)
+    .await
+    .map_err(|err| internal_error(format!("비밀번호 저장 실패: {}", err)))?;
+
+    let session_error =
+        |err: sqlx::Error| internal_error(format!("로그인 세션 폐기 실패: {}", err));
+    match keep_session_id {
+        // 비밀번호를 바꾼 세션은 남기되, 그 세션에서 이미 받은 refresh 토큰은 더 쓰지 못하게 한다.
+        Some(session_id) => {
+            revoke_other_refresh_families(db, user_id, session_id, reason)
+                .await
+                .map_err(session_error)?;
+            discard_unused_refresh_tokens(db, session_id)
+                .await
+                .map_err(session_error)?;
+        }
+        None => {
+            revoke_user_refresh_families(db, user_id, reason)
+                .await
+                .map_err(session_error)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,812 @@
+use std::collections::BTreeSet;
+
+use poem::web::Json;
+use poem::{http::StatusCode, Error, IntoResponse};
+use serde_json::{Map, Value};
+use sqlx::{query, query_as, query_scalar, Sqlite, SqlitePool};
+
+use crate::models::{
+    CustomResponse, PortfolioCareerSection, PortfolioDocument, PortfolioDraftResponse,
+    PortfolioDraftRow, PortfolioIdentity, PortfolioIntroSection, PortfolioMasterRow, PortfolioMeta,
+    PortfolioProject, PortfolioResponse, PortfolioSectionDiff, PortfolioSectionRow,
+    PortfolioSelectorQuery, PortfolioSummary, PortfolioValidationIssue,
+    PortfolioValidationResponse, PortfolioVersionRow, PortfolioVersionSummary,
+};
+use crate::portfolio_render::invalidate_portfolio_renders;
+use crate::portfolio_schema::{check_portfolio_section, ensure_valid_document};
+
+pub const DEFAULT_PORTFOLIO_SLUG: &str = "dev";
+pub const DEFAULT_PORTFOLIO_LANG: &str = "ko";
+
+/// 포트폴리오마다 둘 수 있는 언어.
+pub const PORTFOLIO_LANGS: [&str; 2] = ["ko", "en"];
+
+const MAX_PORTFOLIO_SLUG_LEN: usize = 64;
+
+/// 문서를 이루는 섹션 순서. 비교 결과도 이 순서로 돌려준다.
+pub const PORTFOLIO_SECTION_KEYS: [&str; 5] =
+    ["meta", "identity", "featured_projects", "career", "intro"];
+
+fn internal_error(message: String) -> Error {
+    Error::from_string(message, StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// 섹션 JSON을 읽거나 만들다 난 오류. 핸들러에서는 `?`로 `poem::Error`가 된다.
+#[derive(Debug)]
+pub enum PortfolioContentError {
+    /// 저장된 JSON을 해석하지 못했다.
+    Corrupt(String),
+    /// 요청한 문서를 섹션 JSON으로 만들지 못했다.
+    Invalid(String),
+    /// 스키마 검증에 걸린 곳들. 응답 본문에 JSON Pointer와 함께 모두 담는다.
+    Rejected(Vec<PortfolioValidationIssue>),
+}
+
+impl From<PortfolioContentError> for Error {
+    fn from(err: PortfolioContentError) -> Self {
+        match err {
+            PortfolioContentError::Corrupt(message) => internal_error(message),
+            PortfolioContentError::Invalid(message) => {
+                Error::from_string(message, StatusCode::BAD_REQUEST)
+            }
+            PortfolioContentError::Rejected(errors) => {
+                let body = CustomResponse {
+                    status: false,
+                    message: Some(format!(
+                        "포트폴리오 문서 검증에 실패했습니다 ({}건).",
+                        errors.len()
+                    )),
+                    data: Some(PortfolioValidationResponse {
+                        valid: false,
+                        errors,
+                    }),
+                };
+                Error::from_response(
+                    Json(body)
+                        .with_status(StatusCode::UNPROCESSABLE_ENTITY)
+                        .into_response(),
+                )
+            }
+        }
+    }
+}
+
+pub fn is_valid_portfolio_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && slug.len() <= MAX_PORTFOLIO_SLUG_LEN
+        && slug
+            .bytes()
+            .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-')
+}
+
+/// 포트폴리오 하나를 가리키는 slug와 언어. 공개 문서, 초안, 버전 기록이 모두 이 단위로 나뉜다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortfolioKey {
+    pub slug: String,
+    pub lang: String,
+}
+
+impl PortfolioKey {
+    /// 요청의 `slug`/`lang`을 검사한다. `slug`가 비어 있으면 `fallback_slug`(문서의 slug 등), 그다음 `dev`를 쓴다.
+    pub fn resolve(
+        selector: &PortfolioSelectorQuery,
+        fallback_slug: Option<&str>,
+    ) -> Result<Self, PortfolioContentError> {
+        let slug = [selector.slug.as_deref(), fallback_slug]
+            .into_iter()
+            .flatten()
+            .map(str::trim)
+            .find(|slug| !slug.is_empty())
+            .unwrap_or(DEFAULT_PORTFOLIO_SLUG);
+        if !is_valid_portfolio_slug(slug) {
+            return Err(PortfolioContentError::Invalid(format!(
+                "slug는 {}자 이하의 영문 소문자, 숫자, '-'만 쓸 수 있습니다: {}",
+                MAX_PORTFOLIO_SLUG_LEN, slug
+            )));
+        }
+
+        let lang = selector
+            .lang
+            .as_deref()
+            .map(str::trim)
+            .filter(|lang| !lang.is_empty())
+            .unwrap_or(DEFAULT_PORTFOLIO_LANG);
+        if !PORTFOLIO_LANGS.contains(&lang) {
+            return Err(PortfolioContentError::Invalid(format!(
+                "지원하지 않는 언어입니다: {} (사용 가능: {})",
+                lang,
+                PORTFOLIO_LANGS.join(", ")
+            )));
+        }
+
+        Ok(Self {
+            slug: slug.to_string(),
+            lang: lang.to_string(),
+        })
+    }
+}
+
+fn parse_section<T: serde::de::DeserializeOwned>(
+    section: &PortfolioSectionRow,
+) -> Result<T, PortfolioContentError> {
+    serde_json::from_str(&section.content).map_err(|err| {
+        PortfolioContentError::Corrupt(format!(
+            "Error parsing {} section: {}",
+            section.section_key, err
+        ))
+    })
+}
+
+/// 섹션 행을 모아 문서로 되돌린다. 포트폴리오나 섹션이 없으면 `None`이다.
+pub async fn load_portfolio(
+    db: &SqlitePool,
+    key: &PortfolioKey,
+) -> Result<Option<PortfolioResponse>, Error> {
+    let master = query_as::<Sqlite, PortfolioMasterRow>(
+        "SELECT portfolio_id, slug, lang, created_at FROM portfolio WHERE slug = ? AND lang = ?",
+    )
+    .bind(&key.slug)
+    .bind(&key.lang)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("Error fetching portfolio: {}", err)))?;
+
+    let Some(master) = master else {
+        return Ok(None);
+    };
+
+    let sections = query_as::<Sqlite, PortfolioSectionRow>(
+        r#"
+        SELECT section_id, portfolio_id, section_key, content, created_at, updated_at
+        FROM portfolio_section
+        WHERE portfolio_id = ?
+        "#,
+    )
+    .bind(master.portfolio_id)
+    .fetch_all(db)
+    .await
+    .map_err(|err| internal_error(format!("Error fetching portfolio sections: {}", err)))?;
+
+    if sections.is_empty() {
+        return Ok(None);
+    }
+
+    let mut meta: Option<PortfolioMeta> = None;
+    let mut identity: Option<PortfolioIdentity> = None;
+    let mut featured_projects: Vec<PortfolioProject> = Vec::new();
+    let mut career: Option<PortfolioCareerSection> = None;
+    let mut intro: Option<PortfolioIntroSection> = None;
+    let mut latest_updated_at = String::new();
+
+    for section in &sections {
+        if section.updated_at > latest_updated_at {
+            latest_updated_at = section.updated_at.clone();
+        }
+
+        match section.section_key.as_str() {
+            "meta" => meta = Some(parse_section(section)?),
+            "identity" => identity = Some(parse_section(section)?),
+            "featured_projects" => featured_projects = parse_section(section)?,
+            "career" => career = Some(parse_section(section)?),
+            "intro" => intro = Some(parse_section(section)?),
+            _ => {}
+        }
+    }
+
+    let meta = meta
+        .ok_or_else(|| internal_error(String::from("포트폴리오 meta 섹션을 찾지 못했습니다.")))?;
+    let identity = identity.ok_or_else(|| {
+        internal_error(String::from("포트폴리오 identity 섹션을 찾지 못했습니다."))
+    })?;
+
+    Ok(Some(PortfolioResponse {
+        portfolio_id: master.portfolio_id,
+        slug: master.slug,
+        lang: master.lang,
+        content: PortfolioDocument {
+            slug: meta.slug,
+            version: meta.version,
+            identity,
+            featured_projects,
+            career,
+            intro,
+        },
+        created_at: master.created_at,
+        updated_at: latest_updated_at,
+        project_posts: None,
+    }))
+}
+
+/// 문서의 섹션별 JSON. 값이 없는 선택 섹션(`career`, `intro`)은 빠진다.
+pub fn document_sections(
+    document: &PortfolioDocument,
+) -> Result<Vec<(&'static str, Value)>, PortfolioContentError> {
+    let to_value = |section_key: &str, value: Result<Value, serde_json::Error>| {
+        value.map_err(|err| {
+            PortfolioContentError::Invalid(format!("{} 직렬화 실패: {}", section_key, err))
+        })
+    };
+
+    let meta = PortfolioMeta {
+        slug: document.slug.clone(),
+        version: document.version,
+    };
+    let mut sections = vec![
+        ("meta", to_value("meta", serde_json::to_value(&meta))?),
+        (
+            "identity",
+            to_value("identity", serde_json::to_value(&document.identity))?,
+        ),
+        (
+            "featured_projects",
+            to_value(
+                "featured_projects",
+                serde_json::to_value(&document.featured_projects),
+            )?,
+        ),
+    ];
+    if let Some(career) = &document.career {
+        sections.push(("career", to_value("career", serde_json::to_value(career))?));
+    }
+    if let Some(intro) = &document.intro {
+        sections.push(("intro", to_value("intro", serde_json::to_value(intro))?));
+    }
+
+    Ok(sections)
+}
+
+pub async fn upsert_section(
+    db: &SqlitePool,
+    portfolio_id: i32,
+    section_key: &str,
+    content: &str,
+) -> Result<(), sqlx::Error> {
+    query(
+        r#"
+        INSERT INTO portfolio_section (portfolio_id, section_key, content, created_at, updated_at)
+        VALUES (?, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+        ON CONFLICT(portfolio_id, section_key) DO UPDATE SET
+            content = excluded.content,
+            updated_at = CURRENT_TIMESTAMP
+        "#,
+    )
+    .bind(portfolio_id)
+    .bind(section_key)
+    .bind(content)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// 문서 전체를 `key`의 섹션 행으로 저장한다. 문서에 없는 선택 섹션은 지운다.
+/// 문서의 `slug`는 `key`에 맞춘다.
+pub async fn save_portfolio_document(
+    db: &SqlitePool,
+    key: &PortfolioKey,
+    mut document: PortfolioDocument,
+) -> Result<PortfolioResponse, Error> {
+    document.slug = key.slug.clone();
+    ensure_valid_document(&document)?;
+
+    let portfolio_id: i32 = query_scalar(
+        r#"
+        INSERT INTO portfolio (slug, lang, created_at)
+        VALUES (?, ?, CURRENT_TIMESTAMP)
+        ON CONFLICT(slug, lang) DO UPDATE SET slug = excluded.slug
+        RETURNING portfolio_id
+        "#,
+    )
+    .bind(&key.slug)
+    .bind(&key.lang)
+    .fetch_one(db)
+    .await
+    .map_err(|err| internal_error(format!("포트폴리오 마스터 행 생성 실패: {}", err)))?;
+
+    let sections = document_sections(&document)?;
+    for (section_key, value) in &sections {
+        upsert_section(db, portfolio_id, section_key, &value.to_string())
+            .await
+            .map_err(|err| internal_error(format!("{} 섹션 저장 실패: {}", section_key, err)))?;
+    }
+
+    for section_key in PORTFOLIO_SECTION_KEYS {
+        if sections.iter().any(|(key, _)| *key == section_key) {
+            continue;
+        }
+        query("DELETE FROM portfolio_section WHERE portfolio_id = ? AND section_key = ?")
+            .bind(portfolio_id)
+            .bind(section_key)
+            .execute(db)
+            .await
+            .map_err(|err| internal_error(format!("{} 섹션 삭제 실패: {}", section_key, err)))?;
+    }
+
+    let updated_at: String =
+        query_scalar("SELECT MAX(updated_at) FROM portfolio_section WHERE portfolio_id = ?")
+            .bind(portfolio_id)
+            .fetch_one(db)
+            .await
+            .map_err(|err| internal_error(format!("updated_at 조회 실패: {}", err)))?;
+
+    let created_at: String =
+        query_scalar("SELECT created_at FROM portfolio WHERE portfolio_id = ?")
+            .bind(portfolio_id)
+            .fetch_one(db)
+            .await
+            .map_err(|err| internal_error(format!("created_at 조회 실패: {}", err)))?;
+
+    Ok(PortfolioResponse {
+        portfolio_id,
+        slug: key.slug.clone(),
+        lang: key.lang.clone(),
+        content: document,
+        created_at,
+        updated_at,
+        project_posts: None,
+    })
+}
+
+/// 공개 문서가 있는 포트폴리오 목록. `selector`의 `slug`/`lang`은 주어진 것만 거르는 데 쓴다.
+pub async fn list_portfolios(
+    db: &SqlitePool,
+    selector: &PortfolioSelectorQuery,
+) -> Result<Vec<PortfolioSummary>, Error> {
+    let slug = selector
+        .slug
+        .as_deref()
+        .map(str::trim)
+        .filter(|slug| !slug.is_empty());
+    let lang = selector
+        .lang
+        .as_deref()
+        .map(str::trim)
+        .filter(|lang| !lang.is_empty());
+
+    query_as::<Sqlite, PortfolioSummary>(
+        r#"
+        SELECT p.portfolio_id, p.slug, p.lang, p.created_at, MAX(s.updated_at) AS updated_at
+        FROM portfolio p
+        JOIN portfolio_section s ON s.portfolio_id = p.portfolio_id
+        WHERE (? IS NULL OR p.slug = ?) AND (? IS NULL OR p.lang = ?)
+        GROUP BY p.portfolio_id
+        ORDER BY p.slug, p.lang
+        "#,
+    )
+    .bind(slug)
+    .bind(slug)
+    .bind(lang)
+    .bind(lang)
+    .fetch_all(db)
+    .await
+    .map_err(|err| internal_error(format!("포트폴리오 목록 조회 실패: {}", err)))
+}
+
+/// `key`의 공개 문서(마스터 행과 섹션)를 지운다. 초안과 버전 기록은 남긴다. 지운 문서가 있었으면 `true`다.
+pub async fn remove_portfolio(db: &SqlitePool, key: &PortfolioKey) -> Result<bool, Error> {
+    let portfolio_id: Option<i32> =
+        query_scalar("SELECT portfolio_id FROM portfolio WHERE slug = ? AND lang = ?")
+            .bind(&key.slug)
+            .bind(&key.lang)
+            .fetch_optional(db)
+            .await
+            .map_err(|err| internal_error(format!("포트폴리오 조회 실패: {}", err)))?;
+
+    let Some(portfolio_id) = portfolio_id else {
+        return Ok(false);
+    };
+
+    query("DELETE FROM portfolio_section WHERE portfolio_id = ?")
+        .bind(portfolio_id)
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("포트폴리오 섹션 삭제 실패: {}", err)))?;
+
+    query("DELETE FROM portfolio WHERE portfolio_id = ?")
+        .bind(portfolio_id)
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("포트폴리오 삭제 실패: {}", err)))?;
+
+    invalidate_portfolio_renders(db, key).await?;
+
+    Ok(true)
+}
+
+/// 저장이 끝난 문서를 바꿀 수 없는 스냅샷으로 남긴다. 번호는 slug와 언어마다 1부터 올라간다.
+/// 포트폴리오를 지워도 스냅샷은 남아 있어 다시 되돌릴 수 있다.
+pub async fn record_portfolio_version(
+    db: &SqlitePool,
+    key: &PortfolioKey,
+    document: &PortfolioDocument,
+    source: &str,
+) -> Result<i64, Error> {
+    let content = serde_json::to_string(document)
+        .map_err(|err| internal_error(format!("포트폴리오 스냅샷 직렬화 실패: {}", err)))?;
+
+    let version_number = query_scalar(
+        r#"
+        INSERT INTO portfolio_version (slug, lang, version_number, content, source)
+        SELECT ?, ?, COALESCE(MAX(version_number), 0) + 1, ?, ?
+        FROM portfolio_version
+        WHERE slug = ? AND lang = ?
+        RETURNING version_number
+        "#,
+    )
+    .bind(&key.slug)
+    .bind(&key.lang)
+    .bind(&content)
+    .bind(source)
+    .bind(&key.slug)
+    .bind(&key.lang)
+    .fetch_one(db)
+    .await
+    .map_err(|err| internal_error(format!("포트폴리오 스냅샷 저장 실패: {}", err)))?;
+
+    invalidate_portfolio_renders(db, key).await?;
+
+    Ok(version_number)
+}
+
+pub async fn list_portfolio_versions(
+    db: &SqlitePool,
+    key: &PortfolioKey,
+) -> Result<Vec<PortfolioVersionSummary>, Error> {
+    let rows = query_as::<Sqlite, PortfolioVersionRow>(
+        r#"
+        SELECT version_number, slug, lang, content, source, created_at
+        FROM portfolio_version
+        WHERE slug = ? AND lang = ?
+        ORDER BY version_number DESC
+        "#,
+    )
+    .bind(&key.slug)
+    .bind(&key.lang)
+    .fetch_all(db)
+    .await
+    .map_err(|err| internal_error(format!("포트폴리오 버전 목록 조회 실패: {}", err)))?;
+
+    let mut versions = Vec::with_capacity(rows.len());
+    for row in rows {
+        let document = version_document(&row)?;
+        versions.push(PortfolioVersionSummary {
+            version_number: row.version_number,
+            document_version: document.version,
+            source: row.source,
+            created_at: row.created_at,
+        });
+    }
+
+    Ok(versions)
+}
+
+pub async fn fetch_portfolio_version(
+    db: &SqlitePool,
+    key: &PortfolioKey,
+    version_number: i64,
+) -> Result<PortfolioVersionRow, Error> {
+    query_as::<Sqlite, PortfolioVersionRow>(
+        r#"
+        SELECT version_number, slug, lang, content, source, created_at
+        FROM portfolio_version
+        WHERE slug = ? AND lang = ? AND version_number = ?
+        "#,
+    )
+    .bind(&key.slug)
+    .bind(&key.lang)
+    .bind(version_number)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("포트폴리오 버전 조회 실패: {}", err)))?
+    .ok_or_else(|| {
+        Error::from_string(
+            format!("포트폴리오 {}번 버전을 찾지 못했습니다.", version_number),
+            StatusCode::NOT_FOUND,
+        )
+    })
+}
+
+pub fn version_document(
+    row: &PortfolioVersionRow,
+) -> Result<PortfolioDocument, PortfolioContentError> {
+    serde_json::from_str(&row.content).map_err(|err| {
+        PortfolioContentError::Corrupt(format!(
+            "포트폴리오 {}번 버전 해석 실패: {}",
+            row.version_number, err
+        ))
+    })
+}
+
+fn draft_response(row: PortfolioDraftRow) -> Result<PortfolioDraftResponse, PortfolioContentError> {
+    let content = serde_json::from_str(&row.content).map_err(|err| {
+        PortfolioContentError::Corrupt(format!("포트폴리오 초안 해석 실패: {}", err))
+    })?;
+
+    Ok(PortfolioDraftResponse {
+        slug: row.slug,
+        lang: row.lang,
+        content,
+        updated_by: row.updated_by,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+    })
+}
+
+/// `key`의 초안을 읽는다. 아직 편집을 시작하지 않았으면 `None`이다.
+pub async fn load_portfolio_draft(
+    db: &SqlitePool,
+    key: &PortfolioKey,
+) -> Result<Option<PortfolioDraftResponse>, Error> {
+    let row = query_as::<Sqlite, PortfolioDraftRow>(
+        r#"
+        SELECT slug, lang, content, updated_by, created_at, updated_at
+        FROM portfolio_draft
+        WHERE slug = ? AND lang = ?
+        "#,
+    )
+    .bind(&key.slug)
+    .bind(&key.lang)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("포트폴리오 초안 조회 실패: {}", err)))?;
+
+    match row {
+        Some(row) => Ok(Some(draft_response(row)?)),
+        None => Ok(None),
+    }
+}
+
+/// 초안을 통째로 덮어쓴다. 공개 문서와 버전 기록은 건드리지 않는다.
+pub async fn save_portfolio_draft(
+    db: &SqlitePool,
+    key: &PortfolioKey,
+    mut document: PortfolioDocument,
+    updated_by: &str,
+) -> Result<PortfolioDraftResponse, Error> {
+    document.slug = key.slug.clone();
+    // 검증에 걸리거나 섹션 JSON으로 바꿀 수 없는 문서는 발행할 때가 아니라 지금 거절한다.
+    ensure_valid_document(&document)?;
+    document_sections(&document)?;
+
+    let content = serde_json::to_string(&document)
+        .map_err(|err| internal_error(format!("포트폴리오 초안 직렬화 실패: {}", err)))?;
+
+    let row = query_as::<Sqlite, PortfolioDraftRow>(
+        r#"
+        INSERT INTO portfolio_draft (slug, lang, content, updated_by, created_at, updated_at)
+        VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+        ON CONFLICT(slug, lang) DO UPDATE SET
+            content = excluded.content,
+            updated_by = excluded.updated_by,
+            updated_at = CURRENT_TIMESTAMP
+        RETURNING slug, lang, content, updated_by, created_at, updated_at
+        "#,
+    )
+    .bind(&key.slug)
+    .bind(&key.lang)
+    .bind(&content)
+    .bind(updated_by)
+    .fetch_one(db)
+    .await
+    .map_err(|err| internal_error(format!("포트폴리오 초안 저장 실패: {}", err)))?;
+
+    Ok(draft_response(row)?)
+}
+
+/// 초안을 버린다. 지운 초안이 있었으면 `true`다.
+pub async fn discard_portfolio_draft(db: &SqlitePool, key: &PortfolioKey) -> Result<bool, Error> {
+    let result = query("DELETE FROM portfolio_draft WHERE slug = ? AND lang = ?")
+        .bind(&key.slug)
+        .bind(&key.lang)
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("포트폴리오 초안 삭제 실패: {}", err)))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 문서의 한 섹션을 바꾼 새 문서를 만든다. `meta`는 `slug`와 `version`을 덮어쓴다.
+pub fn apply_section(
+    document: &PortfolioDocument,
+    section_key: &str,
+    content: Value,
+) -> Result<PortfolioDocument, PortfolioContentError> {
+    check_portfolio_section(section_key, &content)?;
+
+    let value = serde_json::to_value(document)
+        .map_err(|err| PortfolioContentError::Invalid(err.to_string()))?;
+    let Value::Object(mut object) = value else {
+        return Err(PortfolioContentError::Invalid(
+            "포트폴리오 문서가 객체가 아닙니다.".to_string(),
+        ));
+    };
+
+    match section_key {
+        "meta" => {
+            let meta: PortfolioMeta = serde_json::from_value(content).map_err(|err| {
+                PortfolioContentError::Invalid(format!("meta 검증 실패: {}", err))
+            })?;
+            object.insert("slug".to_string(), Value::String(meta.slug));
+            object.insert("version".to_string(), Value::from(meta.version));
+        }
+        "identity" | "featured_projects" | "career" | "intro" => {
+            object.insert(section_key.to_string(), content);
+        }
+        _ => {
+            return Err(PortfolioContentError::Invalid(format!(
+                "알 수 없는 섹션: {}",
+                section_key
+            )))
+        }
+    }
+
+    serde_json::from_value(Value::Object(object)).map_err(|err| {
+        PortfolioContentError::Invalid(format!("{} 검증 실패: {}", section_key, err))
+    })
+}
+
+/// 기간 문자열(`2020.01 - 2022.12`, `2021-03 ~ 현재` 등)을 ISO 형식의 시작일과 끝일로 나눈다.
+/// 끝이 비었거나 `현재`/`Present`면 끝일은 `None`이다. 읽을 수 없는 형식이면 `None`.
+pub fn parse_period(period: &str) -> Option<(String, Option<String>)> {
+    let (start, end) = ["~", " - ", "–", "—"]
+        .iter()
+        .find_map(|separator| period.split_once(separator))
+        .unwrap_or((period, ""));
+    let start = parse_date(start)?;
+    let end = end.trim();
+    if end.is_empty() || end == "현재" || end.eq_ignore_ascii_case("present") {
+        return Some((start, None));
+    }
+
+    Some((start, Some(parse_date(end)?)))
+}
+
+fn parse_date(value: &str) -> Option<String> {
+    let parts: Vec<&str> = value.trim().split(['.', '-']).collect();
+    let valid =
+        |part: &&str, len: usize| part.len() == len && part.bytes().all(|b| b.is_ascii_digit());
+    match parts.as_slice() {
+        [year] if valid(year, 4) => Some(year.to_string()),
+        [year, month] if valid(year, 4) && valid(month, 2) => Some(format!("{}-{}", year, month)),
+        [year, month, day] if valid(year, 4) && valid(month, 2) && valid(day, 2) => {
+            Some(format!("{}-{}-{}", year, month, day))
+        }
+        _ => None,
+    }
+}
+
+/// 두 문서를 섹션 단위로 비교한다. 바뀐 섹션은 달라진 값의 JSON Pointer(섹션 기준)를 함께 담는다.
+pub fn diff_portfolio_documents(
+    before: &PortfolioDocument,
+    after: &PortfolioDocument,
+) -> Result<Vec<PortfolioSectionDiff>, PortfolioContentError> {
+    let before_sections = document_sections(before)?;
+    let after_sections = document_sections(after)?;
+    let find = |sections: &[(&'static str, Value)], section_key: &str| {
+        sections
+            .iter()
+            .find(|(key, _)| *key == section_key)
+            .map(|(_, value)| value.clone())
+    };
+
+    let mut diffs = Vec::new();
+    for section_key in PORTFOLIO_SECTION_KEYS {
+        let before_value = find(&before_sections, section_key);
+        let after_value = find(&after_sections, section_key);
+
+        let (change, changed_paths) = match (&before_value, &after_value) {
+            (None, None) => continue,
+            (None, Some(_)) => ("added", Vec::new()),
+            (Some(_), None) => ("removed", Vec::new()),
+            (Some(before_value), Some(after_value)) => {
+                let mut paths = Vec::new();
+                collect_changed_paths(before_value, after_value, String::new(), &mut paths);
+                if paths.is_empty() {
+                    ("unchanged", paths)
+                } else {
+                    ("changed", paths)
+                }
+            }
+        };
+
+        let unchanged = change == "unchanged";
+        diffs.push(PortfolioSectionDiff {
+            section_key: section_key.to_string(),
+            change: change.to_string(),
+            changed_paths,
+            before: if unchanged { None } else { before_value },
+            after: if unchanged { None } else { after_value },
+        });
+    }
+
+    Ok(diffs)
+}
+
+fn collect_changed_paths(before: &Value, after: &Value, path: String, out: &mut Vec<String>) {
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+            for key in keys {
+                collect_changed_paths(
+                    object_field(before, key),
+                    object_field(after, key),
+                    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1")),
+                    out,
+                );
+            }
+        }
+        (Value::Array(before), Value::Array(after)) => {
+            for index in 0..before.len().max(after.len()) {
+                collect_changed_paths(
+                    before.get(index).unwrap_or(&Value::Null),
+                    after.get(index).unwrap_or(&Value::Null),
+                    format!("{}/{}", path, index),
+                    out,
+                );
+            }
+        }
+        _ if before != after => out.push(if path.is_empty() {
+            String::from("/")
+        } else {
+            path
+        }),
+        _ => {}
+    }
+}
+
+fn object_field<'a>(object: &'a Map<String, Value>, key: &str) -> &'a Value {
+    object.get(key).unwrap_or(&Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::diff_portfolio_documents;
+    use crate::models::PortfolioDocument;
+
+    fn document(value: serde_json::Value) -> PortfolioDocument {
+        serde_json::from_value(value).expect("invalid document")
+    }
+
+    #[test]
+    fn diff_reports_changed_paths_per_section() {
+        let before = document(json!({
+            "slug": "dev",
+            "version": 1,
+            "identity": { "name": "TYANGE", "email": "a@example.com", "github_url": "https://github.com/tyange" },
+            "featured_projects": [
+                { "slug": "cms", "title": "CMS", "period": "2024", "summary": "before", "stack": ["rust"] }
+            ],
+            "intro": { "content": "hello" }
+        }));
+        let after = document(json!({
+            "slug": "dev",
+            "version": 2,
+            "identity": { "name": "TYANGE", "email": "a@example.com", "github_url": "https://github.com/tyange" },
+            "featured_projects": [
+                { "slug": "cms", "title": "CMS", "period": "2024", "summary": "after", "stack": ["rust", "poem"] }
+            ],
+            "career": { "summary_label": "경력", "summary_value": "4년" }
+        }));
+
+        let diffs = diff_portfolio_documents(&before, &after).unwrap();
+        let changes: Vec<(&str, &str)> = diffs
+            .iter()
+            .map(|diff| (diff.section_key.as_str(), diff.change.as_str()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("meta", "changed"),
+                ("identity", "unchanged"),
+                ("featured_projects", "changed"),
+                ("career", "added"),
+                ("intro", "removed"),
+            ]
+        );
+        assert_eq!(diffs[0].changed_paths, vec!["/version"]);
+        assert_eq!(diffs[2].changed_paths, vec!["/0/stack/1", "/0/summary"]);
+        assert!(diffs[1].before.is_none());
+        assert_eq!(diffs[4].after, None);
+    }
+}
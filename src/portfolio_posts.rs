@@ -0,0 +1,214 @@
+//! 포트폴리오 프로젝트와 블로그 글의 연결.
+//!
+//! 프로젝트의 `posts`에는 `{ "post_id": ... }` 또는 `{ "tag": ... }`를 적는다. 연결은 공개 문서의
+//! `featured_projects` 섹션에만 있고 따로 테이블을 두지 않으므로, 글 쪽에서 볼 때는 모든 포트폴리오를 훑는다.
+
+use poem::{http::StatusCode, Error};
+use sqlx::{query_as, query_scalar, QueryBuilder, Row, Sqlite, SqlitePool};
+
+use crate::models::{
+    PortfolioDocument, PortfolioPostRef, PortfolioPostReference, PortfolioProject,
+    PortfolioProjectPosts, PostItem,
+};
+use crate::utils::parse_tags;
+
+fn internal_error(message: String) -> Error {
+    Error::from_string(message, StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// 프로젝트마다 연결된 공개(`published`) 글을 최신순으로 모은다. 참조가 없는 프로젝트는 빈 목록이다.
+pub async fn expand_project_posts(
+    db: &SqlitePool,
+    document: &PortfolioDocument,
+) -> Result<Vec<PortfolioProjectPosts>, Error> {
+    let mut expanded = Vec::with_capacity(document.featured_projects.len());
+    for project in &document.featured_projects {
+        expanded.push(PortfolioProjectPosts {
+            project_slug: project.slug.clone(),
+            posts: published_posts_for(db, project).await?,
+        });
+    }
+    Ok(expanded)
+}
+
+async fn published_posts_for(
+    db: &SqlitePool,
+    project: &PortfolioProject,
+) -> Result<Vec<PostItem>, Error> {
+    let (post_ids, tags) = split_refs(&project.posts);
+    if post_ids.is_empty() && tags.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut builder = QueryBuilder::<Sqlite>::new(
+        r#"
+        SELECT p.post_id, p.title, p.description, p.published_at, p.status,
+        IFNULL(GROUP_CONCAT(t.category || '::' || t.name, ','), '') AS tags
+        FROM posts p
+        LEFT JOIN post_tags pt ON p.post_id = pt.post_id
+        LEFT JOIN tags t ON pt.tag_id = t.tag_id
+        WHERE p.status = 'published' AND (0
+        "#,
+    );
+    if !post_ids.is_empty() {
+        builder.push(" OR p.post_id IN (");
+        let mut separated = builder.separated(", ");
+        for post_id in &post_ids {
+            separated.push_bind(*post_id);
+        }
+        builder.push(")");
+    }
+    if !tags.is_empty() {
+        builder.push(
+            " OR p.post_id IN (SELECT tpt.post_id FROM post_tags tpt JOIN tags tt ON tt.tag_id = tpt.tag_id WHERE tt.name IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for tag in &tags {
+            separated.push_bind(*tag);
+        }
+        builder.push("))");
+    }
+    builder.push(") GROUP BY p.post_id ORDER BY p.published_at DESC, p.created_at DESC");
+
+    let rows = builder
+        .build()
+        .fetch_all(db)
+        .await
+        .map_err(|err| internal_error(format!("프로젝트 글 조회 실패: {}", err)))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| PostItem {
+            post_id: row.get("post_id"),
+            title: row.get("title"),
+            description: row.get("description"),
+            published_at: row.get("published_at"),
+            tags: parse_tags(row.get("tags")),
+            status: row.get("status"),
+        })
+        .collect())
+}
+
+fn split_refs(refs: &[PortfolioPostRef]) -> (Vec<&str>, Vec<&str>) {
+    let mut post_ids = Vec::new();
+    let mut tags = Vec::new();
+    for reference in refs {
+        match reference {
+            PortfolioPostRef::Post { post_id } => post_ids.push(post_id.as_str()),
+            PortfolioPostRef::Tag { tag } => tags.push(tag.as_str()),
+        }
+    }
+    (post_ids, tags)
+}
+
+#[derive(sqlx::FromRow)]
+struct ProjectSectionRow {
+    slug: String,
+    lang: String,
+    content: String,
+}
+
+/// 공개 문서의 프로젝트 참조를 훑어 `matches`를 만족하는 것을 돌려준다.
+/// 읽을 수 없는 섹션은 건너뛴다. 글 조회가 포트폴리오 데이터 때문에 실패하면 안 되기 때문이다.
+async fn find_references<F>(
+    db: &SqlitePool,
+    matches: F,
+) -> Result<Vec<PortfolioPostReference>, Error>
+where
+    F: Fn(&PortfolioPostRef) -> bool,
+{
+    let rows = query_as::<Sqlite, ProjectSectionRow>(
+        r#"
+        SELECT p.slug, p.lang, s.content
+        FROM portfolio p
+        JOIN portfolio_section s ON s.portfolio_id = p.portfolio_id
+        WHERE s.section_key = 'featured_projects'
+        ORDER BY p.slug, p.lang
+        "#,
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|err| internal_error(format!("포트폴리오 프로젝트 조회 실패: {}", err)))?;
+
+    let mut references = Vec::new();
+    for row in rows {
+        let projects: Vec<PortfolioProject> = match serde_json::from_str(&row.content) {
+            Ok(projects) => projects,
+            Err(err) => {
+                eprintln!(
+                    "포트폴리오 프로젝트 섹션을 읽지 못해 건너뜀 ({}/{}): {}",
+                    row.slug, row.lang, err
+                );
+                continue;
+            }
+        };
+
+        for (project_index, project) in projects.iter().enumerate() {
+            for (ref_index, reference) in project.posts.iter().enumerate() {
+                if !matches(reference) {
+                    continue;
+                }
+                references.push(PortfolioPostReference {
+                    portfolio_slug: row.slug.clone(),
+                    lang: row.lang.clone(),
+                    project_slug: project.slug.clone(),
+                    project_title: project.title.clone(),
+                    matched_by: match reference {
+                        PortfolioPostRef::Post { .. } => "post_id".to_string(),
+                        PortfolioPostRef::Tag { .. } => "tag".to_string(),
+                    },
+                    pointer: format!("/featured_projects/{}/posts/{}", project_index, ref_index),
+                });
+            }
+        }
+    }
+
+    Ok(references)
+}
+
+/// 글 하나가 속한 프로젝트. 글 ID로 직접 가리키거나 글의 태그 중 하나로 가리키는 참조를 모두 담는다.
+pub async fn projects_for_post(
+    db: &SqlitePool,
+    post_id: &str,
+    tags: &[String],
+) -> Result<Vec<PortfolioPostReference>, Error> {
+    find_references(db, |reference| match reference {
+        PortfolioPostRef::Post { post_id: target } => target == post_id,
+        PortfolioPostRef::Tag { tag } => tags.contains(tag),
+    })
+    .await
+}
+
+/// 글을 지운 뒤 더는 아무 글도 가리키지 않는 참조. 지운 글의 ID를 가리키는 참조와,
+/// 지운 글에 붙어 있던 태그 중 이제 그 태그를 단 글이 하나도 없는 태그 참조다.
+pub async fn dangling_references_after_delete(
+    db: &SqlitePool,
+    post_id: &str,
+    tags: &[String],
+) -> Result<Vec<PortfolioPostReference>, Error> {
+    let mut orphaned_tags = Vec::new();
+    for tag in tags {
+        let remaining: i64 = query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM post_tags pt
+            JOIN tags t ON t.tag_id = pt.tag_id
+            JOIN posts p ON p.post_id = pt.post_id
+            WHERE t.name = ?
+            "#,
+        )
+        .bind(tag)
+        .fetch_one(db)
+        .await
+        .map_err(|err| internal_error(format!("태그 글 수 조회 실패: {}", err)))?;
+        if remaining == 0 {
+            orphaned_tags.push(tag.clone());
+        }
+    }
+
+    find_references(db, |reference| match reference {
+        PortfolioPostRef::Post { post_id: target } => target == post_id,
+        PortfolioPostRef::Tag { tag } => orphaned_tags.contains(tag),
+    })
+    .await
+}
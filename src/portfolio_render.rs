@@ -0,0 +1,1295 @@
+//! 포트폴리오 문서를 제출용 PDF와 단독 HTML로 그린다.
+//!
+//! 헤드리스 브라우저 없이 `pdf-writer`로 A4 페이지를 직접 배치한다. `PORTFOLIO_PDF_FONT_PATH`에
+//! TrueType 글꼴을 지정하면 쓰인 글자만 잘라(subset) 넣으므로 한글도 그대로 나온다. 지정하지 않으면
+//! PDF 기본 글꼴(Helvetica)을 쓰는데, 문서에 Latin-1 밖의 글자가 있으면 `?`로 찍는 대신 설정 오류를 낸다.
+//!
+//! 결과물은 `portfolio_render_cache`에 포트폴리오 버전 번호와 함께 저장하고, 새 버전이 기록되거나
+//! 포트폴리오가 지워지면 비운다.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use pdf_writer::types::{ActionType, AnnotationType, CidFontType, FontFlags, SystemInfo};
+use pdf_writer::types::{TextRenderingMode, UnicodeCmap};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use poem::http::{header, StatusCode};
+use poem::{Error, Request, Response};
+use sqlx::{query, query_scalar, SqlitePool};
+use ttf_parser::{Face, GlyphId};
+
+use crate::attachments::content_disposition;
+use crate::models::{PortfolioDocument, PortfolioLink, PortfolioResponse};
+use crate::portfolio::{load_portfolio, PortfolioKey};
+
+pub const PDF_FONT_PATH_ENV: &str = "PORTFOLIO_PDF_FONT_PATH";
+
+/// 배치나 마크업을 바꾸면 올린다. 캐시에 남은 예전 결과물을 쓰지 않게 된다.
+const RENDER_REVISION: u32 = 1;
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const PAGE_MARGIN: f32 = 50.0;
+const LINE_SPACING: f32 = 1.45;
+
+type Rgb = (f32, f32, f32);
+
+const TEXT_COLOR: Rgb = (0.13, 0.13, 0.15);
+const MUTED_COLOR: Rgb = (0.42, 0.42, 0.46);
+const ACCENT_COLOR: Rgb = (0.15, 0.35, 0.75);
+const RULE_COLOR: Rgb = (0.82, 0.82, 0.85);
+
+/// Helvetica의 ASCII(32~126) 글자 폭. 1000 단위다.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Helvetica-Bold는 폭 표를 따로 두지 않고 넉넉하게 잡는다. 줄바꿈이 조금 일찍 일어날 뿐이다.
+const BOLD_WIDTH_FACTOR: f32 = 1.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortfolioRenderFormat {
+    Pdf,
+    Html,
+}
+
+impl PortfolioRenderFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Html => "html",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Pdf => "application/pdf",
+            Self::Html => "text/html; charset=utf-8",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PortfolioRenderError {
+    /// 지정한 글꼴을 읽거나 해석하지 못했다.
+    Font(String),
+    /// 글꼴을 지정하지 않았는데 Helvetica로 찍을 수 없는 글자가 있다. 처음 만난 글자를 담는다.
+    MissingFont(char),
+}
+
+impl From<PortfolioRenderError> for Error {
+    fn from(err: PortfolioRenderError) -> Self {
+        match err {
+            PortfolioRenderError::Font(message) => {
+                Error::from_string(message, StatusCode::INTERNAL_SERVER_ERROR)
+            }
+            PortfolioRenderError::MissingFont(ch) => Error::from_string(
+                format!(
+                    "PDF 기본 글꼴로 찍을 수 없는 글자('{}')가 있습니다. {}에 한글을 지원하는 TrueType 글꼴을 지정해 주세요.",
+                    ch, PDF_FONT_PATH_ENV
+                ),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+        }
+    }
+}
+
+/// 그려 낸 결과물. `version_number`는 기준이 된 포트폴리오 버전이고 아직 버전이 없으면 0이다.
+pub struct RenderedPortfolio {
+    pub content: Vec<u8>,
+    pub version_number: i64,
+    pub cached: bool,
+}
+
+fn internal_error(message: String) -> Error {
+    Error::from_string(message, StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn pdf_font_path() -> Option<String> {
+    std::env::var(PDF_FONT_PATH_ENV)
+        .ok()
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+}
+
+/// 캐시 항목이 어떤 렌더러로 만들어졌는지 나타낸다. 글꼴 설정이 바뀌어도 예전 PDF를 쓰지 않는다.
+fn renderer_id(format: PortfolioRenderFormat, font_path: Option<&str>) -> String {
+    match format {
+        PortfolioRenderFormat::Pdf => format!(
+            "r{}:font={}",
+            RENDER_REVISION,
+            font_path.unwrap_or("builtin")
+        ),
+        PortfolioRenderFormat::Html => format!("r{}", RENDER_REVISION),
+    }
+}
+
+/// 공개 문서를 `format`으로 그린다. 최신 버전으로 만든 캐시가 있으면 그것을 돌려준다.
+/// 포트폴리오가 없으면 `None`이다.
+pub async fn render_portfolio(
+    db: &SqlitePool,
+    key: &PortfolioKey,
+    format: PortfolioRenderFormat,
+) -> Result<Option<RenderedPortfolio>, Error> {
+    let Some(portfolio) = load_portfolio(db, key).await? else {
+        return Ok(None);
+    };
+
+    let version_number: i64 = query_scalar(
+        "SELECT COALESCE(MAX(version_number), 0) FROM portfolio_version WHERE slug = ? AND lang = ?",
+    )
+    .bind(&key.slug)
+    .bind(&key.lang)
+    .fetch_one(db)
+    .await
+    .map_err(|err| internal_error(format!("포트폴리오 버전 조회 실패: {}", err)))?;
+
+    let font_path = match format {
+        PortfolioRenderFormat::Pdf => pdf_font_path(),
+        PortfolioRenderFormat::Html => None,
+    };
+    let renderer = renderer_id(format, font_path.as_deref());
+
+    let cached: Option<Vec<u8>> = query_scalar(
+        r#"
+        SELECT content FROM portfolio_render_cache
+        WHERE slug = ? AND lang = ? AND format = ? AND version_number = ? AND renderer = ?
+        "#,
+    )
+    .bind(&key.slug)
+    .bind(&key.lang)
+    .bind(format.as_str())
+    .bind(version_number)
+    .bind(&renderer)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("포트폴리오 렌더 캐시 조회 실패: {}", err)))?;
+
+    if let Some(content) = cached {
+        return Ok(Some(RenderedPortfolio {
+            content,
+            version_number,
+            cached: true,
+        }));
+    }
+
+    let content = match format {
+        PortfolioRenderFormat::Html => render_portfolio_html(&portfolio).into_bytes(),
+        PortfolioRenderFormat::Pdf => {
+            let font = match &font_path {
+                Some(path) => Some(tokio::fs::read(path).await.map_err(|err| {
+                    internal_error(format!("PDF 글꼴을 읽지 못했습니다 ({}): {}", path, err))
+                })?),
+                None => None,
+            };
+            render_portfolio_pdf(&portfolio, font.as_deref())?
+        }
+    };
+
+    query(
+        r#"
+        INSERT INTO portfolio_render_cache (slug, lang, format, version_number, renderer, content)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(slug, lang, format) DO UPDATE SET
+            version_number = excluded.version_number,
+            renderer = excluded.renderer,
+            content = excluded.content,
+            created_at = CURRENT_TIMESTAMP
+        "#,
+    )
+    .bind(&key.slug)
+    .bind(&key.lang)
+    .bind(format.as_str())
+    .bind(version_number)
+    .bind(&renderer)
+    .bind(&content)
+    .execute(db)
+    .await
+    .map_err(|err| internal_error(format!("포트폴리오 렌더 캐시 저장 실패: {}", err)))?;
+
+    Ok(Some(RenderedPortfolio {
+        content,
+        version_number,
+        cached: false,
+    }))
+}
+
+/// 포트폴리오의 PDF/HTML 캐시를 지운다. 새 버전을 기록하거나 포트폴리오를 지울 때 부른다.
+pub async fn invalidate_portfolio_renders(
+    db: &SqlitePool,
+    key: &PortfolioKey,
+) -> Result<(), Error> {
+    query("DELETE FROM portfolio_render_cache WHERE slug = ? AND lang = ?")
+        .bind(&key.slug)
+        .bind(&key.lang)
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("포트폴리오 렌더 캐시 삭제 실패: {}", err)))?;
+    Ok(())
+}
+
+/// 그려 낸 결과물을 응답으로 만든다. 같은 버전을 이미 받은 클라이언트(`If-None-Match`)에는 304를 돌려준다.
+pub fn rendered_portfolio_response(
+    req: &Request,
+    key: &PortfolioKey,
+    format: PortfolioRenderFormat,
+    rendered: RenderedPortfolio,
+) -> Response {
+    let etag = format!(
+        "\"{}-{}-v{}-{}\"",
+        key.slug,
+        key.lang,
+        rendered.version_number,
+        format.as_str()
+    );
+    let not_modified = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag));
+
+    let builder = Response::builder()
+        .header(header::ETAG, etag)
+        .header(header::CACHE_CONTROL, "no-cache")
+        .header(
+            "X-Portfolio-Render-Cache",
+            if rendered.cached { "hit" } else { "miss" },
+        );
+    if not_modified {
+        return builder.status(StatusCode::NOT_MODIFIED).finish();
+    }
+
+    let file_name = format!(
+        "portfolio-{}-{}-v{}.{}",
+        key.slug,
+        key.lang,
+        rendered.version_number,
+        format.as_str()
+    );
+    let mut builder = builder
+        .header(header::CONTENT_TYPE, format.content_type())
+        .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+        .header(
+            header::CONTENT_DISPOSITION,
+            content_disposition(&file_name, true),
+        );
+    if format == PortfolioRenderFormat::Html {
+        // 문서 안의 스타일 말고는 아무것도 불러오지 않는다.
+        builder = builder.header(
+            header::CONTENT_SECURITY_POLICY,
+            "default-src 'none'; style-src 'unsafe-inline'; img-src data:",
+        );
+    }
+    builder.body(rendered.content)
+}
+
+struct Labels {
+    intro: &'static str,
+    career: &'static str,
+    projects: &'static str,
+    stack: &'static str,
+}
+
+fn labels(lang: &str) -> Labels {
+    match lang {
+        "en" => Labels {
+            intro: "About",
+            career: "Experience",
+            projects: "Projects",
+            stack: "Stack",
+        },
+        _ => Labels {
+            intro: "소개",
+            career: "경력",
+            projects: "프로젝트",
+            stack: "기술",
+        },
+    }
+}
+
+/// 링크로 걸어도 안전한 주소만 통과시킨다. `javascript:` 같은 주소는 글자로만 남긴다.
+fn safe_url(url: &str) -> Option<&str> {
+    let url = url.trim();
+    let lower = url.to_ascii_lowercase();
+    ["https://", "http://", "mailto:"]
+        .iter()
+        .any(|scheme| lower.starts_with(scheme))
+        .then_some(url)
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn html_link(out: &mut String, label: &str, url: &str) {
+    match safe_url(url) {
+        Some(url) => {
+            let _ = write!(
+                out,
+                r#"<a href="{}">{}</a>"#,
+                escape_html(url),
+                escape_html(label)
+            );
+        }
+        None => out.push_str(&escape_html(label)),
+    }
+}
+
+fn html_paragraphs(out: &mut String, text: &str) {
+    for paragraph in text.split('\n').map(str::trim).filter(|p| !p.is_empty()) {
+        let _ = write!(out, "<p>{}</p>", escape_html(paragraph));
+    }
+}
+
+fn html_list(out: &mut String, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    out.push_str("<ul>");
+    for item in items {
+        let _ = write!(out, "<li>{}</li>", escape_html(item));
+    }
+    out.push_str("</ul>");
+}
+
+const HTML_STYLE: &str = r#"
+*{box-sizing:border-box}
+body{margin:0;background:#f4f4f6;color:#222226;font:15px/1.6 -apple-system,BlinkMacSystemFont,"Apple SD Gothic Neo","Noto Sans KR","Malgun Gothic",sans-serif}
+main{max-width:820px;margin:32px auto;padding:48px 56px;background:#fff;border-radius:8px}
+header{border-bottom:1px solid #d1d1d9;padding-bottom:16px;margin-bottom:8px}
+h1{margin:0 0 4px;font-size:30px}
+h2{margin:32px 0 12px;font-size:18px;color:#2659bf;border-bottom:1px solid #d1d1d9;padding-bottom:4px}
+h3{margin:20px 0 2px;font-size:16px}
+h4{margin:12px 0 2px;font-size:14px}
+p{margin:6px 0}
+ul{margin:6px 0;padding-left:20px}
+a{color:#2659bf;text-decoration:none}
+.contact,.meta,.period{color:#6b6b75;font-size:13px}
+.row{display:flex;justify-content:space-between;align-items:baseline;gap:16px}
+.stack{display:flex;flex-wrap:wrap;gap:6px;list-style:none;padding:0}
+.stack li{background:#eef1f8;border-radius:4px;padding:1px 8px;font-size:13px}
+@media print{body{background:#fff}main{margin:0;padding:0;max-width:none}}
+"#;
+
+/// 외부 리소스 없이 혼자 열리는 HTML을 만든다. 스타일은 문서 안에 넣고, 기술 스택 아이콘은 이름으로 대신한다.
+pub fn render_portfolio_html(portfolio: &PortfolioResponse) -> String {
+    let document = &portfolio.content;
+    let labels = labels(&portfolio.lang);
+    let identity = &document.identity;
+    let mut out = String::new();
+
+    let _ = write!(
+        out,
+        r#"<!DOCTYPE html><html lang="{}"><head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1"><title>{}</title><style>{}</style></head><body><main>"#,
+        escape_html(&portfolio.lang),
+        escape_html(&identity.name),
+        HTML_STYLE
+    );
+
+    let _ = write!(
+        out,
+        r#"<header><h1>{}</h1><div class="contact">"#,
+        escape_html(&identity.name)
+    );
+    html_link(
+        &mut out,
+        &identity.email,
+        &format!("mailto:{}", identity.email),
+    );
+    if !identity.github_url.is_empty() {
+        out.push_str(" · ");
+        html_link(&mut out, &identity.github_url, &identity.github_url);
+    }
+    out.push_str("</div></header>");
+
+    if let Some(intro) = &document.intro {
+        let _ = write!(out, "<section><h2>{}</h2>", labels.intro);
+        html_paragraphs(&mut out, &intro.content);
+        if !intro.tech_stack.is_empty() {
+            out.push_str(r#"<ul class="stack">"#);
+            for item in &intro.tech_stack {
+                let _ = write!(out, "<li>{}</li>", escape_html(&item.name));
+            }
+            out.push_str("</ul>");
+        }
+        out.push_str("</section>");
+    }
+
+    if let Some(career) = &document.career {
+        let _ = write!(out, "<section><h2>{}</h2>", labels.career);
+        if !career.summary_value.is_empty() {
+            let _ = write!(
+                out,
+                r#"<p class="meta">{}: {}</p>"#,
+                escape_html(&career.summary_label),
+                escape_html(&career.summary_value)
+            );
+        }
+        for company in &career.companies {
+            let _ = write!(
+                out,
+                r#"<div class="row"><h3>{}</h3><span class="period">{}</span></div><p class="meta">{}</p>"#,
+                escape_html(&company.company),
+                escape_html(&company.period),
+                escape_html(&company_meta(
+                    &company.role,
+                    &company.position,
+                    &company.employment_type
+                ))
+            );
+            for item in &company.items {
+                let _ = write!(
+                    out,
+                    r#"<div class="row"><h4>{}</h4><span class="period">{}</span></div>"#,
+                    escape_html(&item.title),
+                    escape_html(item.period.as_deref().unwrap_or_default())
+                );
+                html_list(&mut out, &item.bullets);
+            }
+        }
+        out.push_str("</section>");
+    }
+
+    if !document.featured_projects.is_empty() {
+        let _ = write!(out, "<section><h2>{}</h2>", labels.projects);
+        for project in &document.featured_projects {
+            let _ = write!(
+                out,
+                r#"<article><div class="row"><h3>{}</h3><span class="period">{}</span></div>"#,
+                escape_html(&project.title),
+                escape_html(&project.period)
+            );
+            html_paragraphs(&mut out, &project.summary);
+            if !project.stack.is_empty() {
+                let _ = write!(
+                    out,
+                    r#"<p class="meta">{}: {}</p>"#,
+                    labels.stack,
+                    escape_html(&project.stack.join(", "))
+                );
+            }
+            html_list(&mut out, &project.highlights);
+            if !project.links.is_empty() {
+                out.push_str(r#"<p class="meta">"#);
+                for (index, link) in project.links.iter().enumerate() {
+                    if index > 0 {
+                        out.push_str(" · ");
+                    }
+                    html_link(&mut out, &link.label, &link.url);
+                }
+                out.push_str("</p>");
+            }
+            out.push_str("</article>");
+        }
+        out.push_str("</section>");
+    }
+
+    out.push_str("</main></body></html>");
+    out
+}
+
+fn company_meta(role: &str, position: &str, employment_type: &str) -> String {
+    [role, position, employment_type]
+        .into_iter()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+/// PDF에 쓸 글꼴. 글자 폭 계산과 문자열 인코딩이 글꼴마다 다르다.
+enum PdfFont<'a> {
+    /// PDF 기본 Helvetica. WinAnsi 인코딩이라 Latin-1까지만 찍힌다.
+    Builtin,
+    /// 받은 TrueType 글꼴. 글리프 번호를 그대로 CID로 쓴다(Identity-H).
+    Embedded { data: &'a [u8], face: Box<Face<'a>> },
+}
+
+impl PdfFont<'_> {
+    fn char_width(&self, ch: char, bold: bool) -> f32 {
+        match self {
+            PdfFont::Builtin => {
+                let code = ch as u32;
+                let width = if (32..=126).contains(&code) {
+                    HELVETICA_WIDTHS[(code - 32) as usize] as f32
+                } else {
+                    556.0
+                };
+                if bold {
+                    width * BOLD_WIDTH_FACTOR
+                } else {
+                    width
+                }
+            }
+            PdfFont::Embedded { face, .. } => {
+                let glyph = face.glyph_index(ch).unwrap_or(GlyphId(0));
+                glyph_width(face, glyph)
+            }
+        }
+    }
+
+    fn text_width(&self, text: &str, size: f32, bold: bool) -> f32 {
+        text.chars()
+            .map(|ch| self.char_width(ch, bold))
+            .sum::<f32>()
+            * size
+            / 1000.0
+    }
+
+    fn bullet(&self) -> char {
+        match self {
+            PdfFont::Builtin => '•',
+            PdfFont::Embedded { face, .. } => {
+                if face.glyph_index('•').is_some() {
+                    '•'
+                } else {
+                    '-'
+                }
+            }
+        }
+    }
+}
+
+fn glyph_width(face: &Face, glyph: GlyphId) -> f32 {
+    let advance = face.glyph_hor_advance(glyph).unwrap_or(0) as f32;
+    advance * 1000.0 / face.units_per_em() as f32
+}
+
+/// WinAnsi 한 바이트로 옮긴다. 표에 없는 글자는 `None`이다.
+fn win_ansi_byte(ch: char) -> Option<u8> {
+    let byte = 
+    match ch {
+        '\u{20}'..='\u{7e}' => ch as u8,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '…' => 0x85,
+        '\u{a0}'..='\u{ff}' => ch as u32 as u8,
+        _ => return None,
+    };
+    Some(byte)
+}
+
+struct PdfLink {
+    rect: Rect,
+    url: String,
+}
+
+struct PdfPage {
+    content: Content,
+    links: Vec<PdfLink>,
+}
+
+/// 위에서 아래로 줄을 채워 가며 페이지를 넘기는 단순한 배치기.
+struct PdfLayout<'a> {
+    font: &'a PdfFont<'a>,
+    pages: Vec<PdfPage>,
+    y: f32,
+    /// 쓰인 글리프와 대표 글자. 글꼴을 잘라 넣고 ToUnicode 표를 만들 때 쓴다.
+    glyphs: BTreeMap<u16, char>,
+    /// Helvetica로 찍을 수 없어 처음 만난 글자.
+    unsupported: Option<char>,
+}
+
+impl<'a> PdfLayout<'a> {
+    fn new(font: &'a PdfFont<'a>) -> Self {
+        let mut layout = Self {
+            font,
+            pages: Vec::new(),
+            y: 0.0,
+            glyphs: BTreeMap::new(),
+            unsupported: None,
+        };
+        layout.new_page();
+        layout
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(PdfPage {
+            content: Content::new(),
+            links: Vec::new(),
+        });
+        self.y = PAGE_HEIGHT - PAGE_MARGIN;
+    }
+
+    fn page(&mut self) -> &mut PdfPage {
+        self.pages
+            .last_mut()
+            .expect("배치기는 항상 페이지를 하나 이상 가진다")
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < PAGE_MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn encode(&mut self, text: &str) -> Vec<u8> {
+        match self.font {
+            PdfFont::Builtin => text
+                .chars()
+                .map(|ch| {
+                    win_ansi_byte(ch).unwrap_or_else(|| {
+                        self.unsupported.get_or_insert(ch);
+                        b'?'
+                    })
+                })
+                .collect(),
+            PdfFont::Embedded { face, .. } => {
+                let mut bytes = Vec::with_capacity(text.len() * 2);
+                for ch in text.chars() {
+                    let glyph = face.glyph_index(ch).unwrap_or(GlyphId(0));
+                    self.glyphs.entry(glyph.0).or_insert(ch);
+                    bytes.extend_from_slice(&glyph.0.to_be_bytes());
+                }
+                bytes
+            }
+        }
+    }
+
+    /// 현재 줄의 기준선에 글자를 찍는다. 줄 간격은 호출하는 쪽이 관리한다.
+    fn draw_text(&mut self, text: &str, x: f32, baseline: f32, size: f32, bold: bool, color: Rgb) {
+        let encoded = self.encode(text);
+        let (font_name, embedded) = match (self.font, bold) {
+            (PdfFont::Builtin, true) => (Name(b"F2"), false),
+            (PdfFont::Builtin, false) => (Name(b"F1"), false),
+            (PdfFont::Embedded { .. }, _) => (Name(b"F1"), true),
+        };
+        let content = &mut self.page().content;
+        content.begin_text();
+        content.set_fill_rgb(color.0, color.1, color.2);
+        content.set_font(font_name, size);
+        // 넣은 글꼴에는 굵은 꼴이 따로 없어 윤곽선을 함께 그려 굵게 보이게 한다.
+        if embedded && bold {
+            content.set_stroke_rgb(color.0, color.1, color.2);
+            content.set_line_width(size * 0.03);
+            content.set_text_rendering_mode(TextRenderingMode::FillStroke);
+        } else {
+            content.set_text_rendering_mode(TextRenderingMode::Fill);
+        }
+        content.next_line(x, baseline);
+        content.show(Str(&encoded));
+        content.end_text();
+    }
+
+    fn wrap(&self, text: &str, size: f32, bold: bool, max_width: f32) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut current = String::new();
+        for word in text.split_whitespace() {
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current, word)
+            };
+            if self.font.text_width(&candidate, size, bold) <= max_width {
+                current = candidate;
+                continue;
+            }
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            // 한 단어가 줄보다 길면 글자 단위로 자른다.
+            for ch in word.chars() {
+                current.push(ch);
+                if self.font.text_width(&current, size, bold) > max_width && current.len() > 1 {
+                    current.pop();
+                    lines.push(std::mem::take(&mut current));
+                    current.push(ch);
+                }
+            }
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+        lines
+    }
+
+    fn paragraph(&mut self, text: &str, indent: f32, size: f32, bold: bool, color: Rgb) {
+        let max_width = PAGE_WIDTH - PAGE_MARGIN * 2.0 - indent;
+        for source_line in text.split('\n') {
+            for line in self.wrap(source_line, size, bold, max_width) {
+                self.line(&line, indent, size, bold, color);
+            }
+        }
+    }
+
+    fn line(&mut self, text: &str, indent: f32, size: f32, bold: bool, color: Rgb) {
+        let height = size * LINE_SPACING;
+        self.ensure_space(height);
+        let baseline = self.y - size;
+        self.draw_text(text, PAGE_MARGIN + indent, baseline, size, bold, color);
+        self.y -= height;
+    }
+
+    /// 왼쪽에 제목, 오른쪽 끝에 기간을 같은 줄에 놓는다.
+    fn title_row(&mut self, title: &str, period: &str, size: f32) {
+        let period_size = size - 2.0;
+        let period_width = self.font.text_width(period, period_size, false);
+        let title_width = PAGE_WIDTH - PAGE_MARGIN * 2.0 - period_width - 12.0;
+        let lines = self.wrap(title, size, true, title_width);
+        let height = size * LINE_SPACING;
+        self.ensure_space(height * lines.len().max(1) as f32);
+        if !period.is_empty() {
+            let baseline = self.y - size;
+            let x = PAGE_WIDTH - PAGE_MARGIN - period_width;
+            self.draw_text(period, x, baseline, period_size, false, MUTED_COLOR);
+        }
+        if lines.is_empty() {
+            self.y -= height;
+        }
+        for line in lines {
+            self.line(&line, 0.0, size, true, TEXT_COLOR);
+        }
+    }
+
+    fn bullets(&mut self, items: &[String], size: f32) {
+        let bullet = self.font.bullet().to_string();
+        let indent = 14.0;
+        let max_width = PAGE_WIDTH - PAGE_MARGIN * 2.0 - indent;
+        for item in items {
+            let lines = self.wrap(item, size, false, max_width);
+            for (index, line) in lines.iter().enumerate() {
+                if index == 0 {
+                    self.ensure_space(size * LINE_SPACING);
+                    let baseline = self.y - size;
+                    self.draw_text(
+                        &bullet,
+                        PAGE_MARGIN + 4.0,
+                        baseline,
+                        size,
+                        false,
+                        MUTED_COLOR,
+                    );
+                }
+                self.line(line, indent, size, false, TEXT_COLOR);
+            }
+        }
+    }
+
+    /// 링크들을 `·`로 이어 한 줄에 놓고 각 글자 위에 링크 영역을 건다. 넘치면 다음 줄로 넘긴다.
+    fn links(&mut self, links: &[PortfolioLink], size: f32) {
+        let separator = " · ";
+        let max_x = PAGE_WIDTH - PAGE_MARGIN;
+        let height = size * LINE_SPACING;
+        let mut x = PAGE_MARGIN;
+        self.ensure_space(height);
+        for (index, link) in links.iter().enumerate() {
+            let width = self.font.text_width(&link.label, size, false);
+            if index > 0 {
+                let separator_width = self.font.text_width(separator, size, false);
+                if x + separator_width + width > max_x {
+                    self.y -= height;
+                    self.ensure_space(height);
+                    x = PAGE_MARGIN;
+                } else {
+                    let baseline = self.y - size;
+                    self.draw_text(separator, x, baseline, size, false, MUTED_COLOR);
+                    x += separator_width;
+                }
+            }
+            let baseline = self.y - size;
+            let url = safe_url(&link.url).map(str::to_string);
+            let color = if url.is_some() {
+                ACCENT_COLOR
+            } else {
+                TEXT_COLOR
+            };
+            self.draw_text(&link.label, x, baseline, size, false, color);
+            if let Some(url) = url {
+                let rect = Rect::new(x, baseline - size * 0.25, x + width, baseline + size * 0.9);
+                self.page().links.push(PdfLink { rect, url });
+            }
+            x += width;
+        }
+        self.y -= height;
+    }
+
+    fn rule(&mut self) {
+        self.ensure_space(8.0);
+        let y = self.y - 4.0;
+        let content = &mut self.page().content;
+        content.set_stroke_rgb(RULE_COLOR.0, RULE_COLOR.1, RULE_COLOR.2);
+        content.set_line_width(0.75);
+        content.move_to(PAGE_MARGIN, y);
+        content.line_to(PAGE_WIDTH - PAGE_MARGIN, y);
+        content.stroke();
+        self.y -= 8.0;
+    }
+
+    fn heading(&mut self, text: &str) {
+        // 제목만 페이지 끝에 남지 않도록 아래 몇 줄 자리까지 본다.
+        self.ensure_space(60.0);
+        self.gap(14.0);
+        self.line(text, 0.0, 14.0, true, ACCENT_COLOR);
+        self.rule();
+        self.gap(2.0);
+    }
+}
+
+fn lay_out_document(layout: &mut PdfLayout, document: &PortfolioDocument, lang: &str) {
+    let labels = labels(lang);
+    let identity = &document.identity;
+
+    layout.line(&identity.name, 0.0, 24.0, true, TEXT_COLOR);
+    let mut contact = vec![PortfolioLink {
+        label: identity.email.clone(),
+        url: format!("mailto:{}", identity.email),
+    }];
+    if !identity.github_url.is_empty() {
+        contact.push(PortfolioLink {
+            label: identity.github_url.clone(),
+            url: identity.github_url.clone(),
+        });
+    }
+    layout.links(&contact, 10.0);
+    layout.rule();
+
+    if let Some(intro) = &document.intro {
+        layout.heading(labels.intro);
+        layout.paragraph(&intro.content, 0.0, 10.5, false, TEXT_COLOR);
+        if !intro.tech_stack.is_empty() {
+            let names: Vec<&str> = intro
+                .tech_stack
+                .iter()
+                .map(|item| item.name.as_str())
+                .collect();
+            layout.gap(4.0);
+            layout.paragraph(
+                &format!("{}: {}", labels.stack, names.join(", ")),
+                0.0,
+                9.5,
+                false,
+                MUTED_COLOR,
+            );
+        }
+    }
+
+    if let Some(career) = &document.career {
+        layout.heading(labels.career);
+        if !career.summary_value.is_empty() {
+            layout.paragraph(
+                &format!("{}: {}", career.summary_label, career.summary_value),
+                0.0,
+                9.5,
+                false,
+                MUTED_COLOR,
+            );
+        }
+        for company in &career.companies {
+            layout.gap(6.0);
+            layout.title_row(&company.company, &company.period, 12.5);
+            let meta = company_meta(&company.role, &company.position, &company.employment_type);
+            if !meta.is_empty() {
+                layout.paragraph(&meta, 0.0, 9.5, false, MUTED_COLOR);
+            }
+            for item in &company.items {
+                layout.gap(3.0);
+                layout.title_row(
+                    &item.title,
+                    item.period.as_deref().unwrap_or_default(),
+                    10.5,
+                );
+                layout.bullets(&item.bullets, 10.0);
+            }
+        }
+    }
+
+    if !document.featured_projects.is_empty() {
+        layout.heading(labels.projects);
+        for project in &document.featured_projects {
+            layout.gap(6.0);
+            layout.title_row(&project.title, &project.period, 12.5);
+            layout.paragraph(&project.summary, 0.0, 10.5, false, TEXT_COLOR);
+            if !project.stack.is_empty() {
+                layout.paragraph(
+                    &format!("{}: {}", labels.stack, project.stack.join(", ")),
+                    0.0,
+                    9.5,
+                    false,
+                    MUTED_COLOR,
+                );
+            }
+            layout.bullets(&project.highlights, 10.0);
+            if !project.links.is_empty() {
+                layout.links(&project.links, 9.5);
+            }
+        }
+    }
+}
+
+fn flate(data: &[u8]) -> Vec<u8> {
+    miniz_oxide::deflate::compress_to_vec_zlib(data, 6)
+}
+
+/// 넣은 글꼴의 PostScript 이름. 잘라 낸 글꼴임을 나타내는 여섯 글자 접두어를 붙인다.
+fn subset_font_name(face: &Face, glyphs: &BTreeMap<u16, char>) -> String {
+    let postscript_name = face
+        .names()
+        .into_iter()
+        .filter(|name| name.name_id == ttf_parser::name_id::POST_SCRIPT_NAME)
+        .find_map(|name| name.to_string())
+        .unwrap_or_else(|| "Embedded".to_string());
+
+    let mut hash: u32 = 2166136261;
+    for glyph in glyphs.keys() {
+        for byte in glyph.to_be_bytes() {
+            hash = (hash ^ byte as u32).wrapping_mul(16777619);
+        }
+    }
+    let tag: String = (0..6)
+        .map(|index| (b'A' + ((hash >> (index * 5)) % 26) as u8) as char)
+        .collect();
+
+    format!("{}+{}", tag, postscript_name.replace(' ', ""))
+}
+
+/// 문서를 A4 PDF로 그린다. `font`에 TrueType 글꼴을 주면 잘라 넣고, 없으면 Helvetica를 쓴다.
+/// Helvetica로 찍을 수 없는 글자가 있으면 `?`로 바꿔 내보내지 않고 [`PortfolioRenderError::MissingFont`]다.
+pub fn render_portfolio_pdf(
+    portfolio: &PortfolioResponse,
+    font: Option<&[u8]>,
+) -> Result<Vec<u8>, PortfolioRenderError> {
+    let font = match font {
+        Some(data) => PdfFont::Embedded {
+            data,
+            face: Box::new(Face::parse(data, 0).map_err(|err| {
+                PortfolioRenderError::Font(format!("PDF 글꼴 해석 실패: {}", err))
+            })?),
+        },
+        None => PdfFont::Builtin,
+    };
+
+    let mut layout = PdfLayout::new(&font);
+    lay_out_document(&mut layout, &portfolio.content, &portfolio.lang);
+    let PdfLayout {
+        pages,
+        glyphs,
+        unsupported,
+        ..
+    } = layout;
+    if let Some(ch) = unsupported {
+        return Err(PortfolioRenderError::MissingFont(ch));
+    }
+
+    let mut next_id = 1;
+    let mut alloc = || {
+        let id = Ref::new(next_id);
+        next_id += 1;
+        id
+    };
+
+    let catalog_id = alloc();
+    let page_tree_id = alloc();
+    let info_id = alloc();
+    let regular_font_id = alloc();
+    let bold_font_id = alloc();
+    let page_ids: Vec<(Ref, Ref)> = pages.iter().map(|_| (alloc(), alloc())).collect();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id)
+        .kids(page_ids.iter().map(|(page_id, _)| *page_id))
+        .count(page_ids.len() as i32);
+    pdf.document_info(info_id)
+        .title(TextStr(&portfolio.content.identity.name))
+        .creator(TextStr("tyange-cms-api"));
+
+    for (page, (page_id, content_id)) in pages.into_iter().zip(&page_ids) {
+        let mut page_writer = pdf.page(*page_id);
+        page_writer
+            .media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+            .parent(page_tree_id)
+            .contents(*content_id);
+        let mut resources = page_writer.resources();
+        let mut fonts = resources.fonts();
+        fonts.pair(Name(b"F1"), regular_font_id);
+        if matches!(font, PdfFont::Builtin) {
+            fonts.pair(Name(b"F2"), bold_font_id);
+        }
+        fonts.finish();
+        resources.finish();
+        if !page.links.is_empty() {
+            let mut annotations = page_writer.annotations();
+            for link in &page.links {
+                let mut annotation = annotations.push();
+                annotation
+                    .subtype(AnnotationType::Link)
+                    .rect(link.rect)
+                    .border(0.0, 0.0, 0.0, None);
+                annotation
+                    .action()
+                    .action_type(ActionType::Uri)
+                    .uri(Str(link.url.as_bytes()));
+            }
+        }
+        page_writer.finish();
+
+        pdf.stream(*content_id, &flate(&page.content.finish()))
+            .filter(Filter::FlateDecode);
+    }
+
+    match &font {
+        PdfFont::Builtin => {
+            pdf.type1_font(regular_font_id)
+                .base_font(Name(b"Helvetica"))
+                .encoding_predefined(Name(b"WinAnsiEncoding"));
+            pdf.type1_font(bold_font_id)
+                .base_font(Name(b"Helvetica-Bold"))
+                .encoding_predefined(Name(b"WinAnsiEncoding"));
+        }
+        PdfFont::Embedded { data, face } => {
+            let cid_font_id = alloc();
+            let descriptor_id = alloc();
+            let to_unicode_id = alloc();
+            let font_file_id = alloc();
+
+            let base_font = subset_font_name(face, &glyphs);
+            let system_info = SystemInfo {
+                registry: Str(b"Adobe"),
+                ordering: Str(b"Identity"),
+                supplement: 0,
+            };
+
+            let mut glyph_ids: Vec<u16> = glyphs.keys().copied().collect();
+            if !glyph_ids.contains(&0) {
+                glyph_ids.insert(0, 0);
+            }
+            let subset =
+                subsetter::subset(data, 0, subsetter::Profile::pdf(&glyph_ids)).map_err(|err| {
+                    PortfolioRenderError::Font(format!("PDF 글꼴 자르기 실패: {}", err))
+                })?;
+
+            pdf.type0_font(regular_font_id)
+                .base_font(Name(base_font.as_bytes()))
+                .encoding_predefined(Name(b"Identity-H"))
+                .descendant_font(cid_font_id)
+                .to_unicode(to_unicode_id);
+
+            let mut cid_font = pdf.cid_font(cid_font_id);
+            cid_font
+                .subtype(CidFontType::Type2)
+                .base_font(Name(base_font.as_bytes()))
+                .system_info(system_info)
+                .font_descriptor(descriptor_id)
+                .default_width(0.0)
+                .cid_to_gid_map_predefined(Name(b"Identity"));
+            let mut widths = cid_font.widths();
+            for glyph in glyphs.keys() {
+                widths.consecutive(*glyph, [glyph_width(face, GlyphId(*glyph))]);
+            }
+            widths.finish();
+            cid_font.finish();
+
+            let units = face.units_per_em() as f32;
+            let scale = |value: i16| value as f32 * 1000.0 / units;
+            let bbox = face.global_bounding_box();
+            pdf.font_descriptor(descriptor_id)
+                .name(Name(base_font.as_bytes()))
+                .flags(FontFlags::SYMBOLIC)
+                .bbox(Rect::new(
+                    scale(bbox.x_min),
+                    scale(bbox.y_min),
+                    scale(bbox.x_max),
+                    scale(bbox.y_max),
+                ))
+                .italic_angle(0.0)
+                .ascent(scale(face.ascender()))
+                .descent(scale(face.descender()))
+                .cap_height(scale(face.capital_height().unwrap_or(face.ascender())))
+                .stem_v(80.0)
+                .font_file2(font_file_id);
+
+            let mut cmap = UnicodeCmap::new(Name(b"Custom"), system_info);
+            for (glyph, ch) in &glyphs {
+                cmap.pair(*glyph, *ch);
+            }
+            pdf.stream(to_unicode_id, &flate(&cmap.finish()))
+                .filter(Filter::FlateDecode);
+
+            pdf.stream(font_file_id, &flate(&subset))
+                .filter(Filter::FlateDecode)
+                .pair(Name(b"Length1"), subset.len() as i32);
+        }
+    }
+
+    Ok(pdf.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        PortfolioCareerCompany, PortfolioCareerItem, PortfolioCareerSection, PortfolioIdentity,
+        PortfolioIntroSection, PortfolioProject,
+    };
+
+    const DEJAVU_PATH: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+
+    fn sample_portfolio(lang: &str) -> PortfolioResponse {
+        let long_bullet = "Rebuilt the deployment pipeline ".repeat(12);
+        PortfolioResponse {
+            portfolio_id: 1,
+            slug: "dev".to_string(),
+            lang: lang.to_string(),
+            content: PortfolioDocument {
+                slug: "dev".to_string(),
+                version: 1,
+                identity: PortfolioIdentity {
+                    name: "Tyange <Kim>".to_string(),
+                    email: "me@example.com".to_string(),
+                    github_url: "https://github.com/tyange".to_string(),
+                },
+                featured_projects: (0..12)
+                    .map(|index| PortfolioProject {
+                        slug: format!("project-{}", index),
+                        title: format!("Project {}", index),
+                        period: "2023.01 - 2024.02".to_string(),
+                        summary: "A CMS backend written in Rust.".to_string(),
+                        stack: vec!["Rust".to_string(), "SQLite".to_string()],
+                        highlights: vec![long_bullet.clone()],
+                        links: vec![
+                            PortfolioLink {
+                                label: "Repository".to_string(),
+                                url: "https://github.com/tyange/cms".to_string(),
+                            },
+                            PortfolioLink {
+                                label: "Unsafe".to_string(),
+                                url: "javascript:alert(1)".to_string(),
+                            },
+                        ],
+                        posts: Vec::new(),
+                    })
+                    .collect(),
+                career: Some(PortfolioCareerSection {
+                    summary_label: "총 경력".to_string(),
+                    summary_value: "3년".to_string(),
+                    companies: vec![PortfolioCareerCompany {
+                        company: "Example Corp".to_string(),
+                        period: "2021.03 - 현재".to_string(),
+                        employment_type: "정규직".to_string(),
+                        role: "Backend".to_string(),
+                        position: "Engineer".to_string(),
+                        items: vec![PortfolioCareerItem {
+                            title: "결제 시스템".to_string(),
+                            period: None,
+                            bullets: vec!["정산 배치 재작성".to_string()],
+                        }],
+                    }],
+                }),
+                intro: Some(PortfolioIntroSection {
+                    content: "안녕하세요.\nRust로 서버를 만듭니다.".to_string(),
+                    tech_stack: Vec::new(),
+                }),
+            },
+            created_at: "2024-01-01 00:00:00".to_string(),
+            updated_at: "2024-01-01 00:00:00".to_string(),
+            project_posts: None,
+        }
+    }
+
+    #[test]
+    fn html_is_escaped_and_self_contained() {
+        let html = render_portfolio_html(&sample_portfolio("en"));
+
+        assert!(html.starts_with("<!DOCTYPE html><html lang=\"en\">"));
+        assert!(html.contains("Tyange &lt;Kim&gt;"));
+        assert!(html.contains("<h2>Experience</h2>"));
+        assert!(html.contains(r#"<a href="https://github.com/tyange/cms">Repository</a>"#));
+        assert!(!html.contains("javascript:"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<link"));
+        assert!(!html.contains("src="));
+    }
+
+    /// 한글이 든 경력/소개를 뺀, Helvetica로 찍을 수 있는 문서.
+    fn latin_portfolio() -> PortfolioResponse {
+        let mut portfolio = sample_portfolio("en");
+        portfolio.content.career = None;
+        portfolio.content.intro = None;
+        portfolio
+    }
+
+    #[test]
+    fn builtin_pdf_breaks_long_documents_into_pages() {
+        let pdf = render_portfolio_pdf(&latin_portfolio(), None).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.starts_with(b"%PDF-"));
+        assert!(text.contains("/Helvetica"));
+        assert!(text.contains("/URI (https://github.com/tyange/cms)"));
+        assert!(!text.contains("javascript:"));
+        let page_count =
+            text.matches("/Type /Page\n").count() + text.matches("/Type /Page ").count();
+        assert!(page_count > 1, "page count: {}", page_count);
+    }
+
+    #[test]
+    fn hangul_without_a_font_is_a_configuration_error() {
+        let err = render_portfolio_pdf(&sample_portfolio("ko"), None).unwrap_err();
+        assert!(matches!(err, PortfolioRenderError::MissingFont(ch) if !ch.is_ascii()));
+
+        let error = Error::from(err);
+        assert_eq!(error.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(error.to_string().contains(PDF_FONT_PATH_ENV), "{}", error);
+
+        let mut portfolio = latin_portfolio();
+        portfolio.content.identity.name = "김태영".to_string();
+        assert!(matches!(
+            render_portfolio_pdf(&portfolio, None),
+            Err(PortfolioRenderError::MissingFont('김'))
+        ));
+    }
+
+    #[test]
+    fn wrap_keeps_lines_within_width() {
+        let font = PdfFont::Builtin;
+        let layout = PdfLayout::new(&font);
+        let lines = layout.wrap(&"word ".repeat(60), 10.0, false, 200.0);
+
+        assert!(lines.len() > 1);
+        for line in &lines {
+            assert!(font.text_width(line, 10.0, false) <= 200.0, "{}", line);
+        }
+    }
+
+    #[test]
+    fn embedded_font_is_subset_with_unicode_map() {
+        let Ok(data) = std::fs::read(DEJAVU_PATH) else {
+            return;
+        };
+        let pdf = render_portfolio_pdf(&sample_portfolio("en"), Some(&data)).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(text.contains("/Identity-H"));
+        assert!(text.contains("/FontFile2"));
+        assert!(text.contains("/ToUnicode"));
+        assert!(
+            pdf.len() < data.len(),
+            "글꼴 전체가 들어갔다: {}",
+            pdf.len()
+        );
+    }
+}
@@ -0,0 +1,550 @@
+//! 포트폴리오 문서의 JSON Schema와 필드 단위 검증.
+//!
+//! 스키마는 `models.rs`의 구조체에서 `schemars`로 만든다. 검증기는 그 스키마를 그대로 따라가며
+//! 틀린 곳을 모두 모으므로, 모델에 붙인 규칙(`length`, `url`, `portfolio-period` 등)이 곧 검증 규칙이다.
+
+use std::sync::OnceLock;
+
+use schemars::gen::SchemaSettings;
+use serde_json::{json, Map, Value};
+use url::Url;
+
+use crate::models::{PortfolioDocument, PortfolioMeta, PortfolioValidationIssue};
+use crate::portfolio::{is_valid_portfolio_slug, parse_period, PortfolioContentError};
+
+pub const PORTFOLIO_SCHEMA_ID: &str = "https://api.tyange.com/portfolio/schema";
+
+/// 링크로 받는 주소의 scheme. 렌더링할 때 그대로 링크가 되므로 `javascript:` 등은 받지 않는다.
+const URL_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// 문서 전체의 JSON Schema. 섹션 검증에 쓰는 `PortfolioMeta`도 `definitions`에 함께 들어 있다.
+pub fn portfolio_document_schema() -> &'static Value {
+    static SCHEMA: OnceLock<Value> = OnceLock::new();
+    SCHEMA.get_or_init(|| {
+        let mut generator = SchemaSettings::draft07().into_generator();
+        generator.subschema_for::<PortfolioMeta>();
+        let root = generator.into_root_schema_for::<PortfolioDocument>();
+        let mut schema = serde_json::to_value(root).expect("포트폴리오 스키마는 항상 직렬화된다");
+        if let Value::Object(object) = &mut schema {
+            object.insert("$id".to_string(), Value::from(PORTFOLIO_SCHEMA_ID));
+        }
+        schema
+    })
+}
+
+/// 문서 전체를 검사한다. 틀린 곳이 없으면 빈 목록이다.
+pub fn validate_portfolio_document(value: &Value) -> Vec<PortfolioValidationIssue> {
+    let root = portfolio_document_schema();
+    let mut validator = Validator {
+        root,
+        issues: Vec::new(),
+    };
+    validator.check(root, value, "");
+    validator.issues
+}
+
+/// 섹션 하나를 검사한다. 오류 위치는 문서 기준이다(`meta`는 `/slug`, `/version`).
+pub fn validate_portfolio_section(
+    section_key: &str,
+    value: &Value,
+) -> Result<Vec<PortfolioValidationIssue>, PortfolioContentError> {
+    let root = portfolio_document_schema();
+    let (schema, pointer) = match section_key {
+        "meta" => (
+            json!({ "$ref": "#/definitions/PortfolioMeta" }),
+            String::new(),
+        ),
+        "identity" | "featured_projects" | "career" | "intro" => (
+            root["properties"][section_key].clone(),
+            format!("/{}", section_key),
+        ),
+        _ => {
+            return Err(PortfolioContentError::Invalid(format!(
+                "알 수 없는 섹션: {}",
+                section_key
+            )))
+        }
+    };
+
+    let mut validator = Validator {
+        root,
+        issues: Vec::new(),
+    };
+    validator.check(&schema, value, &pointer);
+    Ok(validator.issues)
+}
+
+/// 섹션을 검사해 틀린 곳이 있으면 거절한다.
+pub fn check_portfolio_section(
+    section_key: &str,
+    value: &Value,
+) -> Result<(), PortfolioContentError> {
+    let issues = validate_portfolio_section(section_key, value)?;
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(PortfolioContentError::Rejected(issues))
+    }
+}
+
+/// 요청 본문을 검사한 뒤 문서로 읽는다. 틀린 곳은 하나씩이 아니라 한 번에 모두 돌려준다.
+pub fn parse_portfolio_document(value: Value) -> Result<PortfolioDocument, PortfolioContentError> {
+    let issues = validate_portfolio_document(&value);
+    if !issues.is_empty() {
+        return Err(PortfolioContentError::Rejected(issues));
+    }
+
+    serde_json::from_value(value).map_err(|err| {
+        PortfolioContentError::Rejected(vec![PortfolioValidationIssue {
+            pointer: String::new(),
+            message: err.to_string(),
+        }])
+    })
+}
+
+/// 저장 직전의 문서를 다시 검사한다. 가져오기나 복원처럼 요청 본문을 거치지 않는 저장도 같은 규칙을 따른다.
+pub fn ensure_valid_document(document: &PortfolioDocument) -> Result<(), PortfolioContentError> {
+    let value = serde_json::to_value(document)
+        .map_err(|err| PortfolioContentError::Invalid(err.to_string()))?;
+    let issues = validate_portfolio_document(&value);
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(PortfolioContentError::Rejected(issues))
+    }
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    issues: Vec<PortfolioValidationIssue>,
+}
+
+fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64()
+                || value.is_u64()
+                || value.as_f64().is_some_and(|number| number.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_label(expected: &str) -> &str {
+    match expected {
+        "null" => "null",
+        "boolean" => "true/false",
+        "object" => "객체",
+        "array" => "배열",
+        "string" => "문자열",
+        "number" => "숫자",
+        "integer" => "정수",
+        other => other,
+    }
+}
+
+fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !value.chars().any(char::is_whitespace)
+}
+
+fn is_allowed_url(value: &str) -> bool {
+    Url::parse(value.trim()).is_ok_and(|url| URL_SCHEMES.contains(&url.scheme()))
+}
+
+/// `/images/a.png`처럼 `/` 하나로 시작하는 사이트 기준 경로. `//host`는 다른 사이트라 받지 않는다.
+fn is_root_relative_path(value: &str) -> bool {
+    value.starts_with('/')
+        && !value.starts_with("//")
+        && !value.contains('\\')
+        && !value.chars().any(|ch| ch.is_whitespace() || ch.is_control())
+}
+
+fn format_error(format: &str, value: &Value) -> Option<String> {
+    match (format, value) {
+        ("uri", Value::String(url)) => (!is_allowed_url(url)).then(|| {
+            format!(
+                "올바른 URL이 아닙니다 ({}만 쓸 수 있습니다).",
+                URL_SCHEMES.join(", ")
+            )
+        }),
+        ("portfolio-url", Value::String(url)) => {
+            (!is_allowed_url(url) && !is_root_relative_path(url)).then(|| {
+                format!(
+                    "올바른 URL이 아닙니다 ({} URL이나 /로 시작하는 경로만 쓸 수 있습니다).",
+                    URL_SCHEMES.join(", ")
+                )
+            })
+        }
+        ("email", Value::String(email)) => {
+            (!is_email(email)).then(|| "올바른 이메일 주소가 아닙니다.".to_string())
+        }
+        ("portfolio-period", Value::String(period)) => parse_period(period).is_none().then(|| {
+            "기간을 읽을 수 없습니다 (예: 2020.01 - 2022.12, 2023.03 - 현재).".to_string()
+        }),
+        ("portfolio-slug", Value::String(slug)) => (!is_valid_portfolio_slug(slug))
+            .then(|| "영문 소문자, 숫자, '-'만 쓸 수 있습니다.".to_string()),
+        ("int32", value) => value
+            .as_i64()
+            .filter(|number| i32::try_from(*number).is_err())
+            .map(|_| "정수 범위를 벗어났습니다.".to_string()),
+        _ => None,
+    }
+}
+
+impl<'a> Validator<'a> {
+    fn push(&mut self, pointer: &str, message: impl Into<String>) {
+        self.issues.push(PortfolioValidationIssue {
+            pointer: pointer.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn resolve(&self, reference: &str) -> Option<&'a Value> {
+        let root = self.root;
+        reference
+            .strip_prefix('#')
+            .and_then(|pointer| root.pointer(pointer))
+    }
+
+    fn check(&mut self, schema: &Value, value: &Value, pointer: &str) {
+        let Value::Object(schema) = schema else {
+            return;
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            if let Some(target) = self.resolve(reference) {
+                self.check(target, value, pointer);
+            }
+            return;
+        }
+
+        if let Some(Value::Array(all_of)) = schema.get("allOf") {
+            for sub_schema in all_of {
+                self.check(sub_schema, value, pointer);
+            }
+        }
+
+        if let Some(Value::Array(any_of)) = schema.get("anyOf") {
+            self.check_any_of(any_of, value, pointer);
+        }
+
+        if !self.check_type(schema, value, pointer) {
+            return;
+        }
+
+        match value {
+            Value::String(text) => self.check_string(schema, text, pointer),
+            Value::Number(_) => {
+                if let (Some(minimum), Some(number)) = (
+                    schema.get("minimum").and_then(Value::as_f64),
+                    value.as_f64(),
+                ) {
+                    if number < minimum {
+                        self.push(pointer, format!("{} 이상이어야 합니다.", minimum));
+                    }
+                }
+            }
+            Value::Object(object) => self.check_object(schema, object, pointer),
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        self.check(item_schema, item, &format!("{}/{}", pointer, index));
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(format) = schema.get("format").and_then(Value::as_str) {
+            if let Some(message) = format_error(format, value) {
+                self.push(pointer, message);
+            }
+        }
+    }
+
+    /// 통과하는 갈래가 없으면 값의 타입을 받는 갈래의 오류를 보고한다. `Option` 필드가 이 경우다.
+    fn check_any_of(&mut self, any_of: &[Value], value: &Value, pointer: &str) {
+        let mut reported: Option<(bool, Vec<PortfolioValidationIssue>)> = None;
+        for sub_schema in any_of {
+            let mut branch = Validator {
+                root: self.root,
+                issues: Vec::new(),
+            };
+            branch.check(sub_schema, value, pointer);
+            if branch.issues.is_empty() {
+                return;
+            }
+            let accepts = self.accepts_type(sub_schema, value);
+            if reported.is_none() || accepts && !reported.as_ref().is_some_and(|(found, _)| *found)
+            {
+                reported = Some((accepts, branch.issues));
+            }
+        }
+        if let Some((_, issues)) = reported {
+            self.issues.extend(issues);
+        }
+    }
+
+    fn accepts_type(&self, schema: &Value, value: &Value) -> bool {
+        let schema = match schema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => self.resolve(reference).unwrap_or(schema),
+            None => schema,
+        };
+        match schema.get("type") {
+            Some(Value::String(expected)) => type_matches(expected, value),
+            Some(Value::Array(expected)) => expected
+                .iter()
+                .filter_map(Value::as_str)
+                .any(|expected| type_matches(expected, value)),
+            _ => true,
+        }
+    }
+
+    fn check_type(&mut self, schema: &Map<String, Value>, value: &Value, pointer: &str) -> bool {
+        let expected: Vec<&str> = match schema.get("type") {
+            Some(Value::String(expected)) => vec![expected.as_str()],
+            Some(Value::Array(expected)) => expected.iter().filter_map(Value::as_str).collect(),
+            _ => return true,
+        };
+        if expected
+            .iter()
+            .any(|expected| type_matches(expected, value))
+        {
+            return true;
+        }
+
+        let labels: Vec<&str> = expected
+            .iter()
+            .map(|expected| type_label(expected))
+            .collect();
+        self.push(
+            pointer,
+            format!("{} 형식이어야 합니다.", labels.join(" 또는 ")),
+        );
+        false
+    }
+
+    fn check_string(&mut self, schema: &Map<String, Value>, text: &str, pointer: &str) {
+        let length = text.trim().chars().count() as u64;
+        if let Some(min_length) = schema.get("minLength").and_then(Value::as_u64) {
+            if length < min_length {
+                let message = if min_length == 1 {
+                    "비어 있을 수 없습니다.".to_string()
+                } else {
+                    format!("{}자 이상이어야 합니다.", min_length)
+                };
+                self.push(pointer, message);
+            }
+        }
+        if let Some(max_length) = schema.get("maxLength").and_then(Value::as_u64) {
+            if text.chars().count() as u64 > max_length {
+                self.push(pointer, format!("{}자 이하여야 합니다.", max_length));
+            }
+        }
+    }
+
+    fn check_object(
+        &mut self,
+        schema: &Map<String, Value>,
+        object: &Map<String, Value>,
+        pointer: &str,
+    ) {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for field in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(field) {
+                    self.push(
+                        &format!("{}/{}", pointer, escape_pointer_token(field)),
+                        "필수 항목입니다.",
+                    );
+                }
+            }
+        }
+
+        if let Some(Value::Object(properties)) = schema.get("properties") {
+            for (field, property_schema) in properties {
+                if let Some(field_value) = object.get(field) {
+                    self.check(
+                        property_schema,
+                        field_value,
+                        &format!("{}/{}", pointer, escape_pointer_token(field)),
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn valid_document() -> Value {
+        json!({
+            "slug": "dev",
+            "version": 1,
+            "identity": {
+                "name": "TYANGE",
+                "email": "me@example.com",
+                "github_url": "https://github.com/tyange"
+            },
+            "featured_projects": [{
+                "slug": "cms",
+                "title": "CMS",
+                "period": "2023.01 - 현재",
+                "summary": "블로그 백엔드",
+                "links": [{ "label": "GitHub", "url": "https://github.com/tyange/cms" }]
+            }],
+            "career": {
+                "summary_label": "경력",
+                "summary_value": "4년",
+                "companies": [{
+                    "company": "회사",
+                    "period": "2020.01 - 2022.12",
+                    "employment_type": "정규직",
+                    "role": "개발",
+                    "position": "사원",
+                    "items": [{ "title": "운영", "period": null, "bullets": [] }]
+                }]
+            },
+            "intro": null
+        })
+    }
+
+    fn pointers(issues: &[PortfolioValidationIssue]) -> Vec<&str> {
+        issues.iter().map(|issue| issue.pointer.as_str()).collect()
+    }
+
+    #[test]
+    fn schema_is_derived_from_the_models() {
+        let schema = portfolio_document_schema();
+
+        assert_eq!(schema["$id"], PORTFOLIO_SCHEMA_ID);
+        assert_eq!(schema["title"], "PortfolioDocument");
+        assert!(
+            schema["definitions"]["PortfolioProject"]["properties"]["period"]["format"]
+                .as_str()
+                .is_some_and(|format| format == "portfolio-period")
+        );
+        assert_eq!(
+            schema["definitions"]["PortfolioLink"]["properties"]["url"]["format"],
+            "uri"
+        );
+        assert!(schema["definitions"]["PortfolioMeta"].is_object());
+    }
+
+    #[test]
+    fn valid_document_has_no_issues() {
+        assert_eq!(validate_portfolio_document(&valid_document()), Vec::new());
+        assert!(parse_portfolio_document(valid_document()).is_ok());
+    }
+
+    #[test]
+    fn every_issue_is_reported_with_its_pointer() {
+        let mut document = valid_document();
+        document["version"] = json!("1");
+        document["identity"]
+            .as_object_mut()
+            .unwrap()
+            .remove("email");
+        document["featured_projects"][0]["slug"] = json!("  ");
+        document["featured_projects"][0]["period"] = json!("작년 봄");
+        document["featured_projects"][0]["links"][0]["url"] = json!("javascript:alert(1)");
+        document["career"]["companies"][0]["items"][0]["period"] = json!("2021/01");
+        document["intro"] = json!({ "content": 3 });
+
+        let issues = validate_portfolio_document(&document);
+        let mut found = pointers(&issues);
+        found.sort();
+
+        assert_eq!(
+            found,
+            vec![
+                "/career/companies/0/items/0/period",
+                "/featured_projects/0/links/0/url",
+                "/featured_projects/0/period",
+                "/featured_projects/0/slug",
+                "/identity/email",
+                "/intro/content",
+                "/version",
+            ]
+        );
+        let message_at = |pointer: &str| {
+            issues
+                .iter()
+                .find(|issue| issue.pointer == pointer)
+                .map(|issue| issue.message.as_str())
+        };
+        assert_eq!(message_at("/version"), Some("정수 형식이어야 합니다."));
+        assert_eq!(message_at("/identity/email"), Some("필수 항목입니다."));
+        assert!(matches!(
+            parse_portfolio_document(document),
+            Err(PortfolioContentError::Rejected(found)) if found.len() == 7
+        ));
+    }
+
+    #[test]
+    fn sections_are_reported_at_their_document_path() {
+        let issues = validate_portfolio_section(
+            "featured_projects",
+            &json!([{ "slug": "", "title": "x", "period": "2020", "summary": "" }]),
+        )
+        .unwrap();
+        assert_eq!(pointers(&issues), vec!["/featured_projects/0/slug"]);
+
+        let issues =
+            validate_portfolio_section("meta", &json!({ "slug": "Dev", "version": 0 })).unwrap();
+        let mut found = pointers(&issues);
+        found.sort();
+        assert_eq!(found, vec!["/slug", "/version"]);
+
+        assert!(validate_portfolio_section("unknown", &json!({})).is_err());
+    }
+
+    #[test]
+    fn icon_and_github_urls_accept_site_relative_paths() {
+        let mut document = valid_document();
+        document["identity"]["github_url"] = json!("/about");
+        document["intro"] = json!({
+            "content": "소개",
+            "tech_stack": [
+                { "name": "Rust", "icon_url": "/images/rust.svg" },
+                { "name": "Poem", "icon_url": "" }
+            ]
+        });
+        assert_eq!(validate_portfolio_document(&document), Vec::new());
+
+        document["intro"]["tech_stack"][0]["icon_url"] = json!("//evil.example.com/x.svg");
+        document["intro"]["tech_stack"][1]["icon_url"] = json!("images/poem.svg");
+        document["featured_projects"][0]["links"][0]["url"] = json!("/images/cms.png");
+        let issues = validate_portfolio_document(&document);
+        let mut found = pointers(&issues);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                "/featured_projects/0/links/0/url",
+                "/intro/tech_stack/0/icon_url",
+                "/intro/tech_stack/1/icon_url",
+            ]
+        );
+    }
+}
@@ -1,62 +1,154 @@
 pub mod add_user;
+pub mod cancel_my_account_deletion;
+pub mod confirm_my_totp_enrollment;
+pub mod confirm_password_reset;
 pub mod create_api_key;
 pub mod create_budget_plan;
 pub mod create_match;
 pub mod create_match_message;
+pub mod create_my_data_export;
+pub mod create_my_passkey;
+pub mod create_my_passkey_options;
+pub mod create_passkey_login_options;
 pub mod create_rss_source;
 pub mod create_spending;
+pub mod create_upload_session;
+pub mod delete_admin_user;
 pub mod delete_all_spending;
 pub mod delete_api_key;
+pub mod delete_attachment;
+pub mod delete_my_account;
 pub mod delete_my_match;
+pub mod delete_my_passkey;
+pub mod delete_my_session;
+pub mod delete_image;
+pub mod delete_login_lockout;
 pub mod delete_portfolio;
+pub mod delete_portfolio_draft;
 pub mod delete_post;
 pub mod delete_push_subscription;
 pub mod delete_rss_subscription;
 pub mod delete_spending;
+pub mod delete_upload_session;
+pub mod diff_portfolio_versions;
+pub mod disable_my_totp;
+pub mod download_attachment;
+pub mod download_data_export;
+pub mod export_portfolio_json_resume;
+pub mod finalize_upload_session;
+pub mod force_admin_user_password_reset;
+pub mod get_admin_user;
+pub mod get_admin_users;
 pub mod get_all_posts;
 pub mod get_api_keys;
+pub mod get_attachments;
 pub mod get_budget;
 pub mod get_count_with_tags;
 pub mod get_feed_items;
+pub mod get_image;
+pub mod get_image_detail;
+pub mod get_image_orphans;
+pub mod get_images;
+pub mod get_login_audit_events;
+pub mod get_login_lockouts;
 pub mod get_match_messages;
+pub mod get_my_data_export;
 pub mod get_my_match;
+pub mod get_my_passkeys;
+pub mod get_my_sessions;
+pub mod get_my_two_factor;
 pub mod get_portfolio;
+pub mod get_portfolio_draft;
+pub mod get_portfolio_html;
+pub mod get_portfolio_pdf;
+pub mod get_portfolio_schema;
+pub mod get_portfolio_version;
+pub mod get_portfolio_versions;
+pub mod get_portfolios;
 pub mod get_post;
 pub mod get_posts;
 pub mod get_posts_with_tags;
 pub mod get_push_public_key;
 pub mod get_push_subscriptions;
 pub mod get_rss_sources;
+pub mod get_security_settings;
 pub mod get_spending;
 pub mod get_tags_with_category;
+pub mod get_upload_session;
+pub mod import_portfolio_json_resume;
 pub mod import_spending_excel;
 pub mod login;
 pub mod login_google;
+pub mod login_passkey;
+pub mod login_two_factor;
+pub mod logout;
 pub mod match_utils;
 pub mod me;
+pub mod preview_portfolio;
+pub mod publish_portfolio;
+pub mod refresh;
+pub mod regenerate_my_recovery_codes;
+pub mod request_password_reset;
+pub mod reset_admin_user_two_factor;
 pub mod respond_match;
+pub mod restore_portfolio_version;
+pub mod run_image_gc;
+pub mod run_image_hash_backfill;
 pub mod signup;
+pub mod start_my_totp_enrollment;
 pub mod update_active_budget;
+pub mod update_admin_user;
+pub mod update_attachment;
+pub mod update_image;
+pub mod update_my_password;
+pub mod update_my_passkey;
 pub mod update_my_profile;
-pub mod update_portfolio;
-pub mod update_portfolio_section;
+pub mod update_portfolio_draft;
+pub mod update_portfolio_draft_section;
 pub mod update_post;
+pub mod update_security_settings;
 pub mod update_spending;
+pub mod upload_attachment;
 pub mod upload_image;
+pub mod upload_images_batch;
 pub mod upload_post;
+pub mod upload_session_chunk;
 pub mod upsert_push_subscription;
+pub mod validate_portfolio;
 
+#[cfg(test)]
+mod account_deletion_test;
+#[cfg(test)]
+mod admin_users_test;
+#[cfg(test)]
+mod attachments_test;
 #[cfg(test)]
 mod budget_spending_scope_test;
 #[cfg(test)]
+mod data_export_test;
+#[cfg(test)]
 mod feed_items_test;
 #[cfg(test)]
+mod image_library_test;
+#[cfg(test)]
+mod login_throttle_test;
+#[cfg(test)]
 mod match_flow_test;
 #[cfg(test)]
+mod passkey_test;
+#[cfg(test)]
+mod password_test;
+#[cfg(test)]
 mod post_authorization_test;
 #[cfg(test)]
 mod portfolio_routes_test;
 #[cfg(test)]
+mod session_test;
+#[cfg(test)]
 mod signup_test;
 #[cfg(test)]
+mod two_factor_test;
+#[cfg(test)]
 mod upload_image_test;
+#[cfg(test)]
+mod upload_session_test;
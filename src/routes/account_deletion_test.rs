@@ -0,0 +1,378 @@
+use std::{env, sync::Arc};
+
+use poem::{delete, get, http::StatusCode, post, test::TestClient, Endpoint, EndpointExt, Route};
+use serde_json::json;
+use sqlx::{query, query_scalar, SqlitePool};
+
+use crate::{
+    account_deletion::{purge_due_accounts, AccountDeletionSettings, PostDeletionPolicy},
+    db::init_db,
+    middlewares::auth_middleware::Auth,
+    models::AppState,
+    routes::{
+        cancel_my_account_deletion::cancel_my_account_deletion,
+        delete_my_account::delete_my_account, login::login, me::me, signup::signup,
+    },
+};
+
+async fn create_test_state() -> Arc<AppState> {
+    env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+    env::set_var("JWT_REFRESH_SECRET", "test-refresh-secret");
+
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    init_db(&db).await.expect("failed to init db");
+    Arc::new(AppState::new(db))
+}
+
+fn create_app(state: Arc<AppState>) -> impl Endpoint {
+    Route::new()
+        .at("/signup", post(signup))
+        .at("/login", post(login))
+        .at("/me", get(me).delete(delete_my_account).with(Auth))
+        .at(
+            "/me/deletion",
+            delete(cancel_my_account_deletion).with(Auth),
+        )
+        .data(state)
+}
+
+async fn signup_and_login(
+    cli: &TestClient<impl Endpoint>,
+    db: &SqlitePool,
+    email: &str,
+    role: &str,
+) -> String {
+    cli.post("/signup")
+        .body_json(&json!({ "email": email, "password": "farewell-pass-1" }))
+        .send()
+        .await
+        .assert_status_is_ok();
+    query("UPDATE users SET user_role = ? WHERE user_id = ?")
+        .bind(role)
+        .bind(email)
+        .execute(db)
+        .await
+        .expect("failed to set role");
+
+    let login_response = cli
+        .post("/login")
+        .body_json(&json!({ "user_id": email, "password": "farewell-pass-1" }))
+        .send()
+        .await;
+    login_response.assert_status_is_ok();
+    login_response
+        .json()
+        .await
+        .value()
+        .object()
+        .get("access_token")
+        .string()
+        .to_string()
+}
+
+async fn insert_user(db: &SqlitePool, user_id: &str, role: &str) {
+    query("INSERT INTO users (user_id, password, user_role) VALUES (?, 'unused', ?)")
+        .bind(user_id)
+        .bind(role)
+        .execute(db)
+        .await
+        .expect("failed to insert user");
+}
+
+/// 탈퇴할 계정과 남을 계정 양쪽에 딸린 행을 만든다.
+async fn seed_owned_rows(db: &SqlitePool, leaving: &str, staying: &str) {
+    for (post_id, writer_id) in [("leaving-post", leaving), ("staying-post", staying)] {
+        query(
+            "INSERT INTO posts (post_id, title, content, writer_id, status) VALUES (?, ?, '', ?, 'draft')",
+        )
+        .bind(post_id)
+        .bind(post_id)
+        .bind(writer_id)
+        .execute(db)
+        .await
+        .expect("failed to insert post");
+    }
+
+    for user_id in [leaving, staying] {
+        query(
+            "INSERT INTO spending_records (owner_user_id, amount, merchant, transacted_at) VALUES (?, 1000, 'cafe', '2026-03-01 09:00:00')",
+        )
+        .bind(user_id)
+        .execute(db)
+        .await
+        .expect("failed to insert spending");
+        query(
+            "INSERT INTO budget_periods (owner_user_id, total_budget, from_date, to_date) VALUES (?, 50000, '2026-03-01', '2026-03-31')",
+        )
+        .bind(user_id)
+        .execute(db)
+        .await
+        .expect("failed to insert budget");
+        query(
+            "INSERT INTO api_keys (user_id, name, key_lookup, key_hash) VALUES (?, 'cli', ?, 'hash')",
+        )
+        .bind(user_id)
+        .bind(format!("lookup-{}", user_id))
+        .execute(db)
+        .await
+        .expect("failed to insert api key");
+        query(
+            "INSERT INTO web_push_subscriptions (user_id, endpoint, p256dh, auth) VALUES (?, ?, 'p256dh', 'auth')",
+        )
+        .bind(user_id)
+        .bind(format!("https://push.example.com/{}", user_id))
+        .execute(db)
+        .await
+        .expect("failed to insert push subscription");
+    }
+
+    query(
+        "INSERT INTO rss_sources (source_id, feed_url, normalized_feed_url) VALUES ('feed', 'https://feed.example.com/rss', 'https://feed.example.com/rss')",
+    )
+    .execute(db)
+    .await
+    .expect("failed to insert rss source");
+    query("INSERT INTO user_rss_subscriptions (user_id, source_id) VALUES (?, 'feed')")
+        .bind(leaving)
+        .execute(db)
+        .await
+        .expect("failed to insert rss subscription");
+
+    let match_id = query(
+        "INSERT INTO user_matches (requester_user_id, target_user_id, status) VALUES (?, ?, 'matched')",
+    )
+    .bind(leaving)
+    .bind(staying)
+    .execute(db)
+    .await
+    .expect("failed to insert match")
+    .last_insert_rowid();
+    query(
+        "INSERT INTO match_messages (match_id, sender_user_id, receiver_user_id, content) VALUES (?, ?, ?, 'bye')",
+    )
+    .bind(match_id)
+    .bind(staying)
+    .bind(leaving)
+    .execute(db)
+    .await
+    .expect("failed to insert match message");
+}
+
+async fn count(db: &SqlitePool, sql: &str, user_id: &str) -> i64 {
+    query_scalar(sql)
+        .bind(user_id)
+        .fetch_one(db)
+        .await
+        .expect("failed to count rows")
+}
+
+async fn assert_only_staying_rows_remain(db: &SqlitePool, leaving: &str, staying: &str) {
+    for sql in [
+        "SELECT COUNT(*) FROM users WHERE user_id = ?",
+        "SELECT COUNT(*) FROM spending_records WHERE owner_user_id = ?",
+        "SELECT COUNT(*) FROM budget_periods WHERE owner_user_id = ?",
+        "SELECT COUNT(*) FROM api_keys WHERE user_id = ?",
+        "SELECT COUNT(*) FROM web_push_subscriptions WHERE user_id = ?",
+    ] {
+        assert_eq!(count(db, sql, leaving).await, 0, "{}", sql);
+        assert_eq!(count(db, sql, staying).await, 1, "{}", sql);
+    }
+    assert_eq!(
+        count(
+            db,
+            "SELECT COUNT(*) FROM user_rss_subscriptions WHERE user_id = ?",
+            leaving
+        )
+        .await,
+        0
+    );
+    assert_eq!(
+        count(
+            db,
+            "SELECT COUNT(*) FROM user_matches WHERE requester_user_id = ?1 OR target_user_id = ?1",
+            staying,
+        )
+        .await,
+        0
+    );
+    assert_eq!(
+        count(
+            db,
+            "SELECT COUNT(*) FROM match_messages WHERE sender_user_id = ?",
+            staying
+        )
+        .await,
+        0
+    );
+}
+
+async fn make_due(db: &SqlitePool, user_id: &str) {
+    query(
+        "UPDATE users SET deletion_requested_at = '2026-01-01 00:00:00', deletion_scheduled_at = '2026-01-15 00:00:00' WHERE user_id = ?",
+    )
+    .bind(user_id)
+    .execute(db)
+    .await
+    .expect("failed to backdate deletion");
+}
+
+#[tokio::test]
+async fn account_deletion_can_be_scheduled_and_cancelled_but_not_for_the_last_admin() {
+    let state = create_test_state().await;
+    let db = state.db.clone();
+    let cli = TestClient::new(create_app(state));
+
+    let token = signup_and_login(&cli, &db, "leaving@example.com", "user").await;
+    let scheduled = cli
+        .delete("/me")
+        .header("Authorization", &token)
+        .send()
+        .await;
+    scheduled.assert_status_is_ok();
+    let scheduled_json = scheduled.json().await;
+    let data = scheduled_json.value().object().get("data").object();
+    let scheduled_at = data.get("deletion_scheduled_at").string().to_string();
+    assert_eq!(data.get("post_policy").string(), "reassign");
+
+    // 다시 요청해도 처음 예약을 그대로 돌려준다.
+    let again = cli
+        .delete("/me")
+        .header("Authorization", &token)
+        .send()
+        .await;
+    again.assert_status_is_ok();
+    again
+        .json()
+        .await
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("deletion_scheduled_at")
+        .assert_string(&scheduled_at);
+
+    let me_response = cli.get("/me").header("Authorization", &token).send().await;
+    me_response.assert_status_is_ok();
+    me_response
+        .json()
+        .await
+        .value()
+        .object()
+        .get("deletion_scheduled_at")
+        .assert_string(&scheduled_at);
+
+    cli.delete("/me/deletion")
+        .header("Authorization", &token)
+        .send()
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    cli.delete("/me/deletion")
+        .header("Authorization", &token)
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    let scheduled_after_cancel: Option<String> =
+        query_scalar("SELECT deletion_scheduled_at FROM users WHERE user_id = ?")
+            .bind("leaving@example.com")
+            .fetch_one(&db)
+            .await
+            .expect("failed to read schedule");
+    assert_eq!(scheduled_after_cancel, None);
+
+    let admin_token = signup_and_login(&cli, &db, "admin@example.com", "admin").await;
+    cli.delete("/me")
+        .header("Authorization", &admin_token)
+        .send()
+        .await
+        .assert_status(StatusCode::CONFLICT);
+
+    insert_user(&db, "second-admin@example.com", "admin").await;
+    cli.delete("/me")
+        .header("Authorization", &admin_token)
+        .send()
+        .await
+        .assert_status_is_ok();
+}
+
+#[tokio::test]
+async fn purge_reassigns_posts_and_removes_dependent_rows() {
+    let state = create_test_state().await;
+    let db = state.db.clone();
+    insert_user(&db, "admin@example.com", "admin").await;
+    insert_user(&db, "leaving@example.com", "user").await;
+    insert_user(&db, "staying@example.com", "user").await;
+    seed_owned_rows(&db, "leaving@example.com", "staying@example.com").await;
+
+    let settings = AccountDeletionSettings {
+        grace_days: 14,
+        post_policy: PostDeletionPolicy::Reassign,
+        post_owner: None,
+    };
+
+    // 아직 기한 전이면 지우지 않는다.
+    query(
+        "UPDATE users SET deletion_requested_at = '2026-01-01 00:00:00', deletion_scheduled_at = '2999-01-01 00:00:00' WHERE user_id = ?",
+    )
+    .bind("leaving@example.com")
+    .execute(&db)
+    .await
+    .expect("failed to schedule deletion");
+    assert!(purge_due_accounts(&state, &settings)
+        .await
+        .expect("purge should run")
+        .is_empty());
+
+    make_due(&db, "leaving@example.com").await;
+    let purged = purge_due_accounts(&state, &settings)
+        .await
+        .expect("purge should run");
+    assert_eq!(purged, vec!["leaving@example.com".to_string()]);
+
+    assert_only_staying_rows_remain(&db, "leaving@example.com", "staying@example.com").await;
+    let writer: String = query_scalar("SELECT writer_id FROM posts WHERE post_id = 'leaving-post'")
+        .fetch_one(&db)
+        .await
+        .expect("reassigned post should remain");
+    assert_eq!(writer, "admin@example.com");
+}
+
+#[tokio::test]
+async fn purge_deletes_posts_under_delete_policy_and_keeps_the_last_admin() {
+    let state = create_test_state().await;
+    let db = state.db.clone();
+    insert_user(&db, "admin@example.com", "admin").await;
+    insert_user(&db, "leaving@example.com", "user").await;
+    insert_user(&db, "staying@example.com", "user").await;
+    seed_owned_rows(&db, "leaving@example.com", "staying@example.com").await;
+
+    let settings = AccountDeletionSettings {
+        grace_days: 14,
+        post_policy: PostDeletionPolicy::Delete,
+        post_owner: None,
+    };
+
+    make_due(&db, "leaving@example.com").await;
+    make_due(&db, "admin@example.com").await;
+    let purged = purge_due_accounts(&state, &settings)
+        .await
+        .expect("purge should run");
+    assert_eq!(purged, vec!["leaving@example.com".to_string()]);
+
+    assert_only_staying_rows_remain(&db, "leaving@example.com", "staying@example.com").await;
+    let remaining_posts: Vec<String> = query_scalar("SELECT post_id FROM posts ORDER BY post_id")
+        .fetch_all(&db)
+        .await
+        .expect("failed to list posts");
+    assert_eq!(remaining_posts, vec!["staying-post".to_string()]);
+    assert_eq!(
+        count(
+            &db,
+            "SELECT COUNT(*) FROM users WHERE user_id = ?",
+            "admin@example.com"
+        )
+        .await,
+        1
+    );
+}
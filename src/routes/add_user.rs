@@ -24,8 +24,8 @@ pub async fn create_user(
 
     query(
         r#"
-        INSERT INTO users (user_id, password, user_role, auth_provider)
-        VALUES (?, ?, ?, 'local')
+        INSERT INTO users (user_id, password, user_role, auth_provider, created_at)
+        VALUES (?, ?, ?, 'local', CURRENT_TIMESTAMP)
         "#,
     )
     .bind(user_id)
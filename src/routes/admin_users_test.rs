@@ -0,0 +1,419 @@
+use std::{env, sync::Arc};
+
+use poem::{get, http::StatusCode, post, test::TestClient, Endpoint, EndpointExt, Route};
+use serde_json::json;
+use sqlx::{query, query_scalar, SqlitePool};
+
+use crate::{
+    db::init_db,
+    middlewares::{admin_middleware::AdminOnly, auth_middleware::Auth},
+    models::AppState,
+    routes::{
+        add_user::create_user, delete_admin_user::delete_admin_user,
+        force_admin_user_password_reset::force_admin_user_password_reset,
+        get_admin_user::get_admin_user, get_admin_users::get_admin_users, login::login, me::me,
+        refresh::refresh, update_admin_user::update_admin_user,
+    },
+};
+
+const PASSWORD: &str = "manage-pass-1";
+
+async fn create_test_state() -> Arc<AppState> {
+    env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+    env::set_var("JWT_REFRESH_SECRET", "test-refresh-secret");
+
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    init_db(&db).await.expect("failed to init db");
+    Arc::new(AppState::new(db))
+}
+
+fn create_app(state: Arc<AppState>) -> impl Endpoint {
+    Route::new()
+        .at("/login", post(login))
+        .at("/refresh", post(refresh))
+        .at("/me", get(me).with(Auth))
+        .at(
+            "/admin/users",
+            get(get_admin_users).with(AdminOnly).with(Auth),
+        )
+        .at(
+            "/admin/users/:user_id",
+            get(get_admin_user)
+                .put(update_admin_user)
+                .delete(delete_admin_user)
+                .with(AdminOnly)
+                .with(Auth),
+        )
+        .at(
+            "/admin/users/:user_id/password-reset",
+            post(force_admin_user_password_reset)
+                .with(AdminOnly)
+                .with(Auth),
+        )
+        .data(state)
+}
+
+async fn login_tokens(cli: &TestClient<impl Endpoint>, user_id: &str) -> (String, String) {
+    let response = cli
+        .post("/login")
+        .body_json(&json!({ "user_id": user_id, "password": PASSWORD }))
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    let body = response.json().await;
+    let object = body.value().object();
+    (
+        object.get("access_token").string().to_string(),
+        object.get("refresh_token").string().to_string(),
+    )
+}
+
+#[tokio::test]
+async fn admins_can_search_users_and_see_keys_and_sessions() {
+    let state = create_test_state().await;
+    let db = state.db.clone();
+    create_user(&db, "root@example.com", PASSWORD, "admin")
+        .await
+        .unwrap();
+    for index in 0..3 {
+        create_user(
+            &db,
+            &format!("member{}@example.com", index),
+            PASSWORD,
+            "user",
+        )
+        .await
+        .unwrap();
+    }
+    query("UPDATE users SET display_name = 'Kim Searchable' WHERE user_id = 'member1@example.com'")
+        .execute(&db)
+        .await
+        .unwrap();
+    query("INSERT INTO api_keys (user_id, name, key_lookup, key_hash) VALUES ('member1@example.com', 'deploy', 'lookup', 'hash')")
+        .execute(&db)
+        .await
+        .unwrap();
+
+    let cli = TestClient::new(create_app(state));
+    let (admin_token, _) = login_tokens(&cli, "root@example.com").await;
+    let (member_token, _) = login_tokens(&cli, "member1@example.com").await;
+
+    cli.get("/admin/users")
+        .header("Authorization", &member_token)
+        .send()
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let page = cli
+        .get("/admin/users")
+        .query("limit", &2)
+        .query("offset", &0)
+        .header("Authorization", &admin_token)
+        .send()
+        .await;
+    page.assert_status_is_ok();
+    let page_json = page.json().await;
+    let data = page_json.value().object().get("data").object();
+    data.get("total_count").assert_i64(4);
+    let users = data.get("users").array();
+    users.assert_len(2);
+    // 최근 가입한 계정이 먼저 나온다.
+    users
+        .get(0)
+        .object()
+        .get("user_id")
+        .assert_string("member2@example.com");
+    assert!(!users.get(0).object().get("created_at").string().is_empty());
+
+    let searched = cli
+        .get("/admin/users")
+        .query("q", &"searchABLE")
+        .header("Authorization", &admin_token)
+        .send()
+        .await;
+    searched.assert_status_is_ok();
+    let searched_json = searched.json().await;
+    let searched_data = searched_json.value().object().get("data").object();
+    searched_data.get("total_count").assert_i64(1);
+    let found = searched_data.get("users").array().get(0).object();
+    found.get("user_id").assert_string("member1@example.com");
+    found.get("auth_provider").assert_string("local");
+    assert!(!found.get("last_login_at").string().is_empty());
+
+    let admins = cli
+        .get("/admin/users")
+        .query("role", &"admin")
+        .header("Authorization", &admin_token)
+        .send()
+        .await;
+    admins
+        .json()
+        .await
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("total_count")
+        .assert_i64(1);
+
+    let detail = cli
+        .get("/admin/users/member1@example.com")
+        .header("Authorization", &admin_token)
+        .send()
+        .await;
+    detail.assert_status_is_ok();
+    let detail_json = detail.json().await;
+    let detail = detail_json.value().object();
+    detail.get("user_role").assert_string("user");
+    detail.get("api_keys").array().assert_len(1);
+    detail
+        .get("api_keys")
+        .array()
+        .get(0)
+        .object()
+        .get("name")
+        .assert_string("deploy");
+    let sessions = detail.get("sessions").array();
+    sessions.assert_len(1);
+    sessions.get(0).object().get("revoked_at").assert_null();
+
+    cli.get("/admin/users/missing@example.com")
+        .header("Authorization", &admin_token)
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    // 마지막 관리자는 스스로를 내릴 수 없고, 다른 관리자가 생기면 내릴 수 있다.
+    cli.put("/admin/users/root@example.com")
+        .header("Authorization", &admin_token)
+        .body_json(&json!({ "user_role": "user" }))
+        .send()
+        .await
+        .assert_status(StatusCode::CONFLICT);
+    cli.put("/admin/users/member0@example.com")
+        .header("Authorization", &admin_token)
+        .body_json(&json!({ "user_role": "owner" }))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    let promoted = cli
+        .put("/admin/users/member0@example.com")
+        .header("Authorization", &admin_token)
+        .body_json(&json!({ "user_role": "admin" }))
+        .send()
+        .await;
+    promoted.assert_status_is_ok();
+    promoted
+        .json()
+        .await
+        .value()
+        .object()
+        .get("user_role")
+        .assert_string("admin");
+    cli.put("/admin/users/root@example.com")
+        .header("Authorization", &admin_token)
+        .body_json(&json!({ "user_role": "user" }))
+        .send()
+        .await
+        .assert_status_is_ok();
+}
+
+#[tokio::test]
+async fn disabled_accounts_are_rejected_and_forced_resets_clear_the_password() {
+    let state = create_test_state().await;
+    let db = state.db.clone();
+    create_user(&db, "root@example.com", PASSWORD, "admin")
+        .await
+        .unwrap();
+    create_user(&db, "member@example.com", PASSWORD, "user")
+        .await
+        .unwrap();
+
+    let cli = TestClient::new(create_app(state));
+    let (admin_token, _) = login_tokens(&cli, "root@example.com").await;
+    let (member_token, member_refresh) = login_tokens(&cli, "member@example.com").await;
+
+    cli.put("/admin/users/root@example.com")
+        .header("Authorization", &admin_token)
+        .body_json(&json!({ "disabled": true }))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let disabled = cli
+        .put("/admin/users/member@example.com")
+        .header("Authorization", &admin_token)
+        .body_json(&json!({ "disabled": true, "disabled_reason": "스팸" }))
+        .send()
+        .await;
+    disabled.assert_status_is_ok();
+    let disabled_json = disabled.json().await;
+    disabled_json
+        .value()
+        .object()
+        .get("disabled_reason")
+        .assert_string("스팸");
+
+    // 아직 만료되지 않은 access token도 막히고, refresh와 로그인도 되지 않는다.
+    cli.get("/me")
+        .header("Authorization", &member_token)
+        .send()
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    let refreshed = cli
+        .post("/refresh")
+        .body_json(&json!({ "refresh_token": member_refresh }))
+        .send()
+        .await;
+    refreshed.assert_status(StatusCode::FORBIDDEN);
+    refreshed.assert_text("비활성화된 계정입니다. 관리자에게 문의해 주세요.").await;
+    cli.post("/login")
+        .body_json(&json!({ "user_id": "member@example.com", "password": PASSWORD }))
+        .send()
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let disabled_list = cli
+        .get("/admin/users")
+        .query("status", &"disabled")
+        .header("Authorization", &admin_token)
+        .send()
+        .await;
+    disabled_list
+        .json()
+        .await
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("total_count")
+        .assert_i64(1);
+
+    cli.put("/admin/users/member@example.com")
+        .header("Authorization", &admin_token)
+        .body_json(&json!({ "disabled": false }))
+        .send()
+        .await
+        .assert_status_is_ok();
+    let (member_token, _) = login_tokens(&cli, "member@example.com").await;
+    cli.get("/me")
+        .header("Authorization", &member_token)
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    cli.post("/admin/users/member@example.com/password-reset")
+        .header("Authorization", &admin_token)
+        .send()
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    cli.post("/login")
+        .body_json(&json!({ "user_id": "member@example.com", "password": PASSWORD }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    let live_sessions: i64 = query_scalar(
+        "SELECT COUNT(*) FROM refresh_token_families WHERE user_id = 'member@example.com' AND revoked_at IS NULL",
+    )
+    .fetch_one(&db)
+    .await
+    .unwrap();
+    assert_eq!(live_sessions, 0);
+    let reset_mail: i64 = query_scalar(
+        "SELECT COUNT(*) FROM email_outbox WHERE recipient = 'member@example.com' AND kind = 'password_reset'",
+    )
+    .fetch_one(&db)
+    .await
+    .unwrap();
+    assert_eq!(reset_mail, 1);
+
+    cli.delete("/admin/users/member@example.com")
+        .header("Authorization", &admin_token)
+        .send()
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    cli.get("/admin/users/member@example.com")
+        .header("Authorization", &admin_token)
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    cli.delete("/admin/users/root@example.com")
+        .header("Authorization", &admin_token)
+        .send()
+        .await
+        .assert_status(StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn demoted_admins_lose_admin_access_before_their_tokens_expire() {
+    let state = create_test_state().await;
+    let db = state.db.clone();
+    create_user(&db, "root@example.com", PASSWORD, "admin")
+        .await
+        .unwrap();
+    create_user(&db, "second@example.com", PASSWORD, "admin")
+        .await
+        .unwrap();
+
+    let cli = TestClient::new(create_app(state));
+    let (root_token, _) = login_tokens(&cli, "root@example.com").await;
+    let (second_token, second_refresh) = login_tokens(&cli, "second@example.com").await;
+
+    cli.put("/admin/users/second@example.com")
+        .header("Authorization", &root_token)
+        .body_json(&json!({ "user_role": "user" }))
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    // 역할이 바뀌면 세션이 끊기므로 예전 토큰으로는 관리자 API도, 갱신도 쓸 수 없다.
+    cli.get("/admin/users")
+        .header("Authorization", &second_token)
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    cli.post("/refresh")
+        .body_json(&json!({ "refresh_token": second_refresh }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    let revoked_reason: String = query_scalar(
+        "SELECT revoked_reason FROM refresh_token_families WHERE user_id = 'second@example.com'",
+    )
+    .fetch_one(&db)
+    .await
+    .unwrap();
+    assert_eq!(revoked_reason, "role_changed");
+
+    // 세션이 살아 있어도 DB의 역할이 관리자가 아니면 토큰의 역할 claim을 믿지 않는다.
+    query("UPDATE users SET user_role = 'user' WHERE user_id = 'root@example.com'")
+        .execute(&db)
+        .await
+        .unwrap();
+    cli.get("/admin/users")
+        .header("Authorization", &root_token)
+        .send()
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn routes_without_app_state_fail_closed() {
+    let state = create_test_state().await;
+    create_user(&state.db, "member@example.com", PASSWORD, "user")
+        .await
+        .unwrap();
+    let cli = TestClient::new(create_app(state));
+    let (member_token, _) = login_tokens(&cli, "member@example.com").await;
+
+    // `AppState`를 붙이지 않은 라우트는 비활성화 여부를 확인할 수 없으니 통과시키지 않는다.
+    let stateless = TestClient::new(Route::new().at("/me", get(me).with(Auth)));
+    stateless
+        .get("/me")
+        .header("Authorization", &member_token)
+        .send()
+        .await
+        .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+}
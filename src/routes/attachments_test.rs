@@ -0,0 +1,280 @@
+use std::{env, sync::Arc};
+
+use poem::{
+    get, http::StatusCode, test::TestClient, test::TestForm, test::TestFormField, Endpoint,
+    EndpointExt, Route,
+};
+use serde_json::json;
+use sqlx::{query, query_scalar, SqlitePool};
+
+use crate::{
+    db::init_db,
+    middlewares::auth_middleware::Auth,
+    models::AppState,
+    routes::{
+        delete_attachment::delete_attachment, download_attachment::download_attachment,
+        get_attachments::get_attachments, get_image::get_image,
+        update_attachment::update_attachment, upload_attachment::upload_attachment,
+    },
+    storage::{MemoryStorage, UploadStorage},
+};
+use tyange_cms_api::auth::jwt::Claims;
+
+const PDF_BYTES: &[u8] = b"%PDF-1.7\n1 0 obj\n<<>>\nendobj\ntrailer\n<<>>\n%%EOF\n";
+
+async fn create_test_state() -> (Arc<AppState>, Arc<MemoryStorage>) {
+    env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    init_db(&db).await.expect("failed to init db");
+
+    for (post_id, writer_id) in [("post-1", "writer-1"), ("post-2", "writer-2")] {
+        query(
+            "INSERT INTO posts (post_id, title, content, writer_id, status) VALUES (?, ?, '', ?, 'published')",
+        )
+        .bind(post_id)
+        .bind(post_id)
+        .bind(writer_id)
+        .execute(&db)
+        .await
+        .expect("failed to insert post");
+    }
+
+    let storage = Arc::new(MemoryStorage::default());
+    (
+        Arc::new(AppState::new_with_storage(db, storage.clone())),
+        storage,
+    )
+}
+
+fn create_test_app(state: Arc<AppState>) -> impl Endpoint {
+    Route::new()
+        .at(
+            "/attachments",
+            get(get_attachments.with(Auth)).post(upload_attachment.with(Auth)),
+        )
+        .at(
+            "/attachments/:attachment_id",
+            poem::put(update_attachment)
+                .delete(delete_attachment)
+                .with(Auth),
+        )
+        .at(
+            "/attachments/:attachment_id/download",
+            get(download_attachment),
+        )
+        .at("/images/:file_name", get(get_image))
+        .data(state)
+}
+
+fn issue_access_token(user_id: &str, role: &str) -> String {
+    Claims::create_access_token(user_id, role, b"test-access-secret")
+        .expect("failed to create access token")
+}
+
+async fn upload(
+    cli: &TestClient<impl Endpoint>,
+    token: &str,
+    query_string: &str,
+    file_name: &str,
+    bytes: &[u8],
+) -> poem::test::TestResponse {
+    cli.post(format!("/attachments{}", query_string))
+        .header("Authorization", token)
+        .multipart(
+            TestForm::new().field(
+                TestFormField::bytes(bytes.to_vec())
+                    .name("file")
+                    .filename(file_name)
+                    .content_type("application/octet-stream"),
+            ),
+        )
+        .send()
+        .await
+}
+
+#[tokio::test]
+async fn attachment_upload_list_and_download_counts() {
+    let (state, storage) = create_test_state().await;
+    let cli = TestClient::new(create_test_app(state.clone()));
+    let writer = issue_access_token("writer-1", "user");
+
+    let response = upload(&cli, &writer, "?post_id=post-1", "slides v2.pdf", PDF_BYTES).await;
+    response.assert_status_is_ok();
+    let json = response.json().await;
+    let data = json.value().object().get("data").object();
+    data.get("post_id").assert_string("post-1");
+    data.get("kind").assert_string("document");
+    data.get("mime_type").assert_string("application/pdf");
+    data.get("byte_size").assert_i64(PDF_BYTES.len() as i64);
+    let attachment_id = data.get("attachment_id").string().to_string();
+    let download_path = data.get("download_path").string().to_string();
+
+    let file_name: String =
+        query_scalar("SELECT file_name FROM attachments WHERE attachment_id = ?")
+            .bind(&attachment_id)
+            .fetch_one(&state.db)
+            .await
+            .expect("failed to fetch attachment");
+    assert_eq!(
+        storage.get(&file_name).await.unwrap().as_deref(),
+        Some(PDF_BYTES)
+    );
+
+    let response = cli.get(&download_path).send().await;
+    response.assert_status_is_ok();
+    response.assert_header("content-type", "application/pdf");
+    response.assert_header(
+        "content-disposition",
+        "attachment; filename=\"slides v2.pdf\"; filename*=UTF-8''slides%20v2.pdf",
+    );
+    response.assert_bytes(PDF_BYTES).await;
+
+    let response = cli
+        .get(format!("{}?inline=true", download_path))
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    let disposition = response
+        .0
+        .headers()
+        .get("content-disposition")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    assert!(disposition.starts_with("inline;"));
+
+    // 이어 받기 요청은 새 다운로드로 세지 않는다.
+    cli.get(&download_path)
+        .header("Range", "bytes=10-")
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    // 첨부 파일은 이미지 경로로 꺼낼 수 없다.
+    cli.get(format!("/images/{}", file_name))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let response = cli
+        .get("/attachments?post_id=post-1")
+        .header("Authorization", &writer)
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    let json = response.json().await;
+    let data = json.value().object().get("data").object();
+    data.get("total_count").assert_i64(1);
+    let attachment = data.get("attachments").array().get(0).object();
+    attachment
+        .get("attachment_id")
+        .assert_string(&attachment_id);
+    attachment.get("download_count").assert_i64(2);
+    attachment.get("origin_name").assert_string("slides v2.pdf");
+}
+
+#[tokio::test]
+async fn attachment_upload_rejects_disallowed_mismatched_and_oversized_files() {
+    let (state, storage) = create_test_state().await;
+    let cli = TestClient::new(create_test_app(state.clone()));
+    let writer = issue_access_token("writer-1", "user");
+
+    upload(&cli, &writer, "", "setup.exe", b"MZ\x90\x00\x03")
+        .await
+        .assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    upload(
+        &cli,
+        &writer,
+        "",
+        "sample.zip",
+        b"<html><script></script></html>",
+    )
+    .await
+    .assert_status(StatusCode::BAD_REQUEST);
+
+    env::set_var("ATTACHMENT_MAX_BYTES_AUDIO", "16");
+    let mut clip = b"OggS".to_vec();
+    clip.extend_from_slice(&[0u8; 32]);
+    upload(&cli, &writer, "", "clip.ogg", &clip)
+        .await
+        .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    upload(&cli, &writer, "", "clip.ogg", &clip[..12])
+        .await
+        .assert_status_is_ok();
+
+    let count: i64 = query_scalar("SELECT COUNT(*) FROM attachments")
+        .fetch_one(&state.db)
+        .await
+        .expect("failed to count attachments");
+    assert_eq!(count, 1);
+    assert_eq!(storage.list().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn attachments_follow_post_ownership() {
+    let (state, storage) = create_test_state().await;
+    let cli = TestClient::new(create_test_app(state.clone()));
+    let writer = issue_access_token("writer-1", "user");
+    let other = issue_access_token("writer-2", "user");
+    let admin = issue_access_token("admin-1", "admin");
+
+    upload(&cli, &other, "?post_id=post-1", "slides.pdf", PDF_BYTES)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    // 글과 연결되지 않은 첨부 파일은 올린 사람이 다룬다.
+    let response = upload(&cli, &other, "", "slides.pdf", PDF_BYTES).await;
+    response.assert_status_is_ok();
+    let json = response.json().await;
+    let attachment_id = json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("attachment_id")
+        .string()
+        .to_string();
+    let path = format!("/attachments/{}", attachment_id);
+
+    cli.put(&path)
+        .header("Authorization", &other)
+        .body_json(&json!({ "post_id": "post-1" }))
+        .send()
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    cli.put(&path)
+        .header("Authorization", &other)
+        .body_json(&json!({ "post_id": "post-2" }))
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    // 글에 붙은 뒤에는 그 글의 작성자 규칙을 따른다.
+    cli.delete(&path)
+        .header("Authorization", &writer)
+        .send()
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    cli.delete(&path)
+        .header("Authorization", &admin)
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    let count: i64 = query_scalar("SELECT COUNT(*) FROM attachments")
+        .fetch_one(&state.db)
+        .await
+        .expect("failed to count attachments");
+    assert_eq!(count, 0);
+    assert!(storage.list().await.unwrap().is_empty());
+
+    cli.get(format!("{}/download", path))
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
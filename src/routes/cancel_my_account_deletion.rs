@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use poem::{handler, http::StatusCode, web::Data, Error, Request};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::account_deletion::cancel_account_deletion;
+use crate::models::AppState;
+
+#[handler]
+pub async fn cancel_my_account_deletion(
+    req: &Request,
+    data: Data<&Arc<AppState>>,
+) -> Result<StatusCode, Error> {
+    let user = current_user(req)?;
+
+    if !cancel_account_deletion(&data.db, &user.user_id).await? {
+        return Err(Error::from_string(
+            "예약된 탈퇴가 없습니다.",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::models::{AppState, CustomResponse, RecoveryCodesResponse, TwoFactorCodeRequest};
+use crate::two_factor::confirm_totp_enrollment;
+
+#[handler]
+pub async fn confirm_my_totp_enrollment(
+    req: &Request,
+    Json(payload): Json<TwoFactorCodeRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<RecoveryCodesResponse>>, Error> {
+    let user = current_user(req)?;
+    let code = payload
+        .code
+        .as_deref()
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .ok_or_else(|| Error::from_string("code가 필요합니다.", StatusCode::BAD_REQUEST))?;
+
+    let recovery_codes = confirm_totp_enrollment(&data.db, &user.user_id, code).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(RecoveryCodesResponse { recovery_codes }),
+        message: Some(String::from(
+            "2단계 인증을 켰습니다. 복구 코드는 다시 볼 수 없으니 안전한 곳에 보관해 주세요.",
+        )),
+    }))
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json},
+    Error,
+};
+use tyange_cms_api::auth::password::check_password_policy;
+
+use crate::models::{AppState, CustomResponse, PasswordResetConfirmRequest};
+use crate::password_reset::{
+    consume_password_reset, find_password_reset_user, replace_user_password,
+};
+
+fn invalid_reset_token() -> Error {
+    Error::from_string(
+        "재설정 링크가 만료되었거나 이미 사용되었습니다. 다시 요청해 주세요.",
+        StatusCode::BAD_REQUEST,
+    )
+}
+
+#[handler]
+pub async fn confirm_password_reset(
+    Json(payload): Json<PasswordResetConfirmRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<()>>, Error> {
+    let token = payload.token.trim();
+    let user_id = find_password_reset_user(&data.db, token)
+        .await?
+        .ok_or_else(invalid_reset_token)?;
+
+    // 정책에 걸리면 토큰을 소모하지 않아 같은 링크로 다시 시도할 수 있다.
+    check_password_policy(&payload.new_password, &user_id)
+        .map_err(|err| Error::from_string(err.to_string(), StatusCode::BAD_REQUEST))?;
+
+    if !consume_password_reset(&data.db, token).await? {
+        return Err(invalid_reset_token());
+    }
+    replace_user_password(
+        &data.db,
+        &user_id,
+        &payload.new_password,
+        None,
+        "password_reset",
+    )
+    .await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: None,
+        message: Some(String::from(
+            "비밀번호를 재설정했습니다. 새 비밀번호로 다시 로그인해 주세요.",
+        )),
+    }))
+}
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::data_export::{data_export_response, request_data_export, spawn_data_export};
+use crate::models::{AppState, CustomResponse, DataExportResponse};
+
+/// 내보내기 작업을 등록하고 바로 돌려준다. 이미 진행 중인 작업이 있으면 새로 만들지 않고 그 작업을 돌려준다.
+#[handler]
+pub async fn create_my_data_export(
+    req: &Request,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<DataExportResponse>>, Error> {
+    let user = current_user(req)?;
+
+    let (export, created) = request_data_export(&data.db, &user.user_id).await?;
+    if created {
+        spawn_data_export(data.0.clone(), export.export_id.clone());
+    }
+
+    Ok(Json(CustomResponse {
+        status: true,
+        message: Some(if created {
+            "데이터 내보내기를 시작했습니다.".to_string()
+        } else {
+            "이미 진행 중인 데이터 내보내기가 있습니다.".to_string()
+        }),
+        data: Some(data_export_response(export)?),
+    }))
+}
@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::models::{AppState, CreatePasskeyRequest, PasskeyResponse};
+use crate::passkeys::register_passkey;
+
+/// 패스키 등록 두 번째 단계. `navigator.credentials.create()` 결과를 받아 저장한다.
+#[handler]
+pub async fn create_my_passkey(
+    req: &Request,
+    Json(payload): Json<CreatePasskeyRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<PasskeyResponse>, Error> {
+    let user = current_user(req)?;
+    let passkey = register_passkey(&data.db, &user.user_id, &payload).await?;
+
+    Ok(Json(passkey))
+}
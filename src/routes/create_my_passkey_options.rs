@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::models::{AppState, PasskeyCreationOptions};
+use crate::passkeys::registration_options;
+
+/// 패스키 등록 첫 단계. 응답을 `PublicKeyCredential.parseCreationOptionsFromJSON()`에 넘기면 된다.
+#[handler]
+pub async fn create_my_passkey_options(
+    req: &Request,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<PasskeyCreationOptions>, Error> {
+    let user = current_user(req)?;
+    let options = registration_options(&data.db, &user.user_id).await?;
+
+    Ok(Json(options))
+}
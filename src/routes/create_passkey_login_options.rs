@@ -0,0 +1,20 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json},
+    Error,
+};
+
+use crate::models::{AppState, PasskeyRequestOptions};
+use crate::passkeys::authentication_options;
+
+/// 패스키 로그인 첫 단계. 응답을 `PublicKeyCredential.parseRequestOptionsFromJSON()`에 넘기면 된다.
+#[handler]
+pub async fn create_passkey_login_options(
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<PasskeyRequestOptions>, Error> {
+    let options = authentication_options(&data.db).await?;
+
+    Ok(Json(options))
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::{
+    models::{AppState, CreateUploadSessionRequest, CustomResponse, UploadSessionResponse},
+    upload_sessions::{create_upload_session as create_session, upload_session_response},
+};
+
+#[handler]
+pub async fn create_upload_session(
+    req: &Request,
+    Json(payload): Json<CreateUploadSessionRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<UploadSessionResponse>>, Error> {
+    let user = current_user(req)?;
+    let session = create_session(&data.db, &user.user_id, payload).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(upload_session_response(session)),
+        message: Some(String::from("업로드 세션을 만들었습니다.")),
+    }))
+}
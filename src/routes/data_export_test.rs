@@ -0,0 +1,311 @@
+use std::{env, io::Read, sync::Arc, time::Duration};
+
+use image::{DynamicImage, ImageFormat, RgbImage};
+use poem::{get, http::StatusCode, post, test::TestClient, Endpoint, EndpointExt, Route};
+use serde_json::Value;
+use sqlx::{query, SqlitePool};
+use zip::ZipArchive;
+
+use crate::{
+    data_export::{
+        archive_path, build_export_archive, download_signature, expire_data_exports, find_export,
+    },
+    db::init_db,
+    image_upload::store_uploaded_image,
+    middlewares::auth_middleware::Auth,
+    models::{AppState, UploadImageQueryParmas},
+    routes::{
+        create_my_data_export::create_my_data_export, download_data_export::download_data_export,
+        get_my_data_export::get_my_data_export,
+    },
+    storage::{MemoryStorage, UploadStorage},
+};
+use tyange_cms_api::auth::jwt::Claims;
+
+const IMAGE_BYTES: &[u8] = b"\x89PNG\r\n\x1a\nexport-test-image";
+
+async fn create_test_state() -> Arc<AppState> {
+    env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+    env::set_var("DATA_EXPORT_PATH", "/tmp/tyange-cms-data-export-tests");
+
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    init_db(&db).await.expect("failed to init db");
+
+    let storage = Arc::new(MemoryStorage::default());
+    storage
+        .put("owner-image.png", IMAGE_BYTES.to_vec(), "image/png")
+        .await
+        .expect("failed to store image");
+    Arc::new(AppState::new_with_storage(db, storage))
+}
+
+fn create_test_app(state: Arc<AppState>) -> impl Endpoint {
+    Route::new()
+        .at("/me/exports", post(create_my_data_export).with(Auth))
+        .at("/me/exports/:export_id", get(get_my_data_export).with(Auth))
+        .at("/exports/:export_id/download", get(download_data_export))
+        .data(state)
+}
+
+fn issue_access_token(user_id: &str) -> String {
+    Claims::create_access_token(user_id, "user", b"test-access-secret")
+        .expect("failed to create access token")
+}
+
+async fn exec(db: &SqlitePool, sql: &str) {
+    query(sql).execute(db).await.expect("failed to seed row");
+}
+
+async fn seed_rows(db: &SqlitePool) {
+    for sql in [
+        "INSERT INTO users (user_id, password, user_role, google_sub, display_name) VALUES ('owner@example.com', 'secret-hash', 'user', 'google-sub-1', '주인')",
+        "INSERT INTO users (user_id, password, user_role) VALUES ('other@example.com', 'other-hash', 'user')",
+        "INSERT INTO posts (post_id, title, content, writer_id, status) VALUES ('owner-post', '내 글', 'body', 'owner@example.com', 'draft')",
+        "INSERT INTO posts (post_id, title, content, writer_id, status) VALUES ('other-post', '남의 글', 'body', 'other@example.com', 'draft')",
+        "INSERT INTO images (image_id, post_id, file_name, origin_name, file_path, mime_type, image_type) VALUES ('owner-image', 'owner-post', 'owner-image.png', 'photo.png', 'owner-image.png', 'image/png', 'content')",
+        "INSERT INTO spending_records (owner_user_id, amount, merchant, transacted_at) VALUES ('owner@example.com', 4500, 'Cafe, \"Seoul\"', '2026-03-01 09:00:00')",
+        "INSERT INTO spending_records (owner_user_id, amount, merchant, transacted_at) VALUES ('other@example.com', 9900, 'Other Merchant', '2026-03-01 09:00:00')",
+        "INSERT INTO budget_periods (owner_user_id, total_budget, from_date, to_date) VALUES ('owner@example.com', 300000, '2026-03-01', '2026-03-31')",
+        "INSERT INTO rss_sources (source_id, feed_url, normalized_feed_url, title) VALUES ('feed', 'https://feed.example.com/rss', 'https://feed.example.com/rss', 'Feed')",
+        "INSERT INTO user_rss_subscriptions (user_id, source_id) VALUES ('owner@example.com', 'feed')",
+        "INSERT INTO user_matches (match_id, requester_user_id, target_user_id, status) VALUES (1, 'owner@example.com', 'other@example.com', 'matched')",
+        "INSERT INTO match_messages (match_id, sender_user_id, receiver_user_id, content) VALUES (1, 'other@example.com', 'owner@example.com', '안녕하세요')",
+        "INSERT INTO api_keys (user_id, name, key_lookup, key_hash) VALUES ('owner@example.com', 'cli', 'lookup-owner', 'key-hash-owner')",
+        "INSERT INTO api_keys (user_id, name, key_lookup, key_hash) VALUES ('other@example.com', 'other-cli', 'lookup-other', 'key-hash-other')",
+    ] {
+        exec(db, sql).await;
+    }
+}
+
+async fn wait_until_ready(
+    cli: &TestClient<impl Endpoint>,
+    token: &str,
+    export_id: &str,
+) -> serde_json::Value {
+    for _ in 0..100 {
+        let response = cli
+            .get(format!("/me/exports/{}", export_id))
+            .header("Authorization", token)
+            .send()
+            .await;
+        response.assert_status_is_ok();
+        let body: Value =
+            serde_json::from_str(&response.0.into_body().into_string().await.unwrap())
+                .expect("status should be json");
+        match body["status"].as_str() {
+            Some("ready") => return body,
+            Some("pending") | Some("running") => {
+                tokio::time::sleep(Duration::from_millis(50)).await
+            }
+            other => panic!("unexpected export status: {:?}", other),
+        }
+    }
+    panic!("export did not finish in time");
+}
+
+fn read_entry(archive: &mut ZipArchive<std::io::Cursor<Vec<u8>>>, name: &str) -> Vec<u8> {
+    let mut file = archive
+        .by_name(name)
+        .unwrap_or_else(|_| panic!("{} should be in the archive", name));
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).expect("failed to read entry");
+    bytes
+}
+
+fn read_text(archive: &mut ZipArchive<std::io::Cursor<Vec<u8>>>, name: &str) -> String {
+    String::from_utf8(read_entry(archive, name)).expect("entry should be utf-8")
+}
+
+#[tokio::test]
+async fn data_export_builds_a_scoped_archive_behind_an_expiring_signed_link() {
+    let state = create_test_state().await;
+    seed_rows(&state.db).await;
+    let db = state.db.clone();
+    let cli = TestClient::new(create_test_app(state));
+    let token = issue_access_token("owner@example.com");
+
+    let created = cli
+        .post("/me/exports")
+        .header("Authorization", &token)
+        .send()
+        .await;
+    created.assert_status_is_ok();
+    let created_json = created.json().await;
+    let export_id = created_json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("export_id")
+        .string()
+        .to_string();
+
+    let ready = wait_until_ready(&cli, &token, &export_id).await;
+    let download_url = ready["download_url"]
+        .as_str()
+        .expect("ready export should have a download url")
+        .to_string();
+
+    // 다른 사용자는 상태도 볼 수 없다.
+    cli.get(format!("/me/exports/{}", export_id))
+        .header("Authorization", issue_access_token("other@example.com"))
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    let downloaded = cli.get(&download_url).send().await;
+    downloaded.assert_status_is_ok();
+    downloaded.assert_header("content-type", "application/zip");
+    let bytes = downloaded.0.into_body().into_bytes().await.unwrap();
+    let mut archive = ZipArchive::new(std::io::Cursor::new(bytes.to_vec())).expect("valid zip");
+
+    let profile: Value = serde_json::from_str(&read_text(&mut archive, "profile.json")).unwrap();
+    assert_eq!(profile["user_id"], "owner@example.com");
+    assert_eq!(profile["display_name"], "주인");
+    assert_eq!(profile["google_linked"], 1);
+    assert!(profile.get("password").is_none());
+    assert!(profile.get("google_sub").is_none());
+
+    let posts: Value = serde_json::from_str(&read_text(&mut archive, "posts.json")).unwrap();
+    assert_eq!(posts.as_array().unwrap().len(), 1);
+    assert_eq!(posts[0]["post_id"], "owner-post");
+    assert_eq!(
+        read_entry(&mut archive, "images/owner-image.png"),
+        IMAGE_BYTES
+    );
+
+    let spending_csv = read_text(&mut archive, "spending_records.csv");
+    assert!(spending_csv
+        .starts_with("\u{feff}record_id,amount,merchant,transacted_at,source_type,created_at\r\n"));
+    assert!(spending_csv.contains(",4500,\"Cafe, \"\"Seoul\"\"\",2026-03-01 09:00:00,,"));
+    assert!(!spending_csv.contains("Other Merchant"));
+    let spending: Value =
+        serde_json::from_str(&read_text(&mut archive, "spending_records.json")).unwrap();
+    assert_eq!(spending.as_array().unwrap().len(), 1);
+    assert!(read_text(&mut archive, "budget_periods.csv").contains(",300000,2026-03-01,"));
+
+    let rss: Value =
+        serde_json::from_str(&read_text(&mut archive, "rss_subscriptions.json")).unwrap();
+    assert_eq!(rss[0]["feed_url"], "https://feed.example.com/rss");
+    let messages: Value =
+        serde_json::from_str(&read_text(&mut archive, "match_messages.json")).unwrap();
+    assert_eq!(messages[0]["content"], "안녕하세요");
+    assert_eq!(
+        serde_json::from_str::<Value>(&read_text(&mut archive, "matches.json")).unwrap()[0]
+            ["status"],
+        "matched"
+    );
+
+    let api_keys = read_text(&mut archive, "api_keys.json");
+    assert!(api_keys.contains("\"cli\""));
+    assert!(!api_keys.contains("key-hash"));
+    assert!(!api_keys.contains("lookup-"));
+    assert!(!api_keys.contains("other-cli"));
+
+    let manifest: Value = serde_json::from_str(&read_text(&mut archive, "manifest.json")).unwrap();
+    assert_eq!(manifest["missing_images"], serde_json::json!([]));
+
+    let tampered = download_url.replace("signature=", "signature=00");
+    cli.get(&tampered)
+        .send()
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    query("UPDATE data_exports SET expires_at = '2026-01-01 00:00:00' WHERE export_id = ?")
+        .bind(&export_id)
+        .execute(&db)
+        .await
+        .expect("failed to expire export");
+    // 만료 시각도 서명에 들어가므로 만료 시각을 바꾸면 예전 링크는 서명부터 맞지 않는다.
+    cli.get(&download_url)
+        .send()
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let expired_row = find_export(&db, &export_id).await.unwrap().unwrap();
+    cli.get(format!(
+        "/exports/{}/download?signature={}",
+        export_id,
+        download_signature(&expired_row).unwrap()
+    ))
+    .send()
+    .await
+    .assert_status(StatusCode::GONE);
+
+    assert_eq!(expire_data_exports(&db).await.unwrap(), 1);
+    assert!(!archive_path(&export_id).exists());
+    let expired = cli
+        .get(format!("/me/exports/{}", export_id))
+        .header("Authorization", &token)
+        .send()
+        .await;
+    expired.assert_status_is_ok();
+    let expired_json = expired.json().await;
+    expired_json
+        .value()
+        .object()
+        .get("status")
+        .assert_string("expired");
+    expired_json
+        .value()
+        .object()
+        .get("download_url")
+        .assert_null();
+}
+
+#[tokio::test]
+async fn images_uploaded_twice_are_archived_once_but_listed_per_row() {
+    let state = create_test_state().await;
+    seed_rows(&state.db).await;
+
+    let image = DynamicImage::ImageRgb8(RgbImage::from_fn(4, 4, |x, y| {
+        image::Rgb([(x * 4) as u8, (y * 4) as u8, 200])
+    }));
+    let mut png = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut png), ImageFormat::Png)
+        .expect("failed to encode png");
+    let params = UploadImageQueryParmas {
+        post_id: None,
+        image_type: None,
+    };
+    let mut uploaded = Vec::new();
+    for origin_name in ["first.png", "second.png"] {
+        let response = store_uploaded_image(
+            &state.db,
+            state.storage.as_ref(),
+            "owner@example.com",
+            origin_name,
+            "image/png",
+            png.clone(),
+            &params,
+        )
+        .await
+        .expect("failed to upload image");
+        uploaded.push(response.image_path);
+    }
+    // 내용 해시로 합쳐져 두 행이 같은 파일을 가리킨다.
+    assert_eq!(uploaded[0], uploaded[1]);
+    let file_name = uploaded[0].trim_start_matches("/images/").to_string();
+
+    let bytes = build_export_archive(&state, "owner@example.com")
+        .await
+        .expect("export should not fail on shared image files");
+    let mut archive = ZipArchive::new(std::io::Cursor::new(bytes)).expect("valid zip");
+
+    let image_entries = archive
+        .file_names()
+        .filter(|name| *name == format!("images/{}", file_name))
+        .count();
+    assert_eq!(image_entries, 1);
+    let images: Value = serde_json::from_str(&read_text(&mut archive, "images.json")).unwrap();
+    let shared_rows = images
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|row| row["file_name"] == file_name.as_str())
+        .count();
+    assert_eq!(shared_rows, 2);
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Path},
+    Error,
+};
+
+use crate::{
+    account_deletion::{purge_account, AccountDeletionSettings},
+    models::AppState,
+    user_admin::find_user,
+};
+
+/// 유예 기간 없이 바로 지운다. 글과 딸린 행은 `DELETE /me`의 워커와 같은 정책으로 처리한다.
+#[handler]
+pub async fn delete_admin_user(
+    Path(user_id): Path<String>,
+    data: Data<&Arc<AppState>>,
+) -> Result<StatusCode, Error> {
+    if find_user(&data.db, &user_id).await?.is_none() {
+        return Err(Error::from_string(
+            "사용자를 찾을 수 없습니다.",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    purge_account(&data, &user_id, &AccountDeletionSettings::from_env()).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Path},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::{
+    attachments::{ensure_attachment_owner, fetch_attachment, remove_attachment},
+    models::{CustomResponse, DeleteAttachmentResponse},
+    AppState,
+};
+
+#[handler]
+pub async fn delete_attachment(
+    req: &Request,
+    Path(attachment_id): Path<String>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<DeleteAttachmentResponse>>, Error> {
+    let user = current_user(req)?;
+    let attachment = fetch_attachment(&data.db, &attachment_id).await?;
+    ensure_attachment_owner(user, &attachment, &data.db).await?;
+
+    remove_attachment(&data.db, data.storage.as_ref(), &attachment).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(DeleteAttachmentResponse { attachment_id }),
+        message: Some(String::from("첨부 파일이 삭제되었습니다.")),
+    }))
+}
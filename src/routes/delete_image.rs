@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Path},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::{
+    image_dedup::is_last_blob_reference,
+    image_gc::{find_image_references, remove_image},
+    image_library::{ensure_image_owner, fetch_image},
+    models::{CustomResponse, DeleteImageResponse},
+    AppState,
+};
+
+#[handler]
+pub async fn delete_image(
+    req: &Request,
+    Path(image_id): Path<String>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<DeleteImageResponse>>, Error> {
+    let user = current_user(req)?;
+    let image = fetch_image(&data.db, &image_id).await?;
+    ensure_image_owner(user, &image)?;
+
+    // 같은 파일을 쓰는 다른 이미지가 남아 있으면 파일은 지워지지 않으므로 참조 검사를 건너뛴다.
+    let references = if is_last_blob_reference(&data.db, &image.image_id).await? {
+        find_image_references(&data.db, &image.image_id, &image.file_name).await?
+    } else {
+        Vec::new()
+    };
+    if !references.is_empty() {
+        let sources = references
+            .iter()
+            .map(|reference| format!("{}:{}", reference.source_type, reference.source_id))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(Error::from_string(
+            format!("아직 사용 중인 이미지라 삭제할 수 없습니다: {}", sources),
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    let (removed_files, _) = remove_image(
+        &data.db,
+        data.storage.as_ref(),
+        &image.image_id,
+        Some(&image.file_name),
+        false,
+    )
+    .await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(DeleteImageResponse {
+            image_id,
+            removed_files,
+        }),
+        message: Some(String::from("이미지가 삭제되었습니다.")),
+    }))
+}
@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Path},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::{login_throttle::clear_lockout, models::AppState};
+
+/// `scope`는 `account` 또는 `ip`. 잠금과 실패 횟수를 함께 지우고 감사 기록에 남긴다.
+#[handler]
+pub async fn delete_login_lockout(
+    req: &Request,
+    Path((scope, key)): Path<(String, String)>,
+    data: Data<&Arc<AppState>>,
+) -> Result<StatusCode, Error> {
+    let admin = current_user(req)?;
+    clear_lockout(&data.db, &scope, &key, &admin.user_id).await?;
+    println!("로그인 잠금 해제: {} {} (by {})", scope, key, admin.user_id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::account_deletion::{
+    ensure_not_last_admin, schedule_account_deletion, AccountDeletionSettings,
+};
+use crate::models::{AccountDeletionResponse, AppState, CustomResponse};
+
+/// 탈퇴를 예약한다. 유예 기간이 지나기 전에는 `DELETE /me/deletion`으로 취소할 수 있다.
+#[handler]
+pub async fn delete_my_account(
+    req: &Request,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<AccountDeletionResponse>>, Error> {
+    let user = current_user(req)?;
+    let settings = AccountDeletionSettings::from_env();
+
+    ensure_not_last_admin(&data.db, &user.user_id).await?;
+    let scheduled = schedule_account_deletion(&data.db, &user.user_id, settings.grace_days).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        message: Some(format!(
+            "{}에 계정이 삭제됩니다. 그 전에는 탈퇴를 취소할 수 있습니다.",
+            scheduled.deletion_scheduled_at
+        )),
+        data: Some(AccountDeletionResponse {
+            user_id: user.user_id.clone(),
+            deletion_requested_at: scheduled.deletion_requested_at,
+            deletion_scheduled_at: scheduled.deletion_scheduled_at,
+            post_policy: settings.post_policy.as_str().to_string(),
+        }),
+    }))
+}
@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Path},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::models::AppState;
+use crate::passkeys::revoke_passkey;
+
+#[handler]
+pub async fn delete_my_passkey(
+    req: &Request,
+    Path(passkey_id): Path<i64>,
+    data: Data<&Arc<AppState>>,
+) -> Result<StatusCode, Error> {
+    let user = current_user(req)?;
+    revoke_passkey(&data.db, &user.user_id, passkey_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Path},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::models::AppState;
+use crate::sessions::revoke_session;
+
+/// 세션 하나를 로그아웃시킨다. 그 기기의 access 토큰은 만료 전이어도 바로 `401`이 된다.
+#[handler]
+pub async fn delete_my_session(
+    req: &Request,
+    Path(session_id): Path<String>,
+    data: Data<&Arc<AppState>>,
+) -> Result<StatusCode, Error> {
+    let user = current_user(req)?;
+    revoke_session(&data.db, &user.user_id, &session_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,55 +1,23 @@
-use crate::models::AppState;
+use crate::models::{AppState, PortfolioSelectorQuery};
+use crate::portfolio::{remove_portfolio, PortfolioKey};
 use poem::http::StatusCode;
-use poem::web::Data;
+use poem::web::{Data, Query};
 use poem::{handler, Error};
-use sqlx::query;
 use std::sync::Arc;
 
 #[handler]
-pub async fn delete_portfolio(data: Data<&Arc<AppState>>) -> Result<StatusCode, Error> {
-    let portfolio_id: Option<i32> =
-        sqlx::query_scalar("SELECT portfolio_id FROM portfolio WHERE slug = ?")
-            .bind("dev")
-            .fetch_optional(&data.db)
-            .await
-            .map_err(|err| {
-                Error::from_string(
-                    format!("포트폴리오 조회 실패: {}", err),
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                )
-            })?;
+pub async fn delete_portfolio(
+    Query(selector): Query<PortfolioSelectorQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<StatusCode, Error> {
+    let key = PortfolioKey::resolve(&selector, None)?;
 
-    let portfolio_id = match portfolio_id {
-        Some(id) => id,
-        None => {
-            return Err(Error::from_string(
-                "포트폴리오 데이터를 찾지 못했습니다.",
-                StatusCode::NOT_FOUND,
-            ));
-        }
-    };
-
-    query("DELETE FROM portfolio_section WHERE portfolio_id = ?")
-        .bind(portfolio_id)
-        .execute(&data.db)
-        .await
-        .map_err(|err| {
-            Error::from_string(
-                format!("포트폴리오 섹션 삭제 실패: {}", err),
-                StatusCode::INTERNAL_SERVER_ERROR,
-            )
-        })?;
-
-    query("DELETE FROM portfolio WHERE portfolio_id = ?")
-        .bind(portfolio_id)
-        .execute(&data.db)
-        .await
-        .map_err(|err| {
-            Error::from_string(
-                format!("포트폴리오 삭제 실패: {}", err),
-                StatusCode::INTERNAL_SERVER_ERROR,
-            )
-        })?;
+    if !remove_portfolio(&data.db, &key).await? {
+        return Err(Error::from_string(
+            "포트폴리오 데이터를 찾지 못했습니다.",
+            StatusCode::NOT_FOUND,
+        ));
+    }
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -0,0 +1,26 @@
+use crate::models::{AppState, CustomResponse, PortfolioSelectorQuery};
+use crate::portfolio::{discard_portfolio_draft, PortfolioKey};
+use poem::http::StatusCode;
+use poem::web::{Data, Json, Query};
+use poem::{handler, Error};
+use std::sync::Arc;
+
+#[handler]
+pub async fn delete_portfolio_draft(
+    Query(selector): Query<PortfolioSelectorQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<()>>, Error> {
+    let key = PortfolioKey::resolve(&selector, None)?;
+    if !discard_portfolio_draft(&data.db, &key).await? {
+        return Err(Error::from_string(
+            "포트폴리오 초안이 없습니다.",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: None,
+        message: Some(String::from("포트폴리오 초안을 버렸습니다.")),
+    }))
+}
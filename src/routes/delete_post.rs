@@ -1,5 +1,6 @@
 use crate::blog_redeploy::{is_blog_redeploy_target, BlogContentEvent, BlogVisibility};
 use crate::models::{CustomResponse, DeletePostResponse};
+use crate::portfolio_posts::dangling_references_after_delete;
 use crate::utils::parse_tags;
 use crate::AppState;
 use poem::http::StatusCode;
@@ -41,6 +42,32 @@ pub async fn delete_post(
         )
     })?;
 
+    // 이미지 행은 남겨두고 연결만 끊는다. 파일 정리는 image GC가 맡는다.
+    query("UPDATE images SET post_id = NULL WHERE post_id = ?")
+        .bind(&post_id)
+        .execute(&data.db)
+        .await
+        .map_err(|err| {
+            eprintln!("Error detach images before delete: {}", err);
+            Error::from_string(
+                "게시글 이미지 연결 해제에 실패했습니다.",
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+
+    // 첨부 파일도 행과 파일은 남기고 연결만 끊는다. 올린 사람이 따로 지울 수 있다.
+    query("UPDATE attachments SET post_id = NULL WHERE post_id = ?")
+        .bind(&post_id)
+        .execute(&data.db)
+        .await
+        .map_err(|err| {
+            eprintln!("Error detach attachments before delete: {}", err);
+            Error::from_string(
+                "게시글 첨부 파일 연결 해제에 실패했습니다.",
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+
     let result = query(
         r#"
             DELETE FROM posts WHERE post_id = ?
@@ -59,11 +86,10 @@ pub async fn delete_post(
                 ));
             }
 
+            let tags = parse_tags(&existing_post.tags);
             if is_blog_redeploy_target(
                 &existing_post.status,
-                parse_tags(&existing_post.tags)
-                    .iter()
-                    .map(|tag| tag.tag.as_str()),
+                tags.iter().map(|tag| tag.tag.as_str()),
             ) {
                 data.blog_redeploy
                     .dispatch_content_change(
@@ -74,10 +100,32 @@ pub async fn delete_post(
                     .await;
             }
 
+            // 글은 이미 지워졌으므로 참조 조회가 실패해도 삭제 응답은 돌려준다.
+            let tag_names: Vec<String> = tags.into_iter().map(|tag| tag.tag).collect();
+            let dangling_portfolio_references =
+                match dangling_references_after_delete(&data.db, &post_id, &tag_names).await {
+                    Ok(references) => references,
+                    Err(err) => {
+                        eprintln!("Error find dangling portfolio references: {}", err);
+                        Vec::new()
+                    }
+                };
+            let message = if dangling_portfolio_references.is_empty() {
+                String::from("포스트가 삭제되었습니다.")
+            } else {
+                format!(
+                    "포스트가 삭제되었습니다. 포트폴리오 프로젝트에 끊어진 참조 {}개가 남았습니다.",
+                    dangling_portfolio_references.len()
+                )
+            };
+
             Ok(Json(CustomResponse {
                 status: true,
-                data: Some(DeletePostResponse { post_id }),
-                message: Some(String::from("포스트가 삭제되었습니다.")),
+                data: Some(DeletePostResponse {
+                    post_id,
+                    dangling_portfolio_references,
+                }),
+                message: Some(message),
             }))
         }
         Err(err) => {
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Path},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::{
+    models::{AppState, CustomResponse},
+    upload_sessions::{delete_upload_session as delete_session, fetch_upload_session},
+};
+
+#[handler]
+pub async fn delete_upload_session(
+    req: &Request,
+    Path(session_id): Path<String>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<()>>, Error> {
+    let user = current_user(req)?;
+    let session = fetch_upload_session(&data.db, &session_id, &user.user_id).await?;
+    delete_session(&data.db, &session.session_id).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: None,
+        message: Some(String::from("업로드 세션을 취소했습니다.")),
+    }))
+}
@@ -0,0 +1,32 @@
+use crate::models::{
+    AppState, CustomResponse, PortfolioSelectorQuery, PortfolioVersionDiffQuery,
+    PortfolioVersionDiffResponse,
+};
+use crate::portfolio::{
+    diff_portfolio_documents, fetch_portfolio_version, version_document, PortfolioKey,
+};
+use poem::web::{Data, Json, Query};
+use poem::{handler, Error};
+use std::sync::Arc;
+
+#[handler]
+pub async fn diff_portfolio_versions(
+    Query(params): Query<PortfolioVersionDiffQuery>,
+    Query(selector): Query<PortfolioSelectorQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<PortfolioVersionDiffResponse>>, Error> {
+    let key = PortfolioKey::resolve(&selector, None)?;
+    let from = fetch_portfolio_version(&data.db, &key, params.from).await?;
+    let to = fetch_portfolio_version(&data.db, &key, params.to).await?;
+    let sections = diff_portfolio_documents(&version_document(&from)?, &version_document(&to)?)?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(PortfolioVersionDiffResponse {
+            from: params.from,
+            to: params.to,
+            sections,
+        }),
+        message: None,
+    }))
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::models::{AppState, TwoFactorCodeRequest};
+use crate::two_factor::{admin_totp_required, disable_totp, require_second_factor, totp_enabled};
+
+/// TOTP 코드나 복구 코드를 한 번 더 확인하고 2단계 인증을 끈다.
+/// 관리자 2단계 인증이 필수면 관리자는 끌 수 없다(`409`).
+#[handler]
+pub async fn disable_my_totp(
+    req: &Request,
+    Json(payload): Json<TwoFactorCodeRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<StatusCode, Error> {
+    let user = current_user(req)?;
+    let db_error = |err: sqlx::Error| {
+        Error::from_string(
+            format!("2단계 인증 조회 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    };
+
+    if !totp_enabled(&data.db, &user.user_id)
+        .await
+        .map_err(db_error)?
+    {
+        return Err(Error::from_string(
+            "사용 중인 2단계 인증이 없습니다.",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+    if user.role == "admin" && admin_totp_required(&data.db).await.map_err(db_error)? {
+        return Err(Error::from_string(
+            "관리자는 2단계 인증을 끌 수 없습니다.",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    require_second_factor(&data.db, &user.user_id, &payload).await?;
+    disable_totp(&data.db, &user.user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
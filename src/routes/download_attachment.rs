@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::{header, HeaderValue, StatusCode},
+    web::{Data, Path, Query, StaticFileRequest},
+    Error, IntoResponse, Request, Response,
+};
+
+use crate::{
+    attachments::{content_disposition, fetch_attachment, record_attachment_download},
+    models::{AppState, AttachmentDownloadQuery},
+};
+
+/// 첨부 파일을 내려주고 다운로드 횟수를 센다.
+/// 이어 받기(Range)로 중간부터 요청한 경우는 같은 다운로드로 보고 세지 않는다.
+#[handler]
+pub async fn download_attachment(
+    req: &Request,
+    Path(attachment_id): Path<String>,
+    Query(params): Query<AttachmentDownloadQuery>,
+    static_file: StaticFileRequest,
+    data: Data<&Arc<AppState>>,
+) -> Result<Response, Error> {
+    let attachment = fetch_attachment(&data.db, &attachment_id).await?;
+
+    let is_continuation = req
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|range| !range.trim().starts_with("bytes=0-"));
+
+    // presigned URL로 보내면 Content-Disposition을 붙일 수 없으므로 로컬이 아니면 서버가 대신 읽는다.
+    let response = if let Some(local_path) = data.storage.local_path(&attachment.file_name) {
+        static_file
+            .create_response(local_path, false, false)?
+            .into_response()
+    } else {
+        let bytes = data
+            .storage
+            .get(&attachment.file_name)
+            .await?
+            .ok_or_else(|| {
+                Error::from_string("첨부 파일을 찾을 수 없습니다.", StatusCode::NOT_FOUND)
+            })?;
+        Response::builder().body(bytes)
+    };
+
+    if !response.status().is_success() {
+        return Ok(response);
+    }
+
+    if !is_continuation {
+        record_attachment_download(&data.db, &attachment.attachment_id).await?;
+    }
+
+    let inline = params.inline.unwrap_or(false);
+    let mut response = response;
+    if let Ok(value) = HeaderValue::from_str(&attachment.mime_type) {
+        response.headers_mut().insert(header::CONTENT_TYPE, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&content_disposition(&attachment.origin_name, inline))
+    {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_DISPOSITION, value);
+    }
+    response.headers_mut().insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+
+    Ok(response)
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::{header, HeaderValue, StatusCode},
+    web::{Data, Path, Query, StaticFileRequest},
+    Error, IntoResponse, Response,
+};
+
+use crate::data_export::{archive_path, find_export, is_expired, verify_download_signature};
+use crate::models::{AppState, DataExportDownloadQuery};
+
+/// 서명한 링크로 내보내기 파일을 내려준다. 로그인 없이 받을 수 있으므로 서명과 만료만 본다.
+#[handler]
+pub async fn download_data_export(
+    Path(export_id): Path<String>,
+    Query(params): Query<DataExportDownloadQuery>,
+    static_file: StaticFileRequest,
+    data: Data<&Arc<AppState>>,
+) -> Result<Response, Error> {
+    let invalid_link =
+        || Error::from_string("다운로드 링크가 올바르지 않습니다.", StatusCode::FORBIDDEN);
+
+    let export = find_export(&data.db, &export_id)
+        .await?
+        .ok_or_else(invalid_link)?;
+    if export.status != "ready" && export.status != "expired" {
+        return Err(invalid_link());
+    }
+    if !verify_download_signature(&export, &params.signature)? {
+        return Err(invalid_link());
+    }
+    if export.status == "expired" || is_expired(&export) {
+        return Err(Error::from_string(
+            "다운로드 링크가 만료되었습니다. 내보내기를 다시 요청해 주세요.",
+            StatusCode::GONE,
+        ));
+    }
+
+    let mut response = static_file
+        .create_response(archive_path(&export.export_id), false, false)?
+        .into_response();
+    if !response.status().is_success() {
+        return Ok(response);
+    }
+
+    let date = export
+        .completed_at
+        .as_deref()
+        .and_then(|completed_at| completed_at.get(..10))
+        .unwrap_or("export");
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/zip"),
+    );
+    if let Ok(value) = HeaderValue::from_str(&format!(
+        "attachment; filename=\"tyange-data-{}.zip\"",
+        date
+    )) {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_DISPOSITION, value);
+    }
+
+    Ok(response)
+}
@@ -0,0 +1,35 @@
+use crate::json_resume::export_json_resume;
+use crate::models::{AppState, CustomResponse, JsonResumeExportResponse, PortfolioSelectorQuery};
+use crate::portfolio::{load_portfolio, PortfolioKey};
+use poem::http::StatusCode;
+use poem::web::{Data, Json, Query};
+use poem::{handler, Error};
+use std::sync::Arc;
+
+/// 공개 문서를 JSON Resume으로 내보낸다.
+#[handler]
+pub async fn export_portfolio_json_resume(
+    Query(selector): Query<PortfolioSelectorQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<JsonResumeExportResponse>>, Error> {
+    let key = PortfolioKey::resolve(&selector, None)?;
+    let portfolio = load_portfolio(&data.db, &key).await?.ok_or_else(|| {
+        Error::from_string(
+            "포트폴리오 데이터를 찾지 못했습니다.",
+            StatusCode::NOT_FOUND,
+        )
+    })?;
+
+    let (resume, unmapped) = export_json_resume(&portfolio.content);
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(JsonResumeExportResponse {
+            slug: key.slug,
+            lang: key.lang,
+            resume,
+            unmapped,
+        }),
+        message: None,
+    }))
+}
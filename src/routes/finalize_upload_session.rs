@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Path},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::{
+    models::{AppState, CustomResponse, FinalizeUploadSessionRequest, UploadImageResponse},
+    upload_sessions::{fetch_upload_session, finalize_upload_session as finalize_session},
+};
+
+#[handler]
+pub async fn finalize_upload_session(
+    req: &Request,
+    Path(session_id): Path<String>,
+    Json(payload): Json<FinalizeUploadSessionRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<UploadImageResponse>>, Error> {
+    let user = current_user(req)?;
+    let session = fetch_upload_session(&data.db, &session_id, &user.user_id).await?;
+    let uploaded =
+        finalize_session(&data.db, data.storage.as_ref(), session, &payload.sha256).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(uploaded),
+        message: Some(String::from("이미지 업로드에 성공했습니다.")),
+    }))
+}
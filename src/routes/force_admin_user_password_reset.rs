@@ -0,0 +1,20 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Path},
+    Error,
+};
+
+use crate::{models::AppState, user_admin::force_password_reset};
+
+#[handler]
+pub async fn force_admin_user_password_reset(
+    Path(user_id): Path<String>,
+    data: Data<&Arc<AppState>>,
+) -> Result<StatusCode, Error> {
+    force_password_reset(&data.db, &user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
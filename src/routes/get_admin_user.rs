@@ -0,0 +1,20 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Path},
+    Error,
+};
+
+use crate::{
+    models::{AdminUserDetailResponse, AppState},
+    user_admin::user_detail,
+};
+
+#[handler]
+pub async fn get_admin_user(
+    Path(user_id): Path<String>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<AdminUserDetailResponse>, Error> {
+    Ok(Json(user_detail(&data.db, &user_id).await?))
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Query},
+    Error,
+};
+
+use crate::{
+    models::{AdminUserListQuery, AdminUserListResponse, AppState, CustomResponse},
+    user_admin::list_users,
+};
+
+#[handler]
+pub async fn get_admin_users(
+    Query(params): Query<AdminUserListQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<AdminUserListResponse>>, Error> {
+    let response = list_users(&data.db, &params).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(response),
+        message: None,
+    }))
+}
@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Query},
+    Error,
+};
+
+use crate::{
+    attachments::list_attachments,
+    models::{AttachmentListQuery, AttachmentListResponse, CustomResponse},
+    AppState,
+};
+
+#[handler]
+pub async fn get_attachments(
+    Query(params): Query<AttachmentListQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<AttachmentListResponse>>, Error> {
+    let response = list_attachments(&data.db, params).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(response),
+        message: None,
+    }))
+}
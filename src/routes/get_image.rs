@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::{header, HeaderValue, StatusCode},
+    web::{Data, Path, Query, StaticFileRequest},
+    Error, IntoResponse, Response,
+};
+
+use crate::{
+    attachments::ATTACHMENT_KEY_PREFIX,
+    image_library::stored_mime_type,
+    image_variants::{resolve_variant_file, VariantConfig, VariantFormat},
+    models::{AppState, ImageVariantQuery},
+    storage::content_type_for_key,
+};
+
+#[handler]
+pub async fn get_image(
+    Path(file_name): Path<String>,
+    Query(params): Query<ImageVariantQuery>,
+    static_file: StaticFileRequest,
+    data: Data<&Arc<AppState>>,
+) -> Result<Response, Error> {
+    if file_name.starts_with('.')
+        || file_name.contains('/')
+        || file_name.contains('\\')
+        || file_name.starts_with(ATTACHMENT_KEY_PREFIX)
+    {
+        return Err(Error::from_string(
+            "잘못된 이미지 경로입니다.",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let requested_format = match params.format.as_deref() {
+        Some(format) => Some(VariantFormat::parse(format).ok_or_else(|| {
+            Error::from_string(
+                format!("지원하지 않는 이미지 포맷입니다: {}", format),
+                StatusCode::BAD_REQUEST,
+            )
+        })?),
+        None => None,
+    };
+
+    let served_file_name = if params.w.is_some() || requested_format.is_some() {
+        resolve_variant_file(
+            &data.db,
+            data.storage.as_ref(),
+            &file_name,
+            params.w,
+            requested_format,
+            &VariantConfig::from_env(),
+        )
+        .await?
+    } else {
+        file_name
+    };
+
+    // 확장자 추측 대신 업로드 때 파일 내용으로 판별해 저장한 형식을 내려준다.
+    let content_type = stored_mime_type(&data.db, &served_file_name)
+        .await?
+        .unwrap_or_else(|| content_type_for_key(&served_file_name).to_string());
+
+    // 로컬 디스크면 정적 파일 응답(ETag, Range 처리)을 그대로 쓰고,
+    // 오브젝트 스토리지면 presigned URL로 보내거나 서버가 대신 읽어 내려준다.
+    if let Some(local_path) = data.storage.local_path(&served_file_name) {
+        let response = static_file
+            .create_response(local_path, false, false)?
+            .into_response();
+        return Ok(with_content_headers(response, &content_type));
+    }
+
+    if let Some(url) = data.storage.presigned_read_url(&served_file_name) {
+        return Ok(Response::builder()
+            .status(StatusCode::FOUND)
+            .header(header::LOCATION, url)
+            .finish());
+    }
+
+    let bytes =
+        data.storage.get(&served_file_name).await?.ok_or_else(|| {
+            Error::from_string("이미지를 찾을 수 없습니다.", StatusCode::NOT_FOUND)
+        })?;
+
+    Ok(with_content_headers(
+        Response::builder().body(bytes),
+        &content_type,
+    ))
+}
+
+fn with_content_headers(mut response: Response, content_type: &str) -> Response {
+    if !response.status().is_success() {
+        return response;
+    }
+
+    if let Ok(value) = HeaderValue::from_str(content_type) {
+        response.headers_mut().insert(header::CONTENT_TYPE, value);
+    }
+    response.headers_mut().insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    // SVG를 문서로 직접 열어도 스크립트가 돌지 않게 한다.
+    if content_type == "image/svg+xml" {
+        response.headers_mut().insert(
+            header::CONTENT_SECURITY_POLICY,
+            HeaderValue::from_static("default-src 'none'; style-src 'unsafe-inline'; sandbox"),
+        );
+    }
+
+    response
+}
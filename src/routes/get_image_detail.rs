@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Path},
+    Error,
+};
+
+use crate::{
+    image_gc::find_image_references,
+    image_library::{fetch_image, image_item_response},
+    image_variants::{build_srcsets_with_original, list_image_variants},
+    models::{CustomResponse, ImageDetailResponse},
+    AppState,
+};
+
+#[handler]
+pub async fn get_image_detail(
+    Path(image_id): Path<String>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<ImageDetailResponse>>, Error> {
+    let image = fetch_image(&data.db, &image_id).await?;
+    let variants = list_image_variants(&data.db, &image.image_id).await?;
+    let references = find_image_references(&data.db, &image.image_id, &image.file_name).await?;
+    let file_name = image.file_name.clone();
+    let image = image_item_response(image);
+    let srcset = build_srcsets_with_original(
+        &file_name,
+        &image.mime_type,
+        image.width,
+        image.height,
+        &variants,
+    );
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(ImageDetailResponse {
+            image,
+            variants,
+            srcset,
+            references,
+        }),
+        message: None,
+    }))
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json},
+    Error,
+};
+
+use crate::{
+    image_gc::{build_orphan_report, gc_min_age_hours},
+    models::{CustomResponse, ImageOrphanReportResponse},
+    AppState,
+};
+
+#[handler]
+pub async fn get_image_orphans(
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<ImageOrphanReportResponse>>, Error> {
+    let report = build_orphan_report(&data.db, data.storage.as_ref(), gc_min_age_hours()).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(report),
+        message: None,
+    }))
+}
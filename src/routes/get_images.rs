@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Query},
+    Error,
+};
+
+use crate::{
+    image_library::list_images,
+    models::{CustomResponse, ImageListQuery, ImageListResponse},
+    AppState,
+};
+
+#[handler]
+pub async fn get_images(
+    Query(params): Query<ImageListQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<ImageListResponse>>, Error> {
+    let response = list_images(&data.db, params).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(response),
+        message: None,
+    }))
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Query},
+    Error,
+};
+
+use crate::{
+    login_throttle::list_audit_events,
+    models::{AppState, CustomResponse, LoginAuditEventListResponse, LoginAuditEventQuery},
+};
+
+#[handler]
+pub async fn get_login_audit_events(
+    Query(params): Query<LoginAuditEventQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<LoginAuditEventListResponse>>, Error> {
+    let events = list_audit_events(&data.db, &params).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(LoginAuditEventListResponse { events }),
+        message: None,
+    }))
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Query},
+    Error,
+};
+
+use crate::{
+    login_throttle::list_lockouts,
+    models::{AppState, CustomResponse, LoginLockoutListQuery, LoginLockoutListResponse},
+};
+
+#[handler]
+pub async fn get_login_lockouts(
+    Query(params): Query<LoginLockoutListQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<LoginLockoutListResponse>>, Error> {
+    let lockouts = list_lockouts(&data.db, &params).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(LoginLockoutListResponse { lockouts }),
+        message: None,
+    }))
+}
@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Path},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::data_export::{data_export_response, find_user_export};
+use crate::models::{AppState, DataExportResponse};
+
+/// 내보내기 진행 상태. `ready`면 만료 전까지 쓸 수 있는 `download_url`이 붙는다.
+#[handler]
+pub async fn get_my_data_export(
+    req: &Request,
+    Path(export_id): Path<String>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<DataExportResponse>, Error> {
+    let user = current_user(req)?;
+
+    let export = find_user_export(&data.db, &user.user_id, &export_id)
+        .await?
+        .ok_or_else(|| Error::from_string("내보내기를 찾을 수 없습니다.", StatusCode::NOT_FOUND))?;
+
+    Ok(Json(data_export_response(export)?))
+}
@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::models::{AppState, PasskeyListResponse};
+use crate::passkeys::list_passkeys;
+
+#[handler]
+pub async fn get_my_passkeys(
+    req: &Request,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<PasskeyListResponse>, Error> {
+    let user = current_user(req)?;
+    let passkeys = list_passkeys(&data.db, &user.user_id).await?;
+
+    Ok(Json(PasskeyListResponse { passkeys }))
+}
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::models::{AppState, SessionListResponse};
+use crate::sessions::list_sessions;
+
+/// 로그인해 있는 기기 목록. 이 요청을 보낸 세션은 `current: true`다.
+#[handler]
+pub async fn get_my_sessions(
+    req: &Request,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<SessionListResponse>, Error> {
+    let user = current_user(req)?;
+    let sessions = list_sessions(&data.db, &user.user_id, user.session_id.as_deref()).await?;
+
+    Ok(Json(SessionListResponse { sessions }))
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::models::{AppState, CustomResponse, TwoFactorStatusResponse};
+use crate::two_factor::two_factor_status;
+
+#[handler]
+pub async fn get_my_two_factor(
+    req: &Request,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<TwoFactorStatusResponse>>, Error> {
+    let user = current_user(req)?;
+    let status = two_factor_status(&data.db, &user.user_id, &user.role).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(status),
+        message: None,
+    }))
+}
@@ -0,0 +1,23 @@
+use crate::models::{AppState, CustomResponse, PortfolioDraftResponse, PortfolioSelectorQuery};
+use crate::portfolio::{load_portfolio_draft, PortfolioKey};
+use poem::http::StatusCode;
+use poem::web::{Data, Json, Query};
+use poem::{handler, Error};
+use std::sync::Arc;
+
+#[handler]
+pub async fn get_portfolio_draft(
+    Query(selector): Query<PortfolioSelectorQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<PortfolioDraftResponse>>, Error> {
+    let key = PortfolioKey::resolve(&selector, None)?;
+    let draft = load_portfolio_draft(&data.db, &key)
+        .await?
+        .ok_or_else(|| Error::from_string("포트폴리오 초안이 없습니다.", StatusCode::NOT_FOUND))?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(draft),
+        message: None,
+    }))
+}
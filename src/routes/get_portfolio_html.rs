@@ -0,0 +1,34 @@
+use crate::models::{AppState, PortfolioSelectorQuery};
+use crate::portfolio::PortfolioKey;
+use crate::portfolio_render::{
+    render_portfolio, rendered_portfolio_response, PortfolioRenderFormat,
+};
+use poem::http::StatusCode;
+use poem::web::{Data, Query};
+use poem::{handler, Error, Request, Response};
+use std::sync::Arc;
+
+/// 공개 문서를 외부 리소스 없이 열리는 HTML 한 장으로 내려준다.
+#[handler]
+pub async fn get_portfolio_html(
+    req: &Request,
+    Query(selector): Query<PortfolioSelectorQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Response, Error> {
+    let key = PortfolioKey::resolve(&selector, None)?;
+    let rendered = render_portfolio(&data.db, &key, PortfolioRenderFormat::Html)
+        .await?
+        .ok_or_else(|| {
+            Error::from_string(
+                "포트폴리오 데이터를 찾지 못했습니다.",
+                StatusCode::NOT_FOUND,
+            )
+        })?;
+
+    Ok(rendered_portfolio_response(
+        req,
+        &key,
+        PortfolioRenderFormat::Html,
+        rendered,
+    ))
+}
@@ -0,0 +1,10 @@
+use crate::portfolio_schema::portfolio_document_schema;
+use poem::{handler, Response};
+
+/// 포트폴리오 문서의 JSON Schema(draft-07). 모델에서 만들어지므로 저장할 때 쓰는 검증 규칙과 같다.
+#[handler]
+pub async fn get_portfolio_schema() -> Response {
+    Response::builder()
+        .content_type("application/schema+json")
+        .body(portfolio_document_schema().to_string())
+}
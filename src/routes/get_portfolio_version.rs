@@ -0,0 +1,29 @@
+use crate::models::{AppState, CustomResponse, PortfolioSelectorQuery, PortfolioVersionResponse};
+use crate::portfolio::{fetch_portfolio_version, version_document, PortfolioKey};
+use poem::web::{Data, Json, Path, Query};
+use poem::{handler, Error};
+use std::sync::Arc;
+
+#[handler]
+pub async fn get_portfolio_version(
+    Path(version_number): Path<i64>,
+    Query(selector): Query<PortfolioSelectorQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<PortfolioVersionResponse>>, Error> {
+    let key = PortfolioKey::resolve(&selector, None)?;
+    let version = fetch_portfolio_version(&data.db, &key, version_number).await?;
+    let content = version_document(&version)?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(PortfolioVersionResponse {
+            version_number: version.version_number,
+            slug: version.slug,
+            lang: version.lang,
+            source: version.source,
+            created_at: version.created_at,
+            content,
+        }),
+        message: None,
+    }))
+}
@@ -0,0 +1,20 @@
+use crate::models::{AppState, CustomResponse, PortfolioSelectorQuery, PortfolioVersionSummary};
+use crate::portfolio::{list_portfolio_versions, PortfolioKey};
+use poem::web::{Data, Json, Query};
+use poem::{handler, Error};
+use std::sync::Arc;
+
+#[handler]
+pub async fn get_portfolio_versions(
+    Query(selector): Query<PortfolioSelectorQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<Vec<PortfolioVersionSummary>>>, Error> {
+    let key = PortfolioKey::resolve(&selector, None)?;
+    let versions = list_portfolio_versions(&data.db, &key).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(versions),
+        message: None,
+    }))
+}
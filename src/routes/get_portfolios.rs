@@ -0,0 +1,20 @@
+use crate::models::{AppState, CustomResponse, PortfolioSelectorQuery, PortfolioSummary};
+use crate::portfolio::list_portfolios;
+use poem::web::{Data, Json, Query};
+use poem::{handler, Error};
+use std::sync::Arc;
+
+/// 공개 문서가 있는 포트폴리오를 slug, 언어 순으로 돌려준다. `slug`/`lang`을 주면 그것만 거른다.
+#[handler]
+pub async fn get_portfolios(
+    Query(selector): Query<PortfolioSelectorQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<Vec<PortfolioSummary>>>, Error> {
+    let portfolios = list_portfolios(&data.db, &selector).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(portfolios),
+        message: None,
+    }))
+}
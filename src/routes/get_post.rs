@@ -8,6 +8,7 @@ use poem::{
 };
 use sqlx::{query_as, Sqlite};
 
+use crate::portfolio_posts::projects_for_post;
 use crate::AppState;
 use crate::{
     models::{Post, PostResponseDb},
@@ -37,14 +38,19 @@ pub async fn get_post(
 
     match result {
         Ok(Some(db_post)) => {
+            let tags = parse_tags(&db_post.tags);
+            let tag_names: Vec<String> = tags.iter().map(|tag| tag.tag.clone()).collect();
+            let portfolio_projects =
+                projects_for_post(&data.db, &db_post.post_id, &tag_names).await?;
             let post_response = Post {
                 post_id: db_post.post_id,
                 title: db_post.title,
                 description: db_post.description,
                 published_at: db_post.published_at,
-                tags: parse_tags(&db_post.tags),
+                tags,
                 content: db_post.content,
                 status: db_post.status,
+                portfolio_projects: Some(portfolio_projects),
             };
             Ok(Json(post_response))
         }
@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json},
+    Error,
+};
+
+use crate::models::{AppState, SecuritySettings};
+use crate::two_factor::admin_totp_required;
+
+#[handler]
+pub async fn get_security_settings(
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<SecuritySettings>, Error> {
+    let require_admin_totp = admin_totp_required(&data.db).await.map_err(|err| {
+        Error::from_string(
+            format!("보안 설정 조회 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?;
+
+    Ok(Json(SecuritySettings { require_admin_totp }))
+}
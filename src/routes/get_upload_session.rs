@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Path},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::{
+    models::{AppState, CustomResponse, UploadSessionResponse},
+    upload_sessions::{fetch_upload_session, upload_session_response},
+};
+
+#[handler]
+pub async fn get_upload_session(
+    req: &Request,
+    Path(session_id): Path<String>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<UploadSessionResponse>>, Error> {
+    let user = current_user(req)?;
+    let session = fetch_upload_session(&data.db, &session_id, &user.user_id).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(upload_session_response(session)),
+        message: None,
+    }))
+}
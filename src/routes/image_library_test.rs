@@ -0,0 +1,350 @@
+use std::{env, sync::Arc};
+
+use poem::{
+    get, http::StatusCode, post, test::TestClient, test::TestForm, test::TestFormField, Endpoint,
+    EndpointExt, Route,
+};
+use serde_json::json;
+use sqlx::{query, query_scalar, SqlitePool};
+use tokio::fs;
+
+use crate::{
+    db::init_db,
+    middlewares::auth_middleware::Auth,
+    models::AppState,
+    routes::{
+        delete_image::delete_image, get_image_detail::get_image_detail, get_images::get_images,
+        update_image::update_image, upload_image::upload_image,
+    },
+};
+use tyange_cms_api::auth::jwt::Claims;
+
+// UPLOAD_PATH는 프로세스 전역 환경 변수라 병렬로 도는 upload_image_test와 같은 경로를 쓴다.
+const TEST_UPLOAD_PATH: &str = "/tmp/tyange-cms-upload-image-tests";
+
+async fn create_test_state() -> Arc<AppState> {
+    env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+    env::set_var("UPLOAD_PATH", TEST_UPLOAD_PATH);
+
+    fs::create_dir_all(TEST_UPLOAD_PATH)
+        .await
+        .expect("failed to create upload dir");
+
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    init_db(&db).await.expect("failed to init db");
+
+    for (post_id, writer_id) in [("post-1", "writer-1"), ("post-2", "writer-2")] {
+        query(
+            "INSERT INTO posts (post_id, title, content, writer_id, status) VALUES (?, ?, '', ?, 'published')",
+        )
+        .bind(post_id)
+        .bind(post_id)
+        .bind(writer_id)
+        .execute(&db)
+        .await
+        .expect("failed to insert post");
+    }
+
+    Arc::new(AppState::new(db))
+}
+
+fn create_test_app(state: Arc<AppState>) -> impl Endpoint {
+    Route::new()
+        .at("/images/upload", post(upload_image).with(Auth))
+        .at("/images", get(get_images).with(Auth))
+        .at(
+            "/image/:image_id",
+            get(get_image_detail.with(Auth))
+                .put(update_image.with(Auth))
+                .delete(delete_image.with(Auth)),
+        )
+        .data(state)
+}
+
+fn issue_access_token(user_id: &str, role: &str) -> String {
+    Claims::create_access_token(user_id, role, b"test-access-secret")
+        .expect("failed to create access token")
+}
+
+async fn upload(
+    cli: &TestClient<impl Endpoint>,
+    token: &str,
+    query_string: &str,
+    file_name: &str,
+) -> (String, String) {
+    let response = cli
+        .post(format!("/images/upload{}", query_string))
+        .header("Authorization", token)
+        .multipart(
+            TestForm::new().field(
+                TestFormField::bytes(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>")
+                    .name("file")
+                    .filename(file_name)
+                    .content_type("image/svg+xml"),
+            ),
+        )
+        .send()
+        .await;
+    response.assert_status_is_ok();
+
+    let json = response.json().await;
+    let data = json.value().object().get("data").object();
+    (
+        data.get("image_id").string().to_string(),
+        data.get("image_path").string().to_string(),
+    )
+}
+
+#[tokio::test]
+async fn image_library_lists_with_filters_and_pagination() {
+    let state = create_test_state().await;
+    let cli = TestClient::new(create_test_app(state.clone()));
+    let writer_1 = issue_access_token("writer-1", "user");
+    let writer_2 = issue_access_token("writer-2", "user");
+
+    upload(&cli, &writer_1, "?post_id=post-1", "a.svg").await;
+    upload(&cli, &writer_1, "?image_type=thumbnail", "b.svg").await;
+    upload(&cli, &writer_2, "", "c.svg").await;
+
+    let response = cli
+        .get("/images")
+        .header("Authorization", &writer_1)
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    let json = response.json().await;
+    let data = json.value().object().get("data").object();
+    data.get("total_count").assert_i64(3);
+    data.get("images").array().assert_len(3);
+
+    let response = cli
+        .get("/images?uploaded_by=writer-1&limit=1&offset=1")
+        .header("Authorization", &writer_1)
+        .send()
+        .await;
+    let json = response.json().await;
+    let data = json.value().object().get("data").object();
+    data.get("total_count").assert_i64(2);
+    let images = data.get("images").array();
+    images.assert_len(1);
+    images
+        .get(0)
+        .object()
+        .get("uploaded_by")
+        .assert_string("writer-1");
+
+    let response = cli
+        .get("/images?post_id=post-1")
+        .header("Authorization", &writer_1)
+        .send()
+        .await;
+    let json = response.json().await;
+    let data = json.value().object().get("data").object();
+    data.get("total_count").assert_i64(1);
+    data.get("images")
+        .array()
+        .get(0)
+        .object()
+        .get("origin_name")
+        .assert_string("a.svg");
+
+    let response = cli
+        .get("/images?image_type=thumbnail")
+        .header("Authorization", &writer_1)
+        .send()
+        .await;
+    let json = response.json().await;
+    let data = json.value().object().get("data").object();
+    data.get("total_count").assert_i64(1);
+    data.get("images")
+        .array()
+        .get(0)
+        .object()
+        .get("origin_name")
+        .assert_string("b.svg");
+}
+
+#[tokio::test]
+async fn update_image_edits_alt_text_and_reassigns_post() {
+    let state = create_test_state().await;
+    let cli = TestClient::new(create_test_app(state.clone()));
+    let writer_1 = issue_access_token("writer-1", "user");
+    let writer_2 = issue_access_token("writer-2", "user");
+
+    let (image_id, _) = upload(&cli, &writer_1, "", "a.svg").await;
+
+    let response = cli
+        .put(format!("/image/{}", image_id))
+        .header("Authorization", &writer_1)
+        .body_json(&json!({ "alt_text": "로고", "caption": "새 로고", "post_id": "post-1" }))
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    let json = response.json().await;
+    let data = json.value().object().get("data").object();
+    data.get("alt_text").assert_string("로고");
+    data.get("caption").assert_string("새 로고");
+    data.get("post_id").assert_string("post-1");
+
+    let response = cli
+        .put(format!("/image/{}", image_id))
+        .header("Authorization", &writer_1)
+        .body_json(&json!({ "post_id": "post-2" }))
+        .send()
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    let response = cli
+        .put(format!("/image/{}", image_id))
+        .header("Authorization", &writer_2)
+        .body_json(&json!({ "alt_text": "남의 이미지" }))
+        .send()
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    let response = cli
+        .put(format!("/image/{}", image_id))
+        .header("Authorization", &writer_1)
+        .body_json(&json!({ "post_id": "" }))
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    let json = response.json().await;
+    let data = json.value().object().get("data").object();
+    data.get("post_id").assert_null();
+    data.get("alt_text").assert_string("로고");
+}
+
+#[tokio::test]
+async fn delete_image_refuses_while_referenced_then_removes_file() {
+    let state = create_test_state().await;
+    let cli = TestClient::new(create_test_app(state.clone()));
+    let writer_1 = issue_access_token("writer-1", "user");
+
+    let (image_id, image_path) = upload(&cli, &writer_1, "", "a.svg").await;
+    let file_path = format!(
+        "{}/{}",
+        TEST_UPLOAD_PATH,
+        image_path.trim_start_matches("/images/")
+    );
+
+    query("UPDATE posts SET content = ? WHERE post_id = 'post-1'")
+        .bind(format!("![logo]({})", image_path))
+        .execute(&state.db)
+        .await
+        .expect("failed to update post");
+
+    let detail = cli
+        .get(format!("/image/{}", image_id))
+        .header("Authorization", &writer_1)
+        .send()
+        .await;
+    let json = detail.json().await;
+    let references = json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("references")
+        .array();
+    references.assert_len(1);
+    references
+        .get(0)
+        .object()
+        .get("source_id")
+        .assert_string("post-1");
+
+    let response = cli
+        .delete(format!("/image/{}", image_id))
+        .header("Authorization", &writer_1)
+        .send()
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+    assert!(fs::metadata(&file_path).await.is_ok());
+
+    query("UPDATE posts SET content = '' WHERE post_id = 'post-1'")
+        .execute(&state.db)
+        .await
+        .expect("failed to update post");
+
+    let response = cli
+        .delete(format!("/image/{}", image_id))
+        .header("Authorization", &writer_1)
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    assert!(fs::metadata(&file_path).await.is_err());
+
+    let response = cli
+        .get(format!("/image/{}", image_id))
+        .header("Authorization", &writer_1)
+        .send()
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn duplicate_uploads_share_one_file_until_last_image_is_deleted() {
+    let state = create_test_state().await;
+    let cli = TestClient::new(create_test_app(state.clone()));
+    let writer_1 = issue_access_token("writer-1", "user");
+
+    let (first_id, first_path) = upload(&cli, &writer_1, "", "first.svg").await;
+    let (second_id, second_path) = upload(&cli, &writer_1, "", "second.svg").await;
+    assert_ne!(first_id, second_id);
+    assert_eq!(first_path, second_path);
+
+    let file_path = format!(
+        "{}/{}",
+        TEST_UPLOAD_PATH,
+        first_path.trim_start_matches("/images/")
+    );
+    let ref_count: i64 = query_scalar("SELECT ref_count FROM image_blobs")
+        .fetch_one(&state.db)
+        .await
+        .expect("failed to fetch ref count");
+    assert_eq!(ref_count, 2);
+
+    // 다른 이미지가 같은 파일을 쓰는 동안에는 본문에서 참조 중이어도 논리 이미지만 지운다.
+    query("UPDATE posts SET content = ? WHERE post_id = 'post-1'")
+        .bind(format!("![logo]({})", first_path))
+        .execute(&state.db)
+        .await
+        .expect("failed to update post");
+
+    let response = cli
+        .delete(format!("/image/{}", first_id))
+        .header("Authorization", &writer_1)
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    assert!(fs::metadata(&file_path).await.is_ok());
+
+    let response = cli
+        .delete(format!("/image/{}", second_id))
+        .header("Authorization", &writer_1)
+        .send()
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+
+    query("UPDATE posts SET content = '' WHERE post_id = 'post-1'")
+        .execute(&state.db)
+        .await
+        .expect("failed to update post");
+
+    let response = cli
+        .delete(format!("/image/{}", second_id))
+        .header("Authorization", &writer_1)
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    assert!(fs::metadata(&file_path).await.is_err());
+
+    let blob_count: i64 = query_scalar("SELECT COUNT(*) FROM image_blobs")
+        .fetch_one(&state.db)
+        .await
+        .expect("failed to count blobs");
+    assert_eq!(blob_count, 0);
+}
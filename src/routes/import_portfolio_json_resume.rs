@@ -0,0 +1,57 @@
+use crate::json_resume::import_json_resume;
+use crate::models::{
+    AppState, CustomResponse, JsonResumeImportQuery, JsonResumeImportResponse,
+    PortfolioSelectorQuery,
+};
+use crate::portfolio::{save_portfolio_draft, PortfolioKey};
+use crate::portfolio_schema::ensure_valid_document;
+use poem::web::{Data, Json, Query};
+use poem::{handler, Error, Request};
+use serde_json::Value;
+use std::sync::Arc;
+use tyange_cms_api::auth::authorization::current_user;
+
+/// JSON Resume을 문서로 바꿔 초안에 저장한다. 공개하려면 `/portfolio/publish`를 따로 호출한다.
+#[handler]
+pub async fn import_portfolio_json_resume(
+    req: &Request,
+    Query(selector): Query<PortfolioSelectorQuery>,
+    Query(params): Query<JsonResumeImportQuery>,
+    Json(resume): Json<Value>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<JsonResumeImportResponse>>, Error> {
+    let user = current_user(req)?;
+    let key = PortfolioKey::resolve(&selector, None)?;
+    let dry_run = params.dry_run.unwrap_or(false);
+
+    let (document, unmapped) = import_json_resume(&resume, &key.slug, &key.lang)?;
+    // 미리보기(`dry_run`)도 저장할 때와 같은 검증을 거쳐야 결과를 믿을 수 있다.
+    ensure_valid_document(&document)?;
+    let draft = if dry_run {
+        None
+    } else {
+        Some(save_portfolio_draft(&data.db, &key, document.clone(), &user.user_id).await?)
+    };
+
+    let message = if unmapped.is_empty() {
+        String::from("JSON Resume을 가져왔습니다.")
+    } else {
+        format!(
+            "JSON Resume을 가져왔습니다. 옮기지 못한 항목 {}개",
+            unmapped.len()
+        )
+    };
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(JsonResumeImportResponse {
+            slug: key.slug,
+            lang: key.lang,
+            dry_run,
+            unmapped,
+            document,
+            draft,
+        }),
+        message: Some(message),
+    }))
+}
@@ -1,77 +1,191 @@
 use std::{env, sync::Arc};
 
-use bcrypt::{hash, verify, DEFAULT_COST};
+use bcrypt::{hash, DEFAULT_COST};
 use poem::{
     handler,
     http::StatusCode,
     web::{Data, Json},
-    Body, Response,
+    Body, Request, Response,
 };
 
-use sqlx::Row;
+use sqlx::{Row, SqlitePool};
+use tyange_cms_api::auth::account::{is_account_disabled, record_login};
 use tyange_cms_api::auth::jwt::Claims;
+use tyange_cms_api::auth::password::{is_bcrypt_hash, matches_password_uniform};
+use tyange_cms_api::auth::refresh_token::{create_refresh_family, record_refresh_token};
 
 use crate::{
-    models::{LoginRequest, LoginResponse},
+    login_throttle::{
+        account_key, client_ip, locked_for, record_blocked, record_failure, record_success,
+        too_many_attempts,
+    },
+    models::{LoginOutcome, LoginRequest, LoginResponse},
+    sessions::SessionDevice,
+    two_factor::{admin_setup_required, create_login_challenge, totp_enabled},
     AppState,
 };
 
-pub fn issue_login_response(user_id: &str, user_role: &str) -> Result<LoginResponse, poem::Error> {
-    let access_token_secret = match env::var("JWT_ACCESS_SECRET") {
-        Ok(value) => value,
-        Err(e) => {
-            eprintln!("Server configuration error: {:?}", e);
-            return Err(poem::Error::from_string(
-                "Server configuration error.",
-                StatusCode::INTERNAL_SERVER_ERROR,
-            ));
-        }
-    };
-    let refresh_token_secret = match env::var("JWT_REFRESH_SECRET") {
-        Ok(value) => value,
-        Err(e) => {
-            eprintln!("Server configuration error: {:?}", e);
-            return Err(poem::Error::from_string(
-                "Server configuration error.",
-                StatusCode::INTERNAL_SERVER_ERROR,
-            ));
+/// JWT 서명 키 환경변수가 없을 때. 핸들러에서는 `?`로 `500`이 된다.
+#[derive(Debug)]
+pub struct MissingJwtSecret;
+
+impl From<MissingJwtSecret> for poem::Error {
+    fn from(_: MissingJwtSecret) -> Self {
+        poem::Error::from_string(
+            "Server configuration error.",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    }
+}
+
+pub fn jwt_secret(name: &str) -> Result<String, MissingJwtSecret> {
+    env::var(name).map_err(|e| {
+        eprintln!("Server configuration error: {:?}", e);
+        MissingJwtSecret
+    })
+}
+
+pub fn account_disabled_error() -> poem::Error {
+    poem::Error::from_string(
+        "비활성화된 계정입니다. 관리자에게 문의해 주세요.",
+        StatusCode::FORBIDDEN,
+    )
+}
+
+fn token_store_error(e: sqlx::Error) -> poem::Error {
+    eprintln!("Database error while storing refresh token: {:?}", e);
+    poem::Error::from_string("Database error", StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// 비밀번호나 Google 토큰을 확인한 뒤 부른다. 2단계 인증을 켠 계정은 토큰 대신
+/// `POST /login/2fa`에 쓸 중간 토큰을 받는다. 아이디나 IP가 잠겨 있으면 중간 토큰을 만들지 않는다.
+pub async fn begin_login(
+    db: &SqlitePool,
+    user_id: &str,
+    user_role: &str,
+    device: &SessionDevice,
+) -> Result<LoginOutcome, poem::Error> {
+    if is_account_disabled(db, user_id)
+        .await
+        .map_err(token_store_error)?
+    {
+        return Err(account_disabled_error());
+    }
+    if totp_enabled(db, user_id).await.map_err(token_store_error)? {
+        // Google 로그인처럼 `/login`의 잠금 확인을 거치지 않는 경로도 있으므로 여기서 한 번 더 본다.
+        let account = account_key(user_id);
+        if let Some(retry_after) = locked_for(db, &account, &device.ip_address).await? {
+            record_blocked(db, &account, &device.ip_address).await?;
+            return Err(too_many_attempts(retry_after));
         }
-    };
+        let challenge = create_login_challenge(db, user_id).await?;
+        return Ok(LoginOutcome::TwoFactor(challenge));
+    }
+
+    Ok(LoginOutcome::Tokens(
+        issue_login_response(db, user_id, user_role, device).await?,
+    ))
+}
+
+/// 로그인마다 refresh token family(세션)를 새로 만들고 첫 토큰을 발급한다.
+/// 비활성화된 계정이면 비밀번호가 맞아도 `403`이다. 2단계 인증까지 끝난 여기서야 아이디 실패 카운터를 지운다.
+pub async fn issue_login_response(
+    db: &SqlitePool,
+    user_id: &str,
+    user_role: &str,
+    device: &SessionDevice,
+) -> Result<LoginResponse, poem::Error> {
+    if is_account_disabled(db, user_id)
+        .await
+        .map_err(token_store_error)?
+    {
+        return Err(account_disabled_error());
+    }
+    record_success(db, &account_key(user_id)).await?;
+    record_login(db, user_id).await.map_err(token_store_error)?;
+
+    let family_id = create_refresh_family(
+        db,
+        user_id,
+        device.user_agent.as_deref(),
+        Some(&device.ip_address),
+    )
+    .await
+    .map_err(token_store_error)?;
+    let mut response = issue_family_tokens(db, user_id, user_role, &family_id).await?;
+    response.two_factor_setup_required = admin_setup_required(db, user_id, user_role)
+        .await
+        .map_err(token_store_error)?;
+    Ok(response)
+}
+
+/// `family_id`에 새 refresh 토큰을 기록하고 같은 세션에 묶인 access/refresh 토큰 쌍을 만든다.
+/// `/refresh`의 회전도 이 함수를 쓴다.
+pub async fn issue_family_tokens(
+    db: &SqlitePool,
+    user_id: &str,
+    user_role: &str,
+    family_id: &str,
+) -> Result<LoginResponse, poem::Error> {
+    let access_token_secret = jwt_secret("JWT_ACCESS_SECRET")?;
+    let refresh_token_secret = jwt_secret("JWT_REFRESH_SECRET")?;
 
     let access_token_secret_bytes = access_token_secret.as_bytes();
-    let access_token = Claims::create_access_token(user_id, user_role, access_token_secret_bytes)
+    // access 토큰에도 세션 ID를 넣어 두면 `Auth`가 폐기된 세션의 토큰을 바로 막을 수 있다.
+    let mut access_claims = Claims::new(user_id, user_role, "access", 600);
+    access_claims.family_id = Some(family_id.to_owned());
+    let access_token = access_claims
+        .to_token(access_token_secret_bytes)
         .map_err(|e| {
+            eprintln!("Server configuration error: {:?}", e);
+            poem::Error::from_string(
+                "Can not create access token.",
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+
+    let token_id = record_refresh_token(db, family_id)
+        .await
+        .map_err(token_store_error)?;
+    let refresh_token_secret_bytes = refresh_token_secret.as_bytes();
+    let refresh_token = Claims::create_family_refresh_token(
+        user_id,
+        user_role,
+        family_id,
+        &token_id,
+        refresh_token_secret_bytes,
+    )
+    .map_err(|e| {
         eprintln!("Server configuration error: {:?}", e);
         poem::Error::from_string(
-            "Can not create access token.",
+            "Can not create refresh token.",
             StatusCode::INTERNAL_SERVER_ERROR,
         )
     })?;
 
-    let refresh_token_secret_bytes = refresh_token_secret.as_bytes();
-    let refresh_token =
-        Claims::create_refresh_token(user_id, user_role, refresh_token_secret_bytes).map_err(
-            |e| {
-                eprintln!("Server configuration error: {:?}", e);
-                poem::Error::from_string(
-                    "Can not create refresh token.",
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                )
-            },
-        )?;
-
     Ok(LoginResponse {
         access_token,
         refresh_token,
         user_role: user_role.to_string(),
+        two_factor_setup_required: false,
     })
 }
 
+/// 없는 아이디와 틀린 비밀번호는 같은 `401`이고, 없는 아이디도 bcrypt 검증 한 번만큼 시간을 쓴다.
+/// 아이디나 IP가 잠겨 있으면 비밀번호를 보지 않고 `429`를 돌려준다.
 #[handler]
 pub async fn login(
+    req: &Request,
     Json(payload): Json<LoginRequest>,
     data: Data<&Arc<AppState>>,
 ) -> poem::Result<Response> {
+    let account = account_key(&payload.user_id);
+    let ip_address = client_ip(req);
+    if let Some(retry_after) = locked_for(&data.db, &account, &ip_address).await? {
+        record_blocked(&data.db, &account, &ip_address).await?;
+        return Err(too_many_attempts(retry_after));
+    }
+
     let user = sqlx::query(
         r#"
         SELECT user_id, password, user_role FROM users WHERE user_id = ?
@@ -85,66 +199,51 @@ pub async fn login(
         poem::Error::from_string("Database error", StatusCode::INTERNAL_SERVER_ERROR)
     })?;
 
-    let row = match user {
-        Some(row) => row,
-        None => {
-            return Err(poem::Error::from_string(
-                "Invalid credentials",
-                StatusCode::UNAUTHORIZED,
-            ))
-        }
-    };
+    let user_id: Option<String> = user
+        .as_ref()
+        .map(|row| row.try_get("user_id").unwrap_or_default());
+    let stored_hash: Option<String> = user
+        .as_ref()
+        .and_then(|row| row.try_get("password").unwrap_or(None));
+    let user_role: String = user
+        .as_ref()
+        .map(|row| row.try_get("user_role").unwrap_or_default())
+        .unwrap_or_default();
 
-    let user_id: String = row.try_get("user_id").unwrap_or_default();
-    let stored_hash: Option<String> = row.try_get("password").unwrap_or(None);
-    let user_role: String = row.try_get("user_role").unwrap_or_default();
+    let password_matches = matches_password_uniform(&payload.password, stored_hash.as_deref());
 
-    let password_matches = matches_password(&payload.password, stored_hash.as_deref());
-
-    if password_matches {
-        upgrade_legacy_password_if_needed(
-            &data.db,
-            &user_id,
-            &payload.password,
-            stored_hash.as_deref(),
-        )
-        .await?;
+    let (Some(user_id), true) = (user_id, password_matches) else {
+        record_failure(&data.db, &account, &ip_address).await?;
+        return Err(poem::Error::from_string(
+            "Invalid credentials",
+            StatusCode::UNAUTHORIZED,
+        ));
+    };
 
-        let login_response = issue_login_response(&user_id, &user_role)?;
+    upgrade_legacy_password_if_needed(
+        &data.db,
+        &user_id,
+        &payload.password,
+        stored_hash.as_deref(),
+    )
+    .await?;
 
-        let json_body = serde_json::to_string(&login_response).map_err(|_| {
-            poem::Error::from_string(
-                "JSON serialization error",
-                StatusCode::INTERNAL_SERVER_ERROR,
-            )
-        })?;
+    let device = SessionDevice::from_request(req);
+    let login_response = begin_login(&data.db, &user_id, &user_role, &device).await?;
 
-        println!("로그인 성공: {}", user_id);
-
-        Ok(Response::builder()
-            .status(StatusCode::OK)
-            .content_type("application/json")
-            .body(Body::from(json_body)))
-    } else {
-        Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .content_type("application/json")
-            .body(Body::from_string(String::from(
-                "로그인 실패: 잘못된 비밀번호",
-            ))))
-    }
-}
+    let json_body = serde_json::to_string(&login_response).map_err(|_| {
+        poem::Error::from_string(
+            "JSON serialization error",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?;
 
-fn matches_password(candidate_password: &str, stored_password: Option<&str>) -> bool {
-    let Some(stored_password) = stored_password else {
-        return false;
-    };
+    println!("로그인 성공: {}", user_id);
 
-    if is_bcrypt_hash(stored_password) {
-        verify(candidate_password, stored_password).unwrap_or(false)
-    } else {
-        candidate_password == stored_password
-    }
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .content_type("application/json")
+        .body(Body::from(json_body)))
 }
 
 async fn upgrade_legacy_password_if_needed(
@@ -186,7 +285,3 @@ async fn upgrade_legacy_password_if_needed(
 
     Ok(())
 }
-
-fn is_bcrypt_hash(value: &str) -> bool {
-    value.starts_with("$2a$") || value.starts_with("$2b$") || value.starts_with("$2y$")
-}
@@ -4,21 +4,23 @@ use poem::{
     handler,
     http::StatusCode,
     web::{Data, Json},
-    Error,
+    Error, Request,
 };
 use sqlx::{query, Row};
 use tyange_cms_api::auth::google::GoogleTokenVerifier;
 
 use crate::{
-    models::{AppState, GoogleLoginRequest, LoginResponse},
-    routes::login::issue_login_response,
+    models::{AppState, GoogleLoginRequest, LoginOutcome},
+    routes::login::begin_login,
+    sessions::SessionDevice,
 };
 
 #[handler]
 pub async fn login_google(
+    req: &Request,
     Json(payload): Json<GoogleLoginRequest>,
     data: Data<&Arc<AppState>>,
-) -> Result<Json<LoginResponse>, Error> {
+) -> Result<Json<LoginOutcome>, Error> {
     let google_client_id = env::var("GOOGLE_CLIENT_ID").map_err(|e| {
         eprintln!("Server configuration error: {:?}", e);
         Error::from_string(
@@ -104,8 +106,16 @@ pub async fn login_google(
                 })?;
             }
 
-            if current_display_name.as_deref().unwrap_or("").trim().is_empty()
-                || current_avatar_url.as_deref().unwrap_or("").trim().is_empty()
+            if current_display_name
+                .as_deref()
+                .unwrap_or("")
+                .trim()
+                .is_empty()
+                || current_avatar_url
+                    .as_deref()
+                    .unwrap_or("")
+                    .trim()
+                    .is_empty()
             {
                 query(
                     r#"
@@ -139,9 +149,10 @@ pub async fn login_google(
                     google_sub,
                     display_name,
                     avatar_url,
-                    bio
+                    bio,
+                    created_at
                 )
-                VALUES (?, NULL, 'user', 'google', ?, ?, ?, NULL)
+                VALUES (?, NULL, 'user', 'google', ?, ?, ?, NULL, CURRENT_TIMESTAMP)
                 "#,
             )
             .bind(&verified_user.email)
@@ -159,6 +170,7 @@ pub async fn login_google(
         }
     };
 
-    let response = issue_login_response(&user_id, &user_role)?;
+    let device = SessionDevice::from_request(req);
+    let response = begin_login(&data.db, &user_id, &user_role, &device).await?;
     Ok(Json(response))
 }
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json},
+    Error, Request,
+};
+use sqlx::query_scalar;
+
+use crate::models::{AppState, LoginResponse, PasskeyLoginRequest};
+use crate::passkeys::authenticate_passkey;
+use crate::routes::login::issue_login_response;
+use crate::sessions::SessionDevice;
+
+/// 패스키 로그인 두 번째 단계. 인증기에서 사용자 확인(UV)을 거쳤으므로 TOTP는 따로 묻지 않는다.
+#[handler]
+pub async fn login_passkey(
+    req: &Request,
+    Json(payload): Json<PasskeyLoginRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<LoginResponse>, Error> {
+    let user_id = authenticate_passkey(&data.db, &payload.credential).await?;
+
+    let user_role: String = query_scalar("SELECT user_role FROM users WHERE user_id = ?")
+        .bind(&user_id)
+        .fetch_optional(&data.db)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("사용자 정보 조회 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?
+        .ok_or_else(|| Error::from_string("Invalid credentials", StatusCode::UNAUTHORIZED))?;
+
+    let device = SessionDevice::from_request(req);
+    let response = issue_login_response(&data.db, &user_id, &user_role, &device).await?;
+    println!("로그인 성공(패스키): {}", user_id);
+    Ok(Json(response))
+}
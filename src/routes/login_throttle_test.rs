@@ -0,0 +1,247 @@
+use std::{env, sync::Arc};
+
+use poem::{delete, get, http::StatusCode, post, test::TestClient, Endpoint, EndpointExt, Route};
+use serde_json::{json, Value};
+use sqlx::{query, SqlitePool};
+
+use crate::{
+    db::init_db,
+    middlewares::{admin_middleware::AdminOnly, auth_middleware::Auth},
+    models::AppState,
+    routes::{
+        add_user::create_user, delete_login_lockout::delete_login_lockout,
+        get_login_audit_events::get_login_audit_events, get_login_lockouts::get_login_lockouts,
+        login::login,
+    },
+};
+
+const PASSWORD: &str = "throttle-pass-7";
+
+async fn create_test_state() -> Arc<AppState> {
+    env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+    env::set_var("JWT_REFRESH_SECRET", "test-refresh-secret");
+    env::set_var("TRUST_PROXY_HEADERS", "true");
+
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    init_db(&db).await.expect("failed to init db");
+    Arc::new(AppState::new(db))
+}
+
+fn create_app(state: Arc<AppState>) -> impl Endpoint {
+    Route::new()
+        .at("/login", post(login))
+        .at(
+            "/admin/login-lockouts",
+            get(get_login_lockouts).with(AdminOnly).with(Auth),
+        )
+        .at(
+            "/admin/login-lockouts/:scope/:key",
+            delete(delete_login_lockout).with(AdminOnly).with(Auth),
+        )
+        .at(
+            "/admin/login-audit",
+            get(get_login_audit_events).with(AdminOnly).with(Auth),
+        )
+        .data(state)
+}
+
+async fn read_json(response: poem::test::TestResponse) -> Value {
+    serde_json::from_str(&response.0.into_body().into_string().await.unwrap())
+        .expect("response should be json")
+}
+
+async fn attempt(
+    cli: &TestClient<impl Endpoint>,
+    user_id: &str,
+    password: &str,
+    ip: &str,
+) -> poem::test::TestResponse {
+    cli.post("/login")
+        .header("X-Forwarded-For", ip)
+        .body_json(&json!({ "user_id": user_id, "password": password }))
+        .send()
+        .await
+}
+
+async fn admin_token(cli: &TestClient<impl Endpoint>) -> String {
+    let response = attempt(cli, "root@example.com", PASSWORD, "10.0.0.1").await;
+    response.assert_status_is_ok();
+    read_json(response).await["access_token"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn repeated_failures_lock_the_account_until_an_admin_clears_it() {
+    let state = create_test_state().await;
+    create_user(&state.db, "root@example.com", PASSWORD, "admin")
+        .await
+        .unwrap();
+    create_user(&state.db, "writer@example.com", PASSWORD, "user")
+        .await
+        .unwrap();
+    let db = state.db.clone();
+    let cli = TestClient::new(create_app(state));
+    let token = admin_token(&cli).await;
+
+    // 없는 아이디와 틀린 비밀번호는 구분되지 않는다.
+    let unknown = attempt(&cli, "nobody@example.com", "wrong-pass-1", "10.0.0.2").await;
+    unknown.assert_status(StatusCode::UNAUTHORIZED);
+    let unknown_body = unknown.0.into_body().into_string().await.unwrap();
+    let wrong = attempt(&cli, "writer@example.com", "wrong-pass-1", "10.0.0.2").await;
+    wrong.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        wrong.0.into_body().into_string().await.unwrap(),
+        unknown_body
+    );
+
+    // 대소문자를 바꿔도 같은 카운터다. 다섯 번째 실패에서 잠긴다.
+    for _ in 0..4 {
+        attempt(&cli, "Writer@Example.com", "wrong-pass-1", "10.0.0.3")
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+    }
+    let locked = attempt(&cli, "writer@example.com", PASSWORD, "10.0.0.4").await;
+    locked.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: i64 = locked.0.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=30).contains(&retry_after));
+
+    // 없는 아이디도 똑같이 잠겨서 잠금 응답으로 가입 여부를 알 수 없다.
+    for _ in 0..4 {
+        attempt(&cli, "nobody@example.com", "wrong-pass-1", "10.0.0.5")
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+    }
+    attempt(&cli, "nobody@example.com", "wrong-pass-1", "10.0.0.5")
+        .await
+        .assert_status(StatusCode::TOO_MANY_REQUESTS);
+
+    // 잠금이 풀린 뒤 또 틀리면 두 배로 잠근다.
+    query("UPDATE login_throttles SET locked_until = '2000-01-01 00:00:00' WHERE throttle_key = ?")
+        .bind("writer@example.com")
+        .execute(&db)
+        .await
+        .unwrap();
+    attempt(&cli, "writer@example.com", "wrong-pass-1", "10.0.0.6")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    let locked = attempt(&cli, "writer@example.com", PASSWORD, "10.0.0.6").await;
+    locked.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: i64 = locked.0.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((31..=60).contains(&retry_after));
+
+    let lockouts = read_json(
+        cli.get("/admin/login-lockouts?scope=account&locked_only=true")
+            .header("Authorization", &token)
+            .send()
+            .await,
+    )
+    .await;
+    let lockouts = lockouts["data"]["lockouts"].as_array().unwrap().clone();
+    let writer = lockouts
+        .iter()
+        .find(|item| item["throttle_key"] == "writer@example.com")
+        .expect("writer should be locked");
+    assert_eq!(writer["failure_count"], 6);
+    assert_eq!(writer["locked"], true);
+
+    cli.delete("/admin/login-lockouts/account/Writer@example.com")
+        .header("Authorization", &token)
+        .send()
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    cli.delete("/admin/login-lockouts/account/writer@example.com")
+        .header("Authorization", &token)
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    attempt(&cli, "writer@example.com", PASSWORD, "10.0.0.7")
+        .await
+        .assert_status_is_ok();
+
+    let audit = read_json(
+        cli.get("/admin/login-audit?user_id=writer@example.com")
+            .header("Authorization", &token)
+            .send()
+            .await,
+    )
+    .await;
+    let events: Vec<String> = audit["data"]["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["event"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(events.first().map(String::as_str), Some("cleared"));
+    assert_eq!(events.iter().filter(|event| *event == "failed").count(), 6);
+    assert_eq!(events.iter().filter(|event| *event == "blocked").count(), 2);
+    assert_eq!(
+        audit["data"]["events"][0]["actor_id"],
+        json!("root@example.com")
+    );
+}
+
+#[tokio::test]
+async fn one_address_cycling_through_accounts_gets_locked_out() {
+    let state = create_test_state().await;
+    create_user(&state.db, "root@example.com", PASSWORD, "admin")
+        .await
+        .unwrap();
+    create_user(&state.db, "target@example.com", PASSWORD, "user")
+        .await
+        .unwrap();
+    let cli = TestClient::new(create_app(state));
+    let token = admin_token(&cli).await;
+
+    for index in 0..20 {
+        attempt(
+            &cli,
+            &format!("guess{}@example.com", index),
+            "wrong-pass-1",
+            "203.0.113.9",
+        )
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    }
+    attempt(&cli, "target@example.com", PASSWORD, "203.0.113.9")
+        .await
+        .assert_status(StatusCode::TOO_MANY_REQUESTS);
+    // 다른 주소에서는 그대로 로그인할 수 있다.
+    attempt(&cli, "target@example.com", PASSWORD, "198.51.100.4")
+        .await
+        .assert_status_is_ok();
+
+    cli.delete("/admin/login-lockouts/ip/203.0.113.9")
+        .header("Authorization", &token)
+        .send()
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    attempt(&cli, "target@example.com", PASSWORD, "203.0.113.9")
+        .await
+        .assert_status_is_ok();
+
+    let audit = read_json(
+        cli.get("/admin/login-audit?ip_address=203.0.113.9&event=failed&limit=5")
+            .header("Authorization", &token)
+            .send()
+            .await,
+    )
+    .await;
+    assert_eq!(audit["data"]["events"].as_array().unwrap().len(), 5);
+    cli.delete("/admin/login-lockouts/session/203.0.113.9")
+        .header("Authorization", &token)
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
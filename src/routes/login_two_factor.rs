@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json},
+    Error, Request,
+};
+use sqlx::query_scalar;
+
+use crate::login_throttle::{
+    account_key, client_ip, locked_for, record_blocked, record_failure, too_many_attempts,
+};
+use crate::models::{AppState, LoginResponse, TwoFactorLoginRequest};
+use crate::routes::login::issue_login_response;
+use crate::sessions::SessionDevice;
+use crate::two_factor::{complete_login_challenge, login_challenge_user};
+
+/// 로그인 두 번째 단계. `POST /login`이 준 중간 토큰과 TOTP 코드(또는 복구 코드)를 받아 토큰을 발급한다.
+/// 틀린 코드는 비밀번호 실패와 같이 아이디·IP 카운터에 세므로, 중간 토큰을 새로 받아 가며 코드를 맞혀 볼 수 없다.
+#[handler]
+pub async fn login_two_factor(
+    req: &Request,
+    Json(payload): Json<TwoFactorLoginRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<LoginResponse>, Error> {
+    let challenge_user = login_challenge_user(&data.db, &payload.two_factor_token).await?;
+    let account = account_key(&challenge_user);
+    let ip_address = client_ip(req);
+    if let Some(retry_after) = locked_for(&data.db, &account, &ip_address).await? {
+        record_blocked(&data.db, &account, &ip_address).await?;
+        return Err(too_many_attempts(retry_after));
+    }
+
+    let user_id = match complete_login_challenge(
+        &data.db,
+        &payload.two_factor_token,
+        payload.code.as_deref(),
+        payload.recovery_code.as_deref(),
+    )
+    .await
+    {
+        Ok(user_id) => user_id,
+        Err(err) => {
+            if err.status() == StatusCode::UNAUTHORIZED {
+                record_failure(&data.db, &account, &ip_address).await?;
+            }
+            return Err(err);
+        }
+    };
+
+    // 역할은 중간 토큰을 만든 뒤에 바뀌었을 수 있으므로 지금 값을 다시 읽는다.
+    let user_role: String = query_scalar("SELECT user_role FROM users WHERE user_id = ?")
+        .bind(&user_id)
+        .fetch_optional(&data.db)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("사용자 정보 조회 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?
+        .ok_or_else(|| Error::from_string("Invalid credentials", StatusCode::UNAUTHORIZED))?;
+
+    let device = SessionDevice::from_request(req);
+    let response = issue_login_response(&data.db, &user_id, &user_role, &device).await?;
+    println!("로그인 성공(2단계 인증): {}", user_id);
+    Ok(Json(response))
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json},
+    Error,
+};
+use tyange_cms_api::auth::refresh_token::revoke_refresh_family;
+
+use crate::models::{AppState, RefreshTokenRequest};
+use crate::routes::refresh::decode_refresh_token;
+
+/// 요청한 refresh token이 속한 family를 폐기한다. 이미 폐기된 family여도 성공으로 본다.
+/// 같은 세션의 access token도 `Auth`가 바로 막으므로 로그아웃하면 세션이 곧바로 끝난다.
+#[handler]
+pub async fn logout(
+    Json(payload): Json<RefreshTokenRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<StatusCode, Error> {
+    let (_, family_id, _) = decode_refresh_token(&payload.refresh_token)?;
+
+    revoke_refresh_family(&data.db, &family_id, "logout")
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("로그아웃 처리 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
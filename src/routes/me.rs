@@ -16,7 +16,7 @@ pub async fn me(req: &Request, data: Data<&Arc<AppState>>) -> Result<Json<MeResp
     let user = current_user(req)?;
 
     let me = query_as::<_, MeResponse>(
-        "SELECT user_id, user_role, display_name, avatar_url, bio FROM users WHERE user_id = ?",
+        "SELECT user_id, user_role, display_name, avatar_url, bio, deletion_scheduled_at FROM users WHERE user_id = ?",
     )
         .bind(&user.user_id)
         .fetch_optional(&data.db)
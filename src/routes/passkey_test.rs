@@ -0,0 +1,367 @@
+use std::{env, sync::Arc};
+
+use ciborium::value::Value as CborValue;
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+use poem::{get, http::StatusCode, post, put, test::TestClient, Endpoint, EndpointExt, Route};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use tyange_cms_api::auth::webauthn::{decode_base64url, encode_base64url};
+
+use crate::{
+    db::init_db,
+    middlewares::auth_middleware::Auth,
+    models::AppState,
+    routes::{
+        add_user::create_user, create_my_passkey::create_my_passkey,
+        create_my_passkey_options::create_my_passkey_options,
+        create_passkey_login_options::create_passkey_login_options,
+        delete_my_passkey::delete_my_passkey, get_my_passkeys::get_my_passkeys, login::login,
+        login_passkey::login_passkey, update_my_passkey::update_my_passkey,
+    },
+};
+
+const PASSWORD: &str = "passkey-pass-9";
+const ORIGIN: &str = "http://localhost:3000";
+
+/// 테스트용 소프트웨어 인증기. 고정된 P-256 키와 credential id를 쓴다.
+struct SoftAuthenticator {
+    credential_id: Vec<u8>,
+    key: SigningKey,
+}
+
+impl SoftAuthenticator {
+    fn new(seed: u8) -> Self {
+        Self {
+            credential_id: vec![seed; 16],
+            key: SigningKey::from_slice(&[seed; 32]).expect("seed should be a valid scalar"),
+        }
+    }
+
+    fn id(&self) -> String {
+        encode_base64url(&self.credential_id)
+    }
+
+    fn client_data(kind: &str, challenge: &str, origin: &str) -> Vec<u8> {
+        serde_json::to_vec(&json!({ "type": kind, "challenge": challenge, "origin": origin }))
+            .unwrap()
+    }
+
+    fn authenticator_data(flags: u8, sign_count: u32) -> Vec<u8> {
+        let mut data = Sha256::digest(b"localhost").to_vec();
+        data.push(flags);
+        data.extend_from_slice(&sign_count.to_be_bytes());
+        data
+    }
+
+    fn cose_key(&self) -> Vec<u8> {
+        let point = self.key.verifying_key().to_encoded_point(false);
+        let key = CborValue::Map(vec![
+            (CborValue::Integer(1.into()), CborValue::Integer(2.into())),
+            (
+                CborValue::Integer(3.into()),
+                CborValue::Integer((-7).into()),
+            ),
+            (
+                CborValue::Integer((-1).into()),
+                CborValue::Integer(1.into()),
+            ),
+            (
+                CborValue::Integer((-2).into()),
+                CborValue::Bytes(point.x().unwrap().to_vec()),
+            ),
+            (
+                CborValue::Integer((-3).into()),
+                CborValue::Bytes(point.y().unwrap().to_vec()),
+            ),
+        ]);
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&key, &mut bytes).unwrap();
+        bytes
+    }
+
+    /// `navigator.credentials.create()` 결과(`toJSON()`)를 흉내 낸다. UP | UV | AT.
+    fn create(&self, challenge: &str) -> Value {
+        let mut auth_data = Self::authenticator_data(0x45, 0);
+        auth_data.extend_from_slice(&[0; 16]);
+        auth_data.extend_from_slice(&(self.credential_id.len() as u16).to_be_bytes());
+        auth_data.extend_from_slice(&self.credential_id);
+        auth_data.extend_from_slice(&self.cose_key());
+
+        let attestation = CborValue::Map(vec![
+            (
+                CborValue::Text("fmt".to_string()),
+                CborValue::Text("none".to_string()),
+            ),
+            (
+                CborValue::Text("attStmt".to_string()),
+                CborValue::Map(Vec::new()),
+            ),
+            (
+                CborValue::Text("authData".to_string()),
+                CborValue::Bytes(auth_data),
+            ),
+        ]);
+        let mut attestation_object = Vec::new();
+        ciborium::ser::into_writer(&attestation, &mut attestation_object).unwrap();
+
+        json!({
+            "id": self.id(),
+            "response": {
+                "clientDataJSON": encode_base64url(&Self::client_data("webauthn.create", challenge, ORIGIN)),
+                "attestationObject": encode_base64url(&attestation_object),
+            }
+        })
+    }
+
+    /// `navigator.credentials.get()` 결과(`toJSON()`)를 흉내 낸다. UP | UV.
+    fn get(&self, challenge: &str, origin: &str, sign_count: u32, user_handle: &str) -> Value {
+        let auth_data = Self::authenticator_data(0x05, sign_count);
+        let client_data = Self::client_data("webauthn.get", challenge, origin);
+        let mut signed = auth_data.clone();
+        signed.extend_from_slice(&Sha256::digest(&client_data));
+        let signature: Signature = self.key.sign(&signed);
+
+        json!({
+            "id": self.id(),
+            "response": {
+                "clientDataJSON": encode_base64url(&client_data),
+                "authenticatorData": encode_base64url(&auth_data),
+                "signature": encode_base64url(signature.to_der().as_bytes()),
+                "userHandle": user_handle,
+            }
+        })
+    }
+}
+
+async fn create_test_state() -> Arc<AppState> {
+    env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+    env::set_var("JWT_REFRESH_SECRET", "test-refresh-secret");
+
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    init_db(&db).await.expect("failed to init db");
+    Arc::new(AppState::new(db))
+}
+
+fn create_app(state: Arc<AppState>) -> impl Endpoint {
+    Route::new()
+        .at("/login", post(login))
+        .at("/login/passkey", post(login_passkey))
+        .at("/login/passkey/options", post(create_passkey_login_options))
+        .at(
+            "/me/passkeys",
+            get(get_my_passkeys).post(create_my_passkey).with(Auth),
+        )
+        .at(
+            "/me/passkeys/options",
+            post(create_my_passkey_options).with(Auth),
+        )
+        .at(
+            "/me/passkeys/:passkey_id",
+            put(update_my_passkey).delete(delete_my_passkey).with(Auth),
+        )
+        .data(state)
+}
+
+async fn read_json(response: poem::test::TestResponse) -> Value {
+    serde_json::from_str(&response.0.into_body().into_string().await.unwrap())
+        .expect("response should be json")
+}
+
+async fn registration_options(cli: &TestClient<impl Endpoint>, token: &str) -> Value {
+    let response = cli
+        .post("/me/passkeys/options")
+        .header("Authorization", token)
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    read_json(response).await
+}
+
+async fn login_challenge(cli: &TestClient<impl Endpoint>) -> String {
+    let response = cli.post("/login/passkey/options").send().await;
+    response.assert_status_is_ok();
+    read_json(response).await["challenge"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn passkeys_register_and_log_in_without_a_password() {
+    let state = create_test_state().await;
+    create_user(&state.db, "owner@example.com", PASSWORD, "user")
+        .await
+        .unwrap();
+    let cli = TestClient::new(create_app(state));
+
+    let signed_in = cli
+        .post("/login")
+        .body_json(&json!({ "user_id": "owner@example.com", "password": PASSWORD }))
+        .send()
+        .await;
+    signed_in.assert_status_is_ok();
+    let token = read_json(signed_in).await["access_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let laptop = SoftAuthenticator::new(7);
+    let phone = SoftAuthenticator::new(9);
+
+    let options = registration_options(&cli, &token).await;
+    assert_eq!(options["rp"]["id"], "localhost");
+    assert_eq!(options["pubKeyCredParams"][0]["alg"], -7);
+    assert_eq!(
+        options["authenticatorSelection"]["userVerification"],
+        "required"
+    );
+    let user_handle = options["user"]["id"].as_str().unwrap().to_string();
+    assert_ne!(user_handle, "owner@example.com");
+    let challenge = options["challenge"].as_str().unwrap().to_string();
+    assert_eq!(decode_base64url(&challenge).unwrap().len(), 32);
+
+    let created = cli
+        .post("/me/passkeys")
+        .header("Authorization", &token)
+        .body_json(&json!({ "name": "노트북", "credential": laptop.create(&challenge) }))
+        .send()
+        .await;
+    created.assert_status_is_ok();
+    let laptop_id = read_json(created).await["id"].as_i64().unwrap();
+
+    // challenge는 한 번만 쓸 수 있다.
+    cli.post("/me/passkeys")
+        .header("Authorization", &token)
+        .body_json(&json!({ "credential": phone.create(&challenge) }))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let options = registration_options(&cli, &token).await;
+    assert_eq!(options["user"]["id"], user_handle.as_str());
+    assert_eq!(options["excludeCredentials"][0]["id"], laptop.id());
+    let created = cli
+        .post("/me/passkeys")
+        .header("Authorization", &token)
+        .body_json(&json!({ "credential": phone.create(options["challenge"].as_str().unwrap()) }))
+        .send()
+        .await;
+    created.assert_status_is_ok();
+    let phone_passkey = read_json(created).await;
+    assert_eq!(phone_passkey["name"], "패스키");
+    let phone_id = phone_passkey["id"].as_i64().unwrap();
+
+    let renamed = cli
+        .put(format!("/me/passkeys/{}", phone_id))
+        .header("Authorization", &token)
+        .body_json(&json!({ "name": "휴대폰" }))
+        .send()
+        .await;
+    renamed.assert_status_is_ok();
+    assert_eq!(read_json(renamed).await["name"], "휴대폰");
+
+    let listed = read_json(
+        cli.get("/me/passkeys")
+            .header("Authorization", &token)
+            .send()
+            .await,
+    )
+    .await;
+    assert_eq!(listed["passkeys"].as_array().unwrap().len(), 2);
+
+    let options = read_json(cli.post("/login/passkey/options").send().await).await;
+    assert_eq!(options["rpId"], "localhost");
+    assert_eq!(options["allowCredentials"], json!([]));
+    let assertion = laptop.get(
+        options["challenge"].as_str().unwrap(),
+        ORIGIN,
+        1,
+        &user_handle,
+    );
+    let logged_in = cli
+        .post("/login/passkey")
+        .body_json(&json!({ "credential": assertion }))
+        .send()
+        .await;
+    logged_in.assert_status_is_ok();
+    let logged_in = read_json(logged_in).await;
+    assert!(logged_in["access_token"].as_str().is_some());
+    assert_eq!(logged_in["user_role"], "user");
+
+    // 같은 응답을 다시 보내면 challenge가 이미 쓰였다.
+    cli.post("/login/passkey")
+        .body_json(&json!({ "credential": assertion }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    // 서명 횟수가 늘지 않은 응답은 복제된 인증기로 보고 거절한다.
+    let challenge = login_challenge(&cli).await;
+    cli.post("/login/passkey")
+        .body_json(&json!({ "credential": laptop.get(&challenge, ORIGIN, 1, &user_handle) }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    let challenge = login_challenge(&cli).await;
+    cli.post("/login/passkey")
+        .body_json(&json!({
+            "credential": laptop.get(&challenge, "https://evil.example.com", 2, &user_handle)
+        }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    let challenge = login_challenge(&cli).await;
+    cli.post("/login/passkey")
+        .body_json(&json!({ "credential": laptop.get(&challenge, ORIGIN, 2, "someone-else") }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    cli.delete(format!("/me/passkeys/{}", laptop_id))
+        .header("Authorization", &token)
+        .send()
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let challenge = login_challenge(&cli).await;
+    cli.post("/login/passkey")
+        .body_json(&json!({ "credential": laptop.get(&challenge, ORIGIN, 3, &user_handle) }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    // 횟수를 세지 않는 인증기(항상 0)는 그대로 받는다.
+    for _ in 0..2 {
+        let challenge = login_challenge(&cli).await;
+        cli.post("/login/passkey")
+            .body_json(&json!({ "credential": phone.get(&challenge, ORIGIN, 0, &user_handle) }))
+            .send()
+            .await
+            .assert_status_is_ok();
+    }
+
+    let listed = read_json(
+        cli.get("/me/passkeys")
+            .header("Authorization", &token)
+            .send()
+            .await,
+    )
+    .await;
+    let passkeys = listed["passkeys"].as_array().unwrap();
+    let laptop_entry = passkeys
+        .iter()
+        .find(|passkey| passkey["id"] == laptop_id)
+        .unwrap();
+    assert!(laptop_entry["revoked_at"].as_str().is_some());
+    assert!(laptop_entry["last_used_at"].as_str().is_some());
+
+    cli.delete("/me/passkeys/9999")
+        .header("Authorization", &token)
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,361 @@
+use std::{
+    env,
+    sync::{Arc, Mutex},
+};
+
+use poem::{http::StatusCode, post, put, test::TestClient, Endpoint, EndpointExt, Route};
+use serde_json::json;
+use sqlx::{query_scalar, SqlitePool};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+};
+
+use crate::{
+    db::init_db,
+    mail_outbox::{deliver_pending_emails, SmtpConfig, SmtpMailer, SmtpSecurity},
+    middlewares::auth_middleware::Auth,
+    models::AppState,
+    routes::{
+        confirm_password_reset::confirm_password_reset, login::login, refresh::refresh,
+        request_password_reset::request_password_reset, signup::signup,
+        update_my_password::update_my_password,
+    },
+};
+
+async fn create_test_state() -> Arc<AppState> {
+    env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+    env::set_var("JWT_REFRESH_SECRET", "test-refresh-secret");
+
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    init_db(&db).await.expect("failed to init db");
+    Arc::new(AppState::new(db))
+}
+
+fn create_app(state: Arc<AppState>) -> impl Endpoint {
+    Route::new()
+        .at("/signup", post(signup))
+        .at("/login", post(login))
+        .at("/refresh", post(refresh))
+        .at("/me/password", put(update_my_password).with(Auth))
+        .at("/password-reset", post(request_password_reset))
+        .at("/password-reset/confirm", post(confirm_password_reset))
+        .data(state)
+}
+
+/// 받은 DATA 본문을 그대로 모으는 최소한의 SMTP 서버.
+async fn start_smtp_stand_in() -> (u16, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("failed to bind smtp stand-in");
+    let port = listener.local_addr().expect("local addr").port();
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let captured = messages.clone();
+
+    tokio::spawn(async move {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                return;
+            };
+            let captured = captured.clone();
+            tokio::spawn(async move {
+                let (reader, mut writer) = stream.into_split();
+                let mut lines = BufReader::new(reader).lines();
+                writer.write_all(b"220 stand-in ESMTP\r\n").await.ok();
+
+                while let Ok(Some(line)) = lines.next_line().await {
+                    let command = line.to_ascii_uppercase();
+                    if command.starts_with("EHLO") || command.starts_with("HELO") {
+                        writer.write_all(b"250 stand-in\r\n").await.ok();
+                    } else if command.starts_with("DATA") {
+                        writer.write_all(b"354 go ahead\r\n").await.ok();
+                        let mut data = String::new();
+                        while let Ok(Some(line)) = lines.next_line().await {
+                            if line == "." {
+                                break;
+                            }
+                            data.push_str(&line);
+                            data.push('\n');
+                        }
+                        captured.lock().unwrap().push(data);
+                        writer.write_all(b"250 queued\r\n").await.ok();
+                    } else if command.starts_with("QUIT") {
+                        writer.write_all(b"221 bye\r\n").await.ok();
+                        return;
+                    } else {
+                        writer.write_all(b"250 ok\r\n").await.ok();
+                    }
+                }
+            });
+        }
+    });
+
+    (port, messages)
+}
+
+fn stand_in_mailer(port: u16) -> SmtpMailer {
+    SmtpMailer::new(&SmtpConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        security: SmtpSecurity::None,
+        username: None,
+        password: None,
+        from: "noreply@tyange.com".to_string(),
+    })
+    .expect("failed to build mailer")
+}
+
+/// 헤더 뒤 base64 본문을 풀어서 돌려준다.
+fn decoded_body(message: &str) -> String {
+    let (_, body) = message
+        .split_once("\n\n")
+        .expect("message should have a body");
+    let encoded: String = body.split_whitespace().collect();
+    String::from_utf8(base64::decode(encoded).expect("body should be base64"))
+        .expect("body should be utf-8")
+}
+
+async fn signup_and_login(cli: &TestClient<impl Endpoint>, email: &str) -> (String, String) {
+    cli.post("/signup")
+        .body_json(&json!({ "email": email, "password": "first-pass-1" }))
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    let login_response = cli
+        .post("/login")
+        .body_json(&json!({ "user_id": email, "password": "first-pass-1" }))
+        .send()
+        .await;
+    login_response.assert_status_is_ok();
+    let login_json = login_response.json().await;
+    (
+        login_json
+            .value()
+            .object()
+            .get("access_token")
+            .string()
+            .to_string(),
+        login_json
+            .value()
+            .object()
+            .get("refresh_token")
+            .string()
+            .to_string(),
+    )
+}
+
+#[tokio::test]
+async fn password_change_checks_current_password_and_policy_and_revokes_sessions() {
+    let state = create_test_state().await;
+    let cli = TestClient::new(create_app(state));
+    let (access_token, refresh_token) = signup_and_login(&cli, "change@example.com").await;
+
+    cli.put("/me/password")
+        .header("Authorization", &access_token)
+        .body_json(&json!({ "current_password": "wrong-pass-1", "new_password": "second-pass-2" }))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    cli.put("/me/password")
+        .header("Authorization", &access_token)
+        .body_json(&json!({ "current_password": "first-pass-1", "new_password": "onlyletters" }))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    cli.put("/me/password")
+        .header("Authorization", &access_token)
+        .body_json(&json!({ "current_password": "first-pass-1", "new_password": "change-2026" }))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let changed = cli
+        .put("/me/password")
+        .header("Authorization", &access_token)
+        .body_json(&json!({ "current_password": "first-pass-1", "new_password": "second-pass-2" }))
+        .send()
+        .await;
+    changed.assert_status_is_ok();
+    let new_refresh_token = changed
+        .json()
+        .await
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("refresh_token")
+        .string()
+        .to_string();
+
+    cli.post("/refresh")
+        .body_json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    cli.post("/refresh")
+        .body_json(&json!({ "refresh_token": new_refresh_token }))
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    cli.post("/login")
+        .body_json(&json!({ "user_id": "change@example.com", "password": "first-pass-1" }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    cli.post("/login")
+        .body_json(&json!({ "user_id": "change@example.com", "password": "second-pass-2" }))
+        .send()
+        .await
+        .assert_status_is_ok();
+}
+
+#[tokio::test]
+async fn reset_token_is_mailed_through_the_outbox_and_works_once() {
+    env::set_var("PASSWORD_RESET_URL", "https://tyange.com/reset-password");
+    let state = create_test_state().await;
+    let cli = TestClient::new(create_app(state.clone()));
+    let (_, refresh_token) = signup_and_login(&cli, "reset@example.com").await;
+
+    // 없는 계정도 같은 응답이지만 메일은 쌓이지 않는다.
+    cli.post("/password-reset")
+        .body_json(&json!({ "email": "nobody@example.com" }))
+        .send()
+        .await
+        .assert_status_is_ok();
+    cli.post("/password-reset")
+        .body_json(&json!({ "email": "reset@example.com" }))
+        .send()
+        .await
+        .assert_status_is_ok();
+    let pending: i64 = query_scalar("SELECT COUNT(*) FROM email_outbox WHERE status = 'pending'")
+        .fetch_one(&state.db)
+        .await
+        .expect("count outbox");
+    assert_eq!(pending, 1);
+
+    // SMTP 서버가 없으면 실패 횟수만 남기고 다음 주기를 기다린다.
+    let closed_port = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        listener.local_addr().expect("addr").port()
+    };
+    let failed = deliver_pending_emails(&state.db, &stand_in_mailer(closed_port))
+        .await
+        .expect("delivery should run");
+    assert_eq!(failed.failed, 1);
+    let attempts: i64 = query_scalar("SELECT attempts FROM email_outbox WHERE status = 'pending'")
+        .fetch_one(&state.db)
+        .await
+        .expect("pending row should remain");
+    assert_eq!(attempts, 1);
+
+    let (port, messages) = start_smtp_stand_in().await;
+    let delivered = deliver_pending_emails(&state.db, &stand_in_mailer(port))
+        .await
+        .expect("delivery should run");
+    assert_eq!(delivered.sent, 1);
+
+    let message = messages
+        .lock()
+        .unwrap()
+        .pop()
+        .expect("mail should be captured");
+    assert!(message.contains("To: reset@example.com"));
+    let body = decoded_body(&message);
+    let token = body
+        .split("token=")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .expect("body should contain the reset link")
+        .to_string();
+    assert!(body.contains("https://tyange.com/reset-password?token="));
+
+    cli.post("/password-reset/confirm")
+        .body_json(&json!({ "token": token, "new_password": "short" }))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    cli.post("/password-reset/confirm")
+        .body_json(&json!({ "token": token, "new_password": "brand-new-3" }))
+        .send()
+        .await
+        .assert_status_is_ok();
+    cli.post("/password-reset/confirm")
+        .body_json(&json!({ "token": token, "new_password": "brand-new-4" }))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    cli.post("/refresh")
+        .body_json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    cli.post("/login")
+        .body_json(&json!({ "user_id": "reset@example.com", "password": "brand-new-3" }))
+        .send()
+        .await
+        .assert_status_is_ok();
+}
+
+#[tokio::test]
+async fn reset_requests_are_throttled_per_account_and_ip_without_changing_the_response() {
+    env::set_var("TRUST_PROXY_HEADERS", "true");
+    let state = create_test_state().await;
+    let cli = TestClient::new(create_app(state.clone()));
+    signup_and_login(&cli, "flood@example.com").await;
+    signup_and_login(&cli, "other@example.com").await;
+
+    let request = |email: &'static str, ip: &'static str| {
+        cli.post("/password-reset")
+            .header("X-Forwarded-For", ip)
+            .body_json(&json!({ "email": email }))
+            .send()
+    };
+    let outbox_count = |recipient: &'static str| {
+        query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM email_outbox WHERE recipient = ? AND kind = 'password_reset'",
+        )
+        .bind(recipient)
+        .fetch_one(&state.db)
+    };
+
+    // 아이디 한도(3회)를 넘기면 IP를 바꿔도 메일이 더 쌓이지 않고, 먼저 보낸 토큰도 살아 있다.
+    for ip in ["203.0.113.1", "203.0.113.2", "203.0.113.3", "203.0.113.4", "203.0.113.5"] {
+        let response = request("flood@example.com", ip).await;
+        response.assert_status_is_ok();
+        response
+            .json()
+            .await
+            .value()
+            .object()
+            .get("message")
+            .assert_string("가입된 이메일이면 비밀번호 재설정 메일을 보냈습니다.");
+    }
+    assert_eq!(outbox_count("flood@example.com").await.unwrap(), 3);
+    let live_tokens: i64 = query_scalar(
+        "SELECT COUNT(*) FROM password_reset_tokens WHERE user_id = 'flood@example.com' AND used_at IS NULL",
+    )
+    .fetch_one(&state.db)
+    .await
+    .unwrap();
+    assert_eq!(live_tokens, 1);
+
+    // 한 IP가 여러 아이디를 돌려 가며 요청해도 IP 한도(10회)에서 막힌다.
+    for _ in 0..10 {
+        request("nobody@example.com", "198.51.100.7")
+            .await
+            .assert_status_is_ok();
+    }
+    request("other@example.com", "198.51.100.7")
+        .await
+        .assert_status_is_ok();
+    assert_eq!(outbox_count("other@example.com").await.unwrap(), 0);
+    request("other@example.com", "198.51.100.8")
+        .await
+        .assert_status_is_ok();
+    assert_eq!(outbox_count("other@example.com").await.unwrap(), 1);
+}
@@ -1,20 +1,36 @@
 use std::sync::Arc;
 
-use poem::{EndpointExt, Route, get, http::StatusCode, put, test::TestClient};
+use poem::{
+    delete, get, http::StatusCode, post, put, test::TestClient, Endpoint, EndpointExt, Route,
+};
 use serde_json::json;
-use sqlx::{SqlitePool, query_scalar};
+use sqlx::{query, query_scalar, SqlitePool};
 
 use crate::{
+    blog_redeploy::{BlogRedeployService, MockPortfolioDispatchCall},
     db::init_db,
+    middlewares::auth_middleware::Auth,
     models::AppState,
     routes::{
-        delete_portfolio::delete_portfolio, get_portfolio::get_portfolio,
-        update_portfolio::update_portfolio,
-        update_portfolio_section::update_portfolio_section,
+        delete_portfolio::delete_portfolio, delete_portfolio_draft::delete_portfolio_draft,
+        delete_post::delete_post, diff_portfolio_versions::diff_portfolio_versions,
+        export_portfolio_json_resume::export_portfolio_json_resume, get_portfolio::get_portfolio,
+        get_portfolio_draft::get_portfolio_draft, get_portfolio_html::get_portfolio_html,
+        get_portfolio_pdf::get_portfolio_pdf, get_portfolio_schema::get_portfolio_schema,
+        get_portfolio_version::get_portfolio_version,
+        get_portfolio_versions::get_portfolio_versions, get_portfolios::get_portfolios,
+        get_post::get_post, import_portfolio_json_resume::import_portfolio_json_resume,
+        preview_portfolio::preview_portfolio, publish_portfolio::publish_portfolio,
+        restore_portfolio_version::restore_portfolio_version,
+        update_portfolio_draft::update_portfolio_draft,
+        update_portfolio_draft_section::update_portfolio_draft_section,
+        validate_portfolio::validate_portfolio,
     },
 };
+use tyange_cms_api::auth::jwt::Claims;
 
 async fn create_state() -> Arc<AppState> {
+    std::env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
     let db = SqlitePool::connect("sqlite::memory:")
         .await
         .expect("failed to connect sqlite");
@@ -22,20 +38,55 @@ async fn create_state() -> Arc<AppState> {
     Arc::new(AppState::new(db))
 }
 
+fn admin_token() -> String {
+    Claims::create_access_token("admin-1", "admin", b"test-access-secret")
+        .expect("failed to create access token")
+}
+
+/// `PUT /portfolio`는 초안에만 쓰므로 공개 문서를 바꾸려면 이어서 발행한다.
+async fn save_and_publish(
+    cli: &TestClient<impl Endpoint>,
+    token: &str,
+    query: &str,
+    body: &serde_json::Value,
+) {
+    cli.put(format!("/portfolio{}", query))
+        .header("Authorization", token)
+        .body_json(body)
+        .send()
+        .await
+        .assert_status_is_ok();
+    publish(cli, token, query).await;
+}
+
+async fn publish(cli: &TestClient<impl Endpoint>, token: &str, query: &str) {
+    cli.post(format!("/portfolio/publish{}", query))
+        .header("Authorization", token)
+        .send()
+        .await
+        .assert_status_is_ok();
+}
+
 #[tokio::test]
 async fn get_portfolio_returns_not_found_initially_and_put_creates_and_updates_it() {
     let state = create_state().await;
     let cli = TestClient::new(
         Route::new()
-            .at("/portfolio", get(get_portfolio).put(update_portfolio))
+            .at(
+                "/portfolio",
+                get(get_portfolio).put(update_portfolio_draft.with(Auth)),
+            )
+            .at("/portfolio/publish", post(publish_portfolio).with(Auth))
             .data(state),
     );
+    let token = admin_token();
 
     let initial = cli.get("/portfolio").send().await;
     initial.assert_status(StatusCode::NOT_FOUND);
 
     let created = cli
         .put("/portfolio")
+        .header("Authorization", &token)
         .body_json(&json!({
             "content": {
                 "slug": "dev",
@@ -120,6 +171,13 @@ async fn get_portfolio_returns_not_found_initially_and_put_creates_and_updates_i
         .get(1)
         .assert_string("TypeScript 전환");
 
+    // 저장은 초안에만 들어가고, 발행해야 공개된다.
+    cli.get("/portfolio")
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    publish(&cli, &token, "").await;
+
     // GET should return the saved document
     let fetched = cli.get("/portfolio").send().await;
     fetched.assert_status_is_ok();
@@ -145,14 +203,19 @@ async fn delete_portfolio_removes_document() {
             .at(
                 "/portfolio",
                 get(get_portfolio)
-                    .put(update_portfolio)
+                    .put(update_portfolio_draft.with(Auth))
                     .delete(delete_portfolio),
             )
+            .at("/portfolio/publish", post(publish_portfolio).with(Auth))
             .data(state.clone()),
     );
+    let token = admin_token();
 
-    cli.put("/portfolio")
-        .body_json(&json!({
+    save_and_publish(
+        &cli,
+        &token,
+        "",
+        &json!({
             "content": {
                 "slug": "dev",
                 "version": 1,
@@ -166,10 +229,9 @@ async fn delete_portfolio_removes_document() {
                 },
                 "featured_projects": []
             }
-        }))
-        .send()
-        .await
-        .assert_status_is_ok();
+        }),
+    )
+    .await;
 
     let deleted = cli.delete("/portfolio").send().await;
     deleted.assert_status(StatusCode::NO_CONTENT);
@@ -182,11 +244,10 @@ async fn delete_portfolio_removes_document() {
 
     assert!(remaining.is_none());
 
-    let section_count: i32 =
-        query_scalar("SELECT COUNT(*) FROM portfolio_section")
-            .fetch_one(&state.db)
-            .await
-            .expect("failed to count sections");
+    let section_count: i32 = query_scalar("SELECT COUNT(*) FROM portfolio_section")
+        .fetch_one(&state.db)
+        .await
+        .expect("failed to count sections");
 
     assert_eq!(section_count, 0);
 }
@@ -196,14 +257,25 @@ async fn update_section_updates_individual_section() {
     let state = create_state().await;
     let cli = TestClient::new(
         Route::new()
-            .at("/portfolio", get(get_portfolio).put(update_portfolio))
-            .at("/portfolio/sections/:section_key", put(update_portfolio_section))
+            .at(
+                "/portfolio",
+                get(get_portfolio).put(update_portfolio_draft.with(Auth)),
+            )
+            .at(
+                "/portfolio/sections/:section_key",
+                put(update_portfolio_draft_section).with(Auth),
+            )
+            .at("/portfolio/publish", post(publish_portfolio).with(Auth))
             .data(state),
     );
+    let token = admin_token();
 
     // Create initial portfolio
-    cli.put("/portfolio")
-        .body_json(&json!({
+    save_and_publish(
+        &cli,
+        &token,
+        "",
+        &json!({
             "content": {
                 "slug": "dev",
                 "version": 1,
@@ -217,14 +289,14 @@ async fn update_section_updates_individual_section() {
                 },
                 "featured_projects": []
             }
-        }))
-        .send()
-        .await
-        .assert_status_is_ok();
+        }),
+    )
+    .await;
 
     // Update only the identity section
     let section_updated = cli
         .put("/portfolio/sections/identity")
+        .header("Authorization", &token)
         .body_json(&json!({
             "content": {
                 "name": "After",
@@ -240,6 +312,11 @@ async fn update_section_updates_individual_section() {
 
     section_updated.assert_status_is_ok();
 
+    // 섹션 수정도 초안에 들어가므로 공개 문서는 발행 전까지 그대로다.
+    let live = cli.get("/portfolio").send().await;
+    assert_eq!(identity_name(&live.json().await), "Before");
+    publish(&cli, &token, "").await;
+
     // GET should reflect the updated identity
     let fetched = cli.get("/portfolio").send().await;
     fetched.assert_status_is_ok();
@@ -267,3 +344,1221 @@ async fn update_section_updates_individual_section() {
         .get("name")
         .assert_string("After");
 }
+
+fn portfolio_body(name: &str, email: &str) -> serde_json::Value {
+    json!({
+        "content": {
+            "slug": "dev",
+            "version": 1,
+            "identity": {
+                "name": name,
+                "email": email,
+                "github_url": "https://github.com/tyange"
+            },
+            "featured_projects": [],
+            "intro": { "content": "안녕하세요" }
+        }
+    })
+}
+
+/// Helvetica로 찍을 수 있도록 Latin-1 글자만 쓴 영어 문서.
+fn latin_portfolio_body(name: &str) -> serde_json::Value {
+    json!({
+        "content": {
+            "slug": "dev",
+            "version": 1,
+            "identity": {
+                "name": name,
+                "email": "usun16@gmail.com",
+                "github_url": "https://github.com/tyange"
+            },
+            "featured_projects": [],
+            "intro": { "content": "Hello" }
+        }
+    })
+}
+
+#[tokio::test]
+async fn every_save_is_versioned_and_older_versions_can_be_diffed_and_restored() {
+    std::env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    init_db(&db).await.expect("failed to init db");
+    let (blog_redeploy, mock_handle) = BlogRedeployService::mock();
+    let state = Arc::new(AppState::new_with_blog_redeploy(db, blog_redeploy));
+    let cli = TestClient::new(
+        Route::new()
+            .at(
+                "/portfolio",
+                get(get_portfolio)
+                    .put(update_portfolio_draft.with(Auth))
+                    .delete(delete_portfolio),
+            )
+            .at(
+                "/portfolio/sections/:section_key",
+                put(update_portfolio_draft_section).with(Auth),
+            )
+            .at("/portfolio/publish", post(publish_portfolio).with(Auth))
+            .at("/portfolio/versions", get(get_portfolio_versions))
+            .at("/portfolio/versions/diff", get(diff_portfolio_versions))
+            .at(
+                "/portfolio/versions/:version_number",
+                get(get_portfolio_version),
+            )
+            .at(
+                "/portfolio/versions/:version_number/restore",
+                post(restore_portfolio_version).with(Auth),
+            )
+            .at("/portfolio/draft", get(get_portfolio_draft).with(Auth))
+            .data(state.clone()),
+    );
+    let token = admin_token();
+
+    save_and_publish(
+        &cli,
+        &token,
+        "",
+        &portfolio_body("Before", "old@example.com"),
+    )
+    .await;
+    cli.put("/portfolio/sections/identity")
+        .header("Authorization", &token)
+        .body_json(&json!({
+            "content": {
+                "name": "After",
+                "email": "new@example.com",
+                "github_url": "https://github.com/tyange"
+            }
+        }))
+        .send()
+        .await
+        .assert_status_is_ok();
+    publish(&cli, &token, "").await;
+    let mut without_intro = portfolio_body("After", "new@example.com");
+    without_intro["content"]["intro"] = serde_json::Value::Null;
+    save_and_publish(&cli, &token, "", &without_intro).await;
+
+    let versions = cli.get("/portfolio/versions").send().await;
+    versions.assert_status_is_ok();
+    let versions_json = versions.json().await;
+    let versions = versions_json.value().object().get("data").array();
+    versions.assert_len(3);
+    versions.get(0).object().get("version_number").assert_i64(3);
+    versions
+        .get(1)
+        .object()
+        .get("source")
+        .assert_string("publish");
+    versions
+        .get(2)
+        .object()
+        .get("source")
+        .assert_string("publish");
+
+    let diff = cli.get("/portfolio/versions/diff?from=1&to=3").send().await;
+    diff.assert_status_is_ok();
+    let diff_json = diff.json().await;
+    let sections = diff_json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("sections")
+        .array();
+    let identity = sections.get(1).object();
+    identity.get("section_key").assert_string("identity");
+    identity.get("change").assert_string("changed");
+    identity
+        .get("changed_paths")
+        .assert_string_array(&["/email", "/name"]);
+    identity
+        .get("before")
+        .object()
+        .get("email")
+        .assert_string("old@example.com");
+    let intro = sections.get(3).object();
+    intro.get("section_key").assert_string("intro");
+    intro.get("change").assert_string("removed");
+
+    // 지운 뒤에도 기록에서 되돌릴 수 있다.
+    cli.delete("/portfolio")
+        .send()
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    mock_handle.take_portfolio_calls().await;
+
+    // 되돌리기는 진행 중인 초안을 덮어쓸 뿐 공개 문서와 기록은 발행 전까지 그대로다.
+    cli.put("/portfolio")
+        .header("Authorization", &token)
+        .body_json(&portfolio_body("Pending", "pending@example.com"))
+        .send()
+        .await
+        .assert_status_is_ok();
+    let restored = cli
+        .post("/portfolio/versions/1/restore")
+        .header("Authorization", &token)
+        .send()
+        .await;
+    restored.assert_status_is_ok();
+    let restored_json = restored.json().await;
+    let data = restored_json.value().object().get("data").object();
+    data.get("restored_from").assert_i64(1);
+    data.get("draft")
+        .object()
+        .get("content")
+        .object()
+        .get("identity")
+        .object()
+        .get("email")
+        .assert_string("old@example.com");
+
+    let draft = cli
+        .get("/portfolio/draft")
+        .header("Authorization", &token)
+        .send()
+        .await;
+    draft.assert_status_is_ok();
+    assert_eq!(identity_name(&draft.json().await), "Before");
+    cli.get("/portfolio")
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    let version_count: i64 = query_scalar("SELECT COUNT(*) FROM portfolio_version")
+        .fetch_one(&state.db)
+        .await
+        .expect("failed to count versions");
+    assert_eq!(version_count, 3);
+    assert!(mock_handle.take_portfolio_calls().await.is_empty());
+
+    // 발행하면 되돌린 문서가 공개되고 새 버전과 사이트 빌드 요청이 남는다.
+    publish(&cli, &token, "").await;
+    let fetched = cli.get("/portfolio").send().await;
+    fetched.assert_status_is_ok();
+    let fetched_json = fetched.json().await;
+    let content = fetched_json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("content")
+        .object();
+    content
+        .get("identity")
+        .object()
+        .get("email")
+        .assert_string("old@example.com");
+    content
+        .get("intro")
+        .object()
+        .get("content")
+        .assert_string("안녕하세요");
+    assert_eq!(
+        mock_handle.take_portfolio_calls().await,
+        vec![MockPortfolioDispatchCall {
+            slug: "dev".to_string(),
+            lang: "ko".to_string(),
+            version_number: 4,
+        }]
+    );
+
+    let snapshot = cli.get("/portfolio/versions/4").send().await;
+    snapshot.assert_status_is_ok();
+    snapshot
+        .json()
+        .await
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("source")
+        .assert_string("publish");
+    cli.get("/portfolio/draft")
+        .header("Authorization", &token)
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    cli.post("/portfolio/versions/99/restore")
+        .header("Authorization", &token)
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    cli.get("/portfolio/versions/99")
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    let version_count: i64 = query_scalar("SELECT COUNT(*) FROM portfolio_version")
+        .fetch_one(&state.db)
+        .await
+        .expect("failed to count versions");
+    assert_eq!(version_count, 4);
+}
+
+#[tokio::test]
+async fn draft_edits_stay_private_until_published() {
+    std::env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    init_db(&db).await.expect("failed to init db");
+    let (blog_redeploy, mock_handle) = BlogRedeployService::mock();
+    let state = Arc::new(AppState::new_with_blog_redeploy(db, blog_redeploy));
+    let cli = TestClient::new(
+        Route::new()
+            .at(
+                "/portfolio",
+                get(get_portfolio).put(update_portfolio_draft.with(Auth)),
+            )
+            .at(
+                "/portfolio/draft",
+                get(get_portfolio_draft)
+                    .put(update_portfolio_draft)
+                    .delete(delete_portfolio_draft)
+                    .with(Auth),
+            )
+            .at(
+                "/portfolio/draft/sections/:section_key",
+                put(update_portfolio_draft_section).with(Auth),
+            )
+            .at("/portfolio/preview", get(preview_portfolio).with(Auth))
+            .at("/portfolio/publish", post(publish_portfolio).with(Auth))
+            .at("/portfolio/versions", get(get_portfolio_versions))
+            .data(state.clone()),
+    );
+    let token = admin_token();
+
+    save_and_publish(
+        &cli,
+        &token,
+        "",
+        &portfolio_body("Live", "live@example.com"),
+    )
+    .await;
+    mock_handle.take_portfolio_calls().await;
+
+    // 초안이 없을 때 섹션을 고치면 공개 문서를 복사해 시작한다.
+    let response = cli
+        .put("/portfolio/draft/sections/identity")
+        .header("Authorization", &token)
+        .body_json(&json!({
+            "content": {
+                "name": "Draft",
+                "email": "draft@example.com",
+                "github_url": "https://github.com/tyange"
+            }
+        }))
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    let json = response.json().await;
+    let data = json.value().object().get("data").object();
+    data.get("updated_by").assert_string("admin-1");
+    data.get("content")
+        .object()
+        .get("intro")
+        .object()
+        .get("content")
+        .assert_string("안녕하세요");
+
+    cli.put("/portfolio/draft/sections/unknown")
+        .header("Authorization", &token)
+        .body_json(&json!({ "content": {} }))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    cli.get("/portfolio/preview")
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    let preview = cli
+        .get("/portfolio/preview")
+        .header("Authorization", &token)
+        .send()
+        .await;
+    preview.assert_status_is_ok();
+    let preview_json = preview.json().await;
+    let preview_data = preview_json.value().object().get("data").object();
+    preview_data.get("source").assert_string("draft");
+    preview_data
+        .get("content")
+        .object()
+        .get("identity")
+        .object()
+        .get("name")
+        .assert_string("Draft");
+
+    let live = cli.get("/portfolio").send().await;
+    live.assert_status_is_ok();
+    live.json()
+        .await
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("content")
+        .object()
+        .get("identity")
+        .object()
+        .get("name")
+        .assert_string("Live");
+    assert!(mock_handle.take_portfolio_calls().await.is_empty());
+
+    let published = cli
+        .post("/portfolio/publish")
+        .header("Authorization", &token)
+        .send()
+        .await;
+    published.assert_status_is_ok();
+    let published_json = published.json().await;
+    let published_data = published_json.value().object().get("data").object();
+    published_data.get("version_number").assert_i64(2);
+
+    let live = cli.get("/portfolio").send().await;
+    live.json()
+        .await
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("content")
+        .object()
+        .get("identity")
+        .object()
+        .get("name")
+        .assert_string("Draft");
+
+    let versions = cli.get("/portfolio/versions").send().await;
+    let versions_json = versions.json().await;
+    let versions = versions_json.value().object().get("data").array();
+    versions.assert_len(2);
+    versions
+        .get(0)
+        .object()
+        .get("source")
+        .assert_string("publish");
+
+    assert_eq!(
+        mock_handle.take_portfolio_calls().await,
+        vec![MockPortfolioDispatchCall {
+            slug: "dev".to_string(),
+            lang: "ko".to_string(),
+            version_number: 2,
+        }]
+    );
+
+    // 발행한 초안은 비워지고, 미리보기는 공개 문서를 보여 준다.
+    cli.get("/portfolio/draft")
+        .header("Authorization", &token)
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    cli.post("/portfolio/publish")
+        .header("Authorization", &token)
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    let preview = cli
+        .get("/portfolio/preview")
+        .header("Authorization", &token)
+        .send()
+        .await;
+    preview
+        .json()
+        .await
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("source")
+        .assert_string("live");
+
+    cli.put("/portfolio/draft")
+        .header("Authorization", &token)
+        .body_json(&portfolio_body("Discarded", "x@example.com"))
+        .send()
+        .await
+        .assert_status_is_ok();
+    cli.delete("/portfolio/draft")
+        .header("Authorization", &token)
+        .send()
+        .await
+        .assert_status_is_ok();
+    cli.get("/portfolio/draft")
+        .header("Authorization", &token)
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+fn identity_name(json: &poem::test::TestJson) -> String {
+    json.value()
+        .object()
+        .get("data")
+        .object()
+        .get("content")
+        .object()
+        .get("identity")
+        .object()
+        .get("name")
+        .string()
+        .to_string()
+}
+
+#[tokio::test]
+async fn portfolios_are_scoped_by_slug_and_lang() {
+    let state = create_state().await;
+    let cli = TestClient::new(
+        Route::new()
+            .at("/portfolios", get(get_portfolios))
+            .at(
+                "/portfolio",
+                get(get_portfolio)
+                    .put(update_portfolio_draft.with(Auth))
+                    .delete(delete_portfolio),
+            )
+            .at(
+                "/portfolio/sections/:section_key",
+                put(update_portfolio_draft_section).with(Auth),
+            )
+            .at("/portfolio/publish", post(publish_portfolio).with(Auth))
+            .at("/portfolio/versions", get(get_portfolio_versions))
+            .data(state.clone()),
+    );
+    let token = admin_token();
+
+    // 쿼리가 없으면 문서의 slug와 기본 언어(ko)에 저장한다.
+    save_and_publish(
+        &cli,
+        &token,
+        "",
+        &portfolio_body("Dev KO", "dev@example.com"),
+    )
+    .await;
+    let response = cli
+        .put("/portfolio?lang=en")
+        .header("Authorization", &token)
+        .body_json(&portfolio_body("Dev EN", "dev@example.com"))
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    let json = response.json().await;
+    json.value()
+        .object()
+        .get("data")
+        .object()
+        .get("lang")
+        .assert_string("en");
+    publish(&cli, &token, "?lang=en").await;
+    save_and_publish(
+        &cli,
+        &token,
+        "?slug=design",
+        &portfolio_body("Design KO", "design@example.com"),
+    )
+    .await;
+
+    cli.put("/portfolio?lang=jp")
+        .header("Authorization", &token)
+        .body_json(&portfolio_body("Nope", "x@example.com"))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    cli.get("/portfolio?slug=Bad%20Slug")
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let list = cli.get("/portfolios").send().await;
+    list.assert_status_is_ok();
+    let list_json = list.json().await;
+    let items = list_json.value().object().get("data").array();
+    items.assert_len(3);
+    let keys: Vec<(String, String)> = (0..3)
+        .map(|index| {
+            let item = items.get(index).object();
+            (
+                item.get("slug").string().to_string(),
+                item.get("lang").string().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        keys,
+        vec![
+            ("design".to_string(), "ko".to_string()),
+            ("dev".to_string(), "en".to_string()),
+            ("dev".to_string(), "ko".to_string()),
+        ]
+    );
+    let filtered = cli.get("/portfolios?slug=dev").send().await;
+    filtered
+        .json()
+        .await
+        .value()
+        .object()
+        .get("data")
+        .array()
+        .assert_len(2);
+
+    let en = cli.get("/portfolio?lang=en").send().await;
+    en.assert_status_is_ok();
+    assert_eq!(identity_name(&en.json().await), "Dev EN");
+    let design = cli.get("/portfolio?slug=design").send().await;
+    let design_json = design.json().await;
+    assert_eq!(identity_name(&design_json), "Design KO");
+    design_json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("content")
+        .object()
+        .get("slug")
+        .assert_string("design");
+
+    // 섹션 수정은 고른 포트폴리오에만 닿는다.
+    cli.put("/portfolio/sections/identity?slug=dev&lang=en")
+        .header("Authorization", &token)
+        .body_json(&json!({
+            "content": {
+                "name": "Dev EN 2",
+                "email": "dev@example.com",
+                "github_url": "https://github.com/tyange"
+            }
+        }))
+        .send()
+        .await
+        .assert_status_is_ok();
+    publish(&cli, &token, "?slug=dev&lang=en").await;
+    cli.put("/portfolio/sections/identity?slug=design&lang=en")
+        .header("Authorization", &token)
+        .body_json(&json!({
+            "content": {
+                "name": "Missing",
+                "email": "x@example.com",
+                "github_url": "https://github.com/tyange"
+            }
+        }))
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    let ko = cli.get("/portfolio").send().await;
+    assert_eq!(identity_name(&ko.json().await), "Dev KO");
+    let en = cli.get("/portfolio?lang=en").send().await;
+    assert_eq!(identity_name(&en.json().await), "Dev EN 2");
+
+    let versions = cli.get("/portfolio/versions?lang=en").send().await;
+    let versions_json = versions.json().await;
+    let versions = versions_json.value().object().get("data").array();
+    versions.assert_len(2);
+    versions
+        .get(0)
+        .object()
+        .get("source")
+        .assert_string("publish");
+    let versions = cli.get("/portfolio/versions").send().await;
+    versions
+        .json()
+        .await
+        .value()
+        .object()
+        .get("data")
+        .array()
+        .assert_len(1);
+
+    // 삭제도 고른 언어만 지운다.
+    cli.delete("/portfolio?lang=en")
+        .send()
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    cli.get("/portfolio?lang=en")
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    cli.get("/portfolio").send().await.assert_status_is_ok();
+    cli.delete("/portfolio?lang=en")
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    let sections: i64 = query_scalar("SELECT COUNT(*) FROM portfolio_section")
+        .fetch_one(&state.db)
+        .await
+        .expect("failed to count sections");
+    assert_eq!(sections, 8);
+}
+
+#[tokio::test]
+async fn init_db_moves_single_language_portfolios_to_korean() {
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    for statement in [
+        "CREATE TABLE portfolio (portfolio_id INTEGER PRIMARY KEY AUTOINCREMENT, slug TEXT NOT NULL UNIQUE, created_at DATETIME DEFAULT CURRENT_TIMESTAMP)",
+        "CREATE TABLE portfolio_version (version_id INTEGER PRIMARY KEY AUTOINCREMENT, slug TEXT NOT NULL, version_number INTEGER NOT NULL, content TEXT NOT NULL, source TEXT NOT NULL, created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP, UNIQUE(slug, version_number))",
+        "INSERT INTO portfolio (portfolio_id, slug) VALUES (7, 'dev')",
+        "INSERT INTO portfolio_version (slug, version_number, content, source) VALUES ('dev', 1, '{}', 'update')",
+    ] {
+        query(statement)
+            .execute(&db)
+            .await
+            .expect("failed to prepare legacy schema");
+    }
+
+    init_db(&db).await.expect("failed to init db");
+    // 두 번째 실행은 아무것도 바꾸지 않는다.
+    init_db(&db).await.expect("failed to re-run init db");
+
+    let lang: String = query_scalar("SELECT lang FROM portfolio WHERE portfolio_id = 7")
+        .fetch_one(&db)
+        .await
+        .expect("failed to read migrated portfolio");
+    assert_eq!(lang, "ko");
+    let version_lang: String =
+        query_scalar("SELECT lang FROM portfolio_version WHERE slug = 'dev'")
+            .fetch_one(&db)
+            .await
+            .expect("failed to read migrated version");
+    assert_eq!(version_lang, "ko");
+
+    query("INSERT INTO portfolio (slug, lang) VALUES ('dev', 'en')")
+        .execute(&db)
+        .await
+        .expect("slug should be unique per language only");
+}
+
+#[tokio::test]
+async fn json_resume_export_feeds_import_into_a_draft() {
+    std::env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+    let state = create_state().await;
+    let cli = TestClient::new(
+        Route::new()
+            .at(
+                "/portfolio",
+                get(get_portfolio).put(update_portfolio_draft.with(Auth)),
+            )
+            .at("/portfolio/publish", post(publish_portfolio).with(Auth))
+            .at("/portfolio/draft", get(get_portfolio_draft).with(Auth))
+            .at(
+                "/portfolio/json-resume",
+                get(export_portfolio_json_resume).post(import_portfolio_json_resume.with(Auth)),
+            )
+            .data(state.clone()),
+    );
+    let token = admin_token();
+
+    cli.get("/portfolio/json-resume")
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    save_and_publish(
+        &cli,
+        &token,
+        "",
+        &portfolio_body("Exported", "resume@example.com"),
+    )
+    .await;
+
+    let exported = cli.get("/portfolio/json-resume").send().await;
+    exported.assert_status_is_ok();
+    let exported_json = exported.json().await;
+    let data = exported_json.value().object().get("data").object();
+    data.get("resume")
+        .object()
+        .get("basics")
+        .object()
+        .get("name")
+        .assert_string("Exported");
+    let resume = data.get("resume").deserialize::<serde_json::Value>();
+
+    // dry_run은 변환 결과만 돌려준다.
+    let mut foreign = resume.clone();
+    foreign["education"] = json!([{ "institution": "Somewhere" }]);
+    let response = cli
+        .post("/portfolio/json-resume?slug=design&dry_run=true")
+        .header("Authorization", &token)
+        .body_json(&foreign)
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    let json = response.json().await;
+    let data = json.value().object().get("data").object();
+    data.get("unmapped").assert_string_array(&["/education"]);
+    data.get("draft").assert_null();
+    data.get("document")
+        .object()
+        .get("slug")
+        .assert_string("design");
+
+    cli.post("/portfolio/json-resume?lang=en")
+        .header("Authorization", &token)
+        .body_json(&resume)
+        .send()
+        .await
+        .assert_status_is_ok();
+    let draft = cli
+        .get("/portfolio/draft?lang=en")
+        .header("Authorization", &token)
+        .send()
+        .await;
+    draft.assert_status_is_ok();
+    assert_eq!(identity_name(&draft.json().await), "Exported");
+
+    cli.post("/portfolio/json-resume")
+        .header("Authorization", &token)
+        .body_json(&json!({ "basics": {} }))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn pdf_and_html_are_cached_per_version_until_the_portfolio_changes() {
+    let state = create_state().await;
+    let db = state.db.clone();
+    let cli = TestClient::new(
+        Route::new()
+            .at(
+                "/portfolio",
+                get(get_portfolio).put(update_portfolio_draft.with(Auth)),
+            )
+            .at("/portfolio/publish", post(publish_portfolio).with(Auth))
+            .at("/portfolio/pdf", get(get_portfolio_pdf))
+            .at("/portfolio/html", get(get_portfolio_html))
+            .data(state),
+    );
+    let token = admin_token();
+
+    cli.get("/portfolio/pdf?lang=en")
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    save_and_publish(
+        &cli,
+        &token,
+        "?lang=en",
+        &latin_portfolio_body("TYANGE"),
+    )
+    .await;
+
+    let first = cli.get("/portfolio/pdf?lang=en").send().await;
+    first.assert_status_is_ok();
+    first.assert_content_type("application/pdf");
+    first.assert_header("X-Portfolio-Render-Cache", "miss");
+    first.assert_header("ETag", "\"dev-en-v1-pdf\"");
+    let disposition = first
+        .0
+        .headers()
+        .get("Content-Disposition")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    assert!(disposition.starts_with("inline"), "{}", disposition);
+    assert!(
+        disposition.contains("portfolio-dev-en-v1.pdf"),
+        "{}",
+        disposition
+    );
+    let first_bytes = first.0.into_body().into_bytes().await.unwrap();
+    assert!(first_bytes.starts_with(b"%PDF-"));
+
+    let second = cli.get("/portfolio/pdf?lang=en").send().await;
+    second.assert_status_is_ok();
+    second.assert_header("X-Portfolio-Render-Cache", "hit");
+    let second_bytes = second.0.into_body().into_bytes().await.unwrap();
+    assert_eq!(first_bytes, second_bytes);
+
+    cli.get("/portfolio/pdf?lang=en")
+        .header("If-None-Match", "\"dev-en-v1-pdf\"")
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_MODIFIED);
+
+    let html = cli.get("/portfolio/html?lang=en").send().await;
+    html.assert_status_is_ok();
+    html.assert_content_type("text/html; charset=utf-8");
+    let html = html.0.into_body().into_string().await.unwrap();
+    assert!(html.contains("<h1>TYANGE</h1>"));
+
+    let cached: i64 = query_scalar("SELECT COUNT(*) FROM portfolio_render_cache")
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    assert_eq!(cached, 2);
+
+    save_and_publish(
+        &cli,
+        &token,
+        "?lang=en",
+        &latin_portfolio_body("TYANGE KIM"),
+    )
+    .await;
+
+    let cached: i64 = query_scalar("SELECT COUNT(*) FROM portfolio_render_cache")
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    assert_eq!(cached, 0);
+
+    let updated = cli.get("/portfolio/html?lang=en").send().await;
+    updated.assert_status_is_ok();
+    updated.assert_header("X-Portfolio-Render-Cache", "miss");
+    updated.assert_header("ETag", "\"dev-en-v2-html\"");
+    let updated = updated.0.into_body().into_string().await.unwrap();
+    assert!(updated.contains("<h1>TYANGE KIM</h1>"));
+
+    // 글꼴을 지정하지 않았으면 한글 문서는 `?`로 찍지 않고 설정 오류로 알린다.
+    save_and_publish(
+        &cli,
+        &token,
+        "",
+        &portfolio_body("TYANGE", "usun16@gmail.com"),
+    )
+    .await;
+    let missing_font = cli.get("/portfolio/pdf").send().await;
+    missing_font.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    let message = missing_font.0.into_body().into_string().await.unwrap();
+    assert!(message.contains("PORTFOLIO_PDF_FONT_PATH"), "{}", message);
+}
+
+#[tokio::test]
+async fn invalid_documents_are_rejected_with_every_error_pointer() {
+    let state = create_state().await;
+    let cli = TestClient::new(
+        Route::new()
+            .at(
+                "/portfolio",
+                get(get_portfolio).put(update_portfolio_draft.with(Auth)),
+            )
+            .at(
+                "/portfolio/sections/:section_key",
+                put(update_portfolio_draft_section).with(Auth),
+            )
+            .at("/portfolio/schema", get(get_portfolio_schema))
+            .at("/portfolio/validate", post(validate_portfolio))
+            .data(state),
+    );
+    let token = admin_token();
+
+    let schema = cli.get("/portfolio/schema").send().await;
+    schema.assert_status_is_ok();
+    schema.assert_content_type("application/schema+json");
+    let schema_json = schema.json().await;
+    schema_json
+        .value()
+        .object()
+        .get("title")
+        .assert_string("PortfolioDocument");
+
+    let mut invalid = portfolio_body("", "not-an-email");
+    invalid["content"]["featured_projects"] = json!([{
+        "slug": "",
+        "title": "CMS",
+        "period": "언젠가",
+        "summary": "",
+        "links": [{ "label": "GitHub", "url": "github.com/tyange" }]
+    }]);
+
+    let validated = cli
+        .post("/portfolio/validate")
+        .body_json(&invalid)
+        .send()
+        .await;
+    validated.assert_status_is_ok();
+    let validated_json = validated.json().await;
+    let data = validated_json.value().object().get("data").object();
+    data.get("valid").assert_bool(false);
+    let mut pointers: Vec<String> = data
+        .get("errors")
+        .array()
+        .iter()
+        .map(|error| error.object().get("pointer").string().to_string())
+        .collect();
+    pointers.sort();
+    assert_eq!(
+        pointers,
+        vec![
+            "/featured_projects/0/links/0/url",
+            "/featured_projects/0/period",
+            "/featured_projects/0/slug",
+            "/identity/email",
+            "/identity/name",
+        ]
+    );
+
+    let rejected = cli
+        .put("/portfolio")
+        .header("Authorization", &token)
+        .body_json(&invalid)
+        .send()
+        .await;
+    rejected.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    let rejected_json = rejected.json().await;
+    rejected_json
+        .value()
+        .object()
+        .get("status")
+        .assert_bool(false);
+    rejected_json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("errors")
+        .array()
+        .assert_len(5);
+
+    cli.get("/portfolio")
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    cli.put("/portfolio")
+        .header("Authorization", &token)
+        .body_json(&portfolio_body("TYANGE", "usun16@gmail.com"))
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    let section = cli
+        .post("/portfolio/validate?section_key=career")
+        .body_json(&json!({
+            "content": {
+                "summary_label": "경력",
+                "summary_value": "4년",
+                "companies": [{ "company": "회사", "period": "2020.01 - 현재" }]
+            }
+        }))
+        .send()
+        .await;
+    section.assert_status_is_ok();
+    let section_json = section.json().await;
+    let errors = section_json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("errors")
+        .array();
+    errors.assert_len(3);
+    errors
+        .get(0)
+        .object()
+        .get("pointer")
+        .assert_string("/career/companies/0/employment_type");
+
+    cli.put("/portfolio/sections/identity")
+        .header("Authorization", &token)
+        .body_json(&json!({
+            "content": { "name": "TYANGE", "email": "usun16@gmail.com", "github_url": "javascript:alert(1)" }
+        }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+async fn insert_tagged_post(db: &SqlitePool, post_id: &str, status: &str, tag: Option<&str>) {
+    query(
+        "INSERT INTO posts (post_id, title, description, published_at, content, writer_id, status) VALUES (?, ?, '', ?, '', 'writer-1', ?)",
+    )
+    .bind(post_id)
+    .bind(format!("{} 제목", post_id))
+    .bind(format!("2026-01-0{}T00:00:00Z", &post_id[1..]))
+    .bind(status)
+    .execute(db)
+    .await
+    .expect("failed to insert post");
+
+    if let Some(tag) = tag {
+        query("INSERT OR IGNORE INTO tags (name, category) VALUES (?, 'dev')")
+            .bind(tag)
+            .execute(db)
+            .await
+            .expect("failed to insert tag");
+        query("INSERT INTO post_tags (post_id, tag_id) SELECT ?, tag_id FROM tags WHERE name = ?")
+            .bind(post_id)
+            .bind(tag)
+            .execute(db)
+            .await
+            .expect("failed to link tag");
+    }
+}
+
+#[tokio::test]
+async fn projects_link_posts_by_id_or_tag_and_report_dangling_references_on_delete() {
+    std::env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    init_db(&db).await.expect("failed to init db");
+    query("CREATE TABLE tags (tag_id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL UNIQUE, category TEXT NOT NULL)")
+        .execute(&db)
+        .await
+        .expect("failed to create tags");
+    query("CREATE TABLE post_tags (post_id TEXT NOT NULL, tag_id INTEGER NOT NULL)")
+        .execute(&db)
+        .await
+        .expect("failed to create post_tags");
+
+    insert_tagged_post(&db, "p1", "published", Some("rust")).await;
+    insert_tagged_post(&db, "p2", "draft", Some("rust")).await;
+    insert_tagged_post(&db, "p3", "published", None).await;
+    insert_tagged_post(&db, "p4", "published", Some("cms")).await;
+
+    let state = Arc::new(AppState::new(db));
+    let cli = TestClient::new(
+        Route::new()
+            .at(
+                "/portfolio",
+                get(get_portfolio).put(update_portfolio_draft.with(Auth)),
+            )
+            .at("/portfolio/publish", post(publish_portfolio).with(Auth))
+            .at("/post/:post_id", get(get_post))
+            .at("/post/delete/:post_id", delete(delete_post).with(Auth))
+            .data(state),
+    );
+    let token = admin_token();
+
+    let mut body = portfolio_body("TYANGE", "usun16@gmail.com");
+    body["content"]["featured_projects"] = json!([
+        {
+            "slug": "cms",
+            "title": "CMS",
+            "period": "2025.01 - 현재",
+            "summary": "",
+            "posts": [{ "post_id": "p3" }, { "tag": "rust" }]
+        },
+        {
+            "slug": "blog",
+            "title": "블로그",
+            "period": "2024.01 - 2024.12",
+            "summary": "",
+            "posts": [{ "tag": "cms" }]
+        }
+    ]);
+    save_and_publish(&cli, &token, "", &body).await;
+
+    let plain = cli.get("/portfolio").send().await;
+    plain.assert_status_is_ok();
+    let plain_json = plain.json().await;
+    assert!(plain_json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get_opt("project_posts")
+        .is_none());
+
+    // 초안(p2)은 같은 태그여도 빠지고, 최신 글이 먼저 온다.
+    let expanded = cli.get("/portfolio?expand=posts").send().await;
+    expanded.assert_status_is_ok();
+    let expanded_json = expanded.json().await;
+    let project_posts = expanded_json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("project_posts")
+        .array();
+    project_posts.assert_len(2);
+    let cms_posts = project_posts.get(0).object().get("posts").array();
+    cms_posts.assert_len(2);
+    cms_posts.get(0).object().get("post_id").assert_string("p3");
+    cms_posts.get(1).object().get("post_id").assert_string("p1");
+    project_posts
+        .get(1)
+        .object()
+        .get("posts")
+        .array()
+        .get(0)
+        .object()
+        .get("post_id")
+        .assert_string("p4");
+
+    cli.get("/portfolio?expand=comments")
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let post = cli.get("/post/p1").send().await;
+    post.assert_status_is_ok();
+    let post_json = post.json().await;
+    let projects = post_json.value().object().get("portfolio_projects").array();
+    projects.assert_len(1);
+    projects
+        .get(0)
+        .object()
+        .get("project_slug")
+        .assert_string("cms");
+    projects
+        .get(0)
+        .object()
+        .get("matched_by")
+        .assert_string("tag");
+
+    // rust 태그는 초안 p2에 남아 있으므로 p1을 지워도 끊어진 참조가 아니다.
+    let deleted = cli
+        .delete("/post/delete/p1")
+        .header("Authorization", &token)
+        .send()
+        .await;
+    deleted.assert_status_is_ok();
+    deleted
+        .json()
+        .await
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("dangling_portfolio_references")
+        .array()
+        .assert_len(0);
+
+    let deleted = cli
+        .delete("/post/delete/p4")
+        .header("Authorization", &token)
+        .send()
+        .await;
+    deleted.assert_status_is_ok();
+    let deleted_json = deleted.json().await;
+    let dangling = deleted_json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("dangling_portfolio_references")
+        .array();
+    dangling.assert_len(1);
+    dangling
+        .get(0)
+        .object()
+        .get("pointer")
+        .assert_string("/featured_projects/1/posts/0");
+
+    let deleted = cli
+        .delete("/post/delete/p3")
+        .header("Authorization", &token)
+        .send()
+        .await;
+    deleted.assert_status_is_ok();
+    let deleted_json = deleted.json().await;
+    let dangling = deleted_json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("dangling_portfolio_references")
+        .array();
+    dangling.assert_len(1);
+    dangling
+        .get(0)
+        .object()
+        .get("matched_by")
+        .assert_string("post_id");
+}
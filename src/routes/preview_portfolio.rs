@@ -0,0 +1,47 @@
+use crate::models::{AppState, CustomResponse, PortfolioPreviewResponse, PortfolioSelectorQuery};
+use crate::portfolio::{load_portfolio, load_portfolio_draft, PortfolioKey};
+use poem::http::StatusCode;
+use poem::web::{Data, Json, Query};
+use poem::{handler, Error};
+use std::sync::Arc;
+
+/// 발행하면 공개될 문서를 돌려준다. 초안이 없으면 지금 공개된 문서와 같다.
+#[handler]
+pub async fn preview_portfolio(
+    Query(selector): Query<PortfolioSelectorQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<PortfolioPreviewResponse>>, Error> {
+    let key = PortfolioKey::resolve(&selector, None)?;
+    if let Some(draft) = load_portfolio_draft(&data.db, &key).await? {
+        return Ok(Json(CustomResponse {
+            status: true,
+            data: Some(PortfolioPreviewResponse {
+                slug: draft.slug,
+                lang: draft.lang,
+                source: String::from("draft"),
+                content: draft.content,
+                updated_at: draft.updated_at,
+            }),
+            message: None,
+        }));
+    }
+
+    let portfolio = load_portfolio(&data.db, &key).await?.ok_or_else(|| {
+        Error::from_string(
+            "포트폴리오 데이터를 찾지 못했습니다.",
+            StatusCode::NOT_FOUND,
+        )
+    })?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(PortfolioPreviewResponse {
+            slug: portfolio.slug,
+            lang: portfolio.lang,
+            source: String::from("live"),
+            content: portfolio.content,
+            updated_at: portfolio.updated_at,
+        }),
+        message: None,
+    }))
+}
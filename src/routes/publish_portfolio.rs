@@ -0,0 +1,42 @@
+use crate::models::{AppState, CustomResponse, PortfolioSelectorQuery, PublishPortfolioResponse};
+use crate::portfolio::{
+    discard_portfolio_draft, load_portfolio_draft, record_portfolio_version,
+    save_portfolio_document, PortfolioKey,
+};
+use poem::http::StatusCode;
+use poem::web::{Data, Json, Query};
+use poem::{handler, Error};
+use std::sync::Arc;
+
+/// 초안을 공개 문서로 올리고 버전을 남긴 뒤 사이트 빌드를 요청한다.
+#[handler]
+pub async fn publish_portfolio(
+    Query(selector): Query<PortfolioSelectorQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<PublishPortfolioResponse>>, Error> {
+    let key = PortfolioKey::resolve(&selector, None)?;
+    let draft = load_portfolio_draft(&data.db, &key).await?.ok_or_else(|| {
+        Error::from_string("발행할 포트폴리오 초안이 없습니다.", StatusCode::NOT_FOUND)
+    })?;
+
+    let portfolio = save_portfolio_document(&data.db, &key, draft.content).await?;
+    let version_number =
+        record_portfolio_version(&data.db, &key, &portfolio.content, "publish").await?;
+    discard_portfolio_draft(&data.db, &key).await?;
+
+    data.blog_redeploy
+        .dispatch_portfolio_publish(&key.slug, &key.lang, version_number)
+        .await;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(PublishPortfolioResponse {
+            version_number,
+            portfolio,
+        }),
+        message: Some(format!(
+            "포트폴리오를 발행했습니다. (버전 {})",
+            version_number
+        )),
+    }))
+}
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json},
+    Error,
+};
+use sqlx::query_scalar;
+use tyange_cms_api::auth::account::is_account_disabled;
+use tyange_cms_api::auth::jwt::Claims;
+use tyange_cms_api::auth::refresh_token::{
+    consume_refresh_token, family_user_id, revoke_refresh_family, touch_session, RefreshTokenUse,
+};
+
+use crate::models::{AppState, LoginResponse, RefreshTokenRequest};
+use crate::routes::login::{
+    account_disabled_error, issue_family_tokens, jwt_secret, MissingJwtSecret,
+};
+
+/// refresh token을 읽다 난 오류. 핸들러에서는 `?`로 `poem::Error`가 된다.
+#[derive(Debug)]
+pub enum RefreshTokenError {
+    MissingSecret(MissingJwtSecret),
+    /// 서명/만료/종류가 맞지 않거나 서버 기록이 없는 토큰.
+    Invalid,
+}
+
+impl From<RefreshTokenError> for Error {
+    fn from(err: RefreshTokenError) -> Self {
+        match err {
+            RefreshTokenError::MissingSecret(err) => err.into(),
+            RefreshTokenError::Invalid => invalid_refresh_token(),
+        }
+    }
+}
+
+fn invalid_refresh_token() -> Error {
+    Error::from_string(
+        "유효하지 않은 refresh token입니다. 다시 로그인해 주세요.",
+        StatusCode::UNAUTHORIZED,
+    )
+}
+
+/// 서명과 만료를 확인하고 `(user_id, family_id, token_id)`를 꺼낸다.
+/// `jti`/`family_id`가 없는 예전 토큰은 서버 기록이 없으므로 받지 않는다.
+pub fn decode_refresh_token(token: &str) -> Result<(String, String, String), RefreshTokenError> {
+    let secret = jwt_secret("JWT_REFRESH_SECRET").map_err(RefreshTokenError::MissingSecret)?;
+    let claims = Claims::from_token(token, secret.as_bytes())
+        .map_err(|_| RefreshTokenError::Invalid)?
+        .claims;
+    if claims.token_type != "refresh" {
+        return Err(RefreshTokenError::Invalid);
+    }
+
+    match (claims.family_id, claims.jti) {
+        (Some(family_id), Some(token_id)) => Ok((claims.sub, family_id, token_id)),
+        _ => Err(RefreshTokenError::Invalid),
+    }
+}
+
+#[handler]
+pub async fn refresh(
+    Json(payload): Json<RefreshTokenRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<LoginResponse>, Error> {
+    let (user_id, family_id, token_id) = decode_refresh_token(&payload.refresh_token)?;
+
+    // 비활성화하면 family도 폐기되므로, 폐기 여부보다 먼저 계정 상태를 봐야 로그인처럼 `403`이 된다.
+    let db_error = |err: sqlx::Error| {
+        eprintln!("Database error: {:?}", err);
+        Error::from_string("Database error", StatusCode::INTERNAL_SERVER_ERROR)
+    };
+    if let Some(owner) = family_user_id(&data.db, &family_id)
+        .await
+        .map_err(db_error)?
+    {
+        if is_account_disabled(&data.db, &owner)
+            .await
+            .map_err(db_error)?
+        {
+            return Err(account_disabled_error());
+        }
+    }
+
+    let token_use = consume_refresh_token(&data.db, &family_id, &token_id)
+        .await
+        .map_err(|err| {
+            eprintln!("Error consume refresh token: {:?}", err);
+            Error::from_string("Database error", StatusCode::INTERNAL_SERVER_ERROR)
+        })?;
+
+    match token_use {
+        RefreshTokenUse::Fresh => {}
+        RefreshTokenUse::Reused => {
+            eprintln!(
+                "refresh token 재사용 감지: user={}, family={}",
+                user_id, family_id
+            );
+            return Err(Error::from_string(
+                "이미 사용한 refresh token입니다. 보안을 위해 이 로그인의 모든 토큰을 폐기했습니다.",
+                StatusCode::UNAUTHORIZED,
+            ));
+        }
+        RefreshTokenUse::Revoked | RefreshTokenUse::Unknown => return Err(invalid_refresh_token()),
+    }
+
+    // 역할은 토큰이 아니라 현재 계정에서 읽는다. 그 사이 권한이 바뀌었을 수 있다.
+    let user_role: Option<String> = query_scalar("SELECT user_role FROM users WHERE user_id = ?")
+        .bind(&user_id)
+        .fetch_optional(&data.db)
+        .await
+        .map_err(|err| {
+            eprintln!("Database error: {:?}", err);
+            Error::from_string("Database error", StatusCode::INTERNAL_SERVER_ERROR)
+        })?;
+    let Some(user_role) = user_role else {
+        let _ = revoke_refresh_family(&data.db, &family_id, "user_missing").await;
+        return Err(invalid_refresh_token());
+    };
+
+    touch_session(&data.db, &family_id).await.map_err(|err| {
+        eprintln!("Database error: {:?}", err);
+        Error::from_string("Database error", StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+    let response = issue_family_tokens(&data.db, &user_id, &user_role, &family_id).await?;
+    Ok(Json(response))
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::models::{AppState, CustomResponse, RecoveryCodesResponse, TwoFactorCodeRequest};
+use crate::two_factor::{regenerate_recovery_codes, require_second_factor, totp_enabled};
+
+/// 복구 코드를 새로 만든다. 지금 TOTP 코드(또는 남은 복구 코드)를 확인한 뒤 예전 코드를 모두 무효로 한다.
+#[handler]
+pub async fn regenerate_my_recovery_codes(
+    req: &Request,
+    Json(payload): Json<TwoFactorCodeRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<RecoveryCodesResponse>>, Error> {
+    let user = current_user(req)?;
+
+    let enabled = totp_enabled(&data.db, &user.user_id).await.map_err(|err| {
+        Error::from_string(
+            format!("2단계 인증 조회 실패: {}", err),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?;
+    if !enabled {
+        return Err(Error::from_string(
+            "사용 중인 2단계 인증이 없습니다.",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    require_second_factor(&data.db, &user.user_id, &payload).await?;
+    let recovery_codes = regenerate_recovery_codes(&data.db, &user.user_id).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(RecoveryCodesResponse { recovery_codes }),
+        message: None,
+    }))
+}
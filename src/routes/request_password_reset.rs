@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json},
+    Error, Request,
+};
+use sqlx::query_scalar;
+
+use crate::login_throttle::{account_key, allow_password_reset_request, client_ip};
+use crate::models::{AppState, CustomResponse, PasswordResetRequest};
+use crate::password_reset::issue_password_reset;
+
+/// 가입 여부를 드러내지 않도록 계정이 없어도, 요청이 너무 잦아 메일을 보내지 않을 때도 같은 응답을 돌려준다.
+#[handler]
+pub async fn request_password_reset(
+    req: &Request,
+    Json(payload): Json<PasswordResetRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<()>>, Error> {
+    let email = payload.email.trim();
+    let allowed =
+        allow_password_reset_request(&data.db, &account_key(email), &client_ip(req)).await?;
+
+    let user_id: Option<String> = query_scalar("SELECT user_id FROM users WHERE user_id = ?")
+        .bind(email)
+        .fetch_optional(&data.db)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("사용자 조회 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+
+    if let Some(user_id) = user_id.filter(|_| allowed) {
+        issue_password_reset(&data.db, &user_id).await?;
+    }
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: None,
+        message: Some(String::from(
+            "가입된 이메일이면 비밀번호 재설정 메일을 보냈습니다.",
+        )),
+    }))
+}
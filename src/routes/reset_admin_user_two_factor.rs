@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Path},
+    Error,
+};
+
+use crate::models::AppState;
+use crate::two_factor::disable_totp;
+
+/// 인증 앱과 복구 코드를 모두 잃은 사용자의 2단계 인증을 지운다. 사용자는 다시 등록해야 한다.
+#[handler]
+pub async fn reset_admin_user_two_factor(
+    Path(user_id): Path<String>,
+    data: Data<&Arc<AppState>>,
+) -> Result<StatusCode, Error> {
+    if !disable_totp(&data.db, &user_id).await? {
+        return Err(Error::from_string(
+            "사용 중인 2단계 인증이 없습니다.",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,38 @@
+use crate::models::{
+    AppState, CustomResponse, PortfolioSelectorQuery, RestorePortfolioVersionResponse,
+};
+use crate::portfolio::{
+    fetch_portfolio_version, save_portfolio_draft, version_document, PortfolioKey,
+};
+use poem::web::{Data, Json, Path, Query};
+use poem::{handler, Error, Request};
+use std::sync::Arc;
+use tyange_cms_api::auth::authorization::current_user;
+
+/// 예전 스냅샷을 초안으로 가져온다. 지금 초안은 덮어쓰며, 공개 문서는 `/portfolio/publish`로 발행할 때 바뀐다.
+#[handler]
+pub async fn restore_portfolio_version(
+    req: &Request,
+    Path(version_number): Path<i64>,
+    Query(selector): Query<PortfolioSelectorQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<RestorePortfolioVersionResponse>>, Error> {
+    let user = current_user(req)?;
+    let key = PortfolioKey::resolve(&selector, None)?;
+    let version = fetch_portfolio_version(&data.db, &key, version_number).await?;
+    let document = version_document(&version)?;
+
+    let draft = save_portfolio_draft(&data.db, &key, document, &user.user_id).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(RestorePortfolioVersionResponse {
+            restored_from: version_number,
+            draft,
+        }),
+        message: Some(format!(
+            "{}번 버전을 초안으로 가져왔습니다. 발행하면 공개됩니다.",
+            version_number
+        )),
+    }))
+}
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Query},
+    Error,
+};
+
+use crate::{
+    image_gc::{gc_min_age_hours, sweep_images},
+    models::{CustomResponse, ImageGcQuery, ImageGcResponse},
+    AppState,
+};
+
+#[handler]
+pub async fn run_image_gc(
+    Query(params): Query<ImageGcQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<ImageGcResponse>>, Error> {
+    // 실수로 지우지 않도록 dry_run=false를 명시했을 때만 실제로 삭제한다.
+    let dry_run = params.dry_run.unwrap_or(true);
+    let result = sweep_images(&data.db, data.storage.as_ref(), gc_min_age_hours(), dry_run).await?;
+
+    let message = if dry_run {
+        "삭제 대상만 확인했습니다. 실제로 지우려면 dry_run=false로 호출하세요."
+    } else {
+        "사용하지 않는 이미지를 정리했습니다."
+    };
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(result),
+        message: Some(message.to_string()),
+    }))
+}
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Query},
+    Error,
+};
+
+use crate::{
+    image_dedup::backfill_content_hashes,
+    models::{CustomResponse, ImageHashBackfillQuery, ImageHashBackfillResponse},
+    AppState,
+};
+
+#[handler]
+pub async fn run_image_hash_backfill(
+    Query(params): Query<ImageHashBackfillQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<ImageHashBackfillResponse>>, Error> {
+    // 본문 링크를 바꾸고 파일을 지우므로 dry_run=false를 명시했을 때만 실제로 합친다.
+    let dry_run = params.dry_run.unwrap_or(true);
+    let result = backfill_content_hashes(&data.db, data.storage.as_ref(), dry_run).await?;
+
+    let message = if dry_run {
+        "합칠 대상만 확인했습니다. 실제로 합치려면 dry_run=false로 호출하세요."
+    } else {
+        "해시가 없는 이미지를 등록하고 중복 파일을 합쳤습니다."
+    };
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(result),
+        message: Some(message.to_string()),
+    }))
+}
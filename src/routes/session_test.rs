@@ -0,0 +1,196 @@
+use std::{env, sync::Arc};
+
+use poem::{
+    delete, get, http::StatusCode, post, put, test::TestClient, Endpoint, EndpointExt, Route,
+};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+
+use crate::{
+    db::init_db,
+    middlewares::auth_middleware::Auth,
+    models::AppState,
+    routes::{
+        add_user::create_user, delete_my_session::delete_my_session,
+        get_my_sessions::get_my_sessions, login::login, refresh::refresh,
+        update_my_password::update_my_password,
+    },
+};
+
+const PASSWORD: &str = "session-pass-7";
+
+async fn create_test_state() -> Arc<AppState> {
+    env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+    env::set_var("JWT_REFRESH_SECRET", "test-refresh-secret");
+    env::set_var("TRUST_PROXY_HEADERS", "true");
+
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    init_db(&db).await.expect("failed to init db");
+    Arc::new(AppState::new(db))
+}
+
+fn create_app(state: Arc<AppState>) -> impl Endpoint {
+    Route::new()
+        .at("/login", post(login))
+        .at("/refresh", post(refresh))
+        .at("/me/password", put(update_my_password).with(Auth))
+        .at("/me/sessions", get(get_my_sessions).with(Auth))
+        .at(
+            "/me/sessions/:session_id",
+            delete(delete_my_session).with(Auth),
+        )
+        .data(state)
+}
+
+async fn read_json(response: poem::test::TestResponse) -> Value {
+    serde_json::from_str(&response.0.into_body().into_string().await.unwrap())
+        .expect("response should be json")
+}
+
+/// `(access_token, refresh_token)`
+async fn sign_in(cli: &TestClient<impl Endpoint>, user_agent: &str, ip: &str) -> (String, String) {
+    let response = cli
+        .post("/login")
+        .header("User-Agent", user_agent)
+        .header("X-Forwarded-For", ip)
+        .body_json(&json!({ "user_id": "reader@example.com", "password": PASSWORD }))
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    let body = read_json(response).await;
+    (
+        body["access_token"].as_str().unwrap().to_string(),
+        body["refresh_token"].as_str().unwrap().to_string(),
+    )
+}
+
+async fn sessions(cli: &TestClient<impl Endpoint>, token: &str) -> Vec<Value> {
+    let response = cli
+        .get("/me/sessions")
+        .header("Authorization", token)
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    read_json(response).await["sessions"]
+        .as_array()
+        .unwrap()
+        .clone()
+}
+
+async fn refresh_with(cli: &TestClient<impl Endpoint>, refresh_token: &str) -> StatusCode {
+    cli.post("/refresh")
+        .body_json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .0
+        .status()
+}
+
+#[tokio::test]
+async fn sessions_list_devices_and_revoking_one_cuts_off_its_access_token() {
+    let state = create_test_state().await;
+    create_user(&state.db, "reader@example.com", PASSWORD, "user")
+        .await
+        .unwrap();
+    let cli = TestClient::new(create_app(state));
+
+    let (laptop_access, _) = sign_in(&cli, "Laptop Browser", "198.51.100.10").await;
+    let (phone_access, phone_refresh) = sign_in(&cli, "Phone App", "203.0.113.20").await;
+
+    let listed = sessions(&cli, &laptop_access).await;
+    assert_eq!(listed.len(), 2);
+    let laptop = listed
+        .iter()
+        .find(|session| session["user_agent"] == "Laptop Browser")
+        .expect("laptop session should be listed");
+    let phone = listed
+        .iter()
+        .find(|session| session["user_agent"] == "Phone App")
+        .expect("phone session should be listed");
+    assert_eq!(laptop["current"], true);
+    assert_eq!(laptop["ip_address"], "198.51.100.10");
+    assert_eq!(phone["current"], false);
+    assert_eq!(phone["ip_address"], "203.0.113.20");
+    assert!(phone["last_seen_at"].is_string());
+    let phone_id = phone["id"].as_str().unwrap().to_string();
+
+    cli.delete(format!("/me/sessions/{}", phone_id))
+        .header("Authorization", &laptop_access)
+        .send()
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    cli.delete(format!("/me/sessions/{}", phone_id))
+        .header("Authorization", &laptop_access)
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    // 끊긴 기기는 만료 전 access 토큰도, refresh 토큰도 쓸 수 없다.
+    cli.get("/me/sessions")
+        .header("Authorization", &phone_access)
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        refresh_with(&cli, &phone_refresh).await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    let listed = sessions(&cli, &laptop_access).await;
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["current"], true);
+}
+
+#[tokio::test]
+async fn password_change_keeps_the_current_session_and_revokes_the_others() {
+    let state = create_test_state().await;
+    create_user(&state.db, "reader@example.com", PASSWORD, "user")
+        .await
+        .unwrap();
+    let cli = TestClient::new(create_app(state));
+
+    let (laptop_access, laptop_refresh) = sign_in(&cli, "Laptop Browser", "198.51.100.10").await;
+    let (phone_access, phone_refresh) = sign_in(&cli, "Phone App", "203.0.113.20").await;
+    let laptop_id = sessions(&cli, &laptop_access)
+        .await
+        .into_iter()
+        .find(|session| session["current"] == true)
+        .unwrap()["id"]
+        .clone();
+
+    let response = cli
+        .put("/me/password")
+        .header("Authorization", &laptop_access)
+        .body_json(&json!({
+            "current_password": PASSWORD,
+            "new_password": "changed-secret-42",
+        }))
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    let body = read_json(response).await;
+    let new_refresh = body["data"]["refresh_token"].as_str().unwrap().to_string();
+
+    cli.get("/me/sessions")
+        .header("Authorization", &phone_access)
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        refresh_with(&cli, &phone_refresh).await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    // 비밀번호를 바꾼 기기는 같은 세션으로 남는다. 그 전에 받은 refresh 토큰만 더 쓸 수 없다.
+    let listed = sessions(&cli, &laptop_access).await;
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["id"], laptop_id);
+    assert_eq!(
+        refresh_with(&cli, &laptop_refresh).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(refresh_with(&cli, &new_refresh).await, StatusCode::OK);
+    assert_eq!(sessions(&cli, &laptop_access).await.len(), 1);
+}
@@ -11,8 +11,8 @@ use crate::{
     middlewares::auth_middleware::Auth,
     models::AppState,
     routes::{
-        add_user::add_user, login::login, login_google::login_google, me::me, signup::signup,
-        update_my_profile::update_my_profile,
+        add_user::add_user, login::login, login_google::login_google, logout::logout, me::me,
+        refresh::refresh, signup::signup, update_my_profile::update_my_profile,
     },
 };
 use tyange_cms_api::auth::jwt::Claims;
@@ -35,6 +35,8 @@ fn create_auth_app(state: Arc<AppState>) -> impl Endpoint {
         .at("/signup", post(signup))
         .at("/login", post(login))
         .at("/login/google", post(login_google))
+        .at("/refresh", post(refresh))
+        .at("/logout", post(logout))
         .at("/me", get(me).with(Auth))
         .at("/me/profile", put(update_my_profile).with(Auth))
         .at("/admin/add-user", post(add_user).with(AdminOnly).with(Auth))
@@ -668,3 +670,153 @@ async fn non_admin_still_cannot_use_admin_add_user() {
 
     response.assert_status(StatusCode::FORBIDDEN);
 }
+
+async fn signup_and_login(cli: &TestClient<impl Endpoint>, email: &str) -> String {
+    cli.post("/signup")
+        .body_json(&json!({
+            "email": email,
+            "password": "password123"
+        }))
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    let login_response = cli
+        .post("/login")
+        .body_json(&json!({
+            "user_id": email,
+            "password": "password123"
+        }))
+        .send()
+        .await;
+    login_response.assert_status_is_ok();
+    login_response
+        .json()
+        .await
+        .value()
+        .object()
+        .get("refresh_token")
+        .string()
+        .to_string()
+}
+
+#[tokio::test]
+async fn refresh_rotates_token_and_reuse_revokes_the_whole_family() {
+    let state = create_test_state().await;
+    let cli = TestClient::new(create_auth_app(state.clone()));
+    let first_refresh_token = signup_and_login(&cli, "refresh@example.com").await;
+
+    let refreshed = cli
+        .post("/refresh")
+        .body_json(&json!({ "refresh_token": first_refresh_token }))
+        .send()
+        .await;
+    refreshed.assert_status_is_ok();
+    let refreshed_json = refreshed.json().await;
+    let second_refresh_token = refreshed_json
+        .value()
+        .object()
+        .get("refresh_token")
+        .string()
+        .to_string();
+    assert_ne!(first_refresh_token, second_refresh_token);
+    refreshed_json
+        .value()
+        .object()
+        .get("user_role")
+        .assert_string("user");
+
+    let access_token = refreshed_json
+        .value()
+        .object()
+        .get("access_token")
+        .string()
+        .to_string();
+    cli.get("/me")
+        .header("Authorization", access_token)
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    // 회전된 토큰을 다시 쓰면 그 토큰뿐 아니라 family 전체가 막힌다.
+    cli.post("/refresh")
+        .body_json(&json!({ "refresh_token": first_refresh_token }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    cli.post("/refresh")
+        .body_json(&json!({ "refresh_token": second_refresh_token }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    let revoked_reason: String = query_scalar(
+        "SELECT revoked_reason FROM refresh_token_families WHERE user_id = ?",
+    )
+    .bind("refresh@example.com")
+    .fetch_one(&state.db)
+    .await
+    .expect("family should exist");
+    assert_eq!(revoked_reason, "reuse");
+
+    // 새 로그인은 별도 family라 영향을 받지 않는다.
+    let relogin_token = cli
+        .post("/login")
+        .body_json(&json!({
+            "user_id": "refresh@example.com",
+            "password": "password123"
+        }))
+        .send()
+        .await
+        .json()
+        .await
+        .value()
+        .object()
+        .get("refresh_token")
+        .string()
+        .to_string();
+    cli.post("/refresh")
+        .body_json(&json!({ "refresh_token": relogin_token }))
+        .send()
+        .await
+        .assert_status_is_ok();
+}
+
+#[tokio::test]
+async fn logout_revokes_family_and_refresh_rejects_unrecorded_tokens() {
+    let state = create_test_state().await;
+    let cli = TestClient::new(create_auth_app(state));
+    let refresh_token = signup_and_login(&cli, "logout@example.com").await;
+
+    cli.post("/logout")
+        .body_json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    cli.post("/logout")
+        .body_json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    cli.post("/refresh")
+        .body_json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    let legacy_token =
+        Claims::create_refresh_token("logout@example.com", "user", b"test-refresh-secret")
+            .expect("failed to create refresh token");
+    cli.post("/refresh")
+        .body_json(&json!({ "refresh_token": legacy_token }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    let access_token = issue_access_token("logout@example.com", "user");
+    cli.post("/refresh")
+        .body_json(&json!({ "refresh_token": access_token }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
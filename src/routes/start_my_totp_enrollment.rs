@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::models::{AppState, CustomResponse, TotpEnrollmentResponse};
+use crate::two_factor::start_totp_enrollment;
+
+/// TOTP 등록을 시작한다. `otpauth_uri`를 QR 코드로 보여 주고, 첫 코드로 `confirm`을 불러야 켜진다.
+#[handler]
+pub async fn start_my_totp_enrollment(
+    req: &Request,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<TotpEnrollmentResponse>>, Error> {
+    let user = current_user(req)?;
+    let enrollment = start_totp_enrollment(&data.db, &user.user_id).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(enrollment),
+        message: Some(String::from(
+            "인증 앱에 등록한 뒤 첫 코드로 등록을 확인해 주세요.",
+        )),
+    }))
+}
@@ -0,0 +1,417 @@
+use std::{env, sync::Arc};
+
+use chrono::Utc;
+use poem::{get, http::StatusCode, post, test::TestClient, Endpoint, EndpointExt, Route};
+use serde_json::{json, Value};
+use sqlx::{query, SqlitePool};
+use tyange_cms_api::auth::totp::{code_at, decode_secret, TOTP_STEP_SECONDS};
+
+use crate::{
+    db::init_db,
+    middlewares::{admin_middleware::AdminOnly, auth_middleware::Auth},
+    models::AppState,
+    routes::{
+        add_user::create_user, confirm_my_totp_enrollment::confirm_my_totp_enrollment,
+        disable_my_totp::disable_my_totp, get_my_two_factor::get_my_two_factor,
+        get_security_settings::get_security_settings, login::login,
+        login_two_factor::login_two_factor,
+        regenerate_my_recovery_codes::regenerate_my_recovery_codes,
+        start_my_totp_enrollment::start_my_totp_enrollment,
+        update_security_settings::update_security_settings,
+    },
+};
+
+const PASSWORD: &str = "second-factor-9";
+
+async fn create_test_state() -> Arc<AppState> {
+    env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+    env::set_var("JWT_REFRESH_SECRET", "test-refresh-secret");
+
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    init_db(&db).await.expect("failed to init db");
+    Arc::new(AppState::new(db))
+}
+
+fn create_app(state: Arc<AppState>) -> impl Endpoint {
+    Route::new()
+        .at("/login", post(login))
+        .at("/login/2fa", post(login_two_factor))
+        .at("/me/2fa", get(get_my_two_factor).with(Auth))
+        .at("/me/2fa/totp", post(start_my_totp_enrollment).with(Auth))
+        .at(
+            "/me/2fa/totp/confirm",
+            post(confirm_my_totp_enrollment).with(Auth),
+        )
+        .at("/me/2fa/totp/disable", post(disable_my_totp).with(Auth))
+        .at(
+            "/me/2fa/recovery-codes",
+            post(regenerate_my_recovery_codes).with(Auth),
+        )
+        .at(
+            "/admin/security-settings",
+            get(get_security_settings)
+                .put(update_security_settings)
+                .with(AdminOnly)
+                .with(Auth),
+        )
+        .data(state)
+}
+
+async fn read_json(response: poem::test::TestResponse) -> Value {
+    serde_json::from_str(&response.0.into_body().into_string().await.unwrap())
+        .expect("response should be json")
+}
+
+async fn password_login(cli: &TestClient<impl Endpoint>, user_id: &str) -> Value {
+    let response = cli
+        .post("/login")
+        .body_json(&json!({ "user_id": user_id, "password": PASSWORD }))
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    read_json(response).await
+}
+
+fn current_code(secret: &str, offset_steps: i64) -> String {
+    let key = decode_secret(secret).expect("secret should be base32");
+    code_at(
+        &key,
+        Utc::now().timestamp() + offset_steps * TOTP_STEP_SECONDS,
+    )
+}
+
+#[tokio::test]
+async fn totp_enrollment_turns_login_into_two_steps_with_single_use_codes() {
+    let state = create_test_state().await;
+    create_user(&state.db, "root@example.com", PASSWORD, "admin")
+        .await
+        .unwrap();
+    let db = state.db.clone();
+    let cli = TestClient::new(create_app(state));
+
+    let tokens = password_login(&cli, "root@example.com").await;
+    assert!(tokens.get("two_factor_setup_required").is_none());
+    let token = tokens["access_token"].as_str().unwrap().to_string();
+
+    // 본인이 등록하지 않았으면 필수 설정을 켤 수 없다.
+    cli.put("/admin/security-settings")
+        .header("Authorization", &token)
+        .body_json(&json!({ "require_admin_totp": true }))
+        .send()
+        .await
+        .assert_status(StatusCode::CONFLICT);
+
+    let enrollment = cli
+        .post("/me/2fa/totp")
+        .header("Authorization", &token)
+        .send()
+        .await;
+    enrollment.assert_status_is_ok();
+    let enrollment = read_json(enrollment).await;
+    let secret = enrollment["data"]["secret"].as_str().unwrap().to_string();
+    let uri = enrollment["data"]["otpauth_uri"].as_str().unwrap();
+    assert!(uri.starts_with("otpauth://totp/tyange-cms:root%40example.com?secret="));
+    assert!(uri.contains(&secret));
+
+    // 확인 전에는 로그인이 그대로 한 단계다.
+    assert!(password_login(&cli, "root@example.com")
+        .await
+        .get("access_token")
+        .is_some());
+
+    cli.post("/me/2fa/totp/confirm")
+        .header("Authorization", &token)
+        .body_json(&json!({ "code": "000000" }))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    let confirm_code = current_code(&secret, 0);
+    let confirmed = cli
+        .post("/me/2fa/totp/confirm")
+        .header("Authorization", &token)
+        .body_json(&json!({ "code": confirm_code }))
+        .send()
+        .await;
+    confirmed.assert_status_is_ok();
+    let recovery_codes: Vec<String> =
+        serde_json::from_value(read_json(confirmed).await["data"]["recovery_codes"].clone())
+            .unwrap();
+    assert_eq!(recovery_codes.len(), 10);
+
+    let status = read_json(
+        cli.get("/me/2fa")
+            .header("Authorization", &token)
+            .send()
+            .await,
+    )
+    .await;
+    assert_eq!(status["data"]["totp_enabled"], true);
+    assert_eq!(status["data"]["recovery_codes_remaining"], 10);
+
+    let challenge = password_login(&cli, "root@example.com").await;
+    assert_eq!(challenge["two_factor_required"], true);
+    assert!(challenge.get("access_token").is_none());
+    let challenge_token = challenge["two_factor_token"].as_str().unwrap().to_string();
+
+    // 등록 확인에 쓴 코드는 다시 받지 않는다.
+    cli.post("/login/2fa")
+        .body_json(&json!({ "two_factor_token": challenge_token, "code": confirm_code }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    let completed = cli
+        .post("/login/2fa")
+        .body_json(
+            &json!({ "two_factor_token": challenge_token, "code": current_code(&secret, 1) }),
+        )
+        .send()
+        .await;
+    completed.assert_status_is_ok();
+    let completed = read_json(completed).await;
+    assert_eq!(completed["user_role"], "admin");
+    let token = completed["access_token"].as_str().unwrap().to_string();
+    // 중간 토큰은 한 번만 쓸 수 있다.
+    cli.post("/login/2fa")
+        .body_json(
+            &json!({ "two_factor_token": challenge_token, "recovery_code": recovery_codes[0] }),
+        )
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    // 복구 코드는 형식이 달라도 받고, 한 번 쓰면 끝이다.
+    let challenge = password_login(&cli, "root@example.com").await;
+    cli.post("/login/2fa")
+        .body_json(&json!({
+            "two_factor_token": challenge["two_factor_token"],
+            "recovery_code": recovery_codes[0].to_uppercase().replace('-', " "),
+        }))
+        .send()
+        .await
+        .assert_status_is_ok();
+    let challenge = password_login(&cli, "root@example.com").await;
+    cli.post("/login/2fa")
+        .body_json(&json!({
+            "two_factor_token": challenge["two_factor_token"],
+            "recovery_code": recovery_codes[0],
+        }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    // 틀린 코드는 아이디 실패로 센다. 앞에서 재사용한 복구 코드까지 다섯 번째에서 잠기고,
+    // 잠긴 동안은 맞는 코드도 받지 않는다.
+    let challenge = password_login(&cli, "root@example.com").await;
+    for _ in 0..4 {
+        cli.post("/login/2fa")
+            .body_json(
+                &json!({ "two_factor_token": challenge["two_factor_token"], "code": "000000" }),
+            )
+            .send()
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+    }
+    cli.post("/login/2fa")
+        .body_json(&json!({
+            "two_factor_token": challenge["two_factor_token"],
+            "recovery_code": recovery_codes[1],
+        }))
+        .send()
+        .await
+        .assert_status(StatusCode::TOO_MANY_REQUESTS);
+
+    // 잠금이 풀리면 같은 중간 토큰으로 이어서 로그인할 수 있다. 잠긴 동안의 시도는 세지 않았다.
+    query("DELETE FROM login_throttles")
+        .execute(&db)
+        .await
+        .unwrap();
+    cli.post("/login/2fa")
+        .body_json(&json!({
+            "two_factor_token": challenge["two_factor_token"],
+            "recovery_code": recovery_codes[1],
+        }))
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    let regenerated = cli
+        .post("/me/2fa/recovery-codes")
+        .header("Authorization", &token)
+        .body_json(&json!({ "recovery_code": recovery_codes[3] }))
+        .send()
+        .await;
+    regenerated.assert_status_is_ok();
+    let new_codes: Vec<String> =
+        serde_json::from_value(read_json(regenerated).await["data"]["recovery_codes"].clone())
+            .unwrap();
+    let challenge = password_login(&cli, "root@example.com").await;
+    cli.post("/login/2fa")
+        .body_json(&json!({
+            "two_factor_token": challenge["two_factor_token"],
+            "recovery_code": recovery_codes[2],
+        }))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    cli.post("/me/2fa/totp/disable")
+        .header("Authorization", &token)
+        .body_json(&json!({ "code": "000000" }))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    cli.post("/me/2fa/totp/disable")
+        .header("Authorization", &token)
+        .body_json(&json!({ "recovery_code": new_codes[0] }))
+        .send()
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    assert!(password_login(&cli, "root@example.com")
+        .await
+        .get("access_token")
+        .is_some());
+}
+
+#[tokio::test]
+async fn admins_without_totp_are_blocked_once_it_is_required() {
+    let state = create_test_state().await;
+    create_user(&state.db, "root@example.com", PASSWORD, "admin")
+        .await
+        .unwrap();
+    create_user(&state.db, "helper@example.com", PASSWORD, "admin")
+        .await
+        .unwrap();
+    create_user(&state.db, "member@example.com", PASSWORD, "user")
+        .await
+        .unwrap();
+    let cli = TestClient::new(create_app(state));
+
+    let root = password_login(&cli, "root@example.com").await;
+    let root_token = root["access_token"].as_str().unwrap().to_string();
+    let enrollment = read_json(
+        cli.post("/me/2fa/totp")
+            .header("Authorization", &root_token)
+            .send()
+            .await,
+    )
+    .await;
+    let secret = enrollment["data"]["secret"].as_str().unwrap().to_string();
+    cli.post("/me/2fa/totp/confirm")
+        .header("Authorization", &root_token)
+        .body_json(&json!({ "code": current_code(&secret, 0) }))
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    let updated = cli
+        .put("/admin/security-settings")
+        .header("Authorization", &root_token)
+        .body_json(&json!({ "require_admin_totp": true }))
+        .send()
+        .await;
+    updated.assert_status_is_ok();
+    let settings = read_json(
+        cli.get("/admin/security-settings")
+            .header("Authorization", &root_token)
+            .send()
+            .await,
+    )
+    .await;
+    assert_eq!(settings["require_admin_totp"], true);
+
+    // 등록하지 않은 관리자는 로그인은 되지만 관리자 API는 막히고, 등록은 할 수 있다.
+    let helper = password_login(&cli, "helper@example.com").await;
+    assert_eq!(helper["two_factor_setup_required"], true);
+    let helper_token = helper["access_token"].as_str().unwrap().to_string();
+    cli.get("/admin/security-settings")
+        .header("Authorization", &helper_token)
+        .send()
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    let status = read_json(
+        cli.get("/me/2fa")
+            .header("Authorization", &helper_token)
+            .send()
+            .await,
+    )
+    .await;
+    assert_eq!(status["data"]["required"], true);
+    cli.post("/me/2fa/totp")
+        .header("Authorization", &helper_token)
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    // 일반 사용자는 설정과 상관없다.
+    let member = password_login(&cli, "member@example.com").await;
+    assert!(member.get("two_factor_setup_required").is_none());
+
+    // 필수인 동안 관리자는 2단계 인증을 끌 수 없다.
+    cli.post("/me/2fa/totp/disable")
+        .header("Authorization", &root_token)
+        .body_json(&json!({ "code": current_code(&secret, 1) }))
+        .send()
+        .await
+        .assert_status(StatusCode::CONFLICT);
+
+    cli.put("/admin/security-settings")
+        .header("Authorization", &root_token)
+        .body_json(&json!({ "require_admin_totp": false }))
+        .send()
+        .await
+        .assert_status_is_ok();
+    cli.get("/admin/security-settings")
+        .header("Authorization", &helper_token)
+        .send()
+        .await
+        .assert_status_is_ok();
+}
+
+#[tokio::test]
+async fn wrong_codes_across_new_challenges_lock_the_account() {
+    let state = create_test_state().await;
+    create_user(&state.db, "writer@example.com", PASSWORD, "user")
+        .await
+        .unwrap();
+    let cli = TestClient::new(create_app(state));
+
+    let token = password_login(&cli, "writer@example.com").await["access_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let enrollment = read_json(
+        cli.post("/me/2fa/totp")
+            .header("Authorization", &token)
+            .send()
+            .await,
+    )
+    .await;
+    let secret = enrollment["data"]["secret"].as_str().unwrap().to_string();
+    cli.post("/me/2fa/totp/confirm")
+        .header("Authorization", &token)
+        .body_json(&json!({ "code": current_code(&secret, 0) }))
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    // 비밀번호를 아는 사람이 중간 토큰을 새로 받아 가며 한 번씩만 찍어 봐도 아이디 카운터에 쌓인다.
+    for _ in 0..5 {
+        let challenge = password_login(&cli, "writer@example.com").await;
+        cli.post("/login/2fa")
+            .body_json(
+                &json!({ "two_factor_token": challenge["two_factor_token"], "code": "000000" }),
+            )
+            .send()
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+    }
+
+    // 잠긴 동안은 비밀번호가 맞아도 중간 토큰을 새로 주지 않는다.
+    cli.post("/login")
+        .body_json(&json!({ "user_id": "writer@example.com", "password": PASSWORD }))
+        .send()
+        .await
+        .assert_status(StatusCode::TOO_MANY_REQUESTS);
+}
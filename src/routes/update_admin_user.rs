@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Path},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::{
+    models::{AdminUserItem, AppState, UpdateAdminUserRequest},
+    user_admin::update_user,
+};
+
+/// 역할 변경과 비활성화/재활성화. 바뀐 역할은 그 사용자가 다음에 토큰을 갱신할 때 반영된다.
+#[handler]
+pub async fn update_admin_user(
+    req: &Request,
+    Path(user_id): Path<String>,
+    Json(payload): Json<UpdateAdminUserRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<AdminUserItem>, Error> {
+    let admin = current_user(req)?;
+
+    Ok(Json(
+        update_user(&data.db, admin, &user_id, &payload).await?,
+    ))
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Path},
+    Error, Request,
+};
+use sqlx::query;
+use tyange_cms_api::auth::authorization::{current_user, ensure_post_owner};
+
+use crate::{
+    attachments::{attachment_response, ensure_attachment_owner, fetch_attachment},
+    models::{AttachmentResponse, CustomResponse, UpdateAttachmentRequest},
+    AppState,
+};
+
+#[handler]
+pub async fn update_attachment(
+    req: &Request,
+    Path(attachment_id): Path<String>,
+    Json(payload): Json<UpdateAttachmentRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<AttachmentResponse>>, Error> {
+    let user = current_user(req)?;
+    let attachment = fetch_attachment(&data.db, &attachment_id).await?;
+    ensure_attachment_owner(user, &attachment, &data.db).await?;
+
+    let post_id = match payload.post_id.as_deref().map(str::trim) {
+        None => attachment.post_id.clone(),
+        Some("") => None,
+        Some(post_id) => {
+            ensure_post_owner(user, post_id, &data.db).await?;
+            Some(post_id.to_string())
+        }
+    };
+
+    query("UPDATE attachments SET post_id = ? WHERE attachment_id = ?")
+        .bind(&post_id)
+        .bind(&attachment_id)
+        .execute(&data.db)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("첨부 파일 정보 수정 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+
+    let updated = fetch_attachment(&data.db, &attachment_id).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(attachment_response(updated)),
+        message: Some(String::from("첨부 파일 정보가 수정되었습니다.")),
+    }))
+}
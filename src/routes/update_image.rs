@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Path},
+    Error, Request,
+};
+use sqlx::query;
+use tyange_cms_api::auth::authorization::{current_user, ensure_post_owner};
+
+use crate::{
+    image_library::{ensure_image_owner, fetch_image, image_item_response},
+    models::{CustomResponse, ImageItemResponse, UpdateImageRequest},
+    AppState,
+};
+
+#[handler]
+pub async fn update_image(
+    req: &Request,
+    Path(image_id): Path<String>,
+    Json(payload): Json<UpdateImageRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<ImageItemResponse>>, Error> {
+    let user = current_user(req)?;
+    let image = fetch_image(&data.db, &image_id).await?;
+    ensure_image_owner(user, &image)?;
+
+    let post_id = match payload.post_id.as_deref().map(str::trim) {
+        None => image.post_id.clone(),
+        Some("") => None,
+        Some(post_id) => {
+            ensure_post_owner(user, post_id, &data.db).await?;
+            Some(post_id.to_string())
+        }
+    };
+    let alt_text = payload.alt_text.or(image.alt_text);
+    let caption = payload.caption.or(image.caption);
+
+    query("UPDATE images SET post_id = ?, alt_text = ?, caption = ? WHERE image_id = ?")
+        .bind(&post_id)
+        .bind(&alt_text)
+        .bind(&caption)
+        .bind(&image_id)
+        .execute(&data.db)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("이미지 정보 수정 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+
+    let updated = fetch_image(&data.db, &image_id).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(image_item_response(updated)),
+        message: Some(String::from("이미지 정보가 수정되었습니다.")),
+    }))
+}
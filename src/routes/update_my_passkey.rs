@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Path},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::models::{AppState, PasskeyResponse, UpdatePasskeyRequest};
+use crate::passkeys::rename_passkey;
+
+#[handler]
+pub async fn update_my_passkey(
+    req: &Request,
+    Path(passkey_id): Path<i64>,
+    Json(payload): Json<UpdatePasskeyRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<PasskeyResponse>, Error> {
+    let user = current_user(req)?;
+    let passkey = rename_passkey(&data.db, &user.user_id, passkey_id, &payload.name).await?;
+
+    Ok(Json(passkey))
+}
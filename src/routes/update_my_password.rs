@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json},
+    Error, Request,
+};
+use sqlx::Row;
+use tyange_cms_api::auth::authorization::current_user;
+use tyange_cms_api::auth::password::{check_password_policy, matches_password};
+
+use crate::models::{AppState, ChangePasswordRequest, CustomResponse, LoginResponse};
+use crate::password_reset::replace_user_password;
+use crate::routes::login::{issue_family_tokens, issue_login_response};
+use crate::sessions::SessionDevice;
+
+/// 현재 비밀번호를 확인하고 새 비밀번호로 바꾼다. 요청한 세션만 남기고 다른 세션을 모두 폐기한 뒤,
+/// 요청한 기기가 계속 쓸 수 있도록 같은 세션의 새 토큰을 돌려준다. 세션 ID가 없는 예전 토큰이면 새 세션을 만든다.
+#[handler]
+pub async fn update_my_password(
+    req: &Request,
+    Json(payload): Json<ChangePasswordRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<LoginResponse>>, Error> {
+    let user = current_user(req)?;
+
+    let row = sqlx::query("SELECT password, user_role FROM users WHERE user_id = ?")
+        .bind(&user.user_id)
+        .fetch_optional(&data.db)
+        .await
+        .map_err(|err| {
+            Error::from_string(
+                format!("사용자 정보 조회 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?
+        .ok_or_else(|| Error::from_string("사용자를 찾을 수 없습니다.", StatusCode::NOT_FOUND))?;
+    let stored_password: Option<String> = row.try_get("password").unwrap_or(None);
+    let user_role: String = row.try_get("user_role").unwrap_or_default();
+
+    if stored_password.is_none() {
+        return Err(Error::from_string(
+            "비밀번호가 없는 계정입니다. 비밀번호 재설정으로 먼저 설정해 주세요.",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    if !matches_password(&payload.current_password, stored_password.as_deref()) {
+        return Err(Error::from_string(
+            "현재 비밀번호가 일치하지 않습니다.",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    if payload.new_password == payload.current_password {
+        return Err(Error::from_string(
+            "새 비밀번호가 현재 비밀번호와 같습니다.",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    check_password_policy(&payload.new_password, &user.user_id)
+        .map_err(|err| Error::from_string(err.to_string(), StatusCode::BAD_REQUEST))?;
+
+    replace_user_password(
+        &data.db,
+        &user.user_id,
+        &payload.new_password,
+        user.session_id.as_deref(),
+        "password_change",
+    )
+    .await?;
+    let tokens = match &user.session_id {
+        Some(session_id) => {
+            issue_family_tokens(&data.db, &user.user_id, &user_role, session_id).await?
+        }
+        None => {
+            let device = SessionDevice::from_request(req);
+            issue_login_response(&data.db, &user.user_id, &user_role, &device).await?
+        }
+    };
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(tokens),
+        message: Some(String::from(
+            "비밀번호를 변경했습니다. 다른 기기의 로그인은 모두 해제되었습니다.",
+        )),
+    }))
+}
@@ -6,7 +6,7 @@ use poem::{
     web::{Data, Json},
     Error, Request,
 };
-use sqlx::query;
+use sqlx::query_scalar;
 use tyange_cms_api::auth::authorization::current_user;
 
 use crate::models::{AppState, MeResponse, UpdateMyProfileRequest};
@@ -57,18 +57,19 @@ pub async fn update_my_profile(
         ));
     }
 
-    query(
+    let deletion_scheduled_at: Option<String> = query_scalar(
         r#"
         UPDATE users
         SET display_name = ?, avatar_url = ?, bio = ?
         WHERE user_id = ?
+        RETURNING deletion_scheduled_at
         "#,
     )
     .bind(&display_name)
     .bind(&avatar_url)
     .bind(&bio)
     .bind(&user.user_id)
-    .execute(&data.db)
+    .fetch_one(&data.db)
     .await
     .map_err(|e| {
         Error::from_string(
@@ -83,5 +84,6 @@ pub async fn update_my_profile(
         display_name,
         avatar_url,
         bio,
+        deletion_scheduled_at,
     }))
 }
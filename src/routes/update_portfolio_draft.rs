@@ -0,0 +1,31 @@
+use crate::models::{
+    AppState, CustomResponse, PortfolioDraftResponse, PortfolioSelectorQuery,
+    UpdatePortfolioRequest,
+};
+use crate::portfolio::{save_portfolio_draft, PortfolioKey};
+use crate::portfolio_schema::parse_portfolio_document;
+use poem::web::{Data, Json, Query};
+use poem::{handler, Error, Request};
+use std::sync::Arc;
+use tyange_cms_api::auth::authorization::current_user;
+
+/// 초안만 덮어쓴다. 공개 문서는 `/portfolio/publish`를 호출할 때 바뀐다.
+/// `PUT /portfolio`와 `PUT /portfolio/update`도 이 핸들러로 초안에 저장한다.
+#[handler]
+pub async fn update_portfolio_draft(
+    req: &Request,
+    Query(selector): Query<PortfolioSelectorQuery>,
+    Json(payload): Json<UpdatePortfolioRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<PortfolioDraftResponse>>, Error> {
+    let user = current_user(req)?;
+    let document = parse_portfolio_document(payload.content)?;
+    let key = PortfolioKey::resolve(&selector, Some(&document.slug))?;
+    let draft = save_portfolio_draft(&data.db, &key, document, &user.user_id).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(draft),
+        message: Some(String::from("포트폴리오 초안을 저장했습니다.")),
+    }))
+}
@@ -0,0 +1,50 @@
+use crate::models::{AppState, CustomResponse, PortfolioDraftResponse, PortfolioSelectorQuery};
+use crate::portfolio::{
+    apply_section, load_portfolio, load_portfolio_draft, save_portfolio_draft, PortfolioKey,
+};
+use poem::http::StatusCode;
+use poem::web::{Data, Json, Path, Query};
+use poem::{handler, Error, Request};
+use std::sync::Arc;
+use tyange_cms_api::auth::authorization::current_user;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct UpdateSectionRequest {
+    pub content: serde_json::Value,
+}
+
+/// 초안의 섹션 하나를 바꾼다. 초안이 아직 없으면 공개 문서를 복사해 시작한다.
+/// `PUT /portfolio/sections/:section_key`도 이 핸들러로 초안만 고친다.
+#[handler]
+pub async fn update_portfolio_draft_section(
+    req: &Request,
+    Path(section_key): Path<String>,
+    Query(selector): Query<PortfolioSelectorQuery>,
+    Json(payload): Json<UpdateSectionRequest>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<PortfolioDraftResponse>>, Error> {
+    let user = current_user(req)?;
+    let key = PortfolioKey::resolve(&selector, None)?;
+
+    let base = match load_portfolio_draft(&data.db, &key).await? {
+        Some(draft) => draft.content,
+        None => load_portfolio(&data.db, &key)
+            .await?
+            .map(|portfolio| portfolio.content)
+            .ok_or_else(|| {
+                Error::from_string(
+                    "포트폴리오 데이터를 찾지 못했습니다.",
+                    StatusCode::NOT_FOUND,
+                )
+            })?,
+    };
+
+    let document = apply_section(&base, &section_key, payload.content)?;
+    let draft = save_portfolio_draft(&data.db, &key, document, &user.user_id).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(draft),
+        message: Some(format!("초안의 {} 섹션을 업데이트 했습니다.", section_key)),
+    }))
+}
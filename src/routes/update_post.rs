@@ -135,6 +135,7 @@ pub async fn update_post(
                 .collect(),
             content: payload.content,
             status: payload.status,
+            portfolio_projects: None,
         }),
         message: Some(String::from("포스트를 업데이트 했습니다.")),
     }))
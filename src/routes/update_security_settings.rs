@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::models::{AppState, SecuritySettings};
+use crate::two_factor::{set_admin_totp_required, totp_enabled};
+
+/// 관리자 2단계 인증 필수 여부를 바꾼다. 켜면 등록하지 않은 관리자는 관리자 API에서 `403`을 받는다.
+/// 켜는 관리자 본인은 먼저 등록해 있어야 한다(`409`).
+#[handler]
+pub async fn update_security_settings(
+    req: &Request,
+    Json(payload): Json<SecuritySettings>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<SecuritySettings>, Error> {
+    let user = current_user(req)?;
+
+    if payload.require_admin_totp {
+        let enabled = totp_enabled(&data.db, &user.user_id).await.map_err(|err| {
+            Error::from_string(
+                format!("2단계 인증 조회 실패: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+        if !enabled {
+            return Err(Error::from_string(
+                "먼저 본인 계정에 2단계 인증을 등록해 주세요.",
+                StatusCode::CONFLICT,
+            ));
+        }
+    }
+
+    set_admin_totp_required(&data.db, payload.require_admin_totp).await?;
+    Ok(Json(payload))
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Multipart, Query},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::{current_user, ensure_post_owner};
+
+use crate::{
+    attachments::{attachment_response, store_attachment},
+    models::{AppState, AttachmentResponse, CustomResponse, UploadAttachmentQuery},
+};
+
+#[handler]
+pub async fn upload_attachment(
+    req: &Request,
+    mut multipart: Multipart,
+    Query(params): Query<UploadAttachmentQuery>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<AttachmentResponse>>, Error> {
+    let user = current_user(req)?;
+
+    let post_id = params
+        .post_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|post_id| !post_id.is_empty());
+    if let Some(post_id) = post_id {
+        ensure_post_owner(user, post_id, &data.db).await?;
+    }
+
+    while let Some(field) = multipart.next_field().await? {
+        let Some(origin_name) = field.file_name().map(|name| name.to_owned()) else {
+            continue;
+        };
+
+        let file_bytes = field
+            .bytes()
+            .await
+            .map_err(|e| Error::from_string(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
+
+        let attachment = store_attachment(
+            &data.db,
+            data.storage.as_ref(),
+            &user.user_id,
+            post_id,
+            &origin_name,
+            file_bytes,
+        )
+        .await?;
+
+        return Ok(Json(CustomResponse {
+            status: true,
+            data: Some(attachment_response(attachment)),
+            message: Some(String::from("첨부 파일 업로드에 성공했습니다.")),
+        }));
+    }
+
+    Err(Error::from_string(
+        "업로드할 첨부 파일이 없습니다.",
+        StatusCode::BAD_REQUEST,
+    ))
+}
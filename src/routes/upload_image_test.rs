@@ -1,6 +1,9 @@
 use std::{env, sync::Arc};
 
+use image::{DynamicImage, ImageFormat, RgbImage};
 use poem::{
+    get,
+    http::StatusCode,
     post,
     test::{TestClient, TestForm, TestFormField},
     Endpoint, EndpointExt, Route,
@@ -9,8 +12,14 @@ use sqlx::{query_as, query_scalar, SqlitePool};
 use tokio::fs;
 
 use crate::{
-    db::init_db, middlewares::auth_middleware::Auth, models::AppState,
-    routes::upload_image::upload_image,
+    db::init_db,
+    image_metadata::jpeg_with_exif,
+    middlewares::auth_middleware::Auth,
+    models::AppState,
+    routes::{
+        get_image::get_image, get_image_detail::get_image_detail, upload_image::upload_image,
+    },
+    storage::{s3_stand_in, S3Storage},
 };
 use tyange_cms_api::auth::jwt::Claims;
 
@@ -28,8 +37,10 @@ struct StoredImageRow {
 async fn create_test_state() -> Arc<AppState> {
     env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
     env::set_var("UPLOAD_PATH", TEST_UPLOAD_PATH);
+    env::set_var("IMAGE_VARIANT_WIDTHS", "16,32");
+    env::set_var("IMAGE_VARIANT_FORMATS", "original,webp");
 
-    let _ = fs::remove_dir_all(TEST_UPLOAD_PATH).await;
+    // 같은 디렉토리를 쓰는 테스트가 병렬로 돌기 때문에 비우지 않는다. 파일 이름은 UUID라 겹치지 않는다.
     fs::create_dir_all(TEST_UPLOAD_PATH)
         .await
         .expect("failed to create upload dir");
@@ -46,6 +57,8 @@ fn create_upload_app(state: Arc<AppState>) -> impl Endpoint {
     Route::new()
         .at("/upload-image", post(upload_image).with(Auth))
         .at("/images/upload", post(upload_image).with(Auth))
+        .at("/images/:file_name", get(get_image))
+        .at("/image/:image_id", get(get_image_detail).with(Auth))
         .data(state)
 }
 
@@ -64,7 +77,7 @@ async fn upload_image_accepts_image_without_post_id() {
         .header("Authorization", issue_access_token("writer-1", "user"))
         .multipart(
             TestForm::new().field(
-                TestFormField::bytes(b"fake png bytes")
+                TestFormField::bytes(png_bytes(4, 4))
                     .name("file")
                     .filename("avatar.png")
                     .content_type("image/png"),
@@ -102,7 +115,7 @@ async fn upload_image_accepts_image_without_post_id() {
     let saved_bytes = fs::read(format!("{}/{}", TEST_UPLOAD_PATH, stored.file_name))
         .await
         .expect("failed to read uploaded file");
-    assert_eq!(saved_bytes, b"fake png bytes");
+    assert!(saved_bytes.starts_with(b"\x89PNG"));
 }
 
 #[tokio::test]
@@ -132,3 +145,345 @@ async fn upload_image_rejects_non_image_content_type() {
         .expect("failed to count images");
     assert_eq!(saved_count, 0);
 }
+
+async fn upload_file(
+    cli: &TestClient<impl Endpoint>,
+    bytes: Vec<u8>,
+    file_name: &str,
+    content_type: &str,
+) -> poem::test::TestResponse {
+    cli.post("/images/upload")
+        .header("Authorization", issue_access_token("writer-1", "user"))
+        .multipart(
+            TestForm::new().field(
+                TestFormField::bytes(bytes)
+                    .name("file")
+                    .filename(file_name)
+                    .content_type(content_type),
+            ),
+        )
+        .send()
+        .await
+}
+
+#[tokio::test]
+async fn upload_image_rejects_disguised_and_mismatched_files() {
+    let state = create_test_state().await;
+    let cli = TestClient::new(create_upload_app(state.clone()));
+
+    let response = upload_file(
+        &cli,
+        b"<html><script>alert(1)</script></html>".to_vec(),
+        "cat.png",
+        "image/png",
+    )
+    .await;
+    response.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    let response = upload_file(&cli, png_bytes(4, 4), "cat.gif", "image/gif").await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let saved_count: i64 = query_scalar("SELECT COUNT(*) FROM images")
+        .fetch_one(&state.db)
+        .await
+        .expect("failed to count images");
+    assert_eq!(saved_count, 0);
+}
+
+#[tokio::test]
+async fn upload_image_names_file_by_detected_type() {
+    let state = create_test_state().await;
+    let cli = TestClient::new(create_upload_app(state.clone()));
+
+    let response = upload_file(&cli, png_bytes(4, 4), "page.html", "image/png").await;
+    response.assert_status_is_ok();
+
+    let file_name: String = query_scalar("SELECT file_name FROM images LIMIT 1")
+        .fetch_one(&state.db)
+        .await
+        .expect("failed to fetch file name");
+    assert!(file_name.ends_with(".png"));
+
+    let response = cli.get(format!("/images/{}", file_name)).send().await;
+    response.assert_status_is_ok();
+    response.assert_content_type("image/png");
+    response.assert_header("x-content-type-options", "nosniff");
+}
+
+#[tokio::test]
+async fn upload_image_sanitizes_svg_and_serves_stored_type() {
+    let state = create_test_state().await;
+    let cli = TestClient::new(create_upload_app(state.clone()));
+
+    let response = upload_file(
+        &cli,
+        br#"<svg xmlns="http://www.w3.org/2000/svg" onload="alert(1)"><script>alert(2)</script><rect width="4" height="4"/></svg>"#.to_vec(),
+        "logo.svg",
+        "image/svg+xml",
+    )
+    .await;
+    response.assert_status_is_ok();
+
+    let (file_name, mime_type): (String, String) =
+        query_as("SELECT file_name, mime_type FROM images LIMIT 1")
+            .fetch_one(&state.db)
+            .await
+            .expect("failed to fetch stored image");
+    assert_eq!(mime_type, "image/svg+xml");
+
+    let response = cli.get(format!("/images/{}", file_name)).send().await;
+    response.assert_status_is_ok();
+    response.assert_content_type("image/svg+xml");
+    response.assert_header(
+        "content-security-policy",
+        "default-src 'none'; style-src 'unsafe-inline'; sandbox",
+    );
+    response
+        .assert_text(
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><rect width="4" height="4"/></svg>"#,
+        )
+        .await;
+}
+
+fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+    let image = DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+        image::Rgb([(x * 4) as u8, (y * 4) as u8, 200])
+    }));
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+        .expect("failed to encode png");
+    bytes
+}
+
+#[tokio::test]
+async fn upload_image_creates_width_variants_and_serves_them_on_demand() {
+    let state = create_test_state().await;
+    let cli = TestClient::new(create_upload_app(state.clone()));
+
+    let response = cli
+        .post("/images/upload")
+        .header("Authorization", issue_access_token("writer-1", "user"))
+        .multipart(
+            TestForm::new().field(
+                TestFormField::bytes(png_bytes(48, 24))
+                    .name("file")
+                    .filename("banner.png")
+                    .content_type("image/png"),
+            ),
+        )
+        .send()
+        .await;
+
+    response.assert_status_is_ok();
+    let json = response.json().await;
+    let data = json.value().object().get("data").object();
+    let image_path = data.get("image_path").string().to_string();
+    let stem = image_path
+        .trim_start_matches("/images/")
+        .trim_end_matches(".png")
+        .to_string();
+
+    let variants = data.get("variants").array();
+    variants.assert_len(5);
+    variants
+        .get(0)
+        .object()
+        .get("image_path")
+        .assert_string(&format!("/images/{}-w16.png", stem));
+    variants.get(0).object().get("height").assert_i64(8);
+
+    let srcset = data.get("srcset").array();
+    srcset.get(1).object().get("srcset").assert_string(&format!(
+        "/images/{0}-w16.webp 16w, /images/{0}-w32.webp 32w, /images/{0}-w48.webp 48w",
+        stem
+    ));
+
+    let variant_count: i64 = query_scalar("SELECT COUNT(*) FROM image_variants")
+        .fetch_one(&state.db)
+        .await
+        .expect("failed to count variants");
+    assert_eq!(variant_count, 5);
+
+    let original = cli.get(&image_path).send().await;
+    original.assert_status_is_ok();
+    original.assert_content_type("image/png");
+
+    let resized = cli
+        .get(format!("{}?w=20&format=webp", image_path))
+        .send()
+        .await;
+    resized.assert_status_is_ok();
+    resized.assert_content_type("image/webp");
+    let resized_bytes = resized.0.into_body().into_vec().await.unwrap();
+    let decoded = image::load_from_memory(&resized_bytes).expect("failed to decode variant");
+    assert_eq!(decoded.width(), 32);
+
+    // 저장해 둔 variant는 원본을 다시 읽어 인코딩하지 않고 그대로 내려준다.
+    state
+        .storage
+        .delete(image_path.trim_start_matches("/images/"))
+        .await
+        .expect("failed to delete original");
+    let cached = cli
+        .get(format!("{}?w=20&format=webp", image_path))
+        .send()
+        .await;
+    cached.assert_status_is_ok();
+    cached.assert_content_type("image/webp");
+
+    let unknown_format = cli.get(format!("{}?format=tiff", image_path)).send().await;
+    unknown_format.assert_status(poem::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn upload_image_applies_exif_orientation_and_strips_metadata() {
+    let state = create_test_state().await;
+    let cli = TestClient::new(create_upload_app(state.clone()));
+    let token = issue_access_token("writer-1", "user");
+
+    let response = cli
+        .post("/images/upload")
+        .header("Authorization", &token)
+        .multipart(
+            TestForm::new().field(
+                TestFormField::bytes(jpeg_with_exif(40, 20))
+                    .name("file")
+                    .filename("phone.jpg")
+                    .content_type("image/jpeg"),
+            ),
+        )
+        .send()
+        .await;
+
+    response.assert_status_is_ok();
+    let json = response.json().await;
+    let data = json.value().object().get("data").object();
+    data.get("width").assert_i64(20);
+    data.get("height").assert_i64(40);
+    let image_id = data.get("image_id").string().to_string();
+    let image_path = data.get("image_path").string().to_string();
+
+    let saved_bytes = fs::read(format!(
+        "{}/{}",
+        TEST_UPLOAD_PATH,
+        image_path.trim_start_matches("/images/")
+    ))
+    .await
+    .expect("failed to read uploaded file");
+    assert!(!saved_bytes.windows(4).any(|window| window == b"Exif"));
+    let decoded = image::load_from_memory(&saved_bytes).expect("failed to decode upload");
+    assert_eq!((decoded.width(), decoded.height()), (20, 40));
+
+    let detail = cli
+        .get(format!("/image/{}", image_id))
+        .header("Authorization", &token)
+        .send()
+        .await;
+    detail.assert_status_is_ok();
+    let json = detail.json().await;
+    let data = json.value().object().get("data").object();
+    data.get("image_path").assert_string(&image_path);
+    data.get("width").assert_i64(20);
+    data.get("height").assert_i64(40);
+    data.get("byte_size").assert_i64(saved_bytes.len() as i64);
+    assert!(data.get("dominant_color").string().starts_with('#'));
+    assert!(!data.get("blurhash").string().is_empty());
+    data.get("variants").array().assert_len(3);
+
+    let missing = cli
+        .get("/image/unknown-image")
+        .header("Authorization", &token)
+        .send()
+        .await;
+    missing.assert_status(poem::http::StatusCode::NOT_FOUND);
+}
+
+async fn create_s3_test_state(presigned_reads: bool) -> (Arc<AppState>, s3_stand_in::S3StandIn) {
+    let state = create_test_state().await;
+    let stand_in = s3_stand_in::spawn("uploads", presigned_reads).await;
+    let storage = Arc::new(S3Storage::new(stand_in.config.clone()));
+
+    (
+        Arc::new(AppState::new_with_storage(state.db.clone(), storage)),
+        stand_in,
+    )
+}
+
+#[tokio::test]
+async fn upload_image_stores_in_s3_and_proxies_reads() {
+    let (state, stand_in) = create_s3_test_state(false).await;
+    let cli = TestClient::new(create_upload_app(state.clone()));
+
+    let response = cli
+        .post("/images/upload")
+        .header("Authorization", issue_access_token("writer-1", "user"))
+        .multipart(
+            TestForm::new().field(
+                TestFormField::bytes(png_bytes(40, 20))
+                    .name("file")
+                    .filename("wide.png")
+                    .content_type("image/png"),
+            ),
+        )
+        .send()
+        .await;
+    response.assert_status_is_ok();
+
+    let file_name: String = query_scalar("SELECT file_name FROM images LIMIT 1")
+        .fetch_one(&state.db)
+        .await
+        .expect("failed to fetch file name");
+    let file_path: String = query_scalar("SELECT file_path FROM images LIMIT 1")
+        .fetch_one(&state.db)
+        .await
+        .expect("failed to fetch file path");
+    assert_eq!(file_path, format!("s3://uploads/{}", file_name));
+    assert!(stand_in.objects.lock().unwrap().contains_key(&file_name));
+    assert!(
+        !fs::try_exists(format!("{}/{}", TEST_UPLOAD_PATH, file_name))
+            .await
+            .unwrap()
+    );
+
+    let response = cli.get(format!("/images/{}", file_name)).send().await;
+    response.assert_status_is_ok();
+    response.assert_content_type("image/png");
+
+    let response = cli
+        .get(format!("/images/{}?w=16&format=webp", file_name))
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    response.assert_content_type("image/webp");
+}
+
+#[tokio::test]
+async fn get_image_redirects_to_presigned_url_when_enabled() {
+    let (state, stand_in) = create_s3_test_state(true).await;
+    let cli = TestClient::new(create_upload_app(state.clone()));
+
+    stand_in
+        .objects
+        .lock()
+        .unwrap()
+        .insert("remote.png".to_string(), png_bytes(4, 4));
+
+    let response = cli.get("/images/remote.png").send().await;
+    response.assert_status(StatusCode::FOUND);
+    let location = response
+        .0
+        .headers()
+        .get("location")
+        .and_then(|value| value.to_str().ok())
+        .expect("missing location")
+        .to_string();
+    assert!(location.starts_with(&format!(
+        "{}uploads/remote.png?X-Amz-Algorithm=AWS4-HMAC-SHA256",
+        stand_in.config.endpoint
+    )));
+
+    let fetched = reqwest::get(&location).await.unwrap();
+    assert_eq!(fetched.status(), reqwest::StatusCode::OK);
+    assert_eq!(fetched.bytes().await.unwrap().to_vec(), png_bytes(4, 4));
+}
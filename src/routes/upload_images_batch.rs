@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Multipart, Query},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::{
+    image_upload::store_uploaded_image,
+    models::{
+        AppState, BatchUploadImageItem, BatchUploadImageResponse, CustomResponse,
+        UploadImageQueryParmas,
+    },
+};
+
+/// multipart의 파일 필드를 모두 처리한다. 한 파일이 실패해도 나머지는 계속 올리고 파일별 결과를 돌려준다.
+#[handler]
+pub async fn upload_images_batch(
+    req: &Request,
+    mut multipart: Multipart,
+    Query(params): Query<UploadImageQueryParmas>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<BatchUploadImageResponse>>, Error> {
+    let user = current_user(req)?;
+    let mut results = Vec::new();
+
+    while let Some(field) = multipart.next_field().await? {
+        let Some(origin_name) = field.file_name().map(|name| name.to_owned()) else {
+            continue;
+        };
+
+        let content_type = field
+            .content_type()
+            .map(|mime| mime.to_string())
+            .unwrap_or_default();
+
+        let file_bytes = field
+            .bytes()
+            .await
+            .map_err(|e| Error::from_string(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
+
+        let result = store_uploaded_image(
+            &data.db,
+            data.storage.as_ref(),
+            &user.user_id,
+            &origin_name,
+            &content_type,
+            file_bytes,
+            &params,
+        )
+        .await;
+
+        results.push(match result {
+            Ok(image) => BatchUploadImageItem {
+                origin_name,
+                status: true,
+                status_code: StatusCode::OK.as_u16(),
+                data: Some(image),
+                message: None,
+            },
+            Err(err) => BatchUploadImageItem {
+                origin_name,
+                status: false,
+                status_code: err.status().as_u16(),
+                data: None,
+                message: Some(err.to_string()),
+            },
+        });
+    }
+
+    if results.is_empty() {
+        return Err(Error::from_string(
+            "업로드할 이미지 파일이 없습니다.",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let uploaded_count = results.iter().filter(|result| result.status).count();
+    let failed_count = results.len() - uploaded_count;
+
+    Ok(Json(CustomResponse {
+        status: failed_count == 0,
+        data: Some(BatchUploadImageResponse {
+            uploaded_count,
+            failed_count,
+            results,
+        }),
+        message: Some(format!(
+            "{}개 업로드, {}개 실패했습니다.",
+            uploaded_count, failed_count
+        )),
+    }))
+}
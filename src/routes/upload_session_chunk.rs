@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    web::{Data, Json, Path, Query},
+    Error, Request,
+};
+use tyange_cms_api::auth::authorization::current_user;
+
+use crate::{
+    models::{AppState, CustomResponse, UploadChunkQuery, UploadSessionResponse},
+    upload_sessions::{append_upload_chunk, fetch_upload_session, upload_session_response},
+};
+
+#[handler]
+pub async fn upload_session_chunk(
+    req: &Request,
+    Path(session_id): Path<String>,
+    Query(params): Query<UploadChunkQuery>,
+    chunk: Vec<u8>,
+    data: Data<&Arc<AppState>>,
+) -> Result<Json<CustomResponse<UploadSessionResponse>>, Error> {
+    let user = current_user(req)?;
+    let session = fetch_upload_session(&data.db, &session_id, &user.user_id).await?;
+    let session = append_upload_chunk(&data.db, session, params.offset, chunk).await?;
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(upload_session_response(session)),
+        message: None,
+    }))
+}
@@ -0,0 +1,350 @@
+use std::{env, sync::Arc};
+
+use poem::{
+    get,
+    http::StatusCode,
+    post,
+    test::{TestClient, TestForm, TestFormField},
+    Endpoint, EndpointExt, Route,
+};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::{query_scalar, SqlitePool};
+
+use crate::{
+    db::init_db,
+    middlewares::auth_middleware::Auth,
+    models::AppState,
+    routes::{
+        create_upload_session::create_upload_session, delete_upload_session::delete_upload_session,
+        finalize_upload_session::finalize_upload_session, get_upload_session::get_upload_session,
+        upload_images_batch::upload_images_batch, upload_session_chunk::upload_session_chunk,
+    },
+    storage::{MemoryStorage, UploadStorage},
+};
+use tyange_cms_api::auth::jwt::Claims;
+
+async fn create_test_state() -> (Arc<AppState>, Arc<MemoryStorage>) {
+    env::set_var("JWT_ACCESS_SECRET", "test-access-secret");
+    env::set_var(
+        "UPLOAD_SESSION_PATH",
+        "/tmp/tyange-cms-upload-session-tests",
+    );
+
+    let db = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("failed to connect sqlite");
+    init_db(&db).await.expect("failed to init db");
+
+    let storage = Arc::new(MemoryStorage::default());
+    (
+        Arc::new(AppState::new_with_storage(db, storage.clone())),
+        storage,
+    )
+}
+
+fn create_test_app(state: Arc<AppState>) -> impl Endpoint {
+    Route::new()
+        .at("/images/upload/batch", post(upload_images_batch).with(Auth))
+        .at(
+            "/images/upload-sessions",
+            post(create_upload_session).with(Auth),
+        )
+        .at(
+            "/images/upload-sessions/:session_id",
+            get(get_upload_session.with(Auth))
+                .put(upload_session_chunk.with(Auth))
+                .delete(delete_upload_session.with(Auth)),
+        )
+        .at(
+            "/images/upload-sessions/:session_id/finalize",
+            post(finalize_upload_session).with(Auth),
+        )
+        .data(state)
+}
+
+fn issue_access_token(user_id: &str) -> String {
+    Claims::create_access_token(user_id, "user", b"test-access-secret")
+        .expect("failed to create access token")
+}
+
+fn svg_bytes(label: &str) -> Vec<u8> {
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg"><text>{}</text></svg>"#,
+        label
+    )
+    .into_bytes()
+}
+
+#[tokio::test]
+async fn batch_upload_reports_each_file() {
+    let (state, storage) = create_test_state().await;
+    sqlx::query(
+        "INSERT INTO posts (post_id, title, content, writer_id, status) VALUES ('post-1', 'post', '', 'writer-1', 'published')",
+    )
+    .execute(&state.db)
+    .await
+    .expect("failed to seed post");
+    let cli = TestClient::new(create_test_app(state.clone()));
+
+    let response = cli
+        .post("/images/upload/batch?post_id=post-1")
+        .header("Authorization", issue_access_token("writer-1"))
+        .multipart(
+            TestForm::new()
+                .field(
+                    TestFormField::bytes(svg_bytes("one"))
+                        .name("files")
+                        .filename("one.svg")
+                        .content_type("image/svg+xml"),
+                )
+                .field(
+                    TestFormField::bytes(b"<html></html>".to_vec())
+                        .name("files")
+                        .filename("evil.png")
+                        .content_type("image/png"),
+                )
+                .field(
+                    TestFormField::bytes(svg_bytes("two"))
+                        .name("files")
+                        .filename("two.svg")
+                        .content_type("image/svg+xml"),
+                ),
+        )
+        .send()
+        .await;
+
+    response.assert_status_is_ok();
+
+    let json = response.json().await;
+    let data = json.value().object().get("data").object();
+    data.get("uploaded_count").assert_i64(2);
+    data.get("failed_count").assert_i64(1);
+
+    let results = data.get("results").array();
+    results.assert_len(3);
+    results
+        .get(0)
+        .object()
+        .get("origin_name")
+        .assert_string("one.svg");
+    results.get(0).object().get("status").assert_bool(true);
+    let failed = results.get(1).object();
+    failed.get("status").assert_bool(false);
+    failed.get("status_code").assert_i64(415);
+    failed.get("data").assert_null();
+    results.get(2).object().get("status").assert_bool(true);
+
+    let post_ids: Vec<Option<String>> = query_scalar("SELECT post_id FROM images")
+        .fetch_all(&state.db)
+        .await
+        .expect("failed to fetch images");
+    assert_eq!(
+        post_ids,
+        vec![Some("post-1".to_string()), Some("post-1".to_string())]
+    );
+    assert_eq!(storage.list().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn chunked_upload_resumes_from_reported_offset_and_finalizes() {
+    let (state, storage) = create_test_state().await;
+    let cli = TestClient::new(create_test_app(state.clone()));
+    let token = issue_access_token("writer-1");
+    let file = svg_bytes("chunked upload over a flaky connection");
+    let (first, second) = file.split_at(20);
+
+    let response = cli
+        .post("/images/upload-sessions")
+        .header("Authorization", &token)
+        .body_json(&json!({
+            "file_name": "big.svg",
+            "content_type": "image/svg+xml",
+            "total_size": file.len(),
+            "image_type": "thumbnail"
+        }))
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    let json = response.json().await;
+    let session_id = json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("session_id")
+        .string()
+        .to_string();
+    let session_path = format!("/images/upload-sessions/{}", session_id);
+
+    let response = cli
+        .put(format!("{}?offset=0", session_path))
+        .header("Authorization", &token)
+        .body(first.to_vec())
+        .send()
+        .await;
+    response.assert_status_is_ok();
+
+    // 응답을 못 받은 클라이언트가 같은 조각을 다시 보내면 현재 위치를 알려 준다.
+    let response = cli
+        .put(format!("{}?offset=0", session_path))
+        .header("Authorization", &token)
+        .body(first.to_vec())
+        .send()
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+
+    let response = cli
+        .get(&session_path)
+        .header("Authorization", &token)
+        .send()
+        .await;
+    let json = response.json().await;
+    json.value()
+        .object()
+        .get("data")
+        .object()
+        .get("received_size")
+        .assert_i64(20);
+
+    let response = cli
+        .post(format!("{}/finalize", session_path))
+        .header("Authorization", &token)
+        .body_json(&json!({ "sha256": hex::encode(Sha256::digest(&file)) }))
+        .send()
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+
+    let response = cli
+        .put(format!("{}?offset=20", session_path))
+        .header("Authorization", &token)
+        .body(second.to_vec())
+        .send()
+        .await;
+    response.assert_status_is_ok();
+
+    let response = cli
+        .post(format!("{}/finalize", session_path))
+        .header("Authorization", &token)
+        .body_json(&json!({ "sha256": "0".repeat(64) }))
+        .send()
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let response = cli
+        .post(format!("{}/finalize", session_path))
+        .header("Authorization", &token)
+        .body_json(&json!({ "sha256": hex::encode(Sha256::digest(&file)) }))
+        .send()
+        .await;
+    response.assert_status_is_ok();
+    let json = response.json().await;
+    let image_path = json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("image_path")
+        .string()
+        .to_string();
+
+    let file_name = image_path.trim_start_matches("/images/");
+    assert_eq!(storage.get(file_name).await.unwrap(), Some(file.clone()));
+    let image_type: String = query_scalar("SELECT image_type FROM images")
+        .fetch_one(&state.db)
+        .await
+        .expect("failed to fetch image");
+    assert_eq!(image_type, "thumbnail");
+
+    let response = cli
+        .get(&session_path)
+        .header("Authorization", &token)
+        .send()
+        .await;
+    let json = response.json().await;
+    json.value()
+        .object()
+        .get("data")
+        .object()
+        .get("status")
+        .assert_string("completed");
+
+    let response = cli
+        .put(format!("{}?offset={}", session_path, file.len()))
+        .header("Authorization", &token)
+        .body(b"more".to_vec())
+        .send()
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn upload_session_is_private_and_can_be_cancelled() {
+    let (state, _) = create_test_state().await;
+    let cli = TestClient::new(create_test_app(state.clone()));
+    let owner = issue_access_token("writer-1");
+    let other = issue_access_token("writer-2");
+
+    let response = cli
+        .post("/images/upload-sessions")
+        .header("Authorization", &owner)
+        .body_json(&json!({
+            "file_name": "a.svg",
+            "content_type": "image/svg+xml",
+            "total_size": 10
+        }))
+        .send()
+        .await;
+    let json = response.json().await;
+    let session_id = json
+        .value()
+        .object()
+        .get("data")
+        .object()
+        .get("session_id")
+        .string()
+        .to_string();
+    let session_path = format!("/images/upload-sessions/{}", session_id);
+
+    let response = cli
+        .put(format!("{}?offset=0", session_path))
+        .header("Authorization", &other)
+        .body(b"0123".to_vec())
+        .send()
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+
+    let response = cli
+        .put(format!("{}?offset=0", session_path))
+        .header("Authorization", &owner)
+        .body(b"0123456789abc".to_vec())
+        .send()
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let response = cli
+        .delete(&session_path)
+        .header("Authorization", &owner)
+        .send()
+        .await;
+    response.assert_status_is_ok();
+
+    let response = cli
+        .get(&session_path)
+        .header("Authorization", &owner)
+        .send()
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+
+    let response = cli
+        .post("/images/upload-sessions")
+        .header("Authorization", &owner)
+        .body_json(&json!({
+            "file_name": "notes.txt",
+            "content_type": "text/plain",
+            "total_size": 10
+        }))
+        .send()
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,33 @@
+use crate::models::{
+    CustomResponse, PortfolioValidationQuery, PortfolioValidationResponse, UpdatePortfolioRequest,
+};
+use crate::portfolio_schema::{validate_portfolio_document, validate_portfolio_section};
+use poem::web::{Json, Query};
+use poem::{handler, Error};
+
+/// 저장하지 않고 검사만 한다. 틀린 곳을 모두 JSON Pointer와 함께 돌려주며, 문서가 틀려도 응답은 `200`이다.
+#[handler]
+pub async fn validate_portfolio(
+    Query(params): Query<PortfolioValidationQuery>,
+    Json(payload): Json<UpdatePortfolioRequest>,
+) -> Result<Json<CustomResponse<PortfolioValidationResponse>>, Error> {
+    let errors = match params.section_key.as_deref() {
+        Some(section_key) => validate_portfolio_section(section_key, &payload.content)?,
+        None => validate_portfolio_document(&payload.content),
+    };
+
+    let message = if errors.is_empty() {
+        String::from("문서가 스키마에 맞습니다.")
+    } else {
+        format!("검증 오류 {}건", errors.len())
+    };
+
+    Ok(Json(CustomResponse {
+        status: true,
+        data: Some(PortfolioValidationResponse {
+            valid: errors.is_empty(),
+            errors,
+        }),
+        message: Some(message),
+    }))
+}
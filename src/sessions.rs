@@ -0,0 +1,105 @@
+//! 로그인 세션(기기) 목록과 세션별 로그아웃.
+//!
+//! 세션은 로그인 한 번에 만드는 refresh token family다. access 토큰에 세션 ID(`family_id`)가 들어 있어서
+//! 세션을 폐기하면 그 기기의 refresh뿐 아니라 아직 만료되지 않은 access 토큰도 `Auth`에서 막힌다.
+
+use chrono::Utc;
+use poem::{http::StatusCode, Error, Request};
+use sqlx::{query, query_as, SqlitePool};
+
+use crate::{login_throttle::client_ip, models::SessionItem};
+
+const MAX_USER_AGENT_CHARS: usize = 512;
+const REVOKED_BY_USER: &str = "revoked_by_user";
+
+/// 로그인한 요청에서 남길 기기 정보.
+#[derive(Debug, Clone)]
+pub struct SessionDevice {
+    pub user_agent: Option<String>,
+    pub ip_address: String,
+}
+
+impl SessionDevice {
+    pub fn from_request(req: &Request) -> Self {
+        let user_agent = req
+            .headers()
+            .get("User-Agent")
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.chars().take(MAX_USER_AGENT_CHARS).collect());
+
+        Self {
+            user_agent,
+            ip_address: client_ip(req),
+        }
+    }
+}
+
+fn internal_error(message: String) -> Error {
+    Error::from_string(message, StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn timestamp() -> String {
+    Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// 살아 있는 세션만 최근에 쓴 순서로 돌려준다. 폐기했거나 refresh 토큰이 모두 만료된 세션은 빠진다.
+/// `current_session_id`와 같은 세션에는 `current: true`를 붙인다.
+pub async fn list_sessions(
+    db: &SqlitePool,
+    user_id: &str,
+    current_session_id: Option<&str>,
+) -> Result<Vec<SessionItem>, Error> {
+    let mut sessions: Vec<SessionItem> = query_as(
+        r#"
+        SELECT f.family_id AS id, f.created_at,
+               COALESCE(f.last_seen_at, f.created_at) AS last_seen_at,
+               f.user_agent, f.ip_address, 0 AS current
+        FROM refresh_token_families f
+        WHERE f.user_id = ?
+          AND f.revoked_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM refresh_tokens t
+              WHERE t.family_id = f.family_id AND t.used_at IS NULL AND t.expires_at > ?
+          )
+        ORDER BY COALESCE(f.last_seen_at, f.created_at) DESC, f.rowid DESC
+        "#,
+    )
+    .bind(user_id)
+    .bind(timestamp())
+    .fetch_all(db)
+    .await
+    .map_err(|err| internal_error(format!("세션 조회 실패: {}", err)))?;
+
+    for session in &mut sessions {
+        session.current = current_session_id == Some(session.id.as_str());
+    }
+    Ok(sessions)
+}
+
+/// 본인 세션 하나를 폐기한다. 다른 사용자의 세션이거나 이미 폐기한 세션이면 `404`다.
+pub async fn revoke_session(db: &SqlitePool, user_id: &str, session_id: &str) -> Result<(), Error> {
+    let result = query(
+        r#"
+        UPDATE refresh_token_families
+        SET revoked_at = ?, revoked_reason = ?
+        WHERE family_id = ? AND user_id = ? AND revoked_at IS NULL
+        "#,
+    )
+    .bind(timestamp())
+    .bind(REVOKED_BY_USER)
+    .bind(session_id)
+    .bind(user_id)
+    .execute(db)
+    .await
+    .map_err(|err| internal_error(format!("세션 폐기 실패: {}", err)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::from_string(
+            "세션을 찾을 수 없습니다.",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,470 @@
+//! TOTP 2단계 인증과 관리자 2단계 인증 필수 설정.
+//!
+//! TOTP 비밀키는 코드를 계산하려면 원문이 있어야 해서 `user_totp`에 그대로 두고, 복구 코드와
+//! 로그인 challenge 토큰은 SHA-256만 남긴다. challenge는 5분 동안, 코드를 5번 틀리기 전까지만 쓸 수 있다.
+
+use std::env;
+
+use chrono::{Duration, Utc};
+use poem::{http::StatusCode, Error};
+use sha2::{Digest, Sha256};
+use sqlx::{query, query_as, query_scalar, SqliteConnection, SqlitePool};
+use tyange_cms_api::auth::totp::{
+    decode_secret, generate_recovery_codes, generate_secret, otpauth_uri, recovery_code_hash,
+    verify_code,
+};
+use uuid::Uuid;
+
+use crate::models::{
+    TotpEnrollmentResponse, TwoFactorChallengeResponse, TwoFactorCodeRequest,
+    TwoFactorStatusResponse,
+};
+
+const REQUIRE_ADMIN_TOTP_KEY: &str = "require_admin_totp";
+const CHALLENGE_TTL_MINUTES: i64 = 5;
+const CHALLENGE_MAX_ATTEMPTS: i64 = 5;
+const DEFAULT_TOTP_ISSUER: &str = "tyange-cms";
+
+fn internal_error(message: String) -> Error {
+    Error::from_string(message, StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn timestamp(at: chrono::DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn challenge_token_hash(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn invalid_code() -> Error {
+    Error::from_string("인증 코드가 맞지 않습니다.", StatusCode::BAD_REQUEST)
+}
+
+fn challenge_expired() -> Error {
+    Error::from_string(
+        "2단계 인증 시간이 지났습니다. 다시 로그인해 주세요.",
+        StatusCode::UNAUTHORIZED,
+    )
+}
+
+/// 인증 앱에 표시되는 발급자 이름. `TOTP_ISSUER`(기본 `tyange-cms`).
+pub fn totp_issuer() -> String {
+    env::var("TOTP_ISSUER")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| DEFAULT_TOTP_ISSUER.to_string())
+}
+
+pub async fn admin_totp_required(db: &SqlitePool) -> Result<bool, sqlx::Error> {
+    let value: Option<String> =
+        query_scalar("SELECT setting_value FROM app_settings WHERE setting_key = ?")
+            .bind(REQUIRE_ADMIN_TOTP_KEY)
+            .fetch_optional(db)
+            .await?;
+
+    Ok(value.as_deref() == Some("true"))
+}
+
+pub async fn set_admin_totp_required(db: &SqlitePool, required: bool) -> Result<(), Error> {
+    query(
+        r#"
+        INSERT INTO app_settings (setting_key, setting_value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(setting_key) DO UPDATE
+        SET setting_value = excluded.setting_value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(REQUIRE_ADMIN_TOTP_KEY)
+    .bind(required.to_string())
+    .bind(timestamp(Utc::now()))
+    .execute(db)
+    .await
+    .map_err(|err| internal_error(format!("보안 설정 저장 실패: {}", err)))?;
+
+    Ok(())
+}
+
+/// 등록을 마친(`confirmed_at`이 있는) TOTP가 있는지.
+pub async fn totp_enabled(db: &SqlitePool, user_id: &str) -> Result<bool, sqlx::Error> {
+    query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM user_totp WHERE user_id = ? AND confirmed_at IS NOT NULL)",
+    )
+    .bind(user_id)
+    .fetch_one(db)
+    .await
+}
+
+/// 관리자 2단계 인증이 필수인데 이 관리자가 아직 등록하지 않았는지. 관리자가 아니면 항상 `false`.
+pub async fn admin_setup_required(
+    db: &SqlitePool,
+    user_id: &str,
+    user_role: &str,
+) -> Result<bool, sqlx::Error> {
+    if user_role != "admin" || !admin_totp_required(db).await? {
+        return Ok(false);
+    }
+
+    Ok(!totp_enabled(db, user_id).await?)
+}
+
+pub async fn two_factor_status(
+    db: &SqlitePool,
+    user_id: &str,
+    user_role: &str,
+) -> Result<TwoFactorStatusResponse, Error> {
+    let confirmed_at: Option<String> = query_scalar(
+        "SELECT confirmed_at FROM user_totp WHERE user_id = ? AND confirmed_at IS NOT NULL",
+    )
+    .bind(user_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("2단계 인증 조회 실패: {}", err)))?;
+    let recovery_codes_remaining: i64 = query_scalar(
+        "SELECT COUNT(*) FROM totp_recovery_codes WHERE user_id = ? AND used_at IS NULL",
+    )
+    .bind(user_id)
+    .fetch_one(db)
+    .await
+    .map_err(|err| internal_error(format!("복구 코드 조회 실패: {}", err)))?;
+    let required = user_role == "admin"
+        && admin_totp_required(db)
+            .await
+            .map_err(|err| internal_error(format!("보안 설정 조회 실패: {}", err)))?;
+
+    Ok(TwoFactorStatusResponse {
+        totp_enabled: confirmed_at.is_some(),
+        confirmed_at,
+        recovery_codes_remaining,
+        required,
+    })
+}
+
+/// 새 비밀키를 만든다. 확인 전인 등록은 덮어쓰고, 이미 쓰고 있으면 `409`.
+pub async fn start_totp_enrollment(
+    db: &SqlitePool,
+    user_id: &str,
+) -> Result<TotpEnrollmentResponse, Error> {
+    let secret = generate_secret();
+    let result = query(
+        r#"
+        INSERT INTO user_totp (user_id, secret, created_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(user_id) DO UPDATE
+        SET secret = excluded.secret, last_used_step = NULL, created_at = excluded.created_at
+        WHERE user_totp.confirmed_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(&secret)
+    .bind(timestamp(Utc::now()))
+    .execute(db)
+    .await
+    .map_err(|err| internal_error(format!("2단계 인증 등록 실패: {}", err)))?;
+    if result.rows_affected() == 0 {
+        return Err(Error::from_string(
+            "이미 2단계 인증을 사용하고 있습니다.",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    Ok(TotpEnrollmentResponse {
+        otpauth_uri: otpauth_uri(&totp_issuer(), user_id, &secret),
+        secret,
+    })
+}
+
+async fn replace_recovery_codes(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    query("DELETE FROM totp_recovery_codes WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let codes = generate_recovery_codes();
+    for code in &codes {
+        query("INSERT INTO totp_recovery_codes (code_hash, user_id) VALUES (?, ?)")
+            .bind(recovery_code_hash(code))
+            .bind(user_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(codes)
+}
+
+/// 첫 코드로 등록을 마치고 복구 코드를 만든다. 복구 코드 원문은 이 응답에서만 볼 수 있다.
+pub async fn confirm_totp_enrollment(
+    db: &SqlitePool,
+    user_id: &str,
+    code: &str,
+) -> Result<Vec<String>, Error> {
+    let pending: Option<(String, Option<String>)> =
+        query_as("SELECT secret, confirmed_at FROM user_totp WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(db)
+            .await
+            .map_err(|err| internal_error(format!("2단계 인증 조회 실패: {}", err)))?;
+    let Some((secret, confirmed_at)) = pending else {
+        return Err(Error::from_string(
+            "시작한 2단계 인증 등록이 없습니다.",
+            StatusCode::NOT_FOUND,
+        ));
+    };
+    if confirmed_at.is_some() {
+        return Err(Error::from_string(
+            "이미 2단계 인증을 사용하고 있습니다.",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    let key = decode_secret(&secret)
+        .ok_or_else(|| internal_error(String::from("저장된 TOTP 비밀키가 올바르지 않습니다.")))?;
+    let step = verify_code(&key, code, Utc::now().timestamp(), None).ok_or_else(invalid_code)?;
+
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|err| internal_error(format!("트랜잭션 시작 실패: {}", err)))?;
+    let result = query(
+        r#"
+        UPDATE user_totp SET confirmed_at = ?, last_used_step = ?
+        WHERE user_id = ? AND secret = ? AND confirmed_at IS NULL
+        "#,
+    )
+    .bind(timestamp(Utc::now()))
+    .bind(step)
+    .bind(user_id)
+    .bind(&secret)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| internal_error(format!("2단계 인증 확인 실패: {}", err)))?;
+    if result.rows_affected() == 0 {
+        // 그 사이 다른 요청이 등록을 마쳤거나 비밀키를 새로 만들었다.
+        return Err(invalid_code());
+    }
+    let codes = replace_recovery_codes(&mut tx, user_id)
+        .await
+        .map_err(|err| internal_error(format!("복구 코드 생성 실패: {}", err)))?;
+    tx.commit()
+        .await
+        .map_err(|err| internal_error(format!("트랜잭션 커밋 실패: {}", err)))?;
+
+    Ok(codes)
+}
+
+/// TOTP 코드나 복구 코드를 확인하고, 맞으면 그 코드를 쓴 것으로 남긴다.
+/// TOTP 코드는 같은 구간에서 다시 받지 않고 복구 코드는 한 번만 쓸 수 있다.
+pub async fn verify_second_factor(
+    db: &SqlitePool,
+    user_id: &str,
+    code: Option<&str>,
+    recovery_code: Option<&str>,
+) -> Result<bool, Error> {
+    if let Some(code) = code.map(str::trim).filter(|code| !code.is_empty()) {
+        let row: Option<(String, Option<i64>)> = query_as(
+            "SELECT secret, last_used_step FROM user_totp WHERE user_id = ? AND confirmed_at IS NOT NULL",
+        )
+        .bind(user_id)
+        .fetch_optional(db)
+        .await
+        .map_err(|err| internal_error(format!("2단계 인증 조회 실패: {}", err)))?;
+        let Some((secret, last_used_step)) = row else {
+            return Ok(false);
+        };
+        let Some(key) = decode_secret(&secret) else {
+            return Ok(false);
+        };
+        let Some(step) = verify_code(&key, code, Utc::now().timestamp(), last_used_step) else {
+            return Ok(false);
+        };
+
+        let result = query(
+            r#"
+            UPDATE user_totp SET last_used_step = ?
+            WHERE user_id = ? AND (last_used_step IS NULL OR last_used_step < ?)
+            "#,
+        )
+        .bind(step)
+        .bind(user_id)
+        .bind(step)
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("2단계 인증 기록 실패: {}", err)))?;
+        return Ok(result.rows_affected() == 1);
+    }
+
+    if let Some(recovery_code) = recovery_code.filter(|code| !code.trim().is_empty()) {
+        let result = query(
+            r#"
+            UPDATE totp_recovery_codes SET used_at = ?
+            WHERE code_hash = ? AND user_id = ? AND used_at IS NULL
+            "#,
+        )
+        .bind(timestamp(Utc::now()))
+        .bind(recovery_code_hash(recovery_code))
+        .bind(user_id)
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("복구 코드 사용 처리 실패: {}", err)))?;
+        return Ok(result.rows_affected() == 1);
+    }
+
+    Ok(false)
+}
+
+/// 이미 로그인한 사용자가 2단계 인증 설정을 바꿀 때 코드를 한 번 더 받는다. 틀리면 `400`.
+pub async fn require_second_factor(
+    db: &SqlitePool,
+    user_id: &str,
+    payload: &TwoFactorCodeRequest,
+) -> Result<(), Error> {
+    if verify_second_factor(
+        db,
+        user_id,
+        payload.code.as_deref(),
+        payload.recovery_code.as_deref(),
+    )
+    .await?
+    {
+        Ok(())
+    } else {
+        Err(invalid_code())
+    }
+}
+
+/// TOTP와 복구 코드, 진행 중인 로그인 challenge를 모두 지운다. 지운 것이 없으면 `false`.
+pub async fn disable_totp(db: &SqlitePool, user_id: &str) -> Result<bool, Error> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|err| internal_error(format!("트랜잭션 시작 실패: {}", err)))?;
+    let result = query("DELETE FROM user_totp WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| internal_error(format!("2단계 인증 해제 실패: {}", err)))?;
+    for sql in [
+        "DELETE FROM totp_recovery_codes WHERE user_id = ?",
+        "DELETE FROM login_challenges WHERE user_id = ?",
+    ] {
+        query(sql)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|err| internal_error(format!("2단계 인증 해제 실패: {}", err)))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|err| internal_error(format!("트랜잭션 커밋 실패: {}", err)))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 복구 코드를 새로 만든다. 예전 코드는 쓰지 않은 것까지 모두 무효가 된다.
+pub async fn regenerate_recovery_codes(
+    db: &SqlitePool,
+    user_id: &str,
+) -> Result<Vec<String>, Error> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|err| internal_error(format!("트랜잭션 시작 실패: {}", err)))?;
+    let codes = replace_recovery_codes(&mut tx, user_id)
+        .await
+        .map_err(|err| internal_error(format!("복구 코드 생성 실패: {}", err)))?;
+    tx.commit()
+        .await
+        .map_err(|err| internal_error(format!("트랜잭션 커밋 실패: {}", err)))?;
+
+    Ok(codes)
+}
+
+/// 비밀번호 확인을 마친 로그인에 중간 토큰을 발급한다. 만료된 challenge는 이때 정리한다.
+pub async fn create_login_challenge(
+    db: &SqlitePool,
+    user_id: &str,
+) -> Result<TwoFactorChallengeResponse, Error> {
+    let now = Utc::now();
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let expires_at = timestamp(now + Duration::minutes(CHALLENGE_TTL_MINUTES));
+
+    query("DELETE FROM login_challenges WHERE expires_at <= ?")
+        .bind(timestamp(now))
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("만료된 로그인 challenge 정리 실패: {}", err)))?;
+    query("INSERT INTO login_challenges (token_hash, user_id, expires_at) VALUES (?, ?, ?)")
+        .bind(challenge_token_hash(&token))
+        .bind(user_id)
+        .bind(&expires_at)
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("로그인 challenge 저장 실패: {}", err)))?;
+
+    Ok(TwoFactorChallengeResponse {
+        two_factor_required: true,
+        two_factor_token: token,
+        expires_at,
+    })
+}
+
+/// 아직 쓸 수 있는 중간 토큰의 사용자. 만료됐거나 이미 썼으면 `401`이다.
+pub async fn login_challenge_user(db: &SqlitePool, token: &str) -> Result<String, Error> {
+    query_scalar(
+        r#"
+        SELECT user_id FROM login_challenges
+        WHERE token_hash = ? AND used_at IS NULL AND expires_at > ? AND attempts < ?
+        "#,
+    )
+    .bind(challenge_token_hash(token))
+    .bind(timestamp(Utc::now()))
+    .bind(CHALLENGE_MAX_ATTEMPTS)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("로그인 challenge 조회 실패: {}", err)))?
+    .ok_or_else(challenge_expired)
+}
+
+/// 중간 토큰과 2단계 인증을 확인하고 로그인할 사용자를 돌려준다. 토큰은 한 번만 쓸 수 있다.
+/// 코드가 틀렸거나 토큰이 만료됐으면 `401`이다. 중간 토큰마다의 시도 제한과 별개로,
+/// 호출하는 쪽에서 틀린 코드를 아이디 실패 카운터에도 센다.
+pub async fn complete_login_challenge(
+    db: &SqlitePool,
+    token: &str,
+    code: Option<&str>,
+    recovery_code: Option<&str>,
+) -> Result<String, Error> {
+    let token_hash = challenge_token_hash(token);
+    let user_id = login_challenge_user(db, token).await?;
+
+    if !verify_second_factor(db, &user_id, code, recovery_code).await? {
+        query("UPDATE login_challenges SET attempts = attempts + 1 WHERE token_hash = ?")
+            .bind(&token_hash)
+            .execute(db)
+            .await
+            .map_err(|err| internal_error(format!("로그인 challenge 기록 실패: {}", err)))?;
+        return Err(Error::from_string(
+            "인증 코드가 맞지 않습니다.",
+            StatusCode::UNAUTHORIZED,
+        ));
+    }
+
+    let result =
+        query("UPDATE login_challenges SET used_at = ? WHERE token_hash = ? AND used_at IS NULL")
+            .bind(timestamp(Utc::now()))
+            .bind(&token_hash)
+            .execute(db)
+            .await
+            .map_err(|err| internal_error(format!("로그인 challenge 사용 처리 실패: {}", err)))?;
+    if result.rows_affected() == 0 {
+        return Err(challenge_expired());
+    }
+
+    Ok(user_id)
+}
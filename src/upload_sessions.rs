@@ -0,0 +1,384 @@
+use std::{env, path::PathBuf, time::Duration};
+
+use poem::{http::StatusCode, Error};
+use sha2::{Digest, Sha256};
+use sqlx::{query, query_as, query_scalar, SqlitePool};
+use tokio::{
+    fs::{self, OpenOptions},
+    io::{AsyncSeekExt, AsyncWriteExt},
+    time::interval,
+};
+use uuid::Uuid;
+
+use crate::{
+    image_upload::store_uploaded_image,
+    models::{
+        CreateUploadSessionRequest, UploadImageQueryParmas, UploadImageResponse,
+        UploadSessionResponse, UploadSessionRow,
+    },
+    storage::UploadStorage,
+};
+
+const DEFAULT_SESSION_MAX_BYTES: u64 = 500 * 1024 * 1024;
+const DEFAULT_SESSION_TTL_HOURS: i64 = 24;
+
+/// 완료 전 조각을 모아 두는 로컬 디렉토리. 최종 파일은 업로드 저장소로 옮긴다.
+pub fn upload_session_path() -> PathBuf {
+    PathBuf::from(
+        env::var("UPLOAD_SESSION_PATH").unwrap_or_else(|_| ".uploads/sessions".to_string()),
+    )
+}
+
+fn session_max_bytes() -> u64 {
+    env::var("UPLOAD_SESSION_MAX_BYTES")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_SESSION_MAX_BYTES)
+}
+
+fn session_ttl_hours() -> i64 {
+    env::var("UPLOAD_SESSION_TTL_HOURS")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_SESSION_TTL_HOURS)
+}
+
+fn part_file_path(session_id: &str) -> PathBuf {
+    upload_session_path().join(format!("{}.part", session_id))
+}
+
+fn db_error(action: &str, err: sqlx::Error) -> Error {
+    Error::from_string(
+        format!("업로드 세션 {} 실패: {}", action, err),
+        StatusCode::INTERNAL_SERVER_ERROR,
+    )
+}
+
+fn io_error(action: &str, err: std::io::Error) -> Error {
+    Error::from_string(
+        format!("업로드 조각 {} 실패: {}", action, err),
+        StatusCode::INTERNAL_SERVER_ERROR,
+    )
+}
+
+pub fn upload_session_response(session: UploadSessionRow) -> UploadSessionResponse {
+    UploadSessionResponse {
+        session_id: session.session_id,
+        origin_name: session.origin_name,
+        content_type: session.content_type,
+        total_size: session.total_size as u64,
+        received_size: session.received_size as u64,
+        status: session.status,
+        image_id: session.image_id,
+        created_at: session.created_at,
+        updated_at: session.updated_at,
+    }
+}
+
+pub async fn create_upload_session(
+    db: &SqlitePool,
+    user_id: &str,
+    request: CreateUploadSessionRequest,
+) -> Result<UploadSessionRow, Error> {
+    if !request.content_type.starts_with("image/") {
+        return Err(Error::from_string(
+            format!(
+                "이미지 파일만 업로드할 수 있습니다: {}",
+                request.content_type
+            ),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    if request.total_size == 0 {
+        return Err(Error::from_string(
+            "비어 있는 파일은 업로드할 수 없습니다.",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    let max_bytes = session_max_bytes();
+    if request.total_size > max_bytes {
+        return Err(Error::from_string(
+            format!(
+                "파일이 너무 큽니다. 최대 {}바이트까지 올릴 수 있습니다.",
+                max_bytes
+            ),
+            StatusCode::PAYLOAD_TOO_LARGE,
+        ));
+    }
+
+    let session_id = Uuid::new_v4().to_string();
+    fs::create_dir_all(upload_session_path())
+        .await
+        .map_err(|err| io_error("디렉토리 생성", err))?;
+    fs::write(part_file_path(&session_id), b"")
+        .await
+        .map_err(|err| io_error("파일 생성", err))?;
+
+    query(
+        r#"
+        INSERT INTO upload_sessions (
+            session_id, user_id, origin_name, content_type, total_size, post_id, image_type
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&session_id)
+    .bind(user_id)
+    .bind(request.file_name.trim())
+    .bind(&request.content_type)
+    .bind(request.total_size as i64)
+    .bind(&request.post_id)
+    .bind(&request.image_type)
+    .execute(db)
+    .await
+    .map_err(|err| db_error("생성", err))?;
+
+    fetch_upload_session(db, &session_id, user_id).await
+}
+
+/// 다른 사용자의 세션은 없는 것처럼 404로 돌려준다.
+pub async fn fetch_upload_session(
+    db: &SqlitePool,
+    session_id: &str,
+    user_id: &str,
+) -> Result<UploadSessionRow, Error> {
+    query_as::<_, UploadSessionRow>(
+        r#"
+        SELECT
+            session_id, user_id, origin_name, content_type, total_size, received_size,
+            post_id, image_type, status, image_id, created_at, updated_at
+        FROM upload_sessions
+        WHERE session_id = ?
+        "#,
+    )
+    .bind(session_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| db_error("조회", err))?
+    .filter(|session| session.user_id == user_id)
+    .ok_or_else(|| Error::from_string("업로드 세션을 찾을 수 없습니다.", StatusCode::NOT_FOUND))
+}
+
+fn session_closed_error() -> Error {
+    Error::from_string("이미 완료된 업로드 세션입니다.", StatusCode::CONFLICT)
+}
+
+/// `offset`은 지금까지 받은 크기와 같아야 한다. 다르면 409와 함께 현재 크기를 알려 주어
+/// 클라이언트가 그 위치부터 다시 보내게 한다.
+pub async fn append_upload_chunk(
+    db: &SqlitePool,
+    session: UploadSessionRow,
+    offset: u64,
+    chunk: Vec<u8>,
+) -> Result<UploadSessionRow, Error> {
+    if session.status != "open" {
+        return Err(session_closed_error());
+    }
+
+    let received_size = session.received_size as u64;
+    if offset != received_size {
+        return Err(Error::from_string(
+            format!(
+                "offset이 맞지 않습니다. 현재까지 받은 크기는 {}바이트입니다.",
+                received_size
+            ),
+            StatusCode::CONFLICT,
+        ));
+    }
+    if chunk.is_empty() {
+        return Err(Error::from_string(
+            "비어 있는 조각은 보낼 수 없습니다.",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    let next_size = offset + chunk.len() as u64;
+    if next_size > session.total_size as u64 {
+        return Err(Error::from_string(
+            format!("조각이 파일 크기({}바이트)를 넘습니다.", session.total_size),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    // 이전 요청이 중간에 끊겨 기록된 크기보다 길게 써졌을 수 있어 먼저 잘라 낸다.
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(part_file_path(&session.session_id))
+        .await
+        .map_err(|err| io_error("열기", err))?;
+    file.set_len(offset)
+        .await
+        .map_err(|err| io_error("정리", err))?;
+    file.seek(std::io::SeekFrom::Start(offset))
+        .await
+        .map_err(|err| io_error("이동", err))?;
+    file.write_all(&chunk)
+        .await
+        .map_err(|err| io_error("쓰기", err))?;
+    file.sync_data()
+        .await
+        .map_err(|err| io_error("쓰기", err))?;
+
+    let updated = query(
+        r#"
+        UPDATE upload_sessions
+        SET received_size = ?, updated_at = CURRENT_TIMESTAMP
+        WHERE session_id = ? AND received_size = ? AND status = 'open'
+        "#,
+    )
+    .bind(next_size as i64)
+    .bind(&session.session_id)
+    .bind(offset as i64)
+    .execute(db)
+    .await
+    .map_err(|err| db_error("갱신", err))?;
+
+    if updated.rows_affected() == 0 {
+        return Err(Error::from_string(
+            "같은 위치의 조각이 동시에 도착했습니다. 세션 상태를 다시 확인하세요.",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    fetch_upload_session(db, &session.session_id, &session.user_id).await
+}
+
+/// 모든 조각을 받았고 SHA-256이 맞으면 일반 업로드와 같은 과정으로 이미지를 저장한다.
+pub async fn finalize_upload_session(
+    db: &SqlitePool,
+    storage: &dyn UploadStorage,
+    session: UploadSessionRow,
+    sha256: &str,
+) -> Result<UploadImageResponse, Error> {
+    if session.status != "open" {
+        return Err(session_closed_error());
+    }
+
+    if session.received_size != session.total_size {
+        return Err(Error::from_string(
+            format!(
+                "아직 모든 조각을 받지 않았습니다 ({}/{}바이트).",
+                session.received_size, session.total_size
+            ),
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    let part_path = part_file_path(&session.session_id);
+    let mut bytes = fs::read(&part_path)
+        .await
+        .map_err(|err| io_error("읽기", err))?;
+    bytes.truncate(session.total_size as usize);
+
+    let actual = hex::encode(Sha256::digest(&bytes));
+    if !actual.eq_ignore_ascii_case(sha256.trim()) {
+        return Err(Error::from_string(
+            format!(
+                "체크섬이 맞지 않습니다. 받은 파일의 SHA-256은 {}입니다.",
+                actual
+            ),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    // 같은 세션을 두 번 완료하지 않도록 먼저 상태를 바꿔 둔다.
+    let claimed = query(
+        "UPDATE upload_sessions SET status = 'finalizing', updated_at = CURRENT_TIMESTAMP WHERE session_id = ? AND status = 'open'",
+    )
+    .bind(&session.session_id)
+    .execute(db)
+    .await
+    .map_err(|err| db_error("갱신", err))?;
+    if claimed.rows_affected() == 0 {
+        return Err(session_closed_error());
+    }
+
+    let params = UploadImageQueryParmas {
+        post_id: session.post_id.clone(),
+        image_type: session.image_type.clone(),
+    };
+    let uploaded = match store_uploaded_image(
+        db,
+        storage,
+        &session.user_id,
+        &session.origin_name,
+        &session.content_type,
+        bytes,
+        &params,
+    )
+    .await
+    {
+        Ok(uploaded) => uploaded,
+        Err(err) => {
+            // 파일 자체가 잘못된 경우이므로 세션을 다시 열어 두어 클라이언트가 지울 수 있게 한다.
+            query("UPDATE upload_sessions SET status = 'open' WHERE session_id = ?")
+                .bind(&session.session_id)
+                .execute(db)
+                .await
+                .map_err(|err| db_error("갱신", err))?;
+            return Err(err);
+        }
+    };
+
+    query(
+        "UPDATE upload_sessions SET status = 'completed', image_id = ?, updated_at = CURRENT_TIMESTAMP WHERE session_id = ?",
+    )
+    .bind(&uploaded.image_id)
+    .bind(&session.session_id)
+    .execute(db)
+    .await
+    .map_err(|err| db_error("갱신", err))?;
+
+    let _ = fs::remove_file(&part_path).await;
+
+    Ok(uploaded)
+}
+
+pub async fn delete_upload_session(db: &SqlitePool, session_id: &str) -> Result<(), Error> {
+    query("DELETE FROM upload_sessions WHERE session_id = ?")
+        .bind(session_id)
+        .execute(db)
+        .await
+        .map_err(|err| db_error("삭제", err))?;
+
+    match fs::remove_file(part_file_path(session_id)).await {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(io_error("삭제", err)),
+    }
+}
+
+/// `UPLOAD_SESSION_TTL_HOURS`(기본 24)보다 오래 갱신되지 않은 미완료 세션과 조각 파일을 지운다.
+pub async fn remove_expired_upload_sessions(db: &SqlitePool) -> Result<u64, Error> {
+    let expired: Vec<String> = query_scalar(
+        "SELECT session_id FROM upload_sessions WHERE status != 'completed' AND updated_at <= datetime('now', ?)",
+    )
+    .bind(format!("-{} hours", session_ttl_hours()))
+    .fetch_all(db)
+    .await
+    .map_err(|err| db_error("조회", err))?;
+
+    for session_id in &expired {
+        delete_upload_session(db, session_id).await?;
+    }
+
+    Ok(expired.len() as u64)
+}
+
+pub fn start_upload_session_cleanup_worker(db: SqlitePool) {
+    tokio::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(60 * 60));
+
+        loop {
+            ticker.tick().await;
+            match remove_expired_upload_sessions(&db).await {
+                Ok(0) => {}
+                Ok(count) => println!("expired upload sessions removed: {}", count),
+                Err(err) => eprintln!("upload session cleanup failed: {}", err),
+            }
+        }
+    });
+}
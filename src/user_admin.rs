@@ -0,0 +1,276 @@
+//! 관리자용 사용자 관리.
+//!
+//! 역할 변경과 비활성화는 마지막 관리자를 남기지 않는 방향으로는 하지 않는다. 비활성화한 계정은 `Auth`가 막고
+//! 로그인도 되지 않으며, 비활성화하는 순간 refresh token family를 모두 폐기한다.
+
+use chrono::Utc;
+use poem::{http::StatusCode, Error};
+use sqlx::{query, query_as, QueryBuilder, Sqlite, SqlitePool};
+use tyange_cms_api::auth::{
+    api_key::ApiKeyListItem, authorization::AuthenticatedUser,
+    refresh_token::revoke_user_refresh_families,
+};
+
+use crate::account_deletion::has_other_active_admin;
+use crate::models::{
+    AdminSessionItem, AdminUserDetailResponse, AdminUserItem, AdminUserListQuery,
+    AdminUserListResponse, ApiKeyResponse, UpdateAdminUserRequest,
+};
+use crate::password_reset::issue_password_reset;
+
+const USER_ROLES: [&str; 2] = ["admin", "user"];
+const DISABLED_REASON_LIMIT: usize = 200;
+
+const USER_COLUMNS: &str = r#"
+    user_id, user_role, auth_provider, display_name, created_at, last_login_at, disabled_at,
+    disabled_reason, deletion_scheduled_at,
+    EXISTS (
+        SELECT 1 FROM user_totp t WHERE t.user_id = users.user_id AND t.confirmed_at IS NOT NULL
+    ) AS totp_enabled
+"#;
+
+fn internal_error(message: String) -> Error {
+    Error::from_string(message, StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn user_not_found() -> Error {
+    Error::from_string("사용자를 찾을 수 없습니다.", StatusCode::NOT_FOUND)
+}
+
+fn push_filters<'a>(builder: &mut QueryBuilder<'a, Sqlite>, params: &'a AdminUserListQuery) {
+    builder.push(" WHERE 1 = 1");
+    if let Some(q) = params.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        builder
+            .push(" AND (instr(lower(user_id), lower(")
+            .push_bind(q)
+            .push(")) > 0 OR instr(lower(IFNULL(display_name, '')), lower(")
+            .push_bind(q)
+            .push(")) > 0)");
+    }
+    if let Some(role) = params.role.as_deref() {
+        builder.push(" AND user_role = ").push_bind(role);
+    }
+    match params.status.as_deref() {
+        Some("active") => {
+            builder.push(" AND disabled_at IS NULL");
+        }
+        Some("disabled") => {
+            builder.push(" AND disabled_at IS NOT NULL");
+        }
+        _ => {}
+    }
+}
+
+/// 가입 순서의 역순(최근 가입 먼저)으로 돌려준다. `limit`은 기본 50, 최대 100.
+pub async fn list_users(
+    db: &SqlitePool,
+    params: &AdminUserListQuery,
+) -> Result<AdminUserListResponse, Error> {
+    if let Some(status) = params.status.as_deref() {
+        if status != "active" && status != "disabled" {
+            return Err(Error::from_string(
+                "status는 active 또는 disabled만 쓸 수 있습니다.",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+    }
+    let limit = params.limit.unwrap_or(50).clamp(1, 100) as i64;
+    let offset = params.offset.unwrap_or(0) as i64;
+
+    let mut count = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM users");
+    push_filters(&mut count, params);
+    let total_count: i64 = count
+        .build_query_scalar()
+        .fetch_one(db)
+        .await
+        .map_err(|err| internal_error(format!("사용자 수 조회 실패: {}", err)))?;
+
+    let mut select = QueryBuilder::<Sqlite>::new(format!("SELECT {} FROM users", USER_COLUMNS));
+    push_filters(&mut select, params);
+    select
+        .push(" ORDER BY rowid DESC LIMIT ")
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+    let users = select
+        .build_query_as::<AdminUserItem>()
+        .fetch_all(db)
+        .await
+        .map_err(|err| internal_error(format!("사용자 목록 조회 실패: {}", err)))?;
+
+    Ok(AdminUserListResponse { users, total_count })
+}
+
+pub async fn find_user(db: &SqlitePool, user_id: &str) -> Result<Option<AdminUserItem>, Error> {
+    query_as::<_, AdminUserItem>(&format!(
+        "SELECT {} FROM users WHERE user_id = ?",
+        USER_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|err| internal_error(format!("사용자 조회 실패: {}", err)))
+}
+
+/// 계정 정보와 함께 API key(폐기된 것 포함)와 최근 세션 50개를 돌려준다.
+pub async fn user_detail(db: &SqlitePool, user_id: &str) -> Result<AdminUserDetailResponse, Error> {
+    let user = find_user(db, user_id).await?.ok_or_else(user_not_found)?;
+
+    let api_keys = query_as::<_, ApiKeyListItem>(
+        r#"
+        SELECT api_key_id AS id, name, created_at, last_used_at, revoked_at
+        FROM api_keys
+        WHERE user_id = ?
+        ORDER BY created_at DESC, api_key_id DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+    .map_err(|err| internal_error(format!("API key 조회 실패: {}", err)))?;
+
+    let sessions = query_as::<_, AdminSessionItem>(
+        r#"
+        SELECT f.family_id, f.created_at,
+               (SELECT MAX(t.issued_at) FROM refresh_tokens t WHERE t.family_id = f.family_id)
+                   AS last_used_at,
+               f.revoked_at, f.revoked_reason
+        FROM refresh_token_families f
+        WHERE f.user_id = ?
+        ORDER BY f.created_at DESC, f.rowid DESC
+        LIMIT 50
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+    .map_err(|err| internal_error(format!("세션 조회 실패: {}", err)))?;
+
+    Ok(AdminUserDetailResponse {
+        user,
+        api_keys: api_keys
+            .into_iter()
+            .map(|row| ApiKeyResponse {
+                id: row.id,
+                name: row.name,
+                created_at: row.created_at,
+                last_used_at: row.last_used_at,
+                revoked_at: row.revoked_at,
+            })
+            .collect(),
+        sessions,
+    })
+}
+
+/// 역할을 바꾸거나 계정을 비활성화/재활성화한다. 마지막 관리자를 내리거나 막는 변경은 `409`.
+pub async fn update_user(
+    db: &SqlitePool,
+    admin: &AuthenticatedUser,
+    user_id: &str,
+    payload: &UpdateAdminUserRequest,
+) -> Result<AdminUserItem, Error> {
+    let user = find_user(db, user_id).await?.ok_or_else(user_not_found)?;
+
+    if let Some(role) = payload.user_role.as_deref() {
+        if !USER_ROLES.contains(&role) {
+            return Err(Error::from_string(
+                "user_role은 admin 또는 user만 쓸 수 있습니다.",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+    }
+    let disabled_reason = payload
+        .disabled_reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty());
+    if disabled_reason.map_or(0, |reason| reason.chars().count()) > DISABLED_REASON_LIMIT {
+        return Err(Error::from_string(
+            "비활성화 사유는 200자를 넘길 수 없습니다.",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let demoting = payload
+        .user_role
+        .as_deref()
+        .is_some_and(|role| role != "admin");
+    let disabling = payload.disabled == Some(true) && user.disabled_at.is_none();
+    if disabling && user.user_id == admin.user_id {
+        return Err(Error::from_string(
+            "자기 계정은 비활성화할 수 없습니다.",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    if (demoting || disabling) && !has_other_active_admin(db, user_id).await? {
+        return Err(Error::from_string(
+            "마지막 관리자 계정의 권한을 내리거나 비활성화할 수 없습니다.",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    if let Some(role) = payload.user_role.as_deref() {
+        query("UPDATE users SET user_role = ? WHERE user_id = ?")
+            .bind(role)
+            .bind(user_id)
+            .execute(db)
+            .await
+            .map_err(|err| internal_error(format!("역할 변경 실패: {}", err)))?;
+        // 이전 역할이 담긴 토큰으로 계속 쓰지 못하게 세션을 모두 끊는다.
+        if role != user.user_role {
+            revoke_user_refresh_families(db, user_id, "role_changed")
+                .await
+                .map_err(|err| internal_error(format!("로그인 세션 폐기 실패: {}", err)))?;
+        }
+    }
+
+    match payload.disabled {
+        Some(true) => {
+            // 이미 막힌 계정은 처음 막은 시각을 유지하고 사유만 바꾼다.
+            query(
+                r#"
+                UPDATE users
+                SET disabled_at = COALESCE(disabled_at, ?), disabled_reason = ?
+                WHERE user_id = ?
+                "#,
+            )
+            .bind(Utc::now().format("%Y-%m-%d %H:%M:%S").to_string())
+            .bind(disabled_reason)
+            .bind(user_id)
+            .execute(db)
+            .await
+            .map_err(|err| internal_error(format!("계정 비활성화 실패: {}", err)))?;
+            revoke_user_refresh_families(db, user_id, "disabled")
+                .await
+                .map_err(|err| internal_error(format!("로그인 세션 폐기 실패: {}", err)))?;
+        }
+        Some(false) => {
+            query("UPDATE users SET disabled_at = NULL, disabled_reason = NULL WHERE user_id = ?")
+                .bind(user_id)
+                .execute(db)
+                .await
+                .map_err(|err| internal_error(format!("계정 재활성화 실패: {}", err)))?;
+        }
+        None => {}
+    }
+
+    find_user(db, user_id).await?.ok_or_else(user_not_found)
+}
+
+/// 지금 비밀번호를 지우고 모든 로그인 세션을 폐기한 뒤 재설정 메일을 보낸다.
+/// 사용자는 메일로 새 비밀번호를 정하기 전까지 비밀번호로 로그인할 수 없다.
+pub async fn force_password_reset(db: &SqlitePool, user_id: &str) -> Result<(), Error> {
+    let result = query("UPDATE users SET password = NULL WHERE user_id = ?")
+        .bind(user_id)
+        .execute(db)
+        .await
+        .map_err(|err| internal_error(format!("비밀번호 초기화 실패: {}", err)))?;
+    if result.rows_affected() == 0 {
+        return Err(user_not_found());
+    }
+
+    revoke_user_refresh_families(db, user_id, "forced_password_reset")
+        .await
+        .map_err(|err| internal_error(format!("로그인 세션 폐기 실패: {}", err)))?;
+    issue_password_reset(db, user_id).await
+}